@@ -1,33 +1,412 @@
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+use protocol_infer_core::description::{FieldDescription, FramingRule};
+use protocol_infer_core::tlv::TlvTagStats;
 use protocol_infer_core::{
-    pcap, plugins, Corpus, InferenceEngine, PluginRegistry,
+    pcap, plugins, Checkpoint, ClusterConfig, Config, Corpus, FieldTable, Flow, FormatDescription, InferenceEngine,
+    InferenceResult, ModelSpec, ParseReport, Progress, ProgressSink, Project, ResultDiff,
 };
-use serde_json;
 use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 use tracing::{info, Level};
-use tracing_subscriber;
 
 #[derive(Parser)]
 #[command(name = "protocol_infer")]
 #[command(about = "Infère automatiquement la structure d'un protocole à partir d'un fichier PCAP")]
+#[command(group(ArgGroup::new("source").required(true).args(["pcap", "input_dir", "hex_file", "corpus"])))]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
-    /// Fichier PCAP à analyser
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Fichier PCAP à analyser (répétable, accepte les jokers `*` et `?` dans le nom de fichier)
     #[arg(short, long)]
-    pcap: String,
+    pcap: Vec<String>,
+
+    /// Répertoire de messages binaires (un message par fichier) à analyser au lieu d'un PCAP
+    #[arg(long)]
+    input_dir: Option<String>,
+
+    /// Fichier texte de messages (xxd, hexdump -C, `\x..`, une chaîne hex par ligne)
+    #[arg(long)]
+    hex_file: Option<String>,
+
+    /// Corpus au format JSON Lines (voir --export-corpus)
+    #[arg(long)]
+    corpus: Option<String>,
 
     /// Fichier de sortie JSON
+    #[arg(short, long, required_unless_present = "export_corpus")]
+    out: Option<String>,
+
+    /// Exporter le corpus chargé au format JSON Lines (horodatage, direction, payload hexa)
+    #[arg(long)]
+    export_corpus: Option<String>,
+
+    /// Écrire un squelette `.proto` (numéros de champs, types, messages imbriqués) quand une
+    /// couche est parsée en clés varint protobuf
+    #[arg(long, value_name = "FILE")]
+    proto: Option<String>,
+
+    /// Écrire la description du format inféré (couches, règles de découpage, champs,
+    /// contraintes) : document JSON stable, indépendant de la sortie --out
+    #[arg(long, value_name = "FILE")]
+    describe: Option<String>,
+
+    /// Écrire le JSON Schema du document de --describe
+    #[arg(long, value_name = "FILE")]
+    describe_schema: Option<String>,
+
+    /// Écrire un squelette de dissecteur Wireshark en C (plugin : enregistrement du protocole,
+    /// champs `hf_` des en-têtes inférés, « Decode As » sur un port UDP)
+    #[arg(long, value_name = "FILE")]
+    dissector: Option<String>,
+
+    /// Nom du protocole du dissecteur (filtre d'affichage et identifiants C)
+    #[arg(long, default_value = "inferred", requires = "dissector")]
+    dissector_name: String,
+
+    /// Écrire le diagramme Graphviz (DOT) de la pile de couches et de leurs champs d'en-tête
+    #[arg(long, value_name = "FILE")]
+    dot: Option<String>,
+
+    /// Écrire le même diagramme au format Mermaid
+    #[arg(long, value_name = "FILE")]
+    mermaid: Option<String>,
+
+    /// Écrire la table des champs au format CSV : une ligne par message, une colonne par
+    /// champ inféré (valeurs décodées, taille du SDU)
+    #[arg(long, value_name = "FILE")]
+    csv: Option<String>,
+
+    /// Couche dont --csv écrit les champs (0 : première couche)
+    #[arg(long, default_value = "0", requires = "csv")]
+    csv_layer: usize,
+
+    /// Écrire les SDUs extraits par chaque couche dans ce répertoire (`layer<N>/` ou
+    /// `layer<N>.pcap` selon --sdu-format), pour les passer à d'autres outils
+    #[arg(long, value_name = "DIR")]
+    export_sdus: Option<String>,
+
+    /// Format des SDUs de --export-sdus
+    #[arg(long, value_enum, default_value = "raw", requires = "export_sdus")]
+    sdu_format: SduFormat,
+
+    /// Écrire une synthèse Markdown : hypothèse retenue et paramètres par couche, mesures,
+    /// meilleures hypothèses concurrentes et écarts, champs d'en-tête notables
+    #[arg(long, value_name = "FILE")]
+    report: Option<String>,
+
+    /// Écrire un rapport HTML autonome (résumé des couches, scores, entropie par offset,
+    /// messages représentatifs annotés), lisible sans la GUI
+    #[arg(long, value_name = "FILE")]
+    html: Option<String>,
+
+    /// Profondeur maximale de récursion (6 par défaut)
+    #[arg(long)]
+    max_depth: Option<usize>,
+
+    /// Nombre d'hypothèses top-K à garder par couche (10 par défaut)
+    #[arg(long)]
+    top_k: Option<usize>,
+
+    /// Nombre maximal de retours arrière : quand les couches suivantes n'expliquent plus les
+    /// données, une couche déjà choisie est refaite avec l'hypothèse suivante du classement
+    /// (0 par défaut)
+    #[arg(long)]
+    backtrack: Option<usize>,
+
+    /// Nombre de tours d'affinage local : les voisines des meilleures hypothèses (offsets et
+    /// tailles à ±1, largeur, endianness, inclusion de l'en-tête) sont évaluées à leur tour
+    /// (0 par défaut)
+    #[arg(long)]
+    refine: Option<usize>,
+
+    /// Fait aussi descendre la récursion dans les champs (valeurs d'en-têtes, chaînes...)
+    /// d'au moins ce nombre d'octets, et pas seulement dans les SDUs
+    #[arg(long, value_name = "MIN_SIZE")]
+    recurse_fields: Option<usize>,
+
+    /// Durée maximale de l'inférence en secondes ; à l'échéance, les couches établies sont écrites
+    #[arg(long)]
+    time_budget: Option<f64>,
+
+    /// Nombre maximal d'hypothèses parsées et scorées par inférence
+    #[arg(long)]
+    max_hypotheses: Option<usize>,
+
+    /// Mémoire (Mo) que l'inférence devrait occuper : au-delà, le corpus est échantillonné par
+    /// longueur de PDU et seul le découpage des hypothèses retenues est gardé
+    #[arg(long, value_name = "MB")]
+    memory_budget: Option<usize>,
+
+    /// Point de reprise : les scores déjà calculés y sont relus s'il existe, et les scores de
+    /// cette exécution y sont écrits à la fin (y compris après une interruption ou un budget épuisé)
+    #[arg(long, value_name = "FILE")]
+    checkpoint: Option<String>,
+
+    /// Modèle connu (TOML, une table `[[layers]]` par couche) : ses hypothèses sont imposées
+    /// aux premières couches et l'inférence ne reprend qu'au-delà
+    #[arg(long, value_name = "FILE")]
+    model: Option<String>,
+
+    /// Appliquer le modèle de --model sans inférer de couche au-delà des siennes
+    #[arg(long, requires = "model")]
+    model_only: bool,
+
+    /// Écrire la pile retenue comme modèle TOML, à réappliquer avec --model sur d'autres captures
+    #[arg(long, value_name = "FILE")]
+    save_model: Option<String>,
+
+    /// Projet d'analyse (.aire) : métadonnées du corpus, réglages, résultat et annotations,
+    /// réouvrable par la CLI et la GUI ; les noms de champs et notes d'un projet existant sur
+    /// la même capture sont conservés
+    #[arg(long, value_name = "FILE")]
+    project: Option<String>,
+
+    /// Nombre de PDUs tirées pour pré-filtrer les hypothèses avant le parsing complet (un
+    /// champ de longueur qui ne tient pas dans la plupart des PDUs est écarté) ; 0 désactive
+    /// (50 par défaut)
+    #[arg(long)]
+    prefilter_sample: Option<usize>,
+
+    /// Scorer d'abord les hypothèses sur l'échantillon des pré-filtres et abandonner celles qui,
+    /// hors des --top-k meilleures, sont à plus de cette marge (bits par octet) de la meilleure
+    #[arg(long, value_name = "BITS_PER_BYTE")]
+    screening_margin: Option<f64>,
+
+    /// Écart de score (bits) en deçà duquel une hypothèse d'une autre nature rend une couche
+    /// ambiguë (champ `confidence` des couches ; 8 par défaut)
+    #[arg(long)]
+    ambiguity_epsilon: Option<f64>,
+
+    /// Inférer séparément chaque version quand un champ de version est détecté
+    #[arg(long)]
+    split_versions: bool,
+
+    /// Retirer les encapsulations VXLAN, GENEVE et GRE
+    #[arg(long)]
+    decap_tunnels: bool,
+
+    /// Regrouper les deux directions de chaque conversation dans un flow bidirectionnel
+    #[arg(long)]
+    bidirectional: bool,
+
+    /// Inférer une pile de couches par direction (requêtes et réponses), avec une synthèse
+    /// commune, au lieu de mêler les deux directions
+    #[arg(long, conflicts_with = "window")]
+    per_direction: bool,
+
+    /// Regrouper les PDUs par type de message (longueur, histogramme d'octets, préfixe) et
+    /// inférer une pile par groupe
+    #[arg(long, conflicts_with_all = ["window", "per_direction"])]
+    cluster: bool,
+
+    /// Inférer une pile par flow (--per-flow ou --tcp) et rapporter le modèle sur lequel la
+    /// majorité des flows s'accordent, avec leur taux d'accord couche par couche
+    #[arg(long, conflicts_with_all = ["window", "per_direction", "cluster"])]
+    consensus: bool,
+
+    /// Proportion minimale (0 à 1) des flows qui doivent retenir une couche pour qu'elle
+    /// entre dans le consensus (avec --consensus)
+    #[arg(long, default_value_t = 0.5)]
+    min_agreement: f64,
+
+    /// Distance (0 à 1) au-delà de laquelle une PDU ouvre un nouveau groupe (avec --cluster)
+    #[arg(long, default_value_t = 0.35)]
+    cluster_threshold: f64,
+
+    /// Taille minimale d'un groupe de messages (avec --cluster)
+    #[arg(long, default_value_t = 4)]
+    min_cluster_size: usize,
+
+    /// Octets d'en-tête de lien à sauter avant l'en-tête IP (DLT_USER, formats propriétaires)
+    #[arg(long)]
+    l2_offset: Option<usize>,
+
+    /// Offset IP pour un type de lien donné, au format LINKTYPE=OCTETS (répétable, ex: 147=12)
+    #[arg(long, value_parser = parse_linktype_offset)]
+    linktype_offset: Vec<(u32, usize)>,
+
+    /// Début de la plage temporelle analysée (secondes epoch, ou `+N` relatif à la première PDU)
+    #[arg(long, value_parser = parse_time_bound, allow_hyphen_values = true)]
+    from: Option<TimeBound>,
+
+    /// Fin de la plage temporelle analysée (secondes epoch, ou `+N` relatif à la première PDU)
+    #[arg(long, value_parser = parse_time_bound, allow_hyphen_values = true)]
+    to: Option<TimeBound>,
+
+    /// Découper le corpus en fenêtres de N secondes analysées indépendamment
+    #[arg(long)]
+    window: Option<f64>,
+
+    /// Supprimer les PDUs identiques (heartbeats, ...) en conservant leur nombre d'occurrences
+    #[arg(long)]
+    dedup: bool,
+
+    /// Analyser un échantillon aléatoire de N PDUs
+    #[arg(long)]
+    sample: Option<usize>,
+
+    /// Graine de l'échantillonnage (--sample)
+    #[arg(long, default_value = "0")]
+    seed: u64,
+
+    /// Analyser les flux TCP réassemblés au lieu des datagrammes UDP
+    #[arg(long, value_enum)]
+    tcp: Option<TcpMode>,
+
+    /// Conserver le flow d'origine de chaque datagramme UDP au lieu de les fusionner
+    /// (nécessaire à la détection des identifiants de session)
+    #[arg(long)]
+    per_flow: bool,
+
+    /// Ne garder que les flows de cette adresse source (avec --bidirectional : le client)
+    #[arg(long)]
+    src_ip: Option<std::net::IpAddr>,
+
+    /// Ne garder que les flows de cette adresse destination (avec --bidirectional : le serveur)
+    #[arg(long)]
+    dst_ip: Option<std::net::IpAddr>,
+
+    /// Ne garder que les flows de ce port source
+    #[arg(long)]
+    src_port: Option<u16>,
+
+    /// Ne garder que les flows de ce port destination
+    #[arg(long)]
+    dst_port: Option<u16>,
+
+    /// Ne garder que les flows d'au moins N PDUs
+    #[arg(long, value_name = "N", default_value = "0")]
+    min_packets: usize,
+
+    /// Rechercher dans les PDUs les adresses IP et MAC des en-têtes de la capture
+    #[arg(long)]
+    embedded_addresses: bool,
+
+    /// Évaluer les hypothèses par validation croisée à K plis : proposées sur K-1 plis,
+    /// scorées sur le pli restant
+    #[arg(long, value_name = "K")]
+    cross_validate: Option<usize>,
+
+    /// Fichier de configuration TOML : paramètres du moteur (`[engine]`), coefficients du
+    /// scoreur (`[scorer]`), générateurs et parseurs actifs, plages de recherche (`[search.NOM]`) ;
+    /// les options de la ligne de commande priment sur le fichier
+    #[arg(long, value_name = "FILE")]
+    config: Option<String>,
+
+    /// Fichier JSON des poids et coefficients du scoreur MDL (champs de `MdlScorerConfig`),
+    /// qui remplace la section `[scorer]` de --config
+    #[arg(long)]
+    scorer_config: Option<String>,
+
+    /// Coefficient du scoreur MDL au format NOM=VALEUR (répétable, prioritaire sur --scorer-config) ;
+    /// `reject_at` prend une gravité d'exception (fatal, recoverable, informational) ou none,
+    /// `compression` un modèle de compression (deflate, zstd, lzma, ppm)
+    #[arg(long = "scorer", value_parser = parse_scorer_param)]
+    scorer_params: Vec<(String, String)>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Décoder une capture avec un modèle enregistré (--save-model), sans inférence : champs
+    /// parsés et taux de succès du parsing de chaque couche
+    Parse(ParseArgs),
+    /// Comparer deux résultats (--out ou projet .aire) : couches, paramètres, champs, tags TLV et
+    /// branches qui ont changé
+    Diff(DiffArgs),
+}
+
+#[derive(clap::Args)]
+struct DiffArgs {
+    /// Résultat de référence (ancienne version)
+    old: String,
+
+    /// Résultat à comparer (nouvelle version)
+    new: String,
+
+    /// Fichier de sortie JSON des différences
+    #[arg(short, long)]
+    out: Option<String>,
+}
+
+#[derive(clap::Args)]
+#[command(group(ArgGroup::new("source").required(true).args(["pcap", "input_dir", "hex_file", "corpus"])))]
+struct ParseArgs {
+    /// Fichier PCAP à décoder (répétable, accepte les jokers `*` et `?` dans le nom de fichier)
+    #[arg(short, long)]
+    pcap: Vec<String>,
+
+    /// Répertoire de messages binaires (un message par fichier)
+    #[arg(long)]
+    input_dir: Option<String>,
+
+    /// Fichier texte de messages (xxd, hexdump -C, `\x..`, une chaîne hex par ligne)
+    #[arg(long)]
+    hex_file: Option<String>,
+
+    /// Corpus au format JSON Lines (voir --export-corpus)
+    #[arg(long)]
+    corpus: Option<String>,
+
+    /// Décoder les flux TCP réassemblés au lieu des datagrammes UDP
+    #[arg(long, value_enum)]
+    tcp: Option<TcpMode>,
+
+    /// Modèle TOML à appliquer (une table `[[layers]]` par couche)
+    #[arg(long, value_name = "FILE")]
+    model: String,
+
+    /// Fichier de sortie JSON : bilan de chaque couche et table de ses champs
     #[arg(short, long)]
     out: String,
+}
 
+/// Format des SDUs écrits par --export-sdus
+#[derive(Clone, Copy, ValueEnum)]
+enum SduFormat {
+    /// Un fichier binaire par SDU (relisible par --input-dir)
+    Raw,
+    /// Une capture synthétique par couche : un datagramme UDP par SDU, adresses fictives
+    Pcap,
+}
 
-    /// Profondeur maximale de récursion
-    #[arg(long, default_value = "6")]
-    max_depth: usize,
+/// Découpage des PDUs TCP
+#[derive(Clone, Copy, ValueEnum)]
+enum TcpMode {
+    /// Une PDU par segment TCP
+    Segments,
+    /// Une PDU par direction (flux complet)
+    Stream,
+}
 
-    /// Nombre d'hypothèses top-K à garder par couche
-    #[arg(long, default_value = "10")]
-    top_k: usize,
+/// Borne temporelle d'une plage --from / --to
+#[derive(Clone, Copy)]
+enum TimeBound {
+    /// Horodatage absolu (secondes epoch)
+    Absolute(f64),
+    /// Secondes écoulées depuis la première PDU
+    Relative(f64),
+}
+
+impl TimeBound {
+    fn resolve(self, origin: f64) -> f64 {
+        match self {
+            TimeBound::Absolute(ts) => ts,
+            TimeBound::Relative(offset) => origin + offset,
+        }
+    }
+}
+
+impl From<TcpMode> for pcap::TcpPduMode {
+    fn from(mode: TcpMode) -> Self {
+        match mode {
+            TcpMode::Segments => pcap::TcpPduMode::Segments,
+            TcpMode::Stream => pcap::TcpPduMode::Stream,
+        }
+    }
 }
 
 fn main() -> Result<()> {
@@ -37,43 +416,735 @@ fn main() -> Result<()> {
         .init();
 
     let args = Args::parse();
+    match &args.command {
+        Some(Command::Parse(parse_args)) => return run_parse(parse_args),
+        Some(Command::Diff(diff_args)) => return run_diff(diff_args),
+        None => {}
+    }
+
+    let mut addresses = None;
+    let (corpus, flows) = if !args.pcap.is_empty() {
+        let paths = expand_pcap_paths(&args.pcap)?;
+        let flows = load_pcap_flows(&paths, &args)?;
+        if args.embedded_addresses {
+            addresses = Some(
+                pcap::capture_addresses(&paths, &pcap_options(&args))
+                    .with_context(|| format!("Échec du parsing PCAP: {}", paths.join(", ")))?,
+            );
+        }
+        (Corpus::from_flows(&flows), flows)
+    } else {
+        (load_message_files(&args.input_dir, &args.hex_file, &args.corpus)?, Vec::new())
+    };
+
+    let corpus = if args.from.is_some() || args.to.is_some() {
+        let origin = corpus.time_span().map_or(0.0, |(start, _)| start);
+        let from = args.from.map(|b| b.resolve(origin));
+        let to = args.to.map(|b| b.resolve(origin));
+        let sliced = corpus.between(from, to);
+        info!("Plage temporelle: {} PDUs sur {} retenues", sliced.len(), corpus.len());
+        sliced
+    } else {
+        corpus
+    };
+
+    let corpus = if args.dedup {
+        let deduped = corpus.dedup();
+        info!("Déduplication: {} PDUs distinctes sur {}", deduped.len(), corpus.len());
+        deduped
+    } else {
+        corpus
+    };
+    let corpus = match args.sample {
+        Some(n) => corpus.sample(n, args.seed),
+        None => corpus,
+    };
 
-    info!("Chargement du fichier PCAP: {}", args.pcap);
-    let flow = pcap::parse_pcap(&args.pcap)
-        .with_context(|| format!("Échec du parsing PCAP: {}", args.pcap))?;
+    let total_packets = corpus.len();
+    if total_packets == 0 {
+        anyhow::bail!("Aucune PDU trouvée dans la source");
+    }
 
-    info!("{} paquets UDP trouvés", flow.datagrams.len());
+    if let Some(path) = &args.describe_schema {
+        fs::write(path, serde_json::to_string_pretty(&FormatDescription::json_schema())?)
+            .with_context(|| format!("Échec de l'écriture du schéma: {}", path))?;
+    }
 
-    if flow.datagrams.is_empty() {
-        anyhow::bail!("Aucun paquet UDP trouvé dans le fichier PCAP");
+    if let Some(path) = &args.export_corpus {
+        fs::write(path, corpus.to_jsonl())
+            .with_context(|| format!("Échec de l'écriture du corpus: {}", path))?;
+        info!("Corpus exporté dans: {}", path);
     }
 
-    let registry = plugins::create_default_registry();
-    let engine = InferenceEngine::new()
-        .with_max_depth(args.max_depth)
-        .with_top_k(args.top_k);
+    let Some(out) = &args.out else {
+        return Ok(());
+    };
 
-    info!("Traitement de {} datagrammes", flow.datagrams.len());
+    let config = match &args.config {
+        Some(path) => {
+            Config::load(path).with_context(|| format!("Échec de la lecture de la configuration: {}", path))?
+        }
+        None => Config::default(),
+    };
+    let scorer_config = scorer_config(&args, &config)?;
+    let compression = scorer_config.compression;
+    let registry = config
+        .registry_from(plugins::create_registry(scorer_config))
+        .context("Configuration des plugins invalide")?;
+    let engine = config.engine(InferenceEngine::new());
+    // Seules les options passées sur la ligne de commande remplacent celles du fichier
+    let (max_depth, top_k) = (args.max_depth.unwrap_or(engine.max_depth), args.top_k.unwrap_or(engine.top_k));
+    let ambiguity_epsilon = args.ambiguity_epsilon.unwrap_or(engine.ambiguity_epsilon);
+    let backtrack = args.backtrack.unwrap_or(engine.backtrack_budget);
+    let refine = args.refine.unwrap_or(engine.refinement_rounds);
+    let prefilter_sample = args.prefilter_sample.unwrap_or(engine.prefilter_sample);
+    let split_versions = args.split_versions || engine.split_by_version;
+    let cross_validate = args.cross_validate.unwrap_or(engine.cross_validation_folds);
+    let engine = engine
+        .with_compression(compression)
+        .with_max_depth(max_depth)
+        .with_top_k(top_k)
+        .with_ambiguity_epsilon(ambiguity_epsilon)
+        .with_backtracking(backtrack)
+        .with_refinement(refine)
+        .with_prefilter(prefilter_sample)
+        .with_version_split(split_versions)
+        .with_cross_validation(cross_validate)
+        .with_progress(Arc::new(LogProgress::default()));
+    let engine = match args.time_budget {
+        Some(seconds) => engine.with_time_budget(
+            std::time::Duration::try_from_secs_f64(seconds).context("Budget de temps invalide")?,
+        ),
+        None => engine,
+    };
+    let engine = match args.max_hypotheses {
+        Some(max) => engine.with_max_hypotheses(max),
+        None => engine,
+    };
+    let engine = match args.screening_margin {
+        Some(margin) => engine.with_screening(margin),
+        None => engine,
+    };
+    let engine = match args.memory_budget {
+        Some(megabytes) => engine.with_memory_budget(megabytes.saturating_mul(1 << 20)),
+        None => engine,
+    };
+    let engine = match &args.checkpoint {
+        Some(path) if Path::new(path).exists() => {
+            let checkpoint = Checkpoint::load(path)
+                .with_context(|| format!("Échec de la lecture du point de reprise: {}", path))?;
+            info!("Reprise: {} hypothèses déjà scorées dans {}", checkpoint.len(), path);
+            engine.resume(checkpoint)
+        }
+        Some(_) => engine.with_checkpoints(),
+        None => engine,
+    };
+    let engine = match &args.model {
+        Some(path) => {
+            let model =
+                ModelSpec::load(path).with_context(|| format!("Échec de la lecture du modèle: {}", path))?;
+            info!("Modèle: {} couches imposées depuis {}", model.layers.len(), path);
+            let engine = if args.model_only { engine.with_max_depth(model.layers.len()) } else { engine };
+            engine.with_pinned_layers(model.layers)
+        }
+        None => engine,
+    };
+    let engine = match args.recurse_fields {
+        Some(min_size) => engine.with_field_recursion(min_size),
+        None => engine,
+    };
+    #[cfg(unix)]
+    let engine = {
+        let token = protocol_infer_core::CancellationToken::new();
+        cancel_on_interrupt(token.clone())?;
+        engine.with_cancellation(token)
+    };
 
-    let corpus = Corpus::from_datagrams(&flow.datagrams, Some(0));
     info!("Corpus créé: {} PDUs, {} octets", corpus.len(), corpus.total_bytes());
 
-    let result = engine.infer(corpus, &registry);
-    info!("Inférence terminée: {} couches trouvées", result.layers.len());
+    let source = corpus.meta.source.clone();
+    let mut output = match flows.as_slice() {
+        [] => serde_json::json!({ "source": source }),
+        [flow] => serde_json::json!({ "flow": flow }),
+        _ => serde_json::json!({ "flows": flows }),
+    };
 
-    let output = serde_json::json!({
-        "flow": flow,
-        "result": result,
-        "summary": {
-            "total_packets": flow.datagrams.len(),
+    if let Some(width) = args.window {
+        let windows = corpus.split_windows(width);
+        if windows.is_empty() {
+            anyhow::bail!("Aucune PDU horodatée : découpage en fenêtres impossible");
+        }
+        let mut results = Vec::new();
+        for (start, end, window) in windows {
+            let window_packets = window.len();
+            let mut result = engine.infer(window, &registry);
+            if let Some(addresses) = &addresses {
+                result.annotate_addresses(addresses);
+            }
+            info!(
+                "Fenêtre [{:.3}, {:.3}[ : {} PDUs, {} couches trouvées",
+                start,
+                end,
+                window_packets,
+                result.layers.len()
+            );
+            results.push(serde_json::json!({
+                "start": start,
+                "end": end,
+                "result": result,
+                "summary": {
+                    "total_packets": window_packets,
+                }
+            }));
         }
+        output["windows"] = serde_json::Value::Array(results);
+    } else if args.cluster {
+        let config = ClusterConfig::default()
+            .with_threshold(args.cluster_threshold)
+            .with_min_cluster_size(args.min_cluster_size);
+        let mut clustered = engine.infer_by_cluster(&corpus, &registry, &config);
+        for (n, cluster) in clustered.clusters.iter_mut().enumerate() {
+            if let Some(addresses) = &addresses {
+                cluster.result.annotate_addresses(addresses);
+            }
+            info!(
+                "Groupe {}: {} PDUs, {} couches trouvées",
+                n,
+                cluster.indices.len(),
+                cluster.result.layers.len()
+            );
+        }
+        output["clusters"] = serde_json::to_value(&clustered)?;
+    } else if args.consensus {
+        let mut consensus = engine.infer_consensus(&corpus, &registry, args.min_agreement);
+        if consensus.flows.is_empty() {
+            anyhow::bail!("Aucun flow à comparer : utiliser --per-flow ou --tcp");
+        }
+        for flow in &mut consensus.flows {
+            if let Some(addresses) = &addresses {
+                flow.result.annotate_addresses(addresses);
+            }
+        }
+        for (depth, layer) in consensus.consensus.iter().enumerate() {
+            info!(
+                "Consensus couche {}: {} retenue par {}/{} flows ({:.0}%)",
+                depth,
+                layer.hypothesis.name(),
+                layer.agreeing_flows,
+                layer.candidate_flows,
+                layer.agreement * 100.0
+            );
+        }
+        info!(
+            "{} flow(s) inférés, {} trop courts écartés",
+            consensus.flows.len(),
+            consensus.skipped_flows
+        );
+        output["consensus"] = serde_json::to_value(&consensus)?;
+    } else if args.per_direction {
+        let mut directional = engine.infer_by_direction(&corpus, &registry);
+        for stack in &mut directional.stacks {
+            if let Some(addresses) = &addresses {
+                stack.result.annotate_addresses(addresses);
+            }
+            info!(
+                "Direction {:?}: {} PDUs, {} couches trouvées",
+                stack.direction,
+                stack.result.corpus.len(),
+                stack.result.layers.len()
+            );
+        }
+        if directional.stacks.is_empty() {
+            anyhow::bail!("Aucune PDU de direction connue : utiliser --tcp ou --bidirectional");
+        }
+        info!(
+            "{} couche(s) commune(s) aux directions, piles {}",
+            directional.summary.shared_layers,
+            if directional.summary.symmetric { "identiques" } else { "différentes" }
+        );
+        output["directions"] = serde_json::to_value(&directional)?;
+    } else {
+        let project = match &args.project {
+            Some(path) => Some(open_project(Path::new(path), &corpus, &engine)?),
+            None => None,
+        };
+        let mut result = engine.infer(corpus, &registry);
+        if let Some(addresses) = &addresses {
+            result.annotate_addresses(addresses);
+        }
+        info!("Inférence terminée: {} couches trouvées", result.layers.len());
+        if result.budget_exhausted {
+            info!("Budget épuisé : l'inférence s'est arrêtée aux couches déjà établies");
+        }
+        if let Some(original) = result.sampled_from {
+            info!("Budget mémoire: inférence sur {} PDUs échantillonnées parmi {}", result.corpus.len(), original);
+        }
+        for (idx, layer) in result.layers.iter().enumerate() {
+            if let Some(confidence) = layer.confidence.as_ref().filter(|c| c.ambiguous) {
+                info!(
+                    "Couche {} ambiguë: {} concurrencée par {}",
+                    idx,
+                    layer.hypothesis.name(),
+                    confidence.rivals.iter().map(|h| h.name()).collect::<Vec<_>>().join(", ")
+                );
+            }
+        }
+        if let Some(machine) = &result.state_machine {
+            info!(
+                "Automate: {} types de messages, {} transitions sur {} flow(s)",
+                machine.states.len(),
+                machine.transitions.len(),
+                machine.flows
+            );
+        }
+        output["result"] = serde_json::to_value(&result)?;
+        if let Some(path) = &args.describe {
+            let description = FormatDescription::from_result(&result);
+            fs::write(path, serde_json::to_string_pretty(&description)?)
+                .with_context(|| format!("Échec de l'écriture de la description: {}", path))?;
+            info!("Description du format écrite dans: {}", path);
+        }
+        if let Some(path) = &args.dissector {
+            let description = FormatDescription::from_result(&result);
+            let source = protocol_infer_core::dissector::c_dissector(&description, &args.dissector_name);
+            fs::write(path, source).with_context(|| format!("Échec de l'écriture du dissecteur: {}", path))?;
+            info!("Dissecteur Wireshark écrit dans: {}", path);
+        }
+        if args.dot.is_some() || args.mermaid.is_some() {
+            let description = FormatDescription::from_result(&result);
+            let diagrams = [
+                (&args.dot, protocol_infer_core::diagram::graphviz as fn(&FormatDescription) -> String),
+                (&args.mermaid, protocol_infer_core::diagram::mermaid),
+            ];
+            for (path, render) in diagrams {
+                if let Some(path) = path {
+                    fs::write(path, render(&description))
+                        .with_context(|| format!("Échec de l'écriture du diagramme: {}", path))?;
+                    info!("Diagramme des couches écrit dans: {}", path);
+                }
+            }
+        }
+        if let Some(path) = &args.proto {
+            match result.proto_file() {
+                Some(proto) => {
+                    fs::write(path, proto).with_context(|| format!("Échec de l'écriture du schéma: {}", path))?;
+                    info!("Schéma protobuf écrit dans: {}", path);
+                }
+                None => info!("Aucune couche protobuf : pas de schéma .proto"),
+            }
+        }
+        if let Some(path) = &args.csv {
+            match FieldTable::from_result(&result, args.csv_layer) {
+                Some(table) => {
+                    fs::write(path, table.to_csv())
+                        .with_context(|| format!("Échec de l'écriture de la table des champs: {}", path))?;
+                    info!("Table des champs ({} messages) écrite dans: {}", table.rows.len(), path);
+                }
+                None => info!("Couche {} absente : pas de table des champs", args.csv_layer),
+            }
+        }
+        if let Some(path) = &args.report {
+            fs::write(path, protocol_infer_core::report::markdown(&result))
+                .with_context(|| format!("Échec de l'écriture du rapport: {}", path))?;
+            info!("Rapport Markdown écrit dans: {}", path);
+        }
+        if let Some(path) = &args.html {
+            fs::write(path, protocol_infer_core::report::html(&result))
+                .with_context(|| format!("Échec de l'écriture du rapport: {}", path))?;
+            info!("Rapport HTML écrit dans: {}", path);
+        }
+        if let Some(path) = &args.save_model {
+            fs::write(path, ModelSpec::from_result(&result).to_toml())
+                .with_context(|| format!("Échec de l'écriture du modèle: {}", path))?;
+            info!("Modèle écrit dans: {}", path);
+        }
+        if let Some(dir) = &args.export_sdus {
+            export_sdus(&result, Path::new(dir), args.sdu_format)?;
+        }
+        if let (Some(path), Some(project)) = (&args.project, project) {
+            project
+                .with_result(result)
+                .save(path)
+                .with_context(|| format!("Échec de l'écriture du projet: {}", path))?;
+            info!("Projet écrit dans: {}", path);
+        }
+    }
+    if engine.is_cancelled() {
+        info!("Inférence interrompue : les couches établies avant l'interruption sont écrites");
+        output["cancelled"] = serde_json::Value::Bool(true);
+    }
+    output["summary"] = serde_json::json!({
+        "total_packets": total_packets,
     });
+    if let Some(path) = &args.checkpoint {
+        let checkpoint = engine.checkpoint();
+        checkpoint
+            .save(path)
+            .with_context(|| format!("Échec de l'écriture du point de reprise: {}", path))?;
+        info!("Point de reprise: {} hypothèses scorées sauvegardées dans {}", checkpoint.len(), path);
+    }
+
+    fs::write(out, serde_json::to_string_pretty(&output)?)
+        .with_context(|| format!("Échec de l'écriture du fichier: {}", out))?;
+
+    info!("Résultats sauvegardés dans: {}", out);
 
+    Ok(())
+}
+
+/// Corpus d'un répertoire de messages, d'un fichier texte hexadécimal ou d'un corpus JSONL
+fn load_message_files(
+    input_dir: &Option<String>,
+    hex_file: &Option<String>,
+    corpus: &Option<String>,
+) -> Result<Corpus> {
+    if let Some(dir) = input_dir {
+        info!("Chargement des messages du répertoire: {}", dir);
+        Corpus::from_dir(dir).with_context(|| format!("Échec du chargement du répertoire: {}", dir))
+    } else if let Some(path) = hex_file {
+        info!("Chargement des messages texte: {}", path);
+        Corpus::from_hex_file(path).with_context(|| format!("Échec du chargement du fichier texte: {}", path))
+    } else if let Some(path) = corpus {
+        info!("Chargement du corpus JSONL: {}", path);
+        Corpus::from_jsonl_file(path).with_context(|| format!("Échec du chargement du corpus: {}", path))
+    } else {
+        anyhow::bail!("Aucune source: utiliser --pcap, --input-dir, --hex-file ou --corpus");
+    }
+}
+
+/// Sous-commande `parse` : applique le modèle à la capture et écrit le bilan du décodage
+fn run_parse(args: &ParseArgs) -> Result<()> {
+    let model =
+        ModelSpec::load(&args.model).with_context(|| format!("Échec de la lecture du modèle: {}", args.model))?;
+    let corpus = if args.pcap.is_empty() {
+        load_message_files(&args.input_dir, &args.hex_file, &args.corpus)?
+    } else {
+        let paths = expand_pcap_paths(&args.pcap)?;
+        info!("Chargement des fichiers PCAP: {}", paths.join(", "));
+        let options = pcap::PcapOptions::new();
+        let flows = match args.tcp {
+            Some(mode) => pcap::parse_pcap_tcp_files(&paths, mode.into(), &options),
+            None => pcap::parse_pcap_files(&paths, &options).map(|flow| vec![flow]),
+        }
+        .with_context(|| format!("Échec du parsing PCAP: {}", paths.join(", ")))?;
+        Corpus::from_flows(&flows)
+    };
+    info!("Corpus créé: {} PDUs, {} octets", corpus.len(), corpus.total_bytes());
+
+    let source = corpus.meta.source.clone();
+    let result = model.apply(corpus, &plugins::create_default_registry());
+    let report = ParseReport::new(&model, &result);
+    for layer in &report.layers {
+        info!(
+            "Couche {}: {} sur {} PDUs, PSR {:.1}% ({} en échec)",
+            layer.depth,
+            layer.hypothesis.name(),
+            layer.pdu_count,
+            layer.parse_success_ratio * 100.0,
+            layer.failed_pdus.len()
+        );
+    }
+    if report.missing_layers > 0 {
+        info!("{} couche(s) du modèle non appliquée(s)", report.missing_layers);
+    }
+
+    let output = serde_json::json!({ "source": source, "model": args.model, "report": report });
     fs::write(&args.out, serde_json::to_string_pretty(&output)?)
         .with_context(|| format!("Échec de l'écriture du fichier: {}", args.out))?;
+    info!("Bilan du décodage sauvegardé dans: {}", args.out);
+    Ok(())
+}
 
-    info!("Résultats sauvegardés dans: {}", args.out);
+/// Résultat d'inférence d'un fichier : sortie de la CLI (clé `result`), projet `.aire`, ou
+/// résultat sérialisé seul
+fn load_result(path: &str) -> Result<InferenceResult> {
+    let text = fs::read_to_string(path).with_context(|| format!("Échec de la lecture du fichier: {}", path))?;
+    let mut document: serde_json::Value =
+        serde_json::from_str(&text).with_context(|| format!("JSON invalide: {}", path))?;
+    let result = match document.get_mut("result") {
+        Some(result) if result.is_null() => anyhow::bail!("{}: projet sans résultat", path),
+        Some(result) => result.take(),
+        None => document,
+    };
+    serde_json::from_value(result).with_context(|| {
+        format!("{}: pas de résultat d'inférence unique (sortie --window, --cluster ou --consensus ?)", path)
+    })
+}
 
+/// Sous-commande `diff` : journalise les couches qui ont changé et écrit les différences
+fn run_diff(args: &DiffArgs) -> Result<()> {
+    let old = load_result(&args.old)?;
+    let new = load_result(&args.new)?;
+    let diff = ResultDiff::new(&old, &new);
+    if diff.is_empty() {
+        info!("Aucune différence entre {} et {}", args.old, args.new);
+    }
+    for layer in diff.changed_layers() {
+        let rule = |rule: &Option<FramingRule>| rule.as_ref().map_or("-".to_string(), |r| r.rule.clone());
+        info!("Couche {}: {:?}, {} → {}", layer.depth, layer.change, rule(&layer.old), rule(&layer.new));
+        for delta in &layer.parameters {
+            let value = |v: &Option<serde_json::Value>| v.as_ref().map_or("-".to_string(), |v| v.to_string());
+            info!("  {}: {} → {}", delta.name, value(&delta.old), value(&delta.new));
+        }
+        let fields = |fields: &[FieldDescription]| {
+            fields.iter().map(|f| format!("{:?}@{}", f.role, f.offset)).collect::<Vec<_>>().join(", ")
+        };
+        if !layer.added_fields.is_empty() {
+            info!("  champs ajoutés: {}", fields(&layer.added_fields));
+        }
+        if !layer.removed_fields.is_empty() {
+            info!("  champs retirés: {}", fields(&layer.removed_fields));
+        }
+        if !layer.changed_fields.is_empty() {
+            let changed: Vec<_> = layer.changed_fields.iter().map(|c| c.old.clone()).collect();
+            info!("  champs modifiés: {}", fields(&changed));
+        }
+        let tags = |tags: &[TlvTagStats]| tags.iter().map(|t| t.tag_hex()).collect::<Vec<_>>().join(", ");
+        if !layer.added_tags.is_empty() {
+            info!("  tags TLV nouveaux: {}", tags(&layer.added_tags));
+        }
+        if !layer.removed_tags.is_empty() {
+            info!("  tags TLV disparus: {}", tags(&layer.removed_tags));
+        }
+        if !layer.added_branches.is_empty() || !layer.removed_branches.is_empty() {
+            info!("  branches: +{:?} -{:?}", layer.added_branches, layer.removed_branches);
+        }
+    }
+    if let Some(path) = &args.out {
+        fs::write(path, serde_json::to_string_pretty(&diff)?)
+            .with_context(|| format!("Échec de l'écriture du fichier: {}", path))?;
+        info!("Différences sauvegardées dans: {}", path);
+    }
     Ok(())
 }
 
+/// Journalise l'avancement de chaque couche par quart des hypothèses évaluées
+#[derive(Default)]
+struct LogProgress {
+    /// Profondeur et dernier quart journalisés
+    last: Mutex<(usize, usize)>,
+}
+
+impl ProgressSink for LogProgress {
+    fn report(&self, progress: &Progress) {
+        let quarter = progress.evaluated * 4 / progress.total.max(1);
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        if *last == (progress.depth, quarter) {
+            return;
+        }
+        *last = (progress.depth, quarter);
+        let best = progress
+            .best
+            .as_ref()
+            .map(|(h, bits)| format!(", meilleure: {} ({:.1} bits)", h.name(), bits))
+            .unwrap_or_default();
+        info!(
+            "Couche {}: {}/{} hypothèses évaluées{}",
+            progress.depth, progress.evaluated, progress.total, best
+        );
+    }
+}
+
+/// Projet du corpus analysé, qui reprend les annotations et notes du projet `path` s'il
+/// existe et porte sur le même corpus
+fn open_project(path: &Path, corpus: &Corpus, engine: &InferenceEngine) -> Result<Project> {
+    let mut project = Project::new(corpus, engine);
+    if path.exists() {
+        let previous =
+            Project::load(path).with_context(|| format!("Échec de la lecture du projet: {}", path.display()))?;
+        if previous.matches(corpus) {
+            info!("Projet {}: {} annotations reprises", path.display(), previous.annotations.len());
+            project.annotations = previous.annotations;
+            project.notes = previous.notes;
+        } else {
+            info!("Projet {}: autre corpus, annotations abandonnées", path.display());
+        }
+    }
+    Ok(project)
+}
+
+/// Écrit le corpus de SDUs de chaque couche qui en a extrait
+fn export_sdus(result: &protocol_infer_core::InferenceResult, dir: &Path, format: SduFormat) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("Échec de la création du répertoire: {}", dir.display()))?;
+    for (depth, layer) in result.layers.iter().enumerate() {
+        let Some(sdus) = &layer.sdu_corpus else {
+            continue;
+        };
+        let path = match format {
+            SduFormat::Raw => {
+                let path = dir.join(format!("layer{}", depth));
+                sdus.write_dir(&path)
+                    .with_context(|| format!("Échec de l'écriture des SDUs: {}", path.display()))?;
+                path
+            }
+            SduFormat::Pcap => {
+                let path = dir.join(format!("layer{}.pcap", depth));
+                fs::write(&path, pcap::corpus_to_pcap(sdus))
+                    .with_context(|| format!("Échec de l'écriture des SDUs: {}", path.display()))?;
+                path
+            }
+        };
+        info!("Couche {}: {} SDUs écrits dans {}", depth, sdus.len(), path.display());
+    }
+    Ok(())
+}
+
+/// Au premier Ctrl-C, l'inférence s'arrête et les couches déjà établies sont écrites ;
+/// un second Ctrl-C termine immédiatement le processus
+#[cfg(unix)]
+fn cancel_on_interrupt(token: protocol_infer_core::CancellationToken) -> Result<()> {
+    // SAFETY : le gestionnaire ne fait qu'une écriture atomique ou un `_exit`, tous deux
+    // utilisables dans un gestionnaire de signal
+    unsafe {
+        signal_hook_registry::register(libc::SIGINT, move || {
+            if token.is_cancelled() {
+                libc::_exit(130);
+            }
+            token.cancel();
+        })
+    }
+    .context("Échec de l'installation du gestionnaire de Ctrl-C")?;
+    Ok(())
+}
+
+/// Charge les flows des fichiers PCAP (UDP, ou TCP réassemblé) ; les flows de même
+/// 5-tuple présents dans plusieurs fichiers sont fusionnés
+fn load_pcap_flows(paths: &[String], args: &Args) -> Result<Vec<Flow>> {
+    info!("Chargement des fichiers PCAP: {}", paths.join(", "));
+    let options = pcap_options(args);
+    let flows = match args.tcp {
+        Some(mode) => pcap::parse_pcap_tcp_files(paths, mode.into(), &options)
+            .with_context(|| format!("Échec du parsing PCAP: {}", paths.join(", ")))?,
+        None if args.per_flow => pcap::parse_pcap_files_flows(paths, &options)
+            .with_context(|| format!("Échec du parsing PCAP: {}", paths.join(", ")))?,
+        None => vec![pcap::parse_pcap_files(paths, &options)
+            .with_context(|| format!("Échec du parsing PCAP: {}", paths.join(", ")))?],
+    };
+
+    let total_packets: usize = flows.iter().map(|f| f.datagrams.len()).sum();
+    info!("{} PDUs trouvées dans {} flow(s)", total_packets, flows.len());
+
+    Ok(flows)
+}
+
+/// Options d'extraction des paquets PCAP issues de la ligne de commande
+fn pcap_options(args: &Args) -> pcap::PcapOptions {
+    let mut options = pcap::PcapOptions::new()
+        .with_tunnel_decapsulation(args.decap_tunnels)
+        .with_direction_pairing(args.bidirectional);
+    if let Some(offset) = args.l2_offset {
+        options = options.with_link_offset(offset);
+    }
+    for &(linktype, offset) in &args.linktype_offset {
+        options = options.with_linktype_offset(linktype, offset);
+    }
+    let mut filter = pcap::FlowFilter::new().with_min_packets(args.min_packets);
+    if let Some(ip) = args.src_ip {
+        filter = filter.with_src_ip(ip);
+    }
+    if let Some(ip) = args.dst_ip {
+        filter = filter.with_dst_ip(ip);
+    }
+    if let Some(port) = args.src_port {
+        filter = filter.with_src_port(port);
+    }
+    if let Some(port) = args.dst_port {
+        filter = filter.with_dst_port(port);
+    }
+    options.with_flow_filter(filter)
+}
+
+/// Coefficients du scoreur : section `[scorer]` de --config (valeurs par défaut sans
+/// configuration) ou fichier --scorer-config, puis options --scorer
+fn scorer_config(args: &Args, config: &Config) -> Result<plugins::MdlScorerConfig> {
+    let mut config = match &args.scorer_config {
+        Some(path) => {
+            let text = fs::read_to_string(path)
+                .with_context(|| format!("Échec de la lecture de la configuration du scoreur: {}", path))?;
+            serde_json::from_str(&text)
+                .with_context(|| format!("Configuration du scoreur invalide: {}", path))?
+        }
+        None => config.scorer.clone(),
+    };
+    for (key, value) in &args.scorer_params {
+        config.set_str(key, value).map_err(anyhow::Error::msg)?;
+    }
+    Ok(config)
+}
+
+/// Parse un couple `NOM=VALEUR` de l'option --scorer
+fn parse_scorer_param(value: &str) -> std::result::Result<(String, String), String> {
+    let (key, param) = value
+        .split_once('=')
+        .ok_or_else(|| format!("format attendu NOM=VALEUR: {}", value))?;
+    Ok((key.trim().to_string(), param.trim().to_string()))
+}
+
+/// Parse une borne temporelle : `+N` relatif à la première PDU, sinon horodatage absolu
+fn parse_time_bound(value: &str) -> std::result::Result<TimeBound, String> {
+    let parse = |v: &str| v.trim().parse::<f64>().map_err(|e| format!("horodatage invalide: {}", e));
+    match value.strip_prefix('+') {
+        Some(offset) => parse(offset).map(TimeBound::Relative),
+        None => parse(value).map(TimeBound::Absolute),
+    }
+}
+
+/// Parse un couple `LINKTYPE=OCTETS` de l'option --linktype-offset
+fn parse_linktype_offset(value: &str) -> std::result::Result<(u32, usize), String> {
+    let (linktype, offset) = value
+        .split_once('=')
+        .ok_or_else(|| format!("format attendu LINKTYPE=OCTETS: {}", value))?;
+    let linktype = linktype.trim().parse().map_err(|e| format!("type de lien invalide: {}", e))?;
+    let offset = offset.trim().parse().map_err(|e| format!("offset invalide: {}", e))?;
+    Ok((linktype, offset))
+}
+
+/// Développe les jokers (`*`, `?`) du nom de fichier de chaque motif `--pcap`
+fn expand_pcap_paths(patterns: &[String]) -> Result<Vec<String>> {
+    let mut paths = Vec::new();
+    for pattern in patterns {
+        let path = Path::new(pattern);
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if !name.contains(['*', '?']) {
+            paths.push(pattern.clone());
+            continue;
+        }
+
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let mut matches: Vec<String> = fs::read_dir(dir)
+            .with_context(|| format!("Échec de la lecture du répertoire: {}", dir.display()))?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|file| wildcard_match(name.as_bytes(), file.as_bytes()))
+            })
+            .map(|entry| entry.path().to_string_lossy().into_owned())
+            .collect();
+        if matches.is_empty() {
+            anyhow::bail!("Aucun fichier ne correspond au motif: {}", pattern);
+        }
+        matches.sort();
+        paths.extend(matches);
+    }
+    Ok(paths)
+}
+
+/// Correspondance d'un nom de fichier avec un motif `*` / `?`
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            wildcard_match(&pattern[1..], name)
+                || (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => wildcard_match(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => wildcard_match(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
@@ -0,0 +1,138 @@
+use crate::corpus::{Corpus, Flow};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Nombre minimal de PDUs portant une adresse de la capture à une même position
+const MIN_ADDRESS_MATCHES: usize = 2;
+/// Nombre d'adresses conservées en exemple
+const ADDRESS_SAMPLES: usize = 3;
+
+/// Adresses IP et MAC vues dans les en-têtes de la capture
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureAddresses {
+    pub ipv4: BTreeSet<[u8; 4]>,
+    pub ipv6: BTreeSet<[u8; 16]>,
+    pub macs: BTreeSet<[u8; 6]>,
+}
+
+impl CaptureAddresses {
+    /// Adresses des extrémités des flows (les flows fusionnés, sans adresse, sont ignorés)
+    pub fn from_flows(flows: &[Flow]) -> Self {
+        let mut addresses = Self::default();
+        for flow in flows {
+            for ip in [&flow.src_ip, &flow.dst_ip] {
+                if let Ok(ip) = ip.parse() {
+                    addresses.insert_ip(ip);
+                }
+            }
+        }
+        addresses
+    }
+
+    /// Ajoute une adresse IP ; les adresses non spécifiées et de diffusion, qui se confondent
+    /// avec du remplissage, sont écartées
+    pub fn insert_ip(&mut self, ip: IpAddr) {
+        match ip {
+            IpAddr::V4(ip) if !ip.is_unspecified() && !ip.is_broadcast() => {
+                self.ipv4.insert(ip.octets());
+            }
+            IpAddr::V6(ip) if !ip.is_unspecified() => {
+                self.ipv6.insert(ip.octets());
+            }
+            _ => {}
+        }
+    }
+
+    /// Ajoute une adresse MAC ; les adresses nulle et de diffusion sont écartées
+    pub fn insert_mac(&mut self, mac: [u8; 6]) {
+        if mac != [0; 6] && mac != [0xff; 6] {
+            self.macs.insert(mac);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ipv4.is_empty() && self.ipv6.is_empty() && self.macs.is_empty()
+    }
+
+    /// Adresses de la capture lues au début de `data`, selon leur nature
+    fn lookup(&self, data: &[u8]) -> Vec<(AddressKind, String)> {
+        let mut found = Vec::new();
+        if let Some(ip) = data.get(..4).and_then(|b| <[u8; 4]>::try_from(b).ok()) {
+            if self.ipv4.contains(&ip) {
+                found.push((AddressKind::Ipv4, Ipv4Addr::from(ip).to_string()));
+            }
+        }
+        if let Some(mac) = data.get(..6).and_then(|b| <[u8; 6]>::try_from(b).ok()) {
+            if self.macs.contains(&mac) {
+                let text = mac.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":");
+                found.push((AddressKind::Mac, text));
+            }
+        }
+        if let Some(ip) = data.get(..16).and_then(|b| <[u8; 16]>::try_from(b).ok()) {
+            if self.ipv6.contains(&ip) {
+                found.push((AddressKind::Ipv6, Ipv6Addr::from(ip).to_string()));
+            }
+        }
+        found
+    }
+}
+
+/// Nature d'une adresse embarquée
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum AddressKind {
+    Ipv4,
+    Mac,
+    Ipv6,
+}
+
+impl AddressKind {
+    /// Taille de l'adresse en octets
+    pub fn width(&self) -> usize {
+        match self {
+            AddressKind::Ipv4 => 4,
+            AddressKind::Mac => 6,
+            AddressKind::Ipv6 => 16,
+        }
+    }
+}
+
+/// Adresse de la capture recopiée dans les PDUs (protocole peu compatible avec le NAT,
+/// message d'établissement de session)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddressField {
+    pub offset: usize,
+    pub kind: AddressKind,
+    /// Nombre de PDUs portant une adresse de la capture à cette position
+    pub matches: usize,
+    /// Adresses rencontrées, en notation usuelle
+    pub samples: Vec<String>,
+}
+
+/// Positions des PDUs où se retrouvent, dans plusieurs PDUs, des adresses vues dans les
+/// en-têtes IP ou Ethernet de la capture
+pub fn address_fields(corpus: &Corpus, addresses: &CaptureAddresses) -> Vec<AddressField> {
+    if addresses.is_empty() {
+        return Vec::new();
+    }
+
+    let mut found: BTreeMap<(usize, AddressKind), (usize, Vec<String>)> = BTreeMap::new();
+    for pdu in &corpus.items {
+        let data = pdu.as_slice();
+        for offset in 0..data.len() {
+            for (kind, text) in addresses.lookup(&data[offset..]) {
+                let (matches, samples) = found.entry((offset, kind)).or_default();
+                *matches += 1;
+                if samples.len() < ADDRESS_SAMPLES && !samples.contains(&text) {
+                    samples.push(text);
+                }
+            }
+        }
+    }
+
+    found
+        .into_iter()
+        .filter(|(_, (matches, _))| *matches >= MIN_ADDRESS_MATCHES)
+        .map(|((offset, kind), (matches, samples))| AddressField { offset, kind, matches, samples })
+        .collect()
+}
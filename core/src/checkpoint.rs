@@ -0,0 +1,119 @@
+use crate::corpus::Corpus;
+use crate::hypothesis::Hypothesis;
+use crate::score::Score;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Scores des hypothèses explorées sur un corpus, à une profondeur donnée
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepthCheckpoint {
+    pub depth: usize,
+    /// Empreinte du corpus reçu par la couche (voir [`corpus_digest`])
+    pub corpus_digest: u64,
+    /// Nombre de plis de la validation croisée qui a produit les scores (0 : corpus entier)
+    pub folds: usize,
+    /// Hypothèses déjà scorées, par ordre croissant
+    pub scores: Vec<(Hypothesis, Score)>,
+}
+
+/// Point de reprise d'une inférence : les scores déjà calculés, profondeur par profondeur.
+///
+/// Une inférence reprise (`InferenceEngine::resume`) ne rescore pas ces hypothèses : elle
+/// les parse seulement, sans consommer le budget. Seuls les scores finis sont conservés, et
+/// ils ne valent que pour les mêmes scoreurs et la même configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub depths: Vec<DepthCheckpoint>,
+}
+
+impl Checkpoint {
+    /// Nombre d'hypothèses scorées, toutes profondeurs confondues
+    pub fn len(&self) -> usize {
+        self.depths.iter().map(|d| d.scores.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn to_json(&self) -> String {
+        // Les scores étant finis, la sérialisation ne peut pas échouer
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn from_json(text: &str) -> crate::Result<Self> {
+        serde_json::from_str(text).map_err(|e| crate::Error::ParseError(format!("checkpoint: {}", e)))
+    }
+
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> crate::Result<()> {
+        // Écrit à côté puis renomme : une interruption pendant l'écriture garde l'ancien point
+        let tmp = path.as_ref().with_extension("tmp");
+        std::fs::write(&tmp, self.to_json())?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> crate::Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// Clé d'un groupe de scores : profondeur, empreinte du corpus, plis
+pub(crate) type ExploredKey = (usize, u64, usize);
+
+/// Scores explorés pendant l'inférence, partagés entre les threads
+#[derive(Debug, Default)]
+pub(crate) struct ExploredScores(HashMap<ExploredKey, HashMap<Hypothesis, Score>>);
+
+impl ExploredScores {
+    pub(crate) fn from_checkpoint(checkpoint: Checkpoint) -> Self {
+        let mut explored = Self::default();
+        for depth in checkpoint.depths {
+            explored
+                .0
+                .entry((depth.depth, depth.corpus_digest, depth.folds))
+                .or_default()
+                .extend(depth.scores);
+        }
+        explored
+    }
+
+    pub(crate) fn get(&self, key: ExploredKey, h: &Hypothesis) -> Option<&Score> {
+        self.0.get(&key)?.get(h)
+    }
+
+    pub(crate) fn insert(&mut self, key: ExploredKey, h: Hypothesis, score: Score) {
+        if score.total_bits.is_finite() {
+            self.0.entry(key).or_default().insert(h, score);
+        }
+    }
+
+    pub(crate) fn to_checkpoint(&self) -> Checkpoint {
+        let mut depths: Vec<DepthCheckpoint> = self
+            .0
+            .iter()
+            .map(|(&(depth, corpus_digest, folds), scores)| {
+                let mut scores: Vec<(Hypothesis, Score)> =
+                    scores.iter().map(|(h, s)| (h.clone(), s.clone())).collect();
+                scores.sort_by(|a, b| a.0.cmp(&b.0));
+                DepthCheckpoint { depth, corpus_digest, folds, scores }
+            })
+            .collect();
+        depths.sort_by_key(|d| (d.depth, d.corpus_digest, d.folds));
+        Checkpoint { depths }
+    }
+}
+
+/// Empreinte FNV-1a du contenu du corpus (longueur et octets de chaque PDU), stable d'une
+/// exécution à l'autre
+pub fn corpus_digest(corpus: &Corpus) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let mut hash = OFFSET;
+    for pdu in &corpus.items {
+        for byte in (pdu.len() as u64).to_le_bytes().iter().chain(pdu.as_slice()) {
+            hash = (hash ^ *byte as u64).wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
@@ -0,0 +1,200 @@
+use crate::corpus::Corpus;
+use crate::hypothesis::{ChecksumAlgorithm, ChecksumLocation, Endianness};
+use serde::{Deserialize, Serialize};
+
+/// Nombre maximal de PDUs examinées pour la détection
+const DETECTION_SAMPLE: usize = 128;
+/// Offsets maximaux explorés pour les champs en en-tête et le début de la zone couverte
+const MAX_SCAN_OFFSET: usize = 8;
+
+impl ChecksumAlgorithm {
+    pub const ALL: [ChecksumAlgorithm; 9] = [
+        ChecksumAlgorithm::Sum8,
+        ChecksumAlgorithm::Sum16,
+        ChecksumAlgorithm::Xor8,
+        ChecksumAlgorithm::Internet16,
+        ChecksumAlgorithm::Crc8,
+        ChecksumAlgorithm::Crc16Ccitt,
+        ChecksumAlgorithm::Crc16Modbus,
+        ChecksumAlgorithm::Crc16X25,
+        ChecksumAlgorithm::Crc32,
+    ];
+
+    /// Taille du champ en octets
+    pub fn width(self) -> usize {
+        match self {
+            ChecksumAlgorithm::Sum8 | ChecksumAlgorithm::Xor8 | ChecksumAlgorithm::Crc8 => 1,
+            ChecksumAlgorithm::Sum16
+            | ChecksumAlgorithm::Internet16
+            | ChecksumAlgorithm::Crc16Ccitt
+            | ChecksumAlgorithm::Crc16Modbus
+            | ChecksumAlgorithm::Crc16X25 => 2,
+            ChecksumAlgorithm::Crc32 => 4,
+        }
+    }
+
+    /// Calcule le checksum de `data`
+    pub fn compute(self, data: &[u8]) -> u32 {
+        match self {
+            ChecksumAlgorithm::Sum8 => data.iter().fold(0u8, |acc, &b| acc.wrapping_add(b)) as u32,
+            ChecksumAlgorithm::Sum16 => data.iter().fold(0u16, |acc, &b| acc.wrapping_add(b as u16)) as u32,
+            ChecksumAlgorithm::Xor8 => data.iter().fold(0u8, |acc, &b| acc ^ b) as u32,
+            ChecksumAlgorithm::Internet16 => {
+                let mut sum: u32 = data
+                    .chunks(2)
+                    .map(|w| u16::from_be_bytes([w[0], *w.get(1).unwrap_or(&0)]) as u32)
+                    .sum();
+                while sum > 0xffff {
+                    sum = (sum & 0xffff) + (sum >> 16);
+                }
+                !sum & 0xffff
+            }
+            ChecksumAlgorithm::Crc8 => crc_msb(data, 8, 0x07, 0x00) as u32,
+            ChecksumAlgorithm::Crc16Ccitt => crc_msb(data, 16, 0x1021, 0xffff) as u32,
+            ChecksumAlgorithm::Crc16Modbus => crc_lsb(data, 0xa001, 0xffff) as u32,
+            ChecksumAlgorithm::Crc16X25 => !crc_lsb(data, 0x8408, 0xffff) as u32 & 0xffff,
+            ChecksumAlgorithm::Crc32 => !crc_lsb(data, 0xedb8_8320, 0xffff_ffff) as u32,
+        }
+    }
+
+    /// Lit la valeur du champ de checksum stockée dans `field`
+    pub fn read(self, field: &[u8], endian: Endianness) -> u32 {
+        field[..self.width()].iter().enumerate().fold(0u32, |acc, (i, &b)| match endian {
+            Endianness::Big => (acc << 8) | b as u32,
+            Endianness::Little => acc | (b as u32) << (8 * i),
+        })
+    }
+
+    /// Vérifie le checksum d'une PDU ; `None` si la PDU est trop courte pour l'emplacement
+    pub fn verify(self, data: &[u8], endian: Endianness, location: ChecksumLocation) -> Option<bool> {
+        let (field, covered) = checksum_ranges(data.len(), self.width(), location)?;
+        Some(self.read(&data[field], endian) == self.compute(&data[covered]))
+    }
+}
+
+/// Plages (champ, zone couverte) d'un checksum dans une PDU de `len` octets
+pub fn checksum_ranges(
+    len: usize,
+    width: usize,
+    location: ChecksumLocation,
+) -> Option<(std::ops::Range<usize>, std::ops::Range<usize>)> {
+    match location {
+        ChecksumLocation::Header { offset } => {
+            let field_end = offset + width;
+            (field_end < len).then_some((offset..field_end, field_end..len))
+        }
+        ChecksumLocation::Trailer { covered_start } => {
+            let field_start = len.checked_sub(width)?;
+            (covered_start < field_start).then_some((field_start..len, covered_start..field_start))
+        }
+    }
+}
+
+/// CRC non réfléchi (bit de poids fort en premier), sans XOR final
+fn crc_msb(data: &[u8], width: u32, poly: u64, init: u64) -> u64 {
+    let top = 1u64 << (width - 1);
+    let mask = (1u64 << width) - 1;
+    let mut crc = init;
+    for &byte in data {
+        crc ^= (byte as u64) << (width - 8);
+        for _ in 0..8 {
+            crc = if crc & top != 0 { (crc << 1) ^ poly } else { crc << 1 };
+        }
+        crc &= mask;
+    }
+    crc
+}
+
+/// CRC réfléchi (bit de poids faible en premier), sans XOR final
+fn crc_lsb(data: &[u8], poly: u64, init: u64) -> u64 {
+    let mut crc = init;
+    for &byte in data {
+        crc ^= byte as u64;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ poly } else { crc >> 1 };
+        }
+    }
+    crc
+}
+
+/// Checksum validé sur un corpus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChecksumMatch {
+    pub algorithm: ChecksumAlgorithm,
+    pub endian: Endianness,
+    pub location: ChecksumLocation,
+    /// Proportion des PDUs examinées dont le checksum est valide
+    pub ratio: f64,
+}
+
+/// Recherche les champs de checksum valides sur au moins `min_ratio` des PDUs.
+///
+/// Les emplacements explorés sont un champ en en-tête (offsets 0 à 8) couvrant la suite
+/// de la PDU, et un champ final couvrant la PDU à partir des offsets 0 à 8. Un champ de
+/// valeur constante n'est pas retenu : c'est un magic, pas un checksum.
+pub fn detect_checksums(corpus: &Corpus, min_ratio: f64) -> Vec<ChecksumMatch> {
+    let sample: Vec<&[u8]> = corpus.items.iter().take(DETECTION_SAMPLE).map(|p| p.as_slice()).collect();
+    if sample.len() < 2 {
+        return Vec::new();
+    }
+
+    let locations = (0..=MAX_SCAN_OFFSET)
+        .map(|offset| ChecksumLocation::Header { offset })
+        .chain((0..=MAX_SCAN_OFFSET).map(|covered_start| ChecksumLocation::Trailer { covered_start }));
+
+    let mut matches = Vec::new();
+    for location in locations {
+        for algorithm in ChecksumAlgorithm::ALL {
+            let endians: &[Endianness] = if algorithm.width() == 1 {
+                &[Endianness::Big]
+            } else {
+                &[Endianness::Big, Endianness::Little]
+            };
+            for &endian in endians {
+                if let Some(ratio) = validation_ratio(&sample, algorithm, endian, location, min_ratio) {
+                    matches.push(ChecksumMatch {
+                        algorithm,
+                        endian,
+                        location,
+                        ratio,
+                    });
+                }
+            }
+        }
+    }
+    matches
+}
+
+/// Proportion de PDUs validées, ou `None` dès que `min_ratio` ne peut plus être atteint
+fn validation_ratio(
+    sample: &[&[u8]],
+    algorithm: ChecksumAlgorithm,
+    endian: Endianness,
+    location: ChecksumLocation,
+    min_ratio: f64,
+) -> Option<f64> {
+    let max_failures = ((1.0 - min_ratio) * sample.len() as f64).floor() as usize;
+    let mut failures = 0;
+    let mut first_value = None;
+    let mut varies = false;
+
+    for data in sample {
+        let valid = match checksum_ranges(data.len(), algorithm.width(), location) {
+            Some((field, _)) => {
+                let value = algorithm.read(&data[field], endian);
+                varies |= first_value.is_some_and(|v| v != value);
+                first_value.get_or_insert(value);
+                algorithm.verify(data, endian, location) == Some(true)
+            }
+            None => false,
+        };
+        if !valid {
+            failures += 1;
+            if failures > max_failures {
+                return None;
+            }
+        }
+    }
+
+    varies.then(|| (sample.len() - failures) as f64 / sample.len() as f64)
+}
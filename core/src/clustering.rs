@@ -0,0 +1,161 @@
+use crate::corpus::Corpus;
+use serde::{Deserialize, Serialize};
+
+/// Nombre d'octets de tête comparés entre deux PDUs
+const PREFIX_LEN: usize = 4;
+
+/// Paramètres du regroupement des PDUs par type de message
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClusterConfig {
+    /// Distance (0 à 1) au-delà de laquelle une PDU ouvre un nouveau groupe
+    pub threshold: f64,
+    /// Taille minimale d'un groupe ; les groupes plus petits rejoignent le groupe le plus proche
+    pub min_cluster_size: usize,
+    /// Poids de l'écart de longueur dans la distance
+    pub length_weight: f64,
+    /// Poids de l'écart entre histogrammes d'octets dans la distance
+    pub histogram_weight: f64,
+    /// Poids de l'écart entre préfixes dans la distance
+    pub prefix_weight: f64,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.35,
+            min_cluster_size: 4,
+            length_weight: 1.0,
+            histogram_weight: 1.0,
+            prefix_weight: 1.0,
+        }
+    }
+}
+
+impl ClusterConfig {
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn with_min_cluster_size(mut self, min_cluster_size: usize) -> Self {
+        self.min_cluster_size = min_cluster_size;
+        self
+    }
+
+    pub fn with_weights(mut self, length: f64, histogram: f64, prefix: f64) -> Self {
+        self.length_weight = length;
+        self.histogram_weight = histogram;
+        self.prefix_weight = prefix;
+        self
+    }
+}
+
+/// Caractéristiques d'une PDU utilisées pour le regroupement
+struct Features<'a> {
+    len: usize,
+    histogram: [f64; 256],
+    data: &'a [u8],
+}
+
+impl<'a> Features<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        let mut histogram = [0.0; 256];
+        for &b in data {
+            histogram[b as usize] += 1.0;
+        }
+        if !data.is_empty() {
+            histogram.iter_mut().for_each(|h| *h /= data.len() as f64);
+        }
+        Self {
+            len: data.len(),
+            histogram,
+            data,
+        }
+    }
+
+    /// Distance pondérée (0 à 1) : écart relatif de longueur, variation totale entre histogrammes
+    /// et proportion d'octets de tête différents (un octet absent compte comme différent)
+    fn distance(&self, other: &Features, config: &ClusterConfig) -> f64 {
+        let max_len = self.len.max(other.len);
+        let length = if max_len == 0 {
+            0.0
+        } else {
+            self.len.abs_diff(other.len) as f64 / max_len as f64
+        };
+        let histogram = self
+            .histogram
+            .iter()
+            .zip(other.histogram.iter())
+            .map(|(a, b)| (a - b).abs())
+            .sum::<f64>()
+            / 2.0;
+        let compared = max_len.min(PREFIX_LEN);
+        let prefix = if compared == 0 {
+            0.0
+        } else {
+            (0..compared)
+                .filter(|&i| self.data.get(i) != other.data.get(i))
+                .count() as f64
+                / compared as f64
+        };
+        let total = config.length_weight + config.histogram_weight + config.prefix_weight;
+        if total <= 0.0 {
+            return 0.0;
+        }
+        (config.length_weight * length + config.histogram_weight * histogram + config.prefix_weight * prefix) / total
+    }
+}
+
+/// Regroupe les PDUs du corpus par similarité (longueur, histogramme d'octets, préfixe).
+///
+/// Première passe « leader » : chaque PDU rejoint le premier groupe dont le représentant est
+/// assez proche, sinon elle en ouvre un. Une seconde passe réaffecte chaque PDU au représentant
+/// le plus proche, puis les groupes trop petits sont fondus dans leur voisin le plus proche.
+/// Le résultat est déterministe : groupes ordonnés par première PDU, indices croissants.
+pub fn cluster_pdus(corpus: &Corpus, config: &ClusterConfig) -> Vec<Vec<usize>> {
+    let features: Vec<Features> = corpus.items.iter().map(|p| Features::new(p.as_slice())).collect();
+    if features.is_empty() {
+        return Vec::new();
+    }
+
+    let mut leaders: Vec<usize> = Vec::new();
+    for (i, f) in features.iter().enumerate() {
+        if !leaders.iter().any(|&l| features[l].distance(f, config) <= config.threshold) {
+            leaders.push(i);
+        }
+    }
+
+    let nearest = |i: usize, candidates: &[usize]| -> usize {
+        let mut best = 0;
+        let mut best_distance = f64::INFINITY;
+        for (c, &l) in candidates.iter().enumerate() {
+            let d = features[l].distance(&features[i], config);
+            if d < best_distance {
+                best = c;
+                best_distance = d;
+            }
+        }
+        best
+    };
+
+    let mut groups: Vec<Vec<usize>> = vec![Vec::new(); leaders.len()];
+    for i in 0..features.len() {
+        groups[nearest(i, &leaders)].push(i);
+    }
+
+    let (kept, small): (Vec<usize>, Vec<usize>) =
+        (0..groups.len()).partition(|&g| groups[g].len() >= config.min_cluster_size.max(1));
+    if kept.is_empty() {
+        return vec![(0..features.len()).collect()];
+    }
+    let kept_leaders: Vec<usize> = kept.iter().map(|&g| leaders[g]).collect();
+    let mut clusters: Vec<Vec<usize>> = kept.iter().map(|&g| groups[g].clone()).collect();
+    for g in small {
+        for &i in &groups[g] {
+            clusters[nearest(i, &kept_leaders)].push(i);
+        }
+    }
+    clusters.iter_mut().for_each(|c| c.sort_unstable());
+    clusters.sort_by_key(|c| c[0]);
+    clusters
+}
@@ -0,0 +1,106 @@
+use crate::corpus::Corpus;
+use crate::hypothesis::CompressionAlgorithm;
+use crate::measures::compressed_size;
+use std::io::Read;
+
+/// Signature d'un flux gzip (RFC 1952)
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// Signature d'une trame zstd (RFC 8878)
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+/// Taille maximale d'une charge utile décompressée (protection contre les bombes de décompression)
+const MAX_DECOMPRESSED: u64 = 16 * 1024 * 1024;
+/// Proportion minimale de PDUs reconnues pour retenir un algorithme
+const COMPRESSION_MIN_RATIO: f64 = 0.95;
+
+impl CompressionAlgorithm {
+    pub const ALL: [CompressionAlgorithm; 4] = [
+        CompressionAlgorithm::Gzip,
+        CompressionAlgorithm::Zlib,
+        CompressionAlgorithm::Zstd,
+        CompressionAlgorithm::Deflate,
+    ];
+
+    /// Indique si la charge utile porte la signature de l'algorithme (deflate brut n'en a pas)
+    pub fn has_signature(self, data: &[u8]) -> bool {
+        match self {
+            CompressionAlgorithm::Gzip => data.starts_with(&GZIP_MAGIC) && data.get(2) == Some(&8),
+            CompressionAlgorithm::Zlib => {
+                // En-tête RFC 1950 : méthode 8 (deflate), fenêtre <= 32 Ko, contrôle modulo 31
+                data.len() >= 2
+                    && data[0] & 0x0f == 8
+                    && data[0] >> 4 <= 7
+                    && (u16::from_be_bytes([data[0], data[1]])).is_multiple_of(31)
+            }
+            CompressionAlgorithm::Zstd => data.starts_with(&ZSTD_MAGIC),
+            CompressionAlgorithm::Deflate => false,
+        }
+    }
+
+    /// Indique si la charge utile est reconnue comme compressée par l'algorithme.
+    ///
+    /// Une signature ne suffit pas (un en-tête constant peut en avoir l'air) : le flux doit
+    /// aussi se décompresser.
+    fn recognizes(self, data: &[u8]) -> bool {
+        match self {
+            // Des données déjà compressées ne se recompressent pas : écarte les charges
+            // utiles redondantes qu'un décodeur deflate accepterait par hasard
+            CompressionAlgorithm::Deflate => {
+                self.decompress(data).is_some()
+                    && compressed_size(data).is_ok_and(|size| size * 10 >= data.len() * 9)
+            }
+            _ => self.has_signature(data) && self.decompress(data).is_some(),
+        }
+    }
+
+    /// Décompresse une charge utile ; `None` si le flux est invalide ou incomplet
+    pub fn decompress(self, data: &[u8]) -> Option<Vec<u8>> {
+        let mut out = Vec::new();
+        match self {
+            CompressionAlgorithm::Gzip => {
+                flate2::read::GzDecoder::new(data)
+                    .take(MAX_DECOMPRESSED)
+                    .read_to_end(&mut out)
+                    .ok()?;
+            }
+            CompressionAlgorithm::Zlib => {
+                flate2::read::ZlibDecoder::new(data)
+                    .take(MAX_DECOMPRESSED)
+                    .read_to_end(&mut out)
+                    .ok()?;
+            }
+            CompressionAlgorithm::Deflate => {
+                let mut decoder = flate2::read::DeflateDecoder::new(data);
+                (&mut decoder).take(MAX_DECOMPRESSED).read_to_end(&mut out).ok()?;
+                // Un flux deflate brut doit couvrir toute la charge utile et la faire grossir,
+                // sans quoi des octets aléatoires décodés par hasard seraient acceptés
+                if decoder.total_in() != data.len() as u64 || out.len() <= data.len() {
+                    return None;
+                }
+            }
+            CompressionAlgorithm::Zstd => {
+                zstd::stream::read::Decoder::new(data)
+                    .ok()?
+                    .take(MAX_DECOMPRESSED)
+                    .read_to_end(&mut out)
+                    .ok()?;
+            }
+        }
+        (!out.is_empty()).then_some(out)
+    }
+}
+
+/// Détecte un corpus de charges utiles compressées
+pub fn detect_compression(corpus: &Corpus) -> Option<CompressionAlgorithm> {
+    if corpus.is_empty() {
+        return None;
+    }
+
+    CompressionAlgorithm::ALL.into_iter().find(|algorithm| {
+        let matching = corpus
+            .items
+            .iter()
+            .filter(|pdu| algorithm.recognizes(pdu.as_slice()))
+            .count();
+        matching as f64 >= corpus.len() as f64 * COMPRESSION_MIN_RATIO
+    })
+}
@@ -0,0 +1,204 @@
+use crate::corpus::Corpus;
+use crate::hypothesis::Hypothesis;
+use crate::inference::InferenceEngine;
+use crate::plugin::{HypothesisGenerator, PluginRegistry};
+use crate::plugins::{create_registry, MdlScorerConfig};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Configuration d'une analyse, lue d'un fichier TOML : paramètres du moteur, coefficients du
+/// scoreur MDL, générateurs et parseurs actifs, et plages de recherche des générateurs.
+///
+/// ```toml
+/// [engine]
+/// max_depth = 4
+/// min_gain_epsilon = 64.0
+/// backtrack_budget = 2
+///
+/// [scorer]
+/// min_parse_success_ratio = 0.9
+///
+/// [generators]
+/// disabled = ["FragmentationGenerator", "ChecksumGenerator"]
+///
+/// [parsers]
+/// enabled = ["LengthPrefixParser", "FixedHeaderParser"]
+///
+/// [search.LengthPrefixGenerator]
+/// max_offset = 8
+/// ```
+///
+/// Toutes les sections sont facultatives ; une section absente garde les valeurs par défaut.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub engine: EngineConfig,
+    pub scorer: MdlScorerConfig,
+    pub generators: PluginSelection,
+    pub parsers: PluginSelection,
+    /// Plage de recherche de chaque générateur, par nom
+    pub search: BTreeMap<String, SearchRange>,
+}
+
+/// Paramètres du moteur qui remplacent ceux de [`InferenceEngine::new`], sous le nom des champs
+/// d'[`InferenceEngine`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EngineConfig {
+    pub max_depth: Option<usize>,
+    pub top_k: Option<usize>,
+    pub min_gain_epsilon: Option<f64>,
+    pub min_sdu_size: Option<usize>,
+    pub split_by_version: Option<bool>,
+    pub cross_validation_folds: Option<usize>,
+    pub ambiguity_epsilon: Option<f64>,
+    pub backtrack_budget: Option<usize>,
+    pub max_hypotheses: Option<usize>,
+    pub field_recursion: Option<usize>,
+    pub prefilter_sample: Option<usize>,
+    pub refinement_rounds: Option<usize>,
+    /// Octets (voir [`InferenceEngine::with_memory_budget`])
+    pub memory_budget: Option<usize>,
+    pub screening_margin: Option<f64>,
+}
+
+/// Plugins actifs, par nom (`LengthPrefixGenerator`, `TlvParser`...) : ceux de `enabled` s'il
+/// n'est pas vide (tous sinon), moins ceux de `disabled`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PluginSelection {
+    pub enabled: Vec<String>,
+    pub disabled: Vec<String>,
+}
+
+impl PluginSelection {
+    fn keeps(&self, name: &str) -> bool {
+        (self.enabled.is_empty() || self.enabled.iter().any(|n| n == name)) && !self.disabled.iter().any(|n| n == name)
+    }
+
+    fn names(&self) -> impl Iterator<Item = &String> {
+        self.enabled.iter().chain(&self.disabled)
+    }
+}
+
+/// Bornes des hypothèses proposées par un générateur : les champs `offset` et `*_offset`
+/// doivent tenir dans `min_offset..=max_offset`, les tailles `len` et `*_len` ne pas dépasser
+/// `max_len`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchRange {
+    pub min_offset: Option<usize>,
+    pub max_offset: Option<usize>,
+    pub max_len: Option<usize>,
+}
+
+impl SearchRange {
+    pub fn contains(&self, hypothesis: &Hypothesis) -> bool {
+        let Ok(serde_json::Value::Object(tagged)) = serde_json::to_value(hypothesis) else {
+            return true;
+        };
+        let Some(serde_json::Value::Object(fields)) = tagged.values().next() else {
+            return true;
+        };
+        fields.iter().all(|(key, value)| {
+            let Some(value) = value.as_u64().map(|v| v as usize) else {
+                return true;
+            };
+            if key == "offset" || key.ends_with("_offset") {
+                self.min_offset.is_none_or(|min| value >= min) && self.max_offset.is_none_or(|max| value <= max)
+            } else if key == "len" || key.ends_with("_len") {
+                self.max_len.is_none_or(|max| value <= max)
+            } else {
+                true
+            }
+        })
+    }
+}
+
+/// Générateur dont les hypothèses sont restreintes à une plage de recherche
+struct BoundedGenerator {
+    inner: Box<dyn HypothesisGenerator>,
+    range: SearchRange,
+}
+
+impl HypothesisGenerator for BoundedGenerator {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        self.inner.propose(corpus).into_iter().filter(|h| self.range.contains(h)).collect()
+    }
+}
+
+impl Config {
+    pub fn from_toml(text: &str) -> Result<Self> {
+        toml::from_str(text).map_err(|e| Error::ParseError(format!("config: {}", e)))
+    }
+
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// `engine` avec les paramètres de la section `[engine]`, et le modèle de compression du scoreur
+    pub fn engine(&self, engine: InferenceEngine) -> InferenceEngine {
+        let settings = &self.engine;
+        InferenceEngine {
+            max_depth: settings.max_depth.unwrap_or(engine.max_depth),
+            top_k: settings.top_k.unwrap_or(engine.top_k),
+            min_gain_epsilon: settings.min_gain_epsilon.unwrap_or(engine.min_gain_epsilon),
+            min_sdu_size: settings.min_sdu_size.unwrap_or(engine.min_sdu_size),
+            split_by_version: settings.split_by_version.unwrap_or(engine.split_by_version),
+            cross_validation_folds: settings.cross_validation_folds.unwrap_or(engine.cross_validation_folds),
+            ambiguity_epsilon: settings.ambiguity_epsilon.unwrap_or(engine.ambiguity_epsilon),
+            backtrack_budget: settings.backtrack_budget.unwrap_or(engine.backtrack_budget),
+            max_hypotheses: settings.max_hypotheses.or(engine.max_hypotheses),
+            field_recursion: settings.field_recursion.or(engine.field_recursion),
+            prefilter_sample: settings.prefilter_sample.unwrap_or(engine.prefilter_sample),
+            refinement_rounds: settings.refinement_rounds.unwrap_or(engine.refinement_rounds),
+            memory_budget: settings.memory_budget.or(engine.memory_budget),
+            screening_margin: settings.screening_margin.or(engine.screening_margin),
+            ..engine.with_compression(self.scorer.compression)
+        }
+    }
+
+    /// Registre par défaut avec le scoreur de la section `[scorer]`, réduit aux plugins actifs
+    /// et aux plages de recherche ; un nom de plugin inconnu est une erreur
+    pub fn registry(&self) -> Result<PluginRegistry> {
+        self.registry_from(create_registry(self.scorer.clone()))
+    }
+
+    /// Applique la sélection des plugins et les plages de recherche à `registry`
+    pub fn registry_from(&self, mut registry: PluginRegistry) -> Result<PluginRegistry> {
+        let generator_names: Vec<&str> = registry.generators().iter().map(|g| g.name()).collect();
+        let parser_names: Vec<&str> = registry.parsers().iter().map(|p| p.name()).collect();
+        let unknown = |names: &[&str], name: &String| !names.contains(&name.as_str());
+        if let Some(name) = self
+            .generators
+            .names()
+            .chain(self.search.keys())
+            .find(|name| unknown(&generator_names, name))
+        {
+            return Err(Error::Plugin(format!("unknown generator: {}", name)));
+        }
+        if let Some(name) = self.parsers.names().find(|name| unknown(&parser_names, name)) {
+            return Err(Error::Plugin(format!("unknown parser: {}", name)));
+        }
+
+        for generator in registry.take_generators() {
+            if !self.generators.keeps(generator.name()) {
+                continue;
+            }
+            match self.search.get(generator.name()) {
+                Some(range) => registry.register_generator(Box::new(BoundedGenerator {
+                    inner: generator,
+                    range: range.clone(),
+                })),
+                None => registry.register_generator(generator),
+            }
+        }
+        registry.retain_parsers(|parser| self.parsers.keeps(parser.name()));
+        Ok(registry)
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Représente un datagramme UDP avec ses métadonnées
@@ -8,6 +9,8 @@ pub struct UdpDatagram {
     pub flow_id: usize,
     pub direction: Direction,
     pub payload: Arc<[u8]>,
+    /// Payload incomplet : le paquet a été tronqué à la capture (snaplen)
+    pub truncated: bool,
 }
 
 // Implémentation manuelle de Serialize pour UdpDatagram
@@ -17,11 +20,12 @@ impl serde::Serialize for UdpDatagram {
         S: serde::Serializer,
     {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("UdpDatagram", 4)?;
+        let mut state = serializer.serialize_struct("UdpDatagram", 5)?;
         state.serialize_field("timestamp", &self.timestamp)?;
         state.serialize_field("flow_id", &self.flow_id)?;
         state.serialize_field("direction", &self.direction)?;
         state.serialize_field("payload", &self.payload.as_ref())?;
+        state.serialize_field("truncated", &self.truncated)?;
         state.end()
     }
 }
@@ -61,16 +65,71 @@ impl serde::Serialize for Flow {
     }
 }
 
+/// Étape de la provenance d'une PDU : PDU du corpus parent dont elle est issue et position de
+/// ses octets dans cette PDU, absente si les octets ont été transformés (décodage,
+/// décompression, retrait des échappements, réassemblage de fragments)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub pdu_index: usize,
+    pub offset: Option<usize>,
+}
+
 /// Référence vers une PDU (évite les copies)
 #[derive(Debug, Clone)]
 pub struct PduRef {
     pub data: Arc<[u8]>,
     pub range: std::ops::Range<usize>,
+    /// Horodatage du datagramme d'origine, si connu
+    pub timestamp: Option<f64>,
+    /// Direction du datagramme d'origine, si connue
+    pub direction: Option<Direction>,
+    /// Les données se poursuivaient au-delà de la fin de la PDU (capture tronquée)
+    pub truncated: bool,
+    /// Flow d'origine, si le corpus réunit plusieurs flows
+    pub flow_id: Option<usize>,
+    /// Étapes qui mènent du corpus d'origine à cette PDU (SDUs extraites, sous-ensembles
+    /// de PDUs...), la plus ancienne en tête ; vide pour une PDU du corpus d'origine
+    pub provenance: Vec<Provenance>,
 }
 
 impl PduRef {
     pub fn new(data: Arc<[u8]>, range: std::ops::Range<usize>) -> Self {
-        Self { data, range }
+        Self {
+            data,
+            range,
+            timestamp: None,
+            direction: None,
+            truncated: false,
+            flow_id: None,
+            provenance: Vec::new(),
+        }
+    }
+
+    /// Rattache la PDU à la PDU `pdu_index` (`parent`) du corpus parent, dont elle reprend les
+    /// octets à partir de `offset` (None : octets transformés)
+    pub fn derived_from(mut self, parent: &PduRef, pdu_index: usize, offset: Option<usize>) -> Self {
+        self.provenance = parent.provenance.clone();
+        self.provenance.push(Provenance { pdu_index, offset });
+        self
+    }
+
+    /// Associe l'horodatage et la direction du datagramme d'origine
+    pub fn with_origin(mut self, timestamp: Option<f64>, direction: Option<Direction>) -> Self {
+        self.timestamp = timestamp;
+        self.direction = direction;
+        self
+    }
+
+    /// Associe le flow d'origine
+    pub fn with_flow(mut self, flow_id: Option<usize>) -> Self {
+        self.flow_id = flow_id;
+        self
+    }
+
+    /// Marque la PDU comme tronquée à la capture
+    pub fn with_truncated(mut self, truncated: bool) -> Self {
+        self.truncated = truncated;
+        self
     }
 
     pub fn as_slice(&self) -> &[u8] {
@@ -86,8 +145,78 @@ impl PduRef {
     }
 }
 
+/// Décale `range` de la somme des positions des étapes `steps` ; None si une étape a transformé
+/// les octets
+fn shift_through(steps: &[Provenance], range: std::ops::Range<usize>) -> Option<std::ops::Range<usize>> {
+    let shift = steps.iter().map(|step| step.offset).sum::<Option<usize>>()?;
+    Some(range.start + shift..range.end + shift)
+}
+
+/// Ligne d'un export JSON Lines de corpus, et forme sérialisée d'une [`PduRef`] : les octets
+/// de la PDU seule, sans le buffer qu'elle partage avec ses voisines
+#[derive(Serialize, Deserialize)]
+struct JsonlRecord {
+    #[serde(default)]
+    timestamp: Option<f64>,
+    #[serde(default)]
+    direction: Option<Direction>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    flow_id: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    provenance: Vec<Provenance>,
+    payload: String,
+}
+
+impl From<&PduRef> for JsonlRecord {
+    fn from(pdu: &PduRef) -> Self {
+        Self {
+            timestamp: pdu.timestamp,
+            direction: pdu.direction,
+            truncated: pdu.truncated,
+            flow_id: pdu.flow_id,
+            provenance: pdu.provenance.clone(),
+            payload: crate::hexdump::to_hex(pdu.as_slice()),
+        }
+    }
+}
+
+impl TryFrom<JsonlRecord> for PduRef {
+    type Error = String;
+
+    fn try_from(record: JsonlRecord) -> std::result::Result<Self, String> {
+        let payload = crate::hexdump::parse_hex_string(&record.payload)?;
+        let len = payload.len();
+        let mut pdu = PduRef::new(Arc::from(payload), 0..len)
+            .with_origin(record.timestamp, record.direction)
+            .with_truncated(record.truncated)
+            .with_flow(record.flow_id);
+        pdu.provenance = record.provenance;
+        Ok(pdu)
+    }
+}
+
+impl Serialize for PduRef {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        JsonlRecord::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PduRef {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        PduRef::try_from(JsonlRecord::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Corpus de PDUs à analyser
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Corpus {
     pub items: Vec<PduRef>,
     pub meta: CorpusMeta,
@@ -99,6 +228,10 @@ pub struct CorpusMeta {
     pub total_bytes: usize,
     pub pdu_count: usize,
     pub flow_id: Option<usize>,
+    /// Nombre d'occurrences d'origine de chaque PDU après déduplication (aligné sur `items`) ;
+    /// absent si chaque PDU compte pour une occurrence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multiplicities: Option<Vec<usize>>,
 }
 
 impl Corpus {
@@ -109,7 +242,12 @@ impl Corpus {
     pub fn from_datagrams(datagrams: &[UdpDatagram], flow_id: Option<usize>) -> Self {
         let items: Vec<PduRef> = datagrams
             .iter()
-            .map(|d| PduRef::new(d.payload.clone(), 0..d.payload.len()))
+            .map(|d| {
+                PduRef::new(d.payload.clone(), 0..d.payload.len())
+                    .with_origin(Some(d.timestamp), Some(d.direction))
+                    .with_truncated(d.truncated)
+                    .with_flow(Some(d.flow_id))
+            })
             .collect();
 
         let total_bytes: usize = items.iter().map(|p| p.len()).sum();
@@ -119,11 +257,142 @@ impl Corpus {
             total_bytes,
             pdu_count: items.len(),
             flow_id,
+            multiplicities: None,
         };
 
         Self { items, meta }
     }
 
+    /// Construit un corpus à partir des datagrammes de plusieurs flows (ex: flux TCP réassemblés)
+    pub fn from_flows(flows: &[Flow]) -> Self {
+        let datagrams: Vec<UdpDatagram> = flows
+            .iter()
+            .flat_map(|f| f.datagrams.iter().cloned())
+            .collect();
+        let flow_id = match flows {
+            [single] => single.datagrams.first().map(|d| d.flow_id),
+            _ => None,
+        };
+        Self::from_datagrams(&datagrams, flow_id)
+    }
+
+    /// Construit un corpus à partir de messages déjà en mémoire
+    pub fn from_messages(messages: Vec<Vec<u8>>, source: &str) -> Self {
+        let items: Vec<PduRef> = messages
+            .into_iter()
+            .map(|m| {
+                let len = m.len();
+                PduRef::new(Arc::from(m), 0..len)
+            })
+            .collect();
+
+        let total_bytes: usize = items.iter().map(|p| p.len()).sum();
+
+        let meta = CorpusMeta {
+            source: source.to_string(),
+            total_bytes,
+            pdu_count: items.len(),
+            flow_id: None,
+            multiplicities: None,
+        };
+
+        Self { items, meta }
+    }
+
+    /// Construit un corpus à partir d'un texte (xxd, hexdump -C, `\x..`, hex par ligne)
+    pub fn from_hex_text(text: &str, source: &str) -> crate::Result<Self> {
+        let messages = crate::hexdump::parse_hex_text(text)?;
+        Ok(Self::from_messages(messages, source))
+    }
+
+    /// Construit un corpus à partir d'un fichier texte (voir [`Corpus::from_hex_text`])
+    pub fn from_hex_file<P: AsRef<std::path::Path>>(path: P) -> crate::Result<Self> {
+        let text = std::fs::read_to_string(path.as_ref())?;
+        Self::from_hex_text(&text, &path.as_ref().display().to_string())
+    }
+
+    /// Sérialise le corpus en JSON Lines : une PDU par ligne (horodatage, direction, payload hexa)
+    pub fn to_jsonl(&self) -> String {
+        let mut out = String::new();
+        for pdu in &self.items {
+            // La sérialisation d'une structure simple ne peut pas échouer
+            out.push_str(&serde_json::to_string(pdu).unwrap_or_default());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Construit un corpus à partir de JSON Lines (voir [`Corpus::to_jsonl`])
+    pub fn from_jsonl(text: &str, source: &str) -> crate::Result<Self> {
+        let mut items = Vec::new();
+        for (line_no, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let pdu: PduRef = serde_json::from_str(line)
+                .map_err(|e| crate::Error::ParseError(format!("line {}: {}", line_no + 1, e)))?;
+            items.push(pdu);
+        }
+
+        let total_bytes: usize = items.iter().map(|p| p.len()).sum();
+        let meta = CorpusMeta {
+            source: source.to_string(),
+            total_bytes,
+            pdu_count: items.len(),
+            flow_id: None,
+            multiplicities: None,
+        };
+
+        Ok(Self { items, meta })
+    }
+
+    /// Construit un corpus à partir d'un fichier JSON Lines
+    pub fn from_jsonl_file<P: AsRef<std::path::Path>>(path: P) -> crate::Result<Self> {
+        let text = std::fs::read_to_string(path.as_ref())?;
+        Self::from_jsonl(&text, &path.as_ref().display().to_string())
+    }
+
+    /// Construit un corpus à partir de fichiers binaires (un message par fichier)
+    pub fn from_files<P: AsRef<std::path::Path>>(paths: &[P]) -> crate::Result<Self> {
+        let messages = paths
+            .iter()
+            .map(std::fs::read)
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        let source = match paths {
+            [single] => single.as_ref().display().to_string(),
+            _ => format!("files_{}", paths.len()),
+        };
+
+        Ok(Self::from_messages(messages, &source))
+    }
+
+    /// Construit un corpus à partir des fichiers d'un répertoire (triés par nom, non récursif)
+    pub fn from_dir<P: AsRef<std::path::Path>>(dir: P) -> crate::Result<Self> {
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir.as_ref())? {
+            let path = entry?.path();
+            if path.is_file() {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut corpus = Self::from_files(&paths)?;
+        corpus.meta.source = dir.as_ref().display().to_string();
+        Ok(corpus)
+    }
+
+    /// Écrit chaque PDU dans son propre fichier (`000000.bin`, `000001.bin`...) du répertoire,
+    /// créé au besoin ; [`Corpus::from_dir`] relit les PDUs dans le même ordre
+    pub fn write_dir<P: AsRef<std::path::Path>>(&self, dir: P) -> crate::Result<()> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        for (index, pdu) in self.items.iter().enumerate() {
+            std::fs::write(dir.as_ref().join(format!("{:06}.bin", index)), pdu.as_slice())?;
+        }
+        Ok(())
+    }
+
     pub fn len(&self) -> usize {
         self.items.len()
     }
@@ -135,5 +404,307 @@ impl Corpus {
     pub fn total_bytes(&self) -> usize {
         self.meta.total_bytes
     }
+
+    /// Estimation de la mémoire occupée par le corpus : références et octets des PDUs (les
+    /// vues sur un même buffer sont comptées comme des copies)
+    pub fn memory_footprint(&self) -> usize {
+        self.items.len() * std::mem::size_of::<PduRef>() + self.total_bytes()
+    }
+
+    /// Horodatages extrêmes des PDUs datées
+    pub fn time_span(&self) -> Option<(f64, f64)> {
+        self.items
+            .iter()
+            .filter_map(|p| p.timestamp)
+            .fold(None, |span, ts| match span {
+                None => Some((ts, ts)),
+                Some((start, end)) => Some((start.min(ts), end.max(ts))),
+            })
+    }
+
+    /// Indice de la PDU d'origine dont proviennent les octets `range` de la PDU `index`, et leur
+    /// position dans celle-ci. Le corpus d'origine est celui dont les PDUs n'ont pas de
+    /// provenance (chargé d'une capture ou de fichiers) ; None si l'index est hors du corpus ou
+    /// si les octets ont été transformés en chemin.
+    pub fn resolve_to_origin(
+        &self,
+        index: usize,
+        range: std::ops::Range<usize>,
+    ) -> Option<(usize, std::ops::Range<usize>)> {
+        let pdu = self.items.get(index)?;
+        match pdu.provenance.first() {
+            None => Some((index, range)),
+            Some(root) => shift_through(&pdu.provenance, range).map(|range| (root.pdu_index, range)),
+        }
+    }
+
+    /// Indice dans ce corpus de la PDU dont `pdu` est issue (directement ou après plusieurs
+    /// extractions), et position des octets `range` de `pdu` dans celle-ci
+    pub fn locate(
+        &self,
+        pdu: &PduRef,
+        range: std::ops::Range<usize>,
+    ) -> Option<(usize, std::ops::Range<usize>)> {
+        // L'ancêtre est la PDU du corpus dont la provenance est exactement le début de celle de `pdu`
+        (0..pdu.provenance.len()).find_map(|depth| {
+            let step = pdu.provenance[depth];
+            let ancestor = self.items.get(step.pdu_index)?;
+            if ancestor.provenance.len() != depth || !pdu.provenance.starts_with(&ancestor.provenance) {
+                return None;
+            }
+            Some((step.pdu_index, shift_through(&pdu.provenance[depth..], range.clone())?))
+        })
+    }
+
+    /// Nombre d'occurrences d'origine de la PDU `index` (1 sans déduplication)
+    pub fn multiplicity(&self, index: usize) -> usize {
+        self.meta
+            .multiplicities
+            .as_ref()
+            .and_then(|m| m.get(index).copied())
+            .unwrap_or(1)
+    }
+
+    /// Supprime les PDUs au contenu identique en conservant la première occurrence.
+    ///
+    /// Le nombre d'occurrences de chaque PDU conservée est enregistré dans
+    /// `meta.multiplicities`.
+    pub fn dedup(&self) -> Self {
+        let mut first: HashMap<&[u8], usize> = HashMap::new();
+        let mut kept: Vec<usize> = Vec::new();
+        let mut counts: Vec<usize> = Vec::new();
+
+        for (index, item) in self.items.iter().enumerate() {
+            match first.get(item.as_slice()) {
+                Some(&slot) => counts[slot] += self.multiplicity(index),
+                None => {
+                    first.insert(item.as_slice(), kept.len());
+                    kept.push(index);
+                    counts.push(self.multiplicity(index));
+                }
+            }
+        }
+
+        let mut corpus = self.select(&kept, self.meta.source.clone());
+        corpus.meta.multiplicities = Some(counts);
+        corpus
+    }
+
+    /// Tire `n` PDUs sans remise, de façon reproductible pour une même `seed`.
+    ///
+    /// L'ordre d'origine des PDUs tirées est conservé.
+    pub fn sample(&self, n: usize, seed: u64) -> Self {
+        if n >= self.items.len() {
+            return self.clone();
+        }
+
+        let mut indices = self.shuffled_indices(n, seed);
+        indices.sort_unstable();
+
+        self.select(&indices, self.meta.source.clone())
+    }
+
+    /// Tire environ `n` PDUs en respectant la répartition des longueurs : chaque tranche de
+    /// longueurs (quatre par puissance de deux) fournit sa part des PDUs tirées, et au moins une
+    /// PDU, de sorte que les formats rares restent représentés.
+    ///
+    /// L'ordre d'origine des PDUs tirées est conservé.
+    pub fn stratified_sample(&self, n: usize, seed: u64) -> Self {
+        if n >= self.items.len() {
+            return self.clone();
+        }
+
+        let mut strata: std::collections::BTreeMap<usize, Vec<usize>> = std::collections::BTreeMap::new();
+        for (index, pdu) in self.items.iter().enumerate() {
+            strata.entry(length_stratum(pdu.len())).or_default().push(index);
+        }
+        let mut indices: Vec<usize> = strata
+            .into_iter()
+            .flat_map(|(stratum, members)| {
+                let share = (n * members.len()).div_ceil(self.items.len()).clamp(1, members.len());
+                shuffled_prefix(members.len(), share, seed ^ stratum as u64)
+                    .into_iter()
+                    .map(move |i| members[i])
+            })
+            .collect();
+        indices.sort_unstable();
+
+        self.select(&indices, self.meta.source.clone())
+    }
+
+    /// Répartit les PDUs au hasard (de façon reproductible) en `k` plis de tailles égales à
+    /// une PDU près, et renvoie pour chaque pli le couple (apprentissage, test) : les autres
+    /// plis réunis, et le pli lui-même.
+    pub fn folds(&self, k: usize, seed: u64) -> Vec<(Corpus, Corpus)> {
+        let shuffled = self.shuffled_indices(self.items.len(), seed);
+        (0..k)
+            .map(|fold| {
+                let (mut test, mut train): (Vec<_>, Vec<_>) =
+                    (0..shuffled.len()).partition(|position| position % k == fold);
+                for indices in [&mut test, &mut train] {
+                    for index in indices.iter_mut() {
+                        *index = shuffled[*index];
+                    }
+                    indices.sort_unstable();
+                }
+                (
+                    self.select(&train, format!("{}_train_{}", self.meta.source, fold)),
+                    self.select(&test, format!("{}_test_{}", self.meta.source, fold)),
+                )
+            })
+            .collect()
+    }
+
+    /// `n` premiers indices d'une permutation aléatoire des PDUs
+    fn shuffled_indices(&self, n: usize, seed: u64) -> Vec<usize> {
+        shuffled_prefix(self.items.len(), n, seed)
+    }
+
+    /// Restreint le corpus aux PDUs dont l'horodatage est dans `[from, to]`.
+    ///
+    /// Les PDUs sans horodatage sont écartées dès qu'une borne est donnée.
+    pub fn between(&self, from: Option<f64>, to: Option<f64>) -> Self {
+        if from.is_none() && to.is_none() {
+            return self.clone();
+        }
+        let indices: Vec<usize> = (0..self.items.len())
+            .filter(|&i| {
+                self.items[i]
+                    .timestamp
+                    .is_some_and(|ts| from.is_none_or(|f| ts >= f) && to.is_none_or(|t| ts <= t))
+            })
+            .collect();
+        self.select(&indices, self.meta.source.clone())
+    }
+
+    /// Découpe le corpus en fenêtres de `width` secondes à partir de la première PDU datée.
+    ///
+    /// Retourne `(début, fin, corpus)` pour chaque fenêtre non vide ; les PDUs sans
+    /// horodatage sont écartées.
+    pub fn split_windows(&self, width: f64) -> Vec<(f64, f64, Self)> {
+        let Some((start, _)) = self.time_span() else {
+            return Vec::new();
+        };
+        if width <= 0.0 {
+            return vec![(start, start, self.clone())];
+        }
+
+        let mut windows: std::collections::BTreeMap<u64, Vec<usize>> = std::collections::BTreeMap::new();
+        for (i, item) in self.items.iter().enumerate() {
+            if let Some(ts) = item.timestamp {
+                let index = ((ts - start) / width).floor() as u64;
+                windows.entry(index).or_default().push(i);
+            }
+        }
+
+        windows
+            .into_iter()
+            .map(|(index, indices)| {
+                let window_start = start + index as f64 * width;
+                let source = format!("{}_window{}", self.meta.source, index);
+                (window_start, window_start + width, self.select(&indices, source))
+            })
+            .collect()
+    }
+
+    /// PDUs du corpus suivies de celles de `other` (corpus enrichi au fil d'une capture)
+    pub fn concat(&self, other: &Corpus) -> Self {
+        let items: Vec<PduRef> = self.items.iter().chain(other.items.iter()).cloned().collect();
+        let multiplicities = (self.meta.multiplicities.is_some() || other.meta.multiplicities.is_some()).then(|| {
+            (0..self.len())
+                .map(|i| self.multiplicity(i))
+                .chain((0..other.len()).map(|i| other.multiplicity(i)))
+                .collect()
+        });
+        let meta = CorpusMeta {
+            source: self.meta.source.clone(),
+            total_bytes: items.iter().map(|p| p.len()).sum(),
+            pdu_count: items.len(),
+            flow_id: self.meta.flow_id.filter(|&id| other.is_empty() || other.meta.flow_id == Some(id)),
+            multiplicities,
+        };
+        Self { items, meta }
+    }
+
+    /// Corpus des plages `(index de PDU, plage dans la PDU)` : chaque élément est une vue sur
+    /// les données de sa PDU, dont il hérite l'origine, le flow et le nombre d'occurrences
+    pub(crate) fn sub_ranges(&self, ranges: &[(usize, std::ops::Range<usize>)], source: String) -> Self {
+        let items: Vec<PduRef> = ranges
+            .iter()
+            .map(|(index, range)| {
+                let pdu = &self.items[*index];
+                // Les plages sont relatives à la vue de la PDU, pas au buffer partagé
+                PduRef::new(pdu.data.clone(), pdu.range.start + range.start..pdu.range.start + range.end)
+                    .with_origin(pdu.timestamp, pdu.direction)
+                    .with_flow(pdu.flow_id)
+                    // Seule la plage qui atteint la fin d'une PDU tronquée l'est aussi
+                    .with_truncated(pdu.truncated && range.end >= pdu.len())
+                    .derived_from(pdu, *index, Some(range.start))
+            })
+            .collect();
+        let multiplicities = self
+            .meta
+            .multiplicities
+            .as_ref()
+            .map(|_| ranges.iter().map(|(index, _)| self.multiplicity(*index)).collect());
+        let meta = CorpusMeta {
+            source,
+            total_bytes: items.iter().map(|p| p.len()).sum(),
+            pdu_count: items.len(),
+            flow_id: self.meta.flow_id,
+            multiplicities,
+        };
+        Self { items, meta }
+    }
+
+    /// Corpus de mêmes métadonnées restreint aux PDUs d'indices `indices`
+    pub(crate) fn select(&self, indices: &[usize], source: String) -> Self {
+        let items: Vec<PduRef> = indices
+            .iter()
+            .map(|&i| self.items[i].clone().derived_from(&self.items[i], i, Some(0)))
+            .collect();
+        let multiplicities = self
+            .meta
+            .multiplicities
+            .as_ref()
+            .map(|_| indices.iter().map(|&i| self.multiplicity(i)).collect());
+        let meta = CorpusMeta {
+            source,
+            total_bytes: items.iter().map(|p| p.len()).sum(),
+            pdu_count: items.len(),
+            flow_id: self.meta.flow_id,
+            multiplicities,
+        };
+        Self { items, meta }
+    }
 }
 
+/// `n` premiers indices d'une permutation aléatoire de `0..len` (Fisher-Yates partiel piloté
+/// par un générateur SplitMix64)
+fn shuffled_prefix(len: usize, n: usize, seed: u64) -> Vec<usize> {
+    let mut state = seed;
+    let mut next = move || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+    let mut indices: Vec<usize> = (0..len).collect();
+    for i in 0..n {
+        let j = i + (next() % (indices.len() - i) as u64) as usize;
+        indices.swap(i, j);
+    }
+    indices.truncate(n);
+    indices
+}
+
+/// Tranche de longueurs pour l'échantillonnage stratifié : longueur exacte sous 8 octets, puis
+/// quatre tranches par puissance de deux
+fn length_stratum(len: usize) -> usize {
+    if len < 8 {
+        return len;
+    }
+    let log = len.ilog2() as usize;
+    8 + (log - 3) * 4 + ((len >> (log - 2)) & 3)
+}
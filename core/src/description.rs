@@ -0,0 +1,445 @@
+use crate::hypothesis::{Endianness, Hypothesis, LengthWidth};
+use crate::inference::{InferenceResult, Layer};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// Version du document de description ; incrémentée à chaque changement incompatible
+pub const FORMAT_DESCRIPTION_VERSION: u32 = 1;
+
+/// Ordre des octets d'un champ de plusieurs octets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ByteOrder {
+    Big,
+    Little,
+}
+
+impl From<Endianness> for ByteOrder {
+    fn from(endian: Endianness) -> Self {
+        match endian {
+            Endianness::Big => ByteOrder::Big,
+            Endianness::Little => ByteOrder::Little,
+        }
+    }
+}
+
+/// Rôle d'un champ d'en-tête
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldRole {
+    /// Longueur (du message, de la SDU...)
+    Length,
+    /// Discriminant du type de message
+    Type,
+    /// Peu de valeurs distinctes
+    Enum,
+    Version,
+    Flags,
+    /// Numéro de séquence
+    Counter,
+    Float,
+    Bcd,
+    SessionId,
+}
+
+/// Champ d'en-tête d'une couche, à position fixe dans les PDUs qu'elle reçoit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldDescription {
+    pub role: FieldRole,
+    /// Position du premier octet
+    pub offset: usize,
+    /// Taille en octets
+    pub width: usize,
+    /// Ordre des octets, s'il a été établi
+    pub byte_order: Option<ByteOrder>,
+    /// Valeurs observées (champs énumérés, types, versions), de la plus à la moins fréquente
+    pub values: Vec<u64>,
+}
+
+/// Contrainte vérifiée par toutes les PDUs d'une couche
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Constraint {
+    /// Les octets à `offset` valent `value` (hexadécimal)
+    Equals { offset: usize, value: String },
+    /// Le champ (`offset`, `width`, big-endian) prend l'une des `values`
+    OneOf { offset: usize, width: usize, values: Vec<u64> },
+    /// Un champ de checksum couvre une partie de la PDU (voir les paramètres de la règle)
+    Checksum { algorithm: String },
+}
+
+/// Règle de découpage d'une couche et ses paramètres (offsets et tailles en octets)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FramingRule {
+    /// Nom stable de la règle, en snake_case (`length_prefix`, `fixed_header`, `tlv`...)
+    pub rule: String,
+    pub parameters: Map<String, Value>,
+}
+
+/// Sous-structure d'une valeur de discriminant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BranchDescription {
+    pub type_value: u64,
+    pub pdu_count: usize,
+    pub layers: Vec<LayerDescription>,
+}
+
+/// Description d'une couche
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerDescription {
+    /// Profondeur dans la pile (0 : PDUs analysées), qui repart de 0 dans une branche
+    pub depth: usize,
+    pub framing: FramingRule,
+    pub fields: Vec<FieldDescription>,
+    pub constraints: Vec<Constraint>,
+    /// Probabilité de la règle parmi les hypothèses évaluées (None si non classée)
+    pub probability: Option<f64>,
+    pub branches: Vec<BranchDescription>,
+}
+
+/// Description du format inféré, stable et indépendante des structures internes : seul ce
+/// document est à consommer par des outils externes (voir [`FormatDescription::json_schema`])
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormatDescription {
+    /// Version du document ([`FORMAT_DESCRIPTION_VERSION`])
+    pub format_version: u32,
+    pub source: String,
+    pub pdu_count: usize,
+    pub layers: Vec<LayerDescription>,
+}
+
+impl FormatDescription {
+    pub fn from_result(result: &InferenceResult) -> Self {
+        Self {
+            format_version: FORMAT_DESCRIPTION_VERSION,
+            source: result.corpus.meta.source.clone(),
+            pdu_count: result.corpus.len(),
+            layers: describe_layers(&result.layers),
+        }
+    }
+
+    /// JSON Schema (draft 2020-12) du document
+    pub fn json_schema() -> Value {
+        let byte_order = json!({ "enum": ["big", "little", null] });
+        let field = json!({
+            "type": "object",
+            "required": ["role", "offset", "width", "byte_order", "values"],
+            "properties": {
+                "role": {
+                    "enum": ["length", "type", "enum", "version", "flags", "counter", "float", "bcd", "session_id"]
+                },
+                "offset": { "type": "integer", "minimum": 0 },
+                "width": { "type": "integer", "minimum": 1 },
+                "byte_order": byte_order,
+                "values": { "type": "array", "items": { "type": "integer", "minimum": 0 } }
+            }
+        });
+        let constraint = json!({
+            "type": "object",
+            "required": ["kind"],
+            "properties": {
+                "kind": { "enum": ["equals", "one_of", "checksum"] },
+                "offset": { "type": "integer", "minimum": 0 },
+                "width": { "type": "integer", "minimum": 1 },
+                "value": { "type": "string", "pattern": "^([0-9a-f]{2})*$" },
+                "values": { "type": "array", "items": { "type": "integer", "minimum": 0 } },
+                "algorithm": { "type": "string" }
+            }
+        });
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Description de format inféré",
+            "type": "object",
+            "required": ["format_version", "source", "pdu_count", "layers"],
+            "properties": {
+                "format_version": { "const": FORMAT_DESCRIPTION_VERSION },
+                "source": { "type": "string" },
+                "pdu_count": { "type": "integer", "minimum": 0 },
+                "layers": { "type": "array", "items": { "$ref": "#/$defs/layer" } }
+            },
+            "$defs": {
+                "layer": {
+                    "type": "object",
+                    "required": ["depth", "framing", "fields", "constraints", "probability", "branches"],
+                    "properties": {
+                        "depth": { "type": "integer", "minimum": 0 },
+                        "framing": { "$ref": "#/$defs/framing" },
+                        "fields": { "type": "array", "items": field },
+                        "constraints": { "type": "array", "items": constraint },
+                        "probability": { "type": ["number", "null"], "minimum": 0, "maximum": 1 },
+                        "branches": { "type": "array", "items": { "$ref": "#/$defs/branch" } }
+                    }
+                },
+                "framing": {
+                    "type": "object",
+                    "required": ["rule", "parameters"],
+                    "properties": {
+                        "rule": { "type": "string", "pattern": "^[a-z0-9_]+$" },
+                        "parameters": { "type": "object" }
+                    }
+                },
+                "branch": {
+                    "type": "object",
+                    "required": ["type_value", "pdu_count", "layers"],
+                    "properties": {
+                        "type_value": { "type": "integer", "minimum": 0 },
+                        "pdu_count": { "type": "integer", "minimum": 0 },
+                        "layers": { "type": "array", "items": { "$ref": "#/$defs/layer" } }
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn describe_layers(layers: &[Layer]) -> Vec<LayerDescription> {
+    layers
+        .iter()
+        .enumerate()
+        .map(|(depth, layer)| LayerDescription {
+            depth,
+            framing: framing_rule(&layer.hypothesis),
+            fields: layer_fields(layer),
+            constraints: layer_constraints(layer),
+            probability: layer.confidence.as_ref().map(|c| c.probability),
+            branches: layer
+                .branches
+                .iter()
+                .map(|b| BranchDescription {
+                    type_value: b.type_value,
+                    pdu_count: b.pdu_count,
+                    layers: describe_layers(&b.layers),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Nom snake_case d'une valeur d'énumération d'après son nom Rust (`Crc16Ccitt` : `crc16_ccitt`)
+pub(crate) fn snake_case(value: &impl std::fmt::Debug) -> String {
+    let name = format!("{:?}", value);
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            out.push('_');
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Paramètres d'un champ entier : position, taille et ordre des octets
+fn int_field(offset: usize, width: LengthWidth, endian: Endianness) -> Value {
+    json!({ "offset": offset, "width": width as usize, "byte_order": ByteOrder::from(endian) })
+}
+
+/// Règle de découpage stable d'une hypothèse ; chaque nature d'hypothèse est décrite
+/// explicitement, pour que le document ne suive pas les changements de ses structures
+pub fn framing_rule(h: &Hypothesis) -> FramingRule {
+    let (rule, parameters) = match h {
+        Hypothesis::LengthPrefixBundle { offset, width, endian, includes_header, unit, adjust } => (
+            "length_prefix",
+            json!({
+                "length": int_field(*offset, *width, *endian),
+                "includes_header": includes_header,
+                "unit": unit,
+                "adjust": adjust,
+            }),
+        ),
+        Hypothesis::RemainingLength { offset, width, endian } => {
+            ("remaining_length", json!({ "length": int_field(*offset, *width, *endian) }))
+        }
+        Hypothesis::SyncLengthBundle { sync, width, endian, includes_header } => (
+            "sync_length",
+            json!({
+                "sync": hex(sync),
+                "length": int_field(sync.len(), *width, *endian),
+                "includes_header": includes_header,
+            }),
+        ),
+        Hypothesis::VarintLengthPrefix { offset, encoding } => {
+            ("varint_length_prefix", json!({ "offset": offset, "encoding": snake_case(encoding) }))
+        }
+        Hypothesis::LengthSuffixBundle { offset, width, endian } => (
+            "length_suffix",
+            json!({ "width": *width as usize, "byte_order": ByteOrder::from(*endian), "bytes_after": offset }),
+        ),
+        Hypothesis::ChannelMux { channel_width, len_width, endian } => (
+            "channel_mux",
+            json!({ "channel_width": channel_width, "length": int_field(*channel_width, *len_width, *endian) }),
+        ),
+        Hypothesis::Fragmented { index_offset, index_width, flag_offset, flag_mask, last_when_set } => (
+            "fragmented",
+            json!({
+                "index_offset": index_offset,
+                "index_width": index_width,
+                "flag_offset": flag_offset,
+                "flag_mask": flag_mask,
+                "last_when_set": last_when_set,
+            }),
+        ),
+        Hypothesis::DelimiterBundle { pattern, escape, quote } => {
+            ("delimiter", json!({ "delimiter": hex(pattern), "escape": escape, "quote": quote }))
+        }
+        Hypothesis::ByteStuffed { scheme } => (
+            "byte_stuffed",
+            json!({
+                "delimiter": scheme.delimiter,
+                "escape": scheme.escape,
+                "table": scheme.table.iter().map(|&(code, byte)| json!([code, byte])).collect::<Vec<_>>(),
+            }),
+        ),
+        Hypothesis::Hdlc { fcs } => ("hdlc", json!({ "fcs": fcs.as_ref().map(snake_case) })),
+        Hypothesis::LabelSequence { offset } => ("label_sequence", json!({ "offset": offset })),
+        Hypothesis::RecordArray { record_len, count_field } => (
+            "record_array",
+            json!({
+                "record_len": record_len,
+                "count": count_field.map(|c| int_field(c.offset, c.width, c.endian)),
+            }),
+        ),
+        Hypothesis::FixedHeader { len } => ("fixed_header", json!({ "len": len })),
+        Hypothesis::FixedTrailer { len } => ("fixed_trailer", json!({ "len": len })),
+        Hypothesis::ExtensibleBitmap { start, cont_bit, stop_value, max_bytes } => (
+            "extensible_bitmap",
+            json!({ "offset": start, "continuation_bit": cont_bit, "stop_value": stop_value, "max_bytes": max_bytes }),
+        ),
+        Hypothesis::Tlv { tag_offset, tag_bytes, len_offset, len_rule, length_includes_header, tag_rule, endian } => (
+            "tlv",
+            json!({
+                "tag_offset": tag_offset,
+                "tag_bytes": tag_bytes,
+                "tag_rule": snake_case(tag_rule),
+                "length_offset": len_offset,
+                "length_rule": snake_case(len_rule),
+                "length_includes_header": length_includes_header,
+                "byte_order": ByteOrder::from(*endian),
+            }),
+        ),
+        Hypothesis::VarintKeyWireType { key_max_bytes, allow_embedded } => (
+            "protobuf",
+            json!({ "key_max_bytes": key_max_bytes, "allow_embedded": allow_embedded }),
+        ),
+        Hypothesis::ConstantField { offset, value } => {
+            ("constant_field", json!({ "offset": offset, "value": hex(value) }))
+        }
+        Hypothesis::TypeSwitch { type_offset, type_width } => {
+            ("type_switch", json!({ "offset": type_offset, "width": type_width, "byte_order": ByteOrder::Big }))
+        }
+        Hypothesis::TextLines { line_ending } => ("text_lines", json!({ "line_ending": snake_case(line_ending) })),
+        Hypothesis::KeyValueHeader { line_ending } => {
+            ("key_value_header", json!({ "line_ending": snake_case(line_ending) }))
+        }
+        Hypothesis::ContentLengthMessages { line_ending, length_header } => (
+            "content_length",
+            json!({ "line_ending": snake_case(line_ending), "length_header": length_header }),
+        ),
+        Hypothesis::StructuredText { format } => ("structured_text", json!({ "format": snake_case(format) })),
+        Hypothesis::Encoded { encoding } => ("encoded", json!({ "encoding": snake_case(encoding) })),
+        Hypothesis::Compressed { algorithm } => ("compressed", json!({ "algorithm": snake_case(algorithm) })),
+        Hypothesis::Obfuscated { obfuscation } => (
+            "obfuscated",
+            match obfuscation {
+                crate::hypothesis::Obfuscation::Xor { key } => json!({ "operation": "xor", "key": hex(key) }),
+                crate::hypothesis::Obfuscation::Add { key } => json!({ "operation": "add", "key": hex(&[*key]) }),
+            },
+        ),
+        Hypothesis::Opaque => ("opaque", json!({})),
+        Hypothesis::TrailingPadding { pad_byte } => ("trailing_padding", json!({ "pad_byte": pad_byte })),
+        Hypothesis::BitFields { header_len, boundaries } => {
+            ("bit_fields", json!({ "header_len": header_len, "boundaries": boundaries }))
+        }
+        Hypothesis::Checksum { algorithm, endian, location } => (
+            "checksum",
+            json!({
+                "algorithm": snake_case(algorithm),
+                "byte_order": ByteOrder::from(*endian),
+                "location": match location {
+                    crate::hypothesis::ChecksumLocation::Header { offset } => json!({ "header": offset }),
+                    crate::hypothesis::ChecksumLocation::Trailer { covered_start } => {
+                        json!({ "trailer": covered_start })
+                    }
+                },
+            }),
+        ),
+        Hypothesis::Composite(parts) => (
+            "composite",
+            json!({ "parts": parts.iter().map(framing_rule).collect::<Vec<_>>() }),
+        ),
+    };
+    let Value::Object(parameters) = parameters else {
+        unreachable!("les paramètres d'une règle sont un objet JSON")
+    };
+    FramingRule { rule: rule.to_string(), parameters }
+}
+
+pub(crate) fn layer_fields(layer: &Layer) -> Vec<FieldDescription> {
+    let analysis = &layer.fields;
+    let type_field = match layer.hypothesis {
+        Hypothesis::TypeSwitch { type_offset, type_width } => Some((type_offset, type_width)),
+        _ => None,
+    };
+    let field = |role, offset, width, byte_order, values| FieldDescription { role, offset, width, byte_order, values };
+
+    let mut fields: Vec<FieldDescription> = analysis
+        .enums
+        .iter()
+        .map(|e| {
+            let role = if type_field == Some((e.offset, e.width)) { FieldRole::Type } else { FieldRole::Enum };
+            field(role, e.offset, e.width, None, e.values.iter().map(|v| v.value).collect())
+        })
+        .collect();
+    if let Some((offset, width)) = type_field.filter(|t| !analysis.enums.iter().any(|e| (e.offset, e.width) == *t)) {
+        fields.push(field(FieldRole::Type, offset, width, Some(ByteOrder::Big), Vec::new()));
+    }
+    fields.extend(analysis.version.iter().map(|v| {
+        field(FieldRole::Version, v.offset, 1, None, v.values.iter().map(|x| x.value).collect())
+    }));
+    fields.extend(analysis.flags.iter().map(|f| field(FieldRole::Flags, f.offset, f.width, None, Vec::new())));
+    fields.extend(analysis.endianness.iter().map(|e| {
+        let role = match e.role {
+            crate::fields::NumericRole::Counter => FieldRole::Counter,
+            crate::fields::NumericRole::Length => FieldRole::Length,
+        };
+        field(role, e.offset, e.width, Some(e.endian.into()), Vec::new())
+    }));
+    fields.extend(
+        analysis.floats.iter().map(|f| field(FieldRole::Float, f.offset, f.width, Some(f.endian.into()), Vec::new())),
+    );
+    fields.extend(analysis.bcd.iter().map(|b| field(FieldRole::Bcd, b.offset, b.len, None, Vec::new())));
+    fields.extend(
+        analysis.session_ids.iter().map(|s| field(FieldRole::SessionId, s.offset, s.width, None, Vec::new())),
+    );
+    fields.sort_by_key(|f| (f.offset, f.width));
+    fields
+}
+
+fn layer_constraints(layer: &Layer) -> Vec<Constraint> {
+    let mut constraints: Vec<Constraint> = layer
+        .fields
+        .enums
+        .iter()
+        .map(|e| Constraint::OneOf {
+            offset: e.offset,
+            width: e.width,
+            values: e.values.iter().map(|v| v.value).collect(),
+        })
+        .collect();
+    match &layer.hypothesis {
+        Hypothesis::ConstantField { offset, value } => {
+            constraints.push(Constraint::Equals { offset: *offset, value: hex(value) })
+        }
+        Hypothesis::SyncLengthBundle { sync, .. } => {
+            constraints.push(Constraint::Equals { offset: 0, value: hex(sync) })
+        }
+        Hypothesis::Checksum { algorithm, .. } => {
+            constraints.push(Constraint::Checksum { algorithm: snake_case(algorithm) })
+        }
+        _ => {}
+    }
+    constraints
+}
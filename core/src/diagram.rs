@@ -0,0 +1,123 @@
+use crate::description::{snake_case, FieldDescription, FormatDescription, LayerDescription};
+use std::fmt::Write;
+
+/// Nœud du diagramme : une couche de la pile, ou d'une branche de discriminant
+struct Node {
+    id: String,
+    /// Titre : profondeur et règle de découpage
+    title: String,
+    /// Champs d'en-tête dans l'ordre des offsets, `…` pour les octets non identifiés entre deux
+    fields: Vec<String>,
+}
+
+/// Arête du diagramme : SDU passé à la couche suivante, ou branche d'une valeur de type
+struct Edge {
+    from: String,
+    to: String,
+    label: String,
+}
+
+/// Diagramme Graphviz (DOT) de la pile d'encapsulation : une boîte par couche avec ses champs
+/// d'en-tête, reliée à la couche qui reçoit ses SDUs et aux couches de ses branches
+pub fn graphviz(description: &FormatDescription) -> String {
+    let (nodes, edges) = graph(description);
+    let mut out = String::from("digraph protocol {\n  rankdir=TB;\n  node [shape=record, fontname=\"monospace\"];\n");
+    let _ = writeln!(out, "  pdus [shape=box, style=rounded, label=\"{} PDUs\"];", description.pdu_count);
+    for node in &nodes {
+        let mut label = record_escape(&node.title);
+        if !node.fields.is_empty() {
+            let fields: Vec<String> = node.fields.iter().map(|f| record_escape(f)).collect();
+            label = format!("{}|{{{}}}", label, fields.join("|"));
+        }
+        let _ = writeln!(out, "  {} [label=\"{{{}}}\"];", node.id, label);
+    }
+    for edge in &edges {
+        let _ = writeln!(out, "  {} -> {} [label=\"{}\"];", edge.from, edge.to, record_escape(&edge.label));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Même diagramme en Mermaid (`flowchart`), pour une documentation Markdown
+pub fn mermaid(description: &FormatDescription) -> String {
+    let (nodes, edges) = graph(description);
+    let mut out = String::from("flowchart TD\n");
+    let _ = writeln!(out, "  pdus([\"{} PDUs\"])", description.pdu_count);
+    for node in &nodes {
+        let mut label = mermaid_escape(&node.title);
+        if !node.fields.is_empty() {
+            let fields: Vec<String> = node.fields.iter().map(|f| mermaid_escape(f)).collect();
+            label = format!("{}<br/>{}", label, fields.join(" | "));
+        }
+        let _ = writeln!(out, "  {}[\"{}\"]", node.id, label);
+    }
+    for edge in &edges {
+        let _ = writeln!(out, "  {} -->|\"{}\"| {}", edge.from, mermaid_escape(&edge.label), edge.to);
+    }
+    out
+}
+
+fn graph(description: &FormatDescription) -> (Vec<Node>, Vec<Edge>) {
+    let (mut nodes, mut edges) = (Vec::new(), Vec::new());
+    stack(&description.layers, "l", "pdus", "PDU", &mut nodes, &mut edges);
+    (nodes, edges)
+}
+
+/// Ajoute les couches `layers` sous le nœud `parent`, la première reliée par l'arête `label`
+fn stack(
+    layers: &[LayerDescription],
+    prefix: &str,
+    parent: &str,
+    label: &str,
+    nodes: &mut Vec<Node>,
+    edges: &mut Vec<Edge>,
+) {
+    let mut from = parent.to_string();
+    let mut label = label.to_string();
+    for layer in layers {
+        let id = format!("{}{}", prefix, layer.depth);
+        nodes.push(Node {
+            id: id.clone(),
+            title: format!("couche {} : {}", layer.depth, layer.framing.rule),
+            fields: field_layout(&layer.fields),
+        });
+        edges.push(Edge { from: std::mem::replace(&mut from, id.clone()), to: id.clone(), label });
+        label = "SDU".to_string();
+        for branch in &layer.branches {
+            let branch_prefix = format!("{}_t{}_l", id, branch.type_value);
+            let branch_label = format!("type {:#x} ({} PDUs)", branch.type_value, branch.pdu_count);
+            stack(&branch.layers, &branch_prefix, &id, &branch_label, nodes, edges);
+        }
+    }
+}
+
+/// Cases des champs d'en-tête, `offset: rôle (taille)`, avec `…` pour un trou entre deux champs
+fn field_layout(fields: &[FieldDescription]) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut end = 0;
+    for field in fields {
+        if field.offset > end {
+            cells.push("…".to_string());
+        }
+        cells.push(format!("{}: {} ({})", field.offset, snake_case(&field.role), field.width));
+        end = end.max(field.offset + field.width);
+    }
+    cells
+}
+
+/// Échappe les caractères réservés d'un libellé `record` Graphviz
+fn record_escape(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>' | '"' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Échappe les guillemets et chevrons d'un libellé Mermaid (entités `#quot;`...)
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;").replace('<', "#lt;").replace('>', "#gt;")
+}
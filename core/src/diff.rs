@@ -0,0 +1,199 @@
+use crate::description::{framing_rule, layer_fields, FieldDescription, FramingRule};
+use crate::inference::{InferenceResult, Layer};
+use crate::tlv::TlvTagStats;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Différences entre deux résultats d'inférence (captures de deux versions d'un firmware...),
+/// couche par couche à la même profondeur : règle de découpage et paramètres, champs d'en-tête,
+/// tags TLV et valeurs de discriminant.
+///
+/// Les couches des branches d'un discriminant ne sont pas comparées, seulement la liste des
+/// valeurs qui ont une branche.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ResultDiff {
+    pub layers: Vec<LayerDiff>,
+}
+
+/// Changement de la règle de découpage d'une couche
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayerChange {
+    /// Même hypothèse des deux côtés
+    Same,
+    /// Même règle, paramètres différents (offset, taille, ordre des octets...)
+    Parameters,
+    /// Règle différente
+    Replaced,
+    /// Couche présente seulement dans le nouveau résultat
+    Added,
+    /// Couche présente seulement dans l'ancien résultat
+    Removed,
+}
+
+/// Valeur d'un paramètre de la règle de découpage avant et après ; None si le paramètre n'existe
+/// que d'un côté
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParameterDelta {
+    /// Chemin du paramètre dans [`FramingRule::parameters`] (`length.offset`, `unit`...)
+    pub name: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+/// Champ d'en-tête présent des deux côtés (même rôle, position et taille) dont l'ordre des
+/// octets ou les valeurs observées ont changé
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub old: FieldDescription,
+    pub new: FieldDescription,
+}
+
+/// Différences d'une couche
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LayerDiff {
+    pub depth: usize,
+    pub change: LayerChange,
+    pub old: Option<FramingRule>,
+    pub new: Option<FramingRule>,
+    /// Paramètres modifiés, si la règle est la même
+    pub parameters: Vec<ParameterDelta>,
+    pub added_fields: Vec<FieldDescription>,
+    pub removed_fields: Vec<FieldDescription>,
+    pub changed_fields: Vec<FieldChange>,
+    /// Tags TLV (par niveau et valeur) apparus ou disparus
+    pub added_tags: Vec<TlvTagStats>,
+    pub removed_tags: Vec<TlvTagStats>,
+    /// Valeurs de discriminant dont la branche est apparue ou a disparu
+    pub added_branches: Vec<u64>,
+    pub removed_branches: Vec<u64>,
+}
+
+impl LayerDiff {
+    fn new(depth: usize, old: Option<&Layer>, new: Option<&Layer>) -> Self {
+        let change = match (old, new) {
+            (Some(old), Some(new)) if old.hypothesis == new.hypothesis => LayerChange::Same,
+            (Some(old), Some(new)) if framing_rule(&old.hypothesis).rule == framing_rule(&new.hypothesis).rule => {
+                LayerChange::Parameters
+            }
+            (Some(_), Some(_)) => LayerChange::Replaced,
+            (None, _) => LayerChange::Added,
+            (_, None) => LayerChange::Removed,
+        };
+        let old_rule = old.map(|layer| framing_rule(&layer.hypothesis));
+        let new_rule = new.map(|layer| framing_rule(&layer.hypothesis));
+        let parameters = match (&old_rule, &new_rule) {
+            (Some(old), Some(new)) if change == LayerChange::Parameters => {
+                parameter_deltas(&flatten(&old.parameters), &flatten(&new.parameters))
+            }
+            _ => Vec::new(),
+        };
+
+        let old_fields = old.map(layer_fields).unwrap_or_default();
+        let new_fields = new.map(layer_fields).unwrap_or_default();
+        let same_field =
+            |a: &FieldDescription, b: &FieldDescription| (a.role, a.offset, a.width) == (b.role, b.offset, b.width);
+        let changed_fields = old_fields
+            .iter()
+            .filter_map(|o| {
+                let n = new_fields.iter().find(|n| same_field(o, n))?;
+                (o != n).then(|| FieldChange { old: o.clone(), new: n.clone() })
+            })
+            .collect();
+
+        let tags = |layer: Option<&Layer>| {
+            layer
+                .and_then(|l| l.fields.tlv_tags.as_ref())
+                .map(|dictionary| dictionary.tags.clone())
+                .unwrap_or_default()
+        };
+        let (old_tags, new_tags) = (tags(old), tags(new));
+        let same_tag = |a: &TlvTagStats, b: &TlvTagStats| (a.depth, &a.tag) == (b.depth, &b.tag);
+
+        let branches = |layer: Option<&Layer>| -> Vec<u64> {
+            layer.map(|l| l.branches.iter().map(|b| b.type_value).collect()).unwrap_or_default()
+        };
+        let (old_branches, new_branches) = (branches(old), branches(new));
+
+        Self {
+            depth,
+            change,
+            old: old_rule,
+            new: new_rule,
+            parameters,
+            added_fields: missing_from(&new_fields, &old_fields, same_field),
+            removed_fields: missing_from(&old_fields, &new_fields, same_field),
+            changed_fields,
+            added_tags: missing_from(&new_tags, &old_tags, same_tag),
+            removed_tags: missing_from(&old_tags, &new_tags, same_tag),
+            added_branches: missing_from(&new_branches, &old_branches, |a, b| a == b),
+            removed_branches: missing_from(&old_branches, &new_branches, |a, b| a == b),
+        }
+    }
+
+    /// Vrai si rien n'a changé dans la couche
+    pub fn is_unchanged(&self) -> bool {
+        self.change == LayerChange::Same
+            && self.added_fields.is_empty()
+            && self.removed_fields.is_empty()
+            && self.changed_fields.is_empty()
+            && self.added_tags.is_empty()
+            && self.removed_tags.is_empty()
+            && self.added_branches.is_empty()
+            && self.removed_branches.is_empty()
+    }
+}
+
+impl ResultDiff {
+    /// Différences de `new` par rapport à `old`
+    pub fn new(old: &InferenceResult, new: &InferenceResult) -> Self {
+        let depth = old.layers.len().max(new.layers.len());
+        Self {
+            layers: (0..depth).map(|d| LayerDiff::new(d, old.layers.get(d), new.layers.get(d))).collect(),
+        }
+    }
+
+    /// Vrai si les deux résultats décrivent le même format
+    pub fn is_empty(&self) -> bool {
+        self.layers.iter().all(LayerDiff::is_unchanged)
+    }
+
+    /// Couches qui ont changé
+    pub fn changed_layers(&self) -> impl Iterator<Item = &LayerDiff> {
+        self.layers.iter().filter(|layer| !layer.is_unchanged())
+    }
+}
+
+/// Éléments de `items` absents de `others`
+fn missing_from<T: Clone>(items: &[T], others: &[T], same: impl Fn(&T, &T) -> bool) -> Vec<T> {
+    items.iter().filter(|a| !others.iter().any(|b| same(a, b))).cloned().collect()
+}
+
+/// Paramètres à plat, les objets imbriqués en chemins pointés (`length.offset`)
+fn flatten(parameters: &Map<String, Value>) -> Vec<(String, Value)> {
+    let mut out = Vec::new();
+    for (name, value) in parameters {
+        match value {
+            Value::Object(nested) => {
+                out.extend(flatten(nested).into_iter().map(|(key, v)| (format!("{}.{}", name, key), v)))
+            }
+            _ => out.push((name.clone(), value.clone())),
+        }
+    }
+    out
+}
+
+fn parameter_deltas(old: &[(String, Value)], new: &[(String, Value)]) -> Vec<ParameterDelta> {
+    let lookup =
+        |params: &[(String, Value)], name: &str| params.iter().find(|(n, _)| n == name).map(|(_, v)| v.clone());
+    let mut names: Vec<&String> = old.iter().chain(new).map(|(name, _)| name).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let (old, new) = (lookup(old, name), lookup(new, name));
+            (old != new).then(|| ParameterDelta { name: name.clone(), old, new })
+        })
+        .collect()
+}
@@ -0,0 +1,264 @@
+use crate::description::{snake_case, ByteOrder, FieldDescription, FieldRole, FormatDescription, LayerDescription};
+use std::fmt::Write;
+
+/// Squelette de dissecteur Wireshark en C (plugin `packet-<name>.c`) d'après la description
+/// du format.
+///
+/// Chaque couche de la pile a sa fonction de dissection et ses champs `hf_` (un par champ
+/// d'en-tête identifié, avec la table `value_string` des valeurs observées des champs
+/// énumérés). Seules les couches à en-tête ou trailer fixe passent leur SDU à la suivante ; pour
+/// les autres règles, un commentaire `TODO` rappelle les paramètres du découpage à écrire. Le
+/// dissecteur s'enregistre pour « Decode As » sur un port UDP.
+pub fn c_dissector(description: &FormatDescription, name: &str) -> String {
+    let abbrev = abbreviation(name);
+    let title = abbrev.to_uppercase();
+    let layers = &description.layers;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "/* packet-{}.c", abbrev);
+    let _ = writeln!(
+        out,
+        " * Squelette de dissecteur généré par AIRE ({} PDUs analysées, description v{}).",
+        description.pdu_count, description.format_version
+    );
+    out.push_str(" * Les noms des champs et des valeurs sont provisoires : à renommer d'après la spécification.\n");
+    out.push_str(" *\n * SPDX-License-Identifier: GPL-2.0-or-later\n */\n\n");
+    out.push_str("#include \"config.h\"\n\n#include <epan/packet.h>\n#include <epan/proto.h>\n");
+    out.push_str("#include <ws_version.h>\n#include <wsutil/plugins.h>\n\n");
+    out.push_str("#ifndef VERSION\n#define VERSION \"0.1.0\"\n#endif\n\n");
+    out.push_str("WS_DLL_PUBLIC_DEF const char plugin_version[] = VERSION;\n");
+    out.push_str("WS_DLL_PUBLIC_DEF const int plugin_want_major = WIRESHARK_VERSION_MAJOR;\n");
+    out.push_str("WS_DLL_PUBLIC_DEF const int plugin_want_minor = WIRESHARK_VERSION_MINOR;\n\n");
+    out.push_str("WS_DLL_PUBLIC void plugin_register(void);\n");
+    let _ = writeln!(out, "void proto_register_{}(void);\nvoid proto_reg_handoff_{}(void);\n", abbrev, abbrev);
+
+    let _ = writeln!(out, "static int proto_{};", abbrev);
+    for layer in layers {
+        for field in &layer.fields {
+            let _ = writeln!(out, "static int {};", hf_name(&abbrev, layer.depth, field));
+        }
+    }
+    for layer in layers {
+        let _ = writeln!(out, "static int ett_{}_l{};", abbrev, layer.depth);
+    }
+    let _ = writeln!(out, "\nstatic dissector_handle_t {}_handle;\n", abbrev);
+
+    for layer in layers {
+        for field in layer.fields.iter().filter(|f| has_value_string(f)) {
+            let _ = writeln!(out, "static const value_string {}[] = {{", vals_name(&abbrev, layer.depth, field));
+            for value in &field.values {
+                let _ = writeln!(out, "    {{ {:#x}, \"{} {:#x}\" }},", value, role_label(field.role), value);
+            }
+            out.push_str("    { 0, NULL }\n};\n\n");
+        }
+    }
+
+    // Les couches sont définies de la plus profonde à la première, qui appelle la suivante
+    for (depth, layer) in layers.iter().enumerate().rev() {
+        dissect_layer(&mut out, &abbrev, layer, layers.get(depth + 1).is_some());
+    }
+
+    let _ = writeln!(
+        out,
+        "static int\ndissect_{}(tvbuff_t *tvb, packet_info *pinfo, proto_tree *tree, void *data _U_)\n{{",
+        abbrev
+    );
+    let _ = writeln!(out, "    col_set_str(pinfo->cinfo, COL_PROTOCOL, \"{}\");", title);
+    out.push_str("    col_clear(pinfo->cinfo, COL_INFO);\n\n");
+    let _ = writeln!(out, "    proto_item *ti = proto_tree_add_item(tree, proto_{}, tvb, 0, -1, ENC_NA);", abbrev);
+    if layers.is_empty() {
+        out.push_str("    (void)ti;\n");
+    } else {
+        let _ = writeln!(out, "    dissect_{}_l0(tvb, pinfo, proto_item_add_subtree(ti, ett_{}_l0));", abbrev, abbrev);
+    }
+    out.push_str("    return tvb_captured_length(tvb);\n}\n\n");
+
+    let _ = writeln!(out, "void\nproto_register_{}(void)\n{{\n    static hf_register_info hf[] = {{", abbrev);
+    for layer in layers {
+        for field in &layer.fields {
+            register_field(&mut out, &abbrev, layer.depth, field);
+        }
+    }
+    out.push_str("    };\n\n    static int *ett[] = {\n");
+    for layer in layers {
+        let _ = writeln!(out, "        &ett_{}_l{},", abbrev, layer.depth);
+    }
+    out.push_str("    };\n\n");
+    let _ = writeln!(
+        out,
+        "    proto_{} = proto_register_protocol(\"{} (inféré)\", \"{}\", \"{}\");",
+        abbrev, title, title, abbrev
+    );
+    let _ = writeln!(out, "    proto_register_field_array(proto_{}, hf, array_length(hf));", abbrev);
+    let _ = writeln!(out, "    proto_register_subtree_array(ett, array_length(ett));");
+    let _ = writeln!(
+        out,
+        "    {}_handle = register_dissector(\"{}\", dissect_{}, proto_{});\n}}\n",
+        abbrev, abbrev, abbrev, abbrev
+    );
+
+    let _ = writeln!(out, "void\nproto_reg_handoff_{}(void)\n{{", abbrev);
+    let _ = writeln!(out, "    dissector_add_for_decode_as_with_preference(\"udp.port\", {}_handle);\n}}\n", abbrev);
+
+    out.push_str("void\nplugin_register(void)\n{\n    static proto_plugin plug;\n\n");
+    let _ = writeln!(out, "    plug.register_protoinfo = proto_register_{};", abbrev);
+    let _ = writeln!(out, "    plug.register_handoff = proto_reg_handoff_{};", abbrev);
+    out.push_str("    proto_register_plugin(&plug);\n}\n");
+    out
+}
+
+/// Fonction de dissection d'une couche : ses champs d'en-tête, puis le passage du SDU à la
+/// couche suivante quand la règle de découpage le permet sans analyse
+fn dissect_layer(out: &mut String, abbrev: &str, layer: &LayerDescription, has_next: bool) {
+    let depth = layer.depth;
+    let _ = writeln!(out, "/* Couche {} : {} */", depth, layer.framing.rule);
+    let _ = writeln!(
+        out,
+        "static void\ndissect_{}_l{}(tvbuff_t *tvb, packet_info *pinfo _U_, proto_tree *tree)\n{{",
+        abbrev, depth
+    );
+    for field in &layer.fields {
+        let _ = writeln!(
+            out,
+            "    proto_tree_add_item(tree, {}, tvb, {}, {}, {});",
+            hf_name(abbrev, depth, field),
+            field.offset,
+            field.width,
+            encoding(field)
+        );
+    }
+    for branch in &layer.branches {
+        let _ = writeln!(
+            out,
+            "    /* TODO : branche {:#x} ({} PDUs, {} couche(s) inférée(s)) */",
+            branch.type_value,
+            branch.pdu_count,
+            branch.layers.len()
+        );
+    }
+
+    if has_next {
+        let next = format!("dissect_{}_l{}", abbrev, depth + 1);
+        let subtree = format!("proto_tree_add_subtree(tree, sdu, 0, -1, ett_{}_l{}, NULL, \"SDU\")", abbrev, depth + 1);
+        let len = layer.framing.parameters.get("len").and_then(|v| v.as_u64());
+        match (layer.framing.rule.as_str(), len) {
+            ("fixed_header", Some(len)) => {
+                let _ = writeln!(out, "    if (tvb_reported_length(tvb) > {}) {{", len);
+                let _ = writeln!(out, "        tvbuff_t *sdu = tvb_new_subset_remaining(tvb, {});", len);
+                let _ = writeln!(out, "        {}(sdu, pinfo, {});\n    }}", next, subtree);
+            }
+            ("fixed_trailer", Some(len)) => {
+                let _ = writeln!(out, "    if (tvb_reported_length(tvb) > {}) {{", len);
+                let _ = writeln!(
+                    out,
+                    "        tvbuff_t *sdu = tvb_new_subset_length(tvb, 0, tvb_reported_length(tvb) - {});",
+                    len
+                );
+                let _ = writeln!(out, "        {}(sdu, pinfo, {});\n    }}", next, subtree);
+            }
+            _ => {
+                let _ = writeln!(
+                    out,
+                    "    /* TODO : extraire chaque SDU selon la règle {} {} */",
+                    layer.framing.rule,
+                    serde_json::Value::Object(layer.framing.parameters.clone())
+                );
+                let _ = writeln!(out, "    /* tvbuff_t *sdu = tvb_new_subset_length(tvb, offset, length); */");
+                let _ = writeln!(out, "    /* {}(sdu, pinfo, {}); */", next, subtree);
+            }
+        }
+    }
+    out.push_str("}\n\n");
+}
+
+fn register_field(out: &mut String, abbrev: &str, depth: usize, field: &FieldDescription) {
+    let (field_type, base) = field_type(field);
+    let strings = if has_value_string(field) {
+        format!("VALS({})", vals_name(abbrev, depth, field))
+    } else {
+        "NULL".to_string()
+    };
+    let _ = writeln!(
+        out,
+        "        {{ &{},\n          {{ \"{} (offset {})\", \"{}.l{}.{}_{}\",\n            {}, {}, {}, 0x0, NULL, HFILL }} }},",
+        hf_name(abbrev, depth, field),
+        role_label(field.role),
+        field.offset,
+        abbrev,
+        depth,
+        snake_case(&field.role),
+        field.offset,
+        field_type,
+        base,
+        strings
+    );
+}
+
+/// Type `FT_` et base d'affichage d'un champ
+fn field_type(field: &FieldDescription) -> (&'static str, &'static str) {
+    let base = match field.role {
+        FieldRole::Length | FieldRole::Counter => "BASE_DEC",
+        _ => "BASE_HEX",
+    };
+    match (field.role, field.width) {
+        (FieldRole::Float, 4) => ("FT_FLOAT", "BASE_NONE"),
+        (FieldRole::Float, 8) => ("FT_DOUBLE", "BASE_NONE"),
+        (FieldRole::Bcd, _) => ("FT_BYTES", "BASE_NONE"),
+        (_, 1) => ("FT_UINT8", base),
+        (_, 2) => ("FT_UINT16", base),
+        (_, 3) => ("FT_UINT24", base),
+        (_, 4) => ("FT_UINT32", base),
+        (_, 5) => ("FT_UINT40", base),
+        (_, 6) => ("FT_UINT48", base),
+        (_, 7) => ("FT_UINT56", base),
+        (_, 8) => ("FT_UINT64", base),
+        _ => ("FT_BYTES", "BASE_NONE"),
+    }
+}
+
+fn encoding(field: &FieldDescription) -> &'static str {
+    match (field_type(field).0, field.byte_order) {
+        ("FT_BYTES", _) => "ENC_NA",
+        (_, Some(ByteOrder::Little)) => "ENC_LITTLE_ENDIAN",
+        _ => "ENC_BIG_ENDIAN",
+    }
+}
+
+/// `VALS()` n'accepte que les entiers de 32 bits au plus
+fn has_value_string(field: &FieldDescription) -> bool {
+    !field.values.is_empty() && field.width <= 4 && field_type(field).0.starts_with("FT_UINT")
+}
+
+fn hf_name(abbrev: &str, depth: usize, field: &FieldDescription) -> String {
+    format!("hf_{}_l{}_{}_{}", abbrev, depth, snake_case(&field.role), field.offset)
+}
+
+fn vals_name(abbrev: &str, depth: usize, field: &FieldDescription) -> String {
+    format!("{}_l{}_{}_{}_vals", abbrev, depth, snake_case(&field.role), field.offset)
+}
+
+fn role_label(role: FieldRole) -> &'static str {
+    match role {
+        FieldRole::Length => "Longueur",
+        FieldRole::Type => "Type",
+        FieldRole::Enum => "Valeur",
+        FieldRole::Version => "Version",
+        FieldRole::Flags => "Drapeaux",
+        FieldRole::Counter => "Compteur",
+        FieldRole::Float => "Flottant",
+        FieldRole::Bcd => "BCD",
+        FieldRole::SessionId => "Session",
+    }
+}
+
+/// Identifiant C et filtre Wireshark : minuscules, chiffres et `_`, sans chiffre en tête
+fn abbreviation(name: &str) -> String {
+    let mut abbrev: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    if !abbrev.starts_with(|c: char| c.is_ascii_lowercase()) {
+        abbrev.insert_str(0, "proto_");
+    }
+    abbrev
+}
@@ -0,0 +1,164 @@
+use crate::corpus::Corpus;
+use crate::description::{layer_fields, snake_case, ByteOrder};
+use crate::fields::read_uint;
+use crate::hypothesis::Endianness;
+use crate::inference::{InferenceResult, Layer};
+use crate::segment::{Segment, SegmentKind};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Colonnes présentes dans toutes les tables : PDU, rang du message dans la PDU, position et
+/// taille du message, taille cumulée de ses SDUs
+const FIXED_COLUMNS: [&str; 5] = ["pdu", "message", "offset", "size", "sdu_size"];
+
+/// Table des champs d'une couche : une ligne par message, une colonne par champ inféré.
+///
+/// Les colonnes qui suivent [`FIXED_COLUMNS`] sont d'abord les champs d'en-tête de l'analyse
+/// de la couche (`type@0`, `counter@4`...), lus au début de la PDU et remplis pour son premier
+/// message seulement, puis les champs des segments parsés (`tag`, `length`...) dans l'ordre de
+/// leur première apparition ; la n-ième occurrence d'un champ dans un message a sa colonne
+/// `nom_n`. Une cellule vide signale un champ absent du message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldTable {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl FieldTable {
+    /// Table de la couche `depth`, sur le corpus qu'elle a reçu ; None si la couche n'existe
+    /// pas ou si son corpus n'a pas été conservé
+    pub fn from_result(result: &InferenceResult, depth: usize) -> Option<Self> {
+        Some(Self::from_layer(result.layer_corpus(depth)?, result.layers.get(depth)?))
+    }
+
+    pub fn from_layer(corpus: &Corpus, layer: &Layer) -> Self {
+        let header_fields = layer_fields(layer);
+        let mut columns: Vec<String> = FIXED_COLUMNS.iter().map(|c| c.to_string()).collect();
+        columns.extend(header_fields.iter().map(|f| format!("{}@{}", snake_case(&f.role), f.offset)));
+        let mut index: HashMap<String, usize> = columns.iter().cloned().enumerate().map(|(i, c)| (c, i)).collect();
+
+        let mut cells: Vec<Vec<(usize, String)>> = Vec::new();
+        for (pdu_idx, (pdu, parsed)) in corpus.items.iter().zip(&layer.parsed.parsed_pdus).enumerate() {
+            let data = pdu.as_slice();
+            for (message_idx, segments) in messages(&parsed.segments).into_iter().enumerate() {
+                let start = segments.iter().map(|s| s.range.start).min().unwrap_or(0);
+                let end = segments.iter().map(|s| s.range.end).max().unwrap_or(0);
+                let sdu_size: usize = segments.iter().filter(|s| s.kind == SegmentKind::Sdu).map(|s| s.len()).sum();
+                let mut row: Vec<(usize, String)> = [pdu_idx, message_idx, start, end - start, sdu_size]
+                    .into_iter()
+                    .map(|v| v.to_string())
+                    .enumerate()
+                    .collect();
+
+                if message_idx == 0 {
+                    for (i, field) in header_fields.iter().enumerate() {
+                        let endian = match field.byte_order {
+                            Some(ByteOrder::Little) => Endianness::Little,
+                            _ => Endianness::Big,
+                        };
+                        let value = match field.width {
+                            1..=8 => read_uint(data, field.offset, field.width, endian).map(|v| v.to_string()),
+                            _ => data.get(field.offset..field.offset + field.width).map(hex),
+                        };
+                        if let Some(value) = value {
+                            row.push((FIXED_COLUMNS.len() + i, value));
+                        }
+                    }
+                }
+
+                let mut occurrences: HashMap<&str, usize> = HashMap::new();
+                for segment in &segments {
+                    let SegmentKind::Field(name) = &segment.kind else {
+                        continue;
+                    };
+                    let occurrence = occurrences.entry(name.as_str()).or_default();
+                    *occurrence += 1;
+                    let column = match *occurrence {
+                        1 => name.clone(),
+                        n => format!("{}_{}", name, n),
+                    };
+                    let position = *index.entry(column.clone()).or_insert_with(|| {
+                        columns.push(column);
+                        columns.len() - 1
+                    });
+                    if let Some(value) = field_value(data, segment) {
+                        row.push((position, value));
+                    }
+                }
+                cells.push(row);
+            }
+        }
+
+        let rows = cells
+            .into_iter()
+            .map(|row| {
+                let mut cells = vec![String::new(); columns.len()];
+                for (position, value) in row {
+                    cells[position] = value;
+                }
+                cells
+            })
+            .collect();
+        Self { columns, rows }
+    }
+
+    /// Table au format CSV (RFC 4180) : ligne d'en-tête puis une ligne par message
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        for line in std::iter::once(&self.columns).chain(&self.rows) {
+            let cells: Vec<String> = line.iter().map(|c| csv_cell(c)).collect();
+            out.push_str(&cells.join(","));
+            out.push_str("\r\n");
+        }
+        out
+    }
+}
+
+/// Segments de chaque message d'une PDU, séparés par les frontières ; un groupe sans en-tête,
+/// SDU ni champ (bourrage, erreur seule) n'est pas un message
+fn messages(segments: &[Segment]) -> Vec<Vec<&Segment>> {
+    segments
+        .split(|s| s.kind == SegmentKind::MessageBoundary)
+        .map(|group| group.iter().collect::<Vec<_>>())
+        .filter(|group| {
+            group
+                .iter()
+                .any(|s| matches!(s.kind, SegmentKind::Pci | SegmentKind::Sdu | SegmentKind::Field(_)))
+        })
+        .collect()
+}
+
+/// Valeur décodée d'un champ : entier des bits d'un champ sub-octet, texte s'il est imprimable
+/// sur au moins trois octets, entier big-endian jusqu'à 8 octets, hexadécimal au-delà
+fn field_value(data: &[u8], segment: &Segment) -> Option<String> {
+    if let Some(bits) = segment.bits.clone().filter(|b| b.len() <= 64) {
+        let bytes = data.get(segment.range.clone())?;
+        let value = bits.clone().fold(0u64, |acc, bit| {
+            let bit = bit - segment.range.start * 8;
+            (acc << 1) | ((bytes[bit / 8] >> (7 - bit % 8)) & 1) as u64
+        });
+        return Some(value.to_string());
+    }
+    let bytes = data.get(segment.range.clone())?;
+    Some(if bytes.len() >= 3 && bytes.iter().all(|b| (0x20..0x7f).contains(b)) {
+        String::from_utf8_lossy(bytes).into_owned()
+    } else if bytes.len() <= 8 {
+        bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64).to_string()
+    } else {
+        hex(bytes)
+    })
+}
+
+/// Octets en hexadécimal préfixé, pour qu'un tableur ne les lise pas comme un nombre
+fn hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("0x{}", digits)
+}
+
+fn csv_cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
@@ -0,0 +1,687 @@
+use crate::corpus::Corpus;
+use crate::hypothesis::Endianness;
+use crate::parser::ParsedCorpus;
+use crate::segment::SegmentKind;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Nombre minimal de PDUs pour qu'une distribution de valeurs soit significative
+const MIN_PDUS: usize = 8;
+/// Nombre maximal de valeurs distinctes d'un champ énuméré
+const MAX_ENUM_VALUES: usize = 16;
+/// Nombre maximal d'octets d'en-tête analysés
+const MAX_HEADER_BYTES: usize = 32;
+/// Taille minimale (en octets) d'un champ BCD
+const MIN_BCD_BYTES: usize = 2;
+/// Nombre de valeurs décodées conservées en exemple
+const BCD_SAMPLES: usize = 3;
+/// Quartet de remplissage d'un champ BCD
+const BCD_FILLER: u8 = 0xF;
+/// Offsets explorés pour un champ de version
+const MAX_VERSION_OFFSET: usize = 4;
+/// Plus grande valeur plausible d'un numéro de version
+const MAX_VERSION_VALUE: u64 = 15;
+/// Fréquence minimale de chaque version
+const MIN_VERSION_FREQUENCY: f64 = 0.05;
+/// Proportion maximale de PDUs dont la longueur se retrouve dans l'autre version
+const MAX_VERSION_LENGTH_OVERLAP: f64 = 0.5;
+/// Écart moyen maximal entre fréquence conjointe et produit des fréquences de deux bits
+/// pour les tenir pour indépendants
+const MAX_FLAG_DEPENDENCY: f64 = 0.1;
+/// Plus grand pas entre deux valeurs successives d'un compteur
+const MAX_COUNTER_STEP: u64 = 16;
+/// Proportion minimale de PDUs cohérentes avec l'endianness retenue
+const MIN_ENDIAN_SUPPORT: f64 = 0.9;
+/// Proportion maximale de PDUs cohérentes avec l'endianness écartée
+const MAX_RIVAL_SUPPORT: f64 = 0.5;
+/// Corrélation minimale entre une longueur et la taille des PDUs
+const MIN_LENGTH_CORRELATION: f64 = 0.8;
+/// Plus petite magnitude non nulle d'un flottant plausible
+const MIN_FLOAT_MAGNITUDE: f64 = 1e-6;
+/// Plus grande magnitude d'un flottant plausible
+const MAX_FLOAT_MAGNITUDE: f64 = 1e9;
+/// Écart entre deux PDUs successives, relatif à l'étendue des valeurs, d'une évolution lisse
+const MAX_FLOAT_STEP: f64 = 0.25;
+/// Proportion minimale de pas lisses d'un flottant
+const MIN_SMOOTH_RATIO: f64 = 0.8;
+/// Nombre minimal de flows (d'au moins deux PDUs) pour reconnaître un identifiant de session
+const MIN_SESSION_FLOWS: usize = 3;
+/// Proportion minimale de flows dont l'identifiant diffère de celui des autres
+const MIN_SESSION_DISTINCT: f64 = 0.9;
+
+/// Valeur observée d'un champ énuméré
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnumValue {
+    pub value: u64,
+    pub count: usize,
+    pub frequency: f64,
+}
+
+/// Champ à faible cardinalité (type de message, code de statut, ...)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnumField {
+    pub offset: usize,
+    pub width: usize,
+    /// Valeurs observées, de la plus fréquente à la moins fréquente
+    pub values: Vec<EnumValue>,
+}
+
+/// Champ de chiffres codés en BCD (numéros, identités télécom)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BcdField {
+    pub offset: usize,
+    pub len: usize,
+    /// Quartet de poids faible en premier (TBCD, comme les IMSI)
+    pub swapped: bool,
+    /// Exemples de valeurs décodées, sans les quartets de remplissage
+    pub samples: Vec<String>,
+}
+
+/// Bit d'un champ de drapeaux, avec la proportion de PDUs où il est levé
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlagBit {
+    pub mask: u64,
+    pub frequency: f64,
+}
+
+/// Champ de drapeaux : des bits qui basculent indépendamment les uns des autres
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlagsField {
+    pub offset: usize,
+    pub width: usize,
+    /// Bits levés dans au moins une PDU, de poids faible à poids fort
+    pub bits: Vec<FlagBit>,
+}
+
+/// Champ de version : deux petites valeurs près du début de la PDU qui départagent des
+/// structures (longueurs) différentes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionField {
+    pub offset: usize,
+    /// Versions observées, de la plus fréquente à la moins fréquente
+    pub values: Vec<EnumValue>,
+    /// Proportion de PDUs dont la longueur se retrouve dans l'autre version
+    pub length_overlap: f64,
+}
+
+/// Rôle numérique d'un champ dont l'endianness a été inférée
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NumericRole {
+    /// Valeur croissant par petits pas d'une PDU à la suivante (numéro de séquence)
+    Counter,
+    /// Valeur bornée par la taille de la PDU et corrélée à celle-ci
+    Length,
+}
+
+/// Endianness d'un compteur ou d'une longueur de plusieurs octets, départagée en comparant les
+/// deux lectures sur le corpus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EndianEvidence {
+    pub offset: usize,
+    pub width: usize,
+    pub endian: Endianness,
+    pub role: NumericRole,
+    /// Proportion de PDUs cohérentes avec l'endianness retenue
+    pub support: f64,
+    /// Même proportion pour l'endianness écartée
+    pub rival_support: f64,
+}
+
+/// Champ flottant IEEE-754 (float32 ou float64), courant dans la télémétrie
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloatField {
+    pub offset: usize,
+    /// 4 (float32) ou 8 (float64) octets
+    pub width: usize,
+    pub endian: Endianness,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Identifiant de session ou de connexion : constant dans chaque flow, différent d'un flow à
+/// l'autre
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionIdField {
+    pub offset: usize,
+    pub width: usize,
+    /// Nombre de flows examinés
+    pub flows: usize,
+    /// Nombre de valeurs distinctes parmi ces flows
+    pub distinct: usize,
+}
+
+/// Analyse des champs de l'en-tête d'une couche, après parsing
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldAnalysis {
+    pub enums: Vec<EnumField>,
+    #[serde(default)]
+    pub bcd: Vec<BcdField>,
+    /// Esquisse de schéma d'une couche protobuf
+    #[serde(default)]
+    pub proto: Option<crate::protobuf::ProtoSchema>,
+    #[serde(default)]
+    pub version: Option<VersionField>,
+    #[serde(default)]
+    pub flags: Vec<FlagsField>,
+    #[serde(default)]
+    pub endianness: Vec<EndianEvidence>,
+    #[serde(default)]
+    pub floats: Vec<FloatField>,
+    /// Chaînes de caractères des segments PCI et SDU
+    #[serde(default)]
+    pub strings: Vec<crate::strings::StringField>,
+    /// Identifiants de session, si les PDUs proviennent de plusieurs flows
+    #[serde(default)]
+    pub session_ids: Vec<SessionIdField>,
+    /// Adresses de la capture recopiées dans les PDUs (voir `InferenceResult::annotate_addresses`)
+    #[serde(default)]
+    pub addresses: Vec<crate::addresses::AddressField>,
+    /// Dictionnaire des tags d'une couche TLV
+    #[serde(default)]
+    pub tlv_tags: Option<crate::tlv::TlvDictionary>,
+}
+
+/// Fin de l'en-tête commun : début de la première SDU, ou fin de la PDU sans SDU
+fn header_len(corpus: &Corpus, parsed: &ParsedCorpus) -> usize {
+    corpus
+        .items
+        .iter()
+        .zip(parsed.parsed_pdus.iter())
+        .map(|(pdu, parsed_pdu)| {
+            parsed_pdu
+                .segments
+                .iter()
+                .find(|s| s.kind == SegmentKind::Sdu)
+                .map_or(pdu.len(), |s| s.range.start)
+        })
+        .min()
+        .unwrap_or(0)
+        .min(MAX_HEADER_BYTES)
+}
+
+/// Analyse les octets d'en-tête (avant la première SDU) des PDUs d'une couche
+pub fn analyze_fields(corpus: &Corpus, parsed: &ParsedCorpus) -> FieldAnalysis {
+    if corpus.len() < MIN_PDUS {
+        return FieldAnalysis::default();
+    }
+
+    let header_len = header_len(corpus, parsed);
+    let enums = (0..header_len).filter_map(|offset| enum_field(corpus, offset, 1)).collect();
+    let bcd = bcd_fields(corpus, header_len);
+    let version = (0..header_len.min(MAX_VERSION_OFFSET)).find_map(|offset| version_field(corpus, offset));
+
+    let flags = flags_fields(corpus, header_len);
+    let endianness = endian_evidence(corpus, header_len);
+    let floats = float_fields(corpus, header_len);
+    let strings = crate::strings::string_fields(corpus, parsed);
+    let session_ids = session_id_fields(corpus, header_len);
+
+    FieldAnalysis {
+        enums,
+        bcd,
+        proto: None,
+        version,
+        flags,
+        endianness,
+        floats,
+        strings,
+        session_ids,
+        addresses: Vec::new(),
+        tlv_tags: None,
+    }
+}
+
+/// Identifiants de session dans les `header_len` premiers octets : plages d'octets constants
+/// dans chaque flow, débarrassées des octets constants sur tout le corpus, dont la valeur
+/// distingue les flows.
+///
+/// Seuls comptent les flows d'au moins deux PDUs, un flow d'une PDU étant trivialement constant.
+pub fn session_id_fields(corpus: &Corpus, header_len: usize) -> Vec<SessionIdField> {
+    let mut by_flow: BTreeMap<usize, Vec<&[u8]>> = BTreeMap::new();
+    for pdu in &corpus.items {
+        if let Some(flow_id) = pdu.flow_id {
+            by_flow.entry(flow_id).or_default().push(pdu.as_slice());
+        }
+    }
+    let flows: Vec<Vec<&[u8]>> = by_flow.into_values().filter(|pdus| pdus.len() >= 2).collect();
+    if flows.len() < MIN_SESSION_FLOWS {
+        return Vec::new();
+    }
+
+    let flow_constant = |offset: usize| {
+        flows
+            .iter()
+            .all(|pdus| pdus.iter().all(|d| d.len() > offset && d[offset] == pdus[0][offset]))
+    };
+    let corpus_constant = |offset: usize| flows.iter().all(|pdus| pdus[0][offset] == flows[0][0][offset]);
+
+    let mut fields = Vec::new();
+    let mut offset = 0;
+    while offset < header_len {
+        if !flow_constant(offset) {
+            offset += 1;
+            continue;
+        }
+        let run_end = (offset..header_len).find(|&o| !flow_constant(o)).unwrap_or(header_len);
+        let start = (offset..run_end).find(|&o| !corpus_constant(o));
+        let end = (offset..run_end).rev().find(|&o| !corpus_constant(o)).map(|o| o + 1);
+        if let (Some(start), Some(end)) = (start, end) {
+            let distinct: HashSet<&[u8]> = flows.iter().map(|pdus| &pdus[0][start..end]).collect();
+            if distinct.len() as f64 >= MIN_SESSION_DISTINCT * flows.len() as f64 {
+                fields.push(SessionIdField {
+                    offset: start,
+                    width: end - start,
+                    flows: flows.len(),
+                    distinct: distinct.len(),
+                });
+            }
+        }
+        offset = run_end;
+    }
+    fields
+}
+
+/// Flottants alignés sur leur taille dans les `header_len` premiers octets ; un float64 est
+/// préféré à deux float32, et les champs ne se chevauchent pas
+pub fn float_fields(corpus: &Corpus, header_len: usize) -> Vec<FloatField> {
+    let mut fields = Vec::new();
+    if corpus.len() < MIN_PDUS {
+        return fields;
+    }
+    let mut offset = 0;
+    while offset + 4 <= header_len {
+        let found = [8, 4]
+            .into_iter()
+            .filter(|&width| offset.is_multiple_of(width) && offset + width <= header_len)
+            .find_map(|width| float_field(corpus, offset, width));
+        match found {
+            Some(field) => {
+                offset += field.width;
+                fields.push(field);
+            }
+            None => offset += 4,
+        }
+    }
+    fields
+}
+
+/// Champ flottant à (`offset`, `width`) : valeurs finies de magnitude raisonnable (ou nulles),
+/// non constantes, évoluant par petits pas d'une PDU à la suivante
+fn float_field(corpus: &Corpus, offset: usize, width: usize) -> Option<FloatField> {
+    [Endianness::Big, Endianness::Little].into_iter().find_map(|endian| {
+        let values: Vec<f64> = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let bits = read_uint(pdu.as_slice(), offset, width, endian)?;
+                let value = if width == 4 { f32::from_bits(bits as u32) as f64 } else { f64::from_bits(bits) };
+                let magnitude = value.abs();
+                (value == 0.0 || (MIN_FLOAT_MAGNITUDE..=MAX_FLOAT_MAGNITUDE).contains(&magnitude)).then_some(value)
+            })
+            .collect::<Option<_>>()?;
+
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        if min == max {
+            return None;
+        }
+        let smooth = values.windows(2).filter(|w| (w[1] - w[0]).abs() <= MAX_FLOAT_STEP * (max - min)).count();
+        (smooth as f64 >= MIN_SMOOTH_RATIO * (values.len() - 1) as f64).then_some(FloatField {
+            offset,
+            width,
+            endian,
+            min,
+            max,
+        })
+    })
+}
+
+/// Compteurs et longueurs de 2 ou 4 octets dans les `header_len` premiers octets, dont une
+/// seule lecture (big- ou little-endian) est plausible ; les champs ne se chevauchent pas
+pub fn endian_evidence(corpus: &Corpus, header_len: usize) -> Vec<EndianEvidence> {
+    let mut evidence = Vec::new();
+    if corpus.len() < MIN_PDUS {
+        return evidence;
+    }
+    let mut offset = 0;
+    while offset < header_len {
+        let found = [4, 2]
+            .into_iter()
+            .filter(|&width| offset + width <= header_len)
+            .find_map(|width| numeric_endianness(corpus, offset, width));
+        match found {
+            Some(field) => {
+                offset += field.width;
+                evidence.push(field);
+            }
+            None => offset += 1,
+        }
+    }
+    evidence
+}
+
+/// Endianness majoritaire des compteurs et longueurs du début des PDUs, s'il y en a une
+pub fn infer_endianness(corpus: &Corpus) -> Option<Endianness> {
+    let header_len = corpus.items.iter().map(|p| p.len()).min()?.min(MAX_HEADER_BYTES);
+    let evidence = endian_evidence(corpus, header_len);
+    let little = evidence.iter().filter(|e| e.endian == Endianness::Little).count();
+    match little.cmp(&(evidence.len() - little)) {
+        std::cmp::Ordering::Greater => Some(Endianness::Little),
+        std::cmp::Ordering::Less => Some(Endianness::Big),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// Compare les lectures big- et little-endian du champ (`offset`, `width`) comme compteur
+/// puis comme longueur.
+///
+/// Un octet de poids fort constant et non nul trahit un champ voisin (type, magic) plutôt
+/// qu'un nombre plus large : la lecture correspondante n'est pas retenue.
+fn numeric_endianness(corpus: &Corpus, offset: usize, width: usize) -> Option<EndianEvidence> {
+    let read = |endian| -> Option<Vec<u64>> {
+        corpus.items.iter().map(|pdu| read_uint(pdu.as_slice(), offset, width, endian)).collect()
+    };
+    let big = read(Endianness::Big)?;
+    let little = read(Endianness::Little)?;
+    let lengths: Vec<u64> = corpus.items.iter().map(|p| p.len() as u64).collect();
+
+    [NumericRole::Counter, NumericRole::Length].into_iter().find_map(|role| {
+        let support = |values: &[u64]| match role {
+            NumericRole::Counter => counter_support(values, width),
+            NumericRole::Length => length_support(values, &lengths),
+        };
+        let big_support = support(&big);
+        let little_support = support(&little);
+        let (endian, values, support, rival_support) = if big_support >= little_support {
+            (Endianness::Big, &big, big_support, little_support)
+        } else {
+            (Endianness::Little, &little, little_support, big_support)
+        };
+        let top = values[0] >> (8 * (width - 1));
+        if top != 0 && values.iter().all(|v| v >> (8 * (width - 1)) == top) {
+            return None;
+        }
+        (support >= MIN_ENDIAN_SUPPORT && rival_support <= MAX_RIVAL_SUPPORT).then_some(EndianEvidence {
+            offset,
+            width,
+            endian,
+            role,
+            support,
+            rival_support,
+        })
+    })
+}
+
+/// Proportion de PDUs successives dont la valeur avance d'un petit pas (modulo la largeur)
+fn counter_support(values: &[u64], width: usize) -> f64 {
+    let modulus = 1u128 << (8 * width);
+    let steps = values
+        .windows(2)
+        .filter(|w| {
+            let step = (w[1] as u128 + modulus - w[0] as u128) % modulus;
+            (1..=MAX_COUNTER_STEP as u128).contains(&step)
+        })
+        .count();
+    steps as f64 / (values.len() - 1) as f64
+}
+
+/// Proportion de valeurs ne dépassant pas la taille de leur PDU, nulle si elles ne suivent pas
+/// cette taille
+fn length_support(values: &[u64], lengths: &[u64]) -> f64 {
+    if correlation(values, lengths).is_none_or(|r| r < MIN_LENGTH_CORRELATION) {
+        return 0.0;
+    }
+    let bounded = values.iter().zip(lengths).filter(|(v, l)| v <= l).count();
+    bounded as f64 / values.len() as f64
+}
+
+/// Corrélation de Pearson, indéfinie si une série est constante
+fn correlation(a: &[u64], b: &[u64]) -> Option<f64> {
+    let n = a.len() as f64;
+    let mean_a = a.iter().map(|&v| v as f64).sum::<f64>() / n;
+    let mean_b = b.iter().map(|&v| v as f64).sum::<f64>() / n;
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (&x, &y) in a.iter().zip(b) {
+        let (dx, dy) = (x as f64 - mean_a, y as f64 - mean_b);
+        cov += dx * dy;
+        var_a += dx * dx;
+        var_b += dy * dy;
+    }
+    (var_a > 0.0 && var_b > 0.0).then(|| cov / (var_a * var_b).sqrt())
+}
+
+/// Champs de drapeaux de l'en-tête, de 1, 2 ou 4 octets ; un champ large n'est retenu que si
+/// ses bits actifs s'étendent sur plusieurs octets, et les champs ne se chevauchent pas
+fn flags_fields(corpus: &Corpus, header_len: usize) -> Vec<FlagsField> {
+    let mut fields = Vec::new();
+    let mut offset = 0;
+    while offset < header_len {
+        let found = [1, 2, 4]
+            .into_iter()
+            .filter(|&width| offset + width <= header_len)
+            .find_map(|width| flags_field(corpus, offset, width));
+        match found {
+            Some(field) => {
+                offset += field.width;
+                fields.push(field);
+            }
+            None => offset += 1,
+        }
+    }
+    fields
+}
+
+/// Champ de drapeaux à (`offset`, `width`) : au moins deux bits variables, pas plus de la
+/// moitié des bits du champ (un compteur les fait tous varier), deux à deux indépendants
+fn flags_field(corpus: &Corpus, offset: usize, width: usize) -> Option<FlagsField> {
+    let mut values = Vec::new();
+    let mut total = 0;
+    for (index, pdu) in corpus.items.iter().enumerate() {
+        let weight = corpus.multiplicity(index);
+        values.push((read_be(pdu.as_slice(), offset, width)?, weight));
+        total += weight;
+    }
+
+    let frequency = |mask: u64| {
+        values.iter().filter(|(v, _)| v & mask == mask).map(|(_, w)| w).sum::<usize>() as f64 / total as f64
+    };
+    let bits: Vec<FlagBit> = (0..width * 8)
+        .map(|bit| 1u64 << bit)
+        .map(|mask| FlagBit { mask, frequency: frequency(mask) })
+        .filter(|b| b.frequency > 0.0)
+        .collect();
+    let varying: Vec<&FlagBit> = bits.iter().filter(|b| b.frequency < 1.0).collect();
+    if varying.len() < 2 || varying.len() > width * 4 {
+        return None;
+    }
+    // Un champ large doit avoir des bits variables dans plusieurs octets
+    if width > 1 {
+        let bytes: std::collections::HashSet<u32> = varying.iter().map(|b| b.mask.trailing_zeros() / 8).collect();
+        if bytes.len() < 2 {
+            return None;
+        }
+    }
+
+    let mut dependency = 0.0;
+    let mut pairs = 0;
+    for (i, a) in varying.iter().enumerate() {
+        for b in &varying[i + 1..] {
+            dependency += (frequency(a.mask | b.mask) - a.frequency * b.frequency).abs();
+            pairs += 1;
+        }
+    }
+    if dependency / pairs as f64 > MAX_FLAG_DEPENDENCY {
+        return None;
+    }
+
+    Some(FlagsField { offset, width, bits })
+}
+
+/// Champ de version à `offset` : exactement deux petites valeurs, chacune assez fréquente,
+/// dont les PDUs ont des longueurs distinctes.
+///
+/// Un champ constant ne départage rien et n'est pas retenu.
+fn version_field(corpus: &Corpus, offset: usize) -> Option<VersionField> {
+    let field = enum_field(corpus, offset, 1)?;
+    if field.values.len() != 2
+        || field
+            .values
+            .iter()
+            .any(|v| v.value > MAX_VERSION_VALUE || v.frequency < MIN_VERSION_FREQUENCY)
+    {
+        return None;
+    }
+
+    let mut lengths: HashMap<u8, std::collections::HashSet<usize>> = HashMap::new();
+    for pdu in &corpus.items {
+        let data = pdu.as_slice();
+        lengths.entry(data[offset]).or_default().insert(data.len());
+    }
+    let overlapping = corpus
+        .items
+        .iter()
+        .filter(|pdu| {
+            let data = pdu.as_slice();
+            lengths
+                .iter()
+                .any(|(&version, set)| version != data[offset] && set.contains(&data.len()))
+        })
+        .count();
+    let length_overlap = overlapping as f64 / corpus.len() as f64;
+    if length_overlap > MAX_VERSION_LENGTH_OVERLAP {
+        return None;
+    }
+
+    Some(VersionField {
+        offset,
+        values: field.values,
+        length_overlap,
+    })
+}
+
+/// Quartets d'un octet dans l'ordre de lecture
+fn nibbles(byte: u8, swapped: bool) -> [u8; 2] {
+    if swapped {
+        [byte & 0x0f, byte >> 4]
+    } else {
+        [byte >> 4, byte & 0x0f]
+    }
+}
+
+/// Décode une plage BCD ; `None` si un quartet n'est ni un chiffre ni un remplissage final
+fn decode_bcd(bytes: &[u8], swapped: bool) -> Option<String> {
+    let mut digits = String::new();
+    let mut filled = false;
+    for nibble in bytes.iter().flat_map(|&b| nibbles(b, swapped)) {
+        match nibble {
+            0..=9 if !filled => digits.push((b'0' + nibble) as char),
+            BCD_FILLER => filled = true,
+            _ => return None,
+        }
+    }
+    Some(digits)
+}
+
+/// Plages d'en-tête dont tous les quartets sont des chiffres (ou un remplissage final 0xF)
+fn bcd_fields(corpus: &Corpus, header_len: usize) -> Vec<BcdField> {
+    let is_bcd_byte = |b: u8| nibbles(b, false).iter().all(|&n| n <= 9 || n == BCD_FILLER);
+    let column_ok = |offset: usize| {
+        corpus
+            .items
+            .iter()
+            .all(|pdu| pdu.as_slice().get(offset).is_some_and(|&b| is_bcd_byte(b)))
+    };
+
+    let mut fields = Vec::new();
+    let mut offset = 0;
+    while offset < header_len {
+        if !column_ok(offset) {
+            offset += 1;
+            continue;
+        }
+        let start = offset;
+        while offset < header_len && column_ok(offset) {
+            offset += 1;
+        }
+        if offset - start >= MIN_BCD_BYTES {
+            fields.extend(bcd_field(corpus, start, offset - start));
+        }
+    }
+    fields
+}
+
+/// Champ BCD sur `offset..offset + len`, si la plage varie et se décode dans toutes les PDUs
+fn bcd_field(corpus: &Corpus, offset: usize, len: usize) -> Option<BcdField> {
+    let values: Vec<&[u8]> = corpus
+        .items
+        .iter()
+        .map(|pdu| &pdu.as_slice()[offset..offset + len])
+        .collect();
+    // Une valeur constante relève des champs magic, pas d'un nombre
+    if values.iter().all(|v| *v == values[0]) {
+        return None;
+    }
+
+    // Le remplissage en quartet de poids fort du dernier octet trahit l'ordre TBCD
+    let swapped = values.iter().any(|v| v[len - 1] >> 4 == BCD_FILLER && v[len - 1] & 0x0f <= 9);
+    let mut samples: Vec<String> = Vec::new();
+    for value in &values {
+        let digits = decode_bcd(value, swapped)?;
+        if samples.len() < BCD_SAMPLES && !samples.contains(&digits) {
+            samples.push(digits);
+        }
+    }
+
+    Some(BcdField {
+        offset,
+        len,
+        swapped,
+        samples,
+    })
+}
+
+/// Lit un entier big-endian de `width` octets à `offset`
+pub(crate) fn read_be(data: &[u8], offset: usize, width: usize) -> Option<u64> {
+    read_uint(data, offset, width, Endianness::Big)
+}
+
+/// Lit un entier de `width` octets à `offset` dans l'endianness donnée
+pub(crate) fn read_uint(data: &[u8], offset: usize, width: usize, endian: Endianness) -> Option<u64> {
+    let bytes = data.get(offset..offset.checked_add(width)?)?;
+    Some(match endian {
+        Endianness::Big => bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64),
+        Endianness::Little => bytes.iter().rev().fold(0u64, |acc, &b| (acc << 8) | b as u64),
+    })
+}
+
+/// Distribution des valeurs du champ big-endian (`offset`, `width`), si elle est de
+/// faible cardinalité.
+///
+/// Un champ constant n'est pas un énuméré (voir les champs magic), et le nombre de
+/// valeurs distinctes doit rester petit devant le nombre d'occurrences.
+pub(crate) fn enum_field(corpus: &Corpus, offset: usize, width: usize) -> Option<EnumField> {
+    let mut counts: HashMap<u64, usize> = HashMap::new();
+    let mut total = 0;
+    for (index, pdu) in corpus.items.iter().enumerate() {
+        let weight = corpus.multiplicity(index);
+        *counts.entry(read_be(pdu.as_slice(), offset, width)?).or_default() += weight;
+        total += weight;
+    }
+
+    let distinct = counts.len();
+    if !(2..=MAX_ENUM_VALUES).contains(&distinct) || distinct * 4 > total {
+        return None;
+    }
+
+    let mut values: Vec<EnumValue> = counts
+        .into_iter()
+        .map(|(value, count)| EnumValue {
+            value,
+            count,
+            frequency: count as f64 / total as f64,
+        })
+        .collect();
+    values.sort_by(|a, b| b.count.cmp(&a.count).then(a.value.cmp(&b.value)));
+
+    Some(EnumField { offset, width, values })
+}
@@ -0,0 +1,165 @@
+use crate::corpus::{Corpus, CorpusMeta, PduRef};
+use crate::fields::read_be;
+use crate::hypothesis::Hypothesis;
+
+/// Nombre maximal de PDUs examinées pour la détection
+const DETECTION_SAMPLE: usize = 2048;
+/// Octets d'en-tête explorés pour l'index et le drapeau
+const MAX_HEADER_OFFSET: usize = 4;
+/// Proportion minimale de fragments cohérents avec la séquence attendue
+const FRAGMENT_MIN_RATIO: f64 = 0.95;
+/// Nombre maximal d'en-têtes candidats retenus
+const MAX_CANDIDATES: usize = 8;
+
+/// Taille de l'en-tête de fragment décrit par l'hypothèse
+pub fn fragment_header_len(h: &Hypothesis) -> usize {
+    match h {
+        Hypothesis::Fragmented {
+            index_offset,
+            index_width,
+            flag_offset,
+            ..
+        } => (index_offset + index_width).max(flag_offset + 1),
+        _ => 0,
+    }
+}
+
+/// Index du fragment et présence de fragments suivants, si l'en-tête est lisible
+pub fn fragment_fields(data: &[u8], h: &Hypothesis) -> Option<(u64, bool)> {
+    let Hypothesis::Fragmented {
+        index_offset,
+        index_width,
+        flag_offset,
+        flag_mask,
+        last_when_set,
+    } = h
+    else {
+        return None;
+    };
+    if data.len() < fragment_header_len(h) {
+        return None;
+    }
+    let index = read_be(data, *index_offset, *index_width)?;
+    let flag = data[*flag_offset] & flag_mask != 0;
+    Some((index, flag != *last_when_set))
+}
+
+/// Proportion des PDUs qui suivent la séquence 0, 1, 2... jusqu'au dernier fragment, et
+/// présence d'au moins un message en plusieurs fragments
+fn sequence_ratio(pdus: &[&[u8]], h: &Hypothesis) -> (f64, bool) {
+    let mut expected = 0;
+    let mut consistent = 0;
+    let mut multi = false;
+    for data in pdus {
+        let Some((index, more)) = fragment_fields(data, h) else {
+            expected = 0;
+            continue;
+        };
+        if index == expected {
+            consistent += 1;
+            multi |= index > 0;
+        }
+        expected = if more { index + 1 } else { 0 };
+    }
+    (consistent as f64 / pdus.len().max(1) as f64, multi)
+}
+
+/// Cherche un en-tête de fragmentation (index + drapeau « autres fragments » ou « dernier
+/// fragment ») cohérent avec l'ordre du corpus
+pub fn detect_fragmentation(corpus: &Corpus) -> Vec<Hypothesis> {
+    let pdus: Vec<&[u8]> = corpus.items.iter().take(DETECTION_SAMPLE).map(|p| p.as_slice()).collect();
+    if pdus.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut found = Vec::new();
+    for index_offset in 0..MAX_HEADER_OFFSET {
+        for index_width in [1, 2] {
+            for flag_offset in 0..MAX_HEADER_OFFSET {
+                // Le drapeau ne peut pas partager un octet avec l'index
+                if (index_offset..index_offset + index_width).contains(&flag_offset) {
+                    continue;
+                }
+                for bit in 0..8 {
+                    for last_when_set in [false, true] {
+                        let h = Hypothesis::Fragmented {
+                            index_offset,
+                            index_width,
+                            flag_offset,
+                            flag_mask: 1 << bit,
+                            last_when_set,
+                        };
+                        let (ratio, multi) = sequence_ratio(&pdus, &h);
+                        if multi && ratio >= FRAGMENT_MIN_RATIO {
+                            found.push(h);
+                            if found.len() == MAX_CANDIDATES {
+                                return found;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Réassemble les messages fragmentés dans l'ordre du corpus ; les messages incomplets sont écartés
+pub fn reassemble(corpus: &Corpus, h: &Hypothesis) -> Option<Corpus> {
+    let header_len = fragment_header_len(h);
+    let mut items = Vec::new();
+    let mut multiplicities = Vec::new();
+    // Message en cours : données, premier fragment, index attendu, troncature
+    let mut current: Option<(Vec<u8>, usize, u64, bool)> = None;
+
+    for (position, pdu) in corpus.items.iter().enumerate() {
+        let data = pdu.as_slice();
+        let Some((index, more)) = fragment_fields(data, h) else {
+            current = None;
+            continue;
+        };
+
+        let payload = &data[header_len..];
+        current = match current.take() {
+            Some((mut message, first, expected, truncated)) if index == expected && index > 0 => {
+                message.extend_from_slice(payload);
+                Some((message, first, index + 1, truncated || pdu.truncated))
+            }
+            // Un index 0 ouvre un nouveau message ; tout autre écart abandonne le message en cours
+            _ if index == 0 => Some((payload.to_vec(), position, 1, pdu.truncated)),
+            _ => None,
+        };
+
+        if !more {
+            if let Some((message, first, _, truncated)) = current.take() {
+                let origin = &corpus.items[first];
+                let len = message.len();
+                items.push(
+                    PduRef::new(message.into(), 0..len)
+                        .with_origin(origin.timestamp, origin.direction)
+                        .with_flow(origin.flow_id)
+                        .with_truncated(truncated)
+                        .derived_from(origin, first, None),
+                );
+                multiplicities.push(corpus.multiplicity(first));
+            }
+        }
+    }
+
+    if items.is_empty() {
+        return None;
+    }
+
+    let total_bytes = items.iter().map(|p| p.len()).sum();
+    let pdu_count = items.len();
+    Some(Corpus::new(
+        items,
+        CorpusMeta {
+            source: format!("{}_reassembled", corpus.meta.source),
+            total_bytes,
+            pdu_count,
+            flow_id: corpus.meta.flow_id,
+            multiplicities: corpus.meta.multiplicities.as_ref().map(|_| multiplicities),
+        },
+    ))
+}
@@ -0,0 +1,160 @@
+use crate::Error;
+use crate::Result;
+
+/// Parse des messages saisis sous forme textuelle.
+///
+/// Formats reconnus (détectés ligne par ligne) :
+/// - sortie `xxd` (`00000010: 4865 6c6c  Hello`) et `hexdump -C` (`00000010  48 65 6c  |Hel|`) :
+///   les lignes d'un même message se suivent, un offset nul ou une ligne vide commence un nouveau message ;
+/// - chaînes échappées (`\x01\x02abc`, éventuellement entourées de `b'...'` ou de guillemets) : un message par ligne ;
+/// - chaînes hexadécimales (`deadbeef`, `de ad be ef`, `de:ad:be:ef`) : un message par ligne.
+///
+/// Les lignes commençant par `#` sont ignorées.
+pub fn parse_hex_text(text: &str) -> Result<Vec<Vec<u8>>> {
+    let mut messages = Vec::new();
+    let mut current: Vec<u8> = Vec::new();
+
+    for (line_no, raw_line) in text.lines().enumerate() {
+        let line = raw_line.trim();
+        let err = |msg: &str| Error::ParseError(format!("line {}: {}", line_no + 1, msg));
+
+        if line.is_empty() || line.starts_with('#') {
+            if !current.is_empty() {
+                messages.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if let Some((offset, bytes)) = parse_dump_line(line).map_err(|e| err(&e))? {
+            if offset == 0 && !current.is_empty() {
+                messages.push(std::mem::take(&mut current));
+            }
+            current.extend(bytes);
+            continue;
+        }
+
+        // Ligne autonome : un message à elle seule
+        if !current.is_empty() {
+            messages.push(std::mem::take(&mut current));
+        }
+        let bytes = if line.contains("\\x") {
+            parse_escaped(line).map_err(|e| err(&e))?
+        } else {
+            parse_hex_string(line).map_err(|e| err(&e))?
+        };
+        if !bytes.is_empty() {
+            messages.push(bytes);
+        }
+    }
+
+    if !current.is_empty() {
+        messages.push(current);
+    }
+
+    Ok(messages)
+}
+
+/// Encode des octets en chaîne hexadécimale minuscule, sans séparateur
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Reconnaît une ligne `xxd` ou `hexdump -C` ; retourne l'offset et les octets de la ligne
+fn parse_dump_line(line: &str) -> std::result::Result<Option<(usize, Vec<u8>)>, String> {
+    let Some((first, rest)) = line.split_once(char::is_whitespace) else {
+        return Ok(None);
+    };
+
+    // xxd : "00000010: 4865 6c6c 6f0a  Hello."
+    if let Some(offset) = first.strip_suffix(':') {
+        let Ok(offset) = usize::from_str_radix(offset, 16) else {
+            return Ok(None);
+        };
+        // La colonne ASCII est séparée des octets par deux espaces
+        let hex = rest.trim_start().split("  ").next().unwrap_or("");
+        return parse_hex_string(hex).map(|bytes| Some((offset, bytes)));
+    }
+
+    // hexdump -C : "00000010  48 65 6c 6c  6f 0a  |Hello.|"
+    if first.len() >= 7 && first.chars().all(|c| c.is_ascii_hexdigit()) && rest.starts_with(' ') {
+        let offset = usize::from_str_radix(first, 16).map_err(|e| e.to_string())?;
+        let hex = rest.split('|').next().unwrap_or("");
+        return parse_hex_string(hex).map(|bytes| Some((offset, bytes)));
+    }
+
+    Ok(None)
+}
+
+/// Parse une chaîne hexadécimale, avec séparateurs optionnels (espaces, `:`, `-`, `0x`)
+pub(crate) fn parse_hex_string(text: &str) -> std::result::Result<Vec<u8>, String> {
+    let digits: String = text
+        .split(|c: char| c.is_whitespace() || c == ':' || c == '-' || c == ',')
+        .map(|token| token.trim_start_matches("0x").trim_start_matches("0X"))
+        .collect();
+
+    if !digits.is_ascii() {
+        return Err(format!("invalid hex string '{}'", text));
+    }
+    if !digits.len().is_multiple_of(2) {
+        return Err(format!("odd number of hex digits in '{}'", text));
+    }
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .map_err(|_| format!("invalid hex byte '{}'", &digits[i..i + 2]))
+        })
+        .collect()
+}
+
+/// Parse une chaîne avec échappements `\xHH` (style C/Python)
+fn parse_escaped(text: &str) -> std::result::Result<Vec<u8>, String> {
+    // Retirer un éventuel préfixe b'...' / "..." / '...'
+    let text = text.strip_prefix('b').filter(|t| t.starts_with(['\'', '"'])).unwrap_or(text);
+    let text = match text.chars().next() {
+        Some(q @ ('\'' | '"')) if text.len() >= 2 && text.ends_with(q) => &text[1..text.len() - 1],
+        _ => text,
+    };
+
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        match bytes.get(i + 1) {
+            Some(b'x') => {
+                let hex = text.get(i + 2..i + 4).ok_or("truncated \\x escape")?;
+                out.push(u8::from_str_radix(hex, 16).map_err(|_| format!("invalid escape '\\x{}'", hex))?);
+                i += 4;
+            }
+            Some(b'n') => {
+                out.push(b'\n');
+                i += 2;
+            }
+            Some(b'r') => {
+                out.push(b'\r');
+                i += 2;
+            }
+            Some(b't') => {
+                out.push(b'\t');
+                i += 2;
+            }
+            Some(b'0') => {
+                out.push(0);
+                i += 2;
+            }
+            Some(&c) => {
+                out.push(c);
+                i += 2;
+            }
+            None => return Err("trailing backslash".to_string()),
+        }
+    }
+
+    Ok(out)
+}
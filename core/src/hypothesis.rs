@@ -1,23 +1,99 @@
 use serde::{Deserialize, Serialize};
 
 /// Hypothèse sur la structure d'une couche protocolaire
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Hypothesis {
-    /// Bundling avec préfixe de longueur
+    /// Bundling avec préfixe de longueur : le message fait `longueur * unit + adjust` octets,
+    /// comptés depuis le début du message si `includes_header`, sinon après le champ
     LengthPrefixBundle {
         offset: usize,
         width: LengthWidth,
         endian: Endianness,
         includes_header: bool,
+        /// Taille de l'unité de longueur en octets (1, ou 2/4 pour des mots)
+        unit: usize,
+        /// Constante ajoutée à la longueur annoncée
+        adjust: i32,
     },
-    /// Bundling avec délimiteur
+    /// Un seul message par PDU : le champ de longueur vaut exactement le nombre d'octets qui le
+    /// suivent jusqu'à la fin de la PDU
+    RemainingLength {
+        offset: usize,
+        width: LengthWidth,
+        endian: Endianness,
+    },
+    /// Trames `[mot de synchronisation][longueur][payload]` (0xAA55 + longueur u16...) ; la
+    /// longueur compte le message entier si `includes_header`, sinon le payload seul
+    SyncLengthBundle {
+        sync: Vec<u8>,
+        width: LengthWidth,
+        endian: Endianness,
+        includes_header: bool,
+    },
+    /// Bundling avec préfixe de longueur encodé en varint (protobuf, QUIC)
+    VarintLengthPrefix {
+        offset: usize,
+        encoding: VarintEncoding,
+    },
+    /// Bundling avec longueur en fin de message : `[payload][longueur][offset octets]`,
+    /// validé en remontant depuis la fin de la PDU
+    LengthSuffixBundle {
+        /// Octets entre la fin du champ de longueur et la fin du message
+        offset: usize,
+        width: LengthWidth,
+        endian: Endianness,
+    },
+    /// Multiplexage de canaux : trames `[canal][longueur][payload]`, chaque canal étant un
+    /// sous-flux indépendant
+    ChannelMux {
+        channel_width: usize,
+        len_width: LengthWidth,
+        endian: Endianness,
+    },
+    /// Fragmentation applicative : index de fragment et drapeau de continuation en en-tête,
+    /// les fragments successifs sont réassemblés en une SDU
+    Fragmented {
+        index_offset: usize,
+        index_width: usize,
+        flag_offset: usize,
+        flag_mask: u8,
+        /// Drapeau « dernier fragment » plutôt que « autres fragments à suivre »
+        last_when_set: bool,
+    },
+    /// Bundling avec délimiteur ; un délimiteur précédé de l'octet d'échappement ou situé
+    /// entre deux caractères de citation ne sépare pas les messages (CSV, commandes AT)
     DelimiterBundle {
         pattern: Vec<u8>,
+        escape: Option<u8>,
+        quote: Option<u8>,
+    },
+    /// Trames séparées par un délimiteur, échappé dans la charge utile (SLIP et assimilés)
+    ByteStuffed {
+        scheme: EscapeScheme,
+    },
+    /// Trames HDLC : fanions 0x7E, échappement 0x7D (octet XOR 0x20), FCS optionnel en fin
+    Hdlc {
+        fcs: Option<ChecksumAlgorithm>,
+    },
+    /// Nom en labels préfixés par leur longueur et terminé par un octet nul (DNS), avec
+    /// pointeurs de compression ; le reste de la PDU est la SDU
+    LabelSequence {
+        offset: usize,
+    },
+    /// Tableau d'enregistrements de taille fixe, éventuellement précédé d'un compteur ;
+    /// chaque enregistrement est une SDU
+    RecordArray {
+        record_len: usize,
+        count_field: Option<CountField>,
     },
     /// En-tête fixe
     FixedHeader {
         len: usize,
     },
+    /// Trailer fixe (CRC, marqueur de fin) en fin de PDU
+    FixedTrailer {
+        len: usize,
+    },
     /// Bitmap extensible (PER-like)
     ExtensibleBitmap {
         start: usize,
@@ -32,45 +108,498 @@ pub enum Hypothesis {
         len_offset: usize,  // Offset relatif au début du tag
         len_rule: TlvLenRule,
         length_includes_header: bool, // Si true, le length inclut tag+length
+        tag_rule: TlvTagRule,
+        /// Endianness des longueurs de 2 ou 4 octets
+        endian: Endianness,
     },
     /// Varint fields (protobuf-like)
     VarintKeyWireType {
         key_max_bytes: usize,
         allow_embedded: bool,
     },
+    /// Champ constant (magic) : mêmes octets à la même position dans toutes les PDUs
+    ConstantField {
+        offset: usize,
+        value: Vec<u8>,
+    },
+    /// Discriminant de type de message : chaque valeur a sa propre structure
+    TypeSwitch {
+        type_offset: usize,
+        /// Taille du discriminant en octets (1 ou 2, big-endian)
+        type_width: usize,
+    },
+    /// Protocole texte ligne par ligne (verbe puis arguments séparés par espaces ou ':')
+    TextLines {
+        line_ending: LineEnding,
+    },
+    /// Bloc d'en-têtes `Nom: Valeur` terminé par une ligne vide, suivi d'un corps (SDU)
+    KeyValueHeader {
+        line_ending: LineEnding,
+    },
+    /// Messages à en-têtes texte et corps binaire (SIP, RTSP) : le corps fait la taille annoncée
+    /// par l'en-tête `length_header`, plusieurs messages peuvent se suivre dans une PDU
+    ContentLengthMessages {
+        line_ending: LineEnding,
+        length_header: String,
+    },
+    /// Couche terminale de texte structuré (documents JSON ou XML)
+    StructuredText {
+        format: StructuredFormat,
+    },
+    /// Charge utile encodée en texte (base64, hex) : la couche suivante porte sur les octets décodés
+    Encoded {
+        encoding: PayloadEncoding,
+    },
+    /// Charge utile compressée : la couche suivante porte sur les octets décompressés
+    Compressed {
+        algorithm: CompressionAlgorithm,
+    },
+    /// Charge utile obfusquée par une clé simple : la couche suivante porte sur les octets
+    /// désobfusqués
+    Obfuscated {
+        obfuscation: Obfuscation,
+    },
+    /// Couche terminale opaque : octets quasi uniformes qu'aucune hypothèse ne structure
+    /// (probablement chiffrés ou compressés)
+    Opaque,
+    /// Bourrage final : série d'un même octet en fin de PDU, retirée avant les autres hypothèses
+    TrailingPadding {
+        pad_byte: u8,
+    },
+    /// Champs de bits dans les `header_len` premiers octets (version, flags, type en quartets...)
+    BitFields {
+        header_len: usize,
+        /// Bits de début de chaque champ, depuis le bit de poids fort du premier octet
+        boundaries: Vec<usize>,
+    },
+    /// Champ de checksum validé sur le corpus
+    Checksum {
+        algorithm: ChecksumAlgorithm,
+        endian: Endianness,
+        location: ChecksumLocation,
+    },
+    /// Composition évaluée d'un seul tenant dans une même couche : chaque hypothèse découpe les
+    /// SDUs de la précédente (en-tête fixe, puis messages préfixés par leur longueur...)
+    Composite(Vec<Hypothesis>),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum LengthWidth {
     One = 1,
     Two = 2,
     Four = 4,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Endianness {
     Little,
     Big,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Champ donnant le nombre d'enregistrements d'un tableau ; les enregistrements suivent le champ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CountField {
+    pub offset: usize,
+    pub width: LengthWidth,
+    pub endian: Endianness,
+}
+
+impl CountField {
+    /// Début du premier enregistrement
+    pub fn end(&self) -> usize {
+        self.offset + self.width as usize
+    }
+}
+
+/// Encodage d'un entier de longueur variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum VarintEncoding {
+    /// LEB128 (protobuf) : 7 bits par octet, bit de poids fort = octets suivants
+    Leb128,
+    /// Varint QUIC (RFC 9000) : les 2 bits de poids fort donnent la taille (1, 2, 4 ou 8 octets)
+    Quic,
+}
+
+impl VarintEncoding {
+    /// Décode un varint au début de `data` : valeur et nombre d'octets consommés
+    pub fn read(&self, data: &[u8]) -> Option<(u64, usize)> {
+        match self {
+            VarintEncoding::Leb128 => {
+                let mut value = 0u64;
+                for (i, &byte) in data.iter().take(10).enumerate() {
+                    value |= ((byte & 0x7f) as u64) << (7 * i);
+                    if byte & 0x80 == 0 {
+                        return Some((value, i + 1));
+                    }
+                }
+                None
+            }
+            VarintEncoding::Quic => {
+                let len = 1 << (data.first()? >> 6);
+                let bytes = data.get(..len)?;
+                let value = bytes[1..].iter().fold((bytes[0] & 0x3f) as u64, |acc, &b| (acc << 8) | b as u64);
+                Some((value, len))
+            }
+        }
+    }
+}
+
+/// Encodage du tag d'un TLV
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TlvTagRule {
+    /// Tag de `tag_bytes` octets
+    Fixed,
+    /// Tag BER (X.690 §8.1.2) : un numéro 0x1F dans le premier octet annonce des octets de
+    /// suite, le bit de poids fort marquant la continuation ; `len_offset` est décalé d'autant
+    BerMultiByte,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum TlvLenRule {
     DefiniteShort,    // 1 byte
     DefiniteMedium,   // 2 bytes
     DefiniteLong,     // 4 bytes
     IndefiniteWithEoc,
+    /// Longueur définie BER (X.690) : forme courte (< 0x80) ou longue (0x81..0x84 + 1 à 4 octets)
+    BerDefinite,
+}
+
+/// Schéma d'échappement d'un tramage par byte-stuffing
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct EscapeScheme {
+    /// Octet délimitant les trames
+    pub delimiter: u8,
+    /// Octet introduisant une séquence d'échappement
+    pub escape: u8,
+    /// Correspondance code suivant l'échappement -> octet d'origine
+    pub table: Vec<(u8, u8)>,
+}
+
+/// Terminaison de ligne d'un protocole texte
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LineEnding {
+    /// `\r\n`
+    CrLf,
+    /// `\n` seul
+    Lf,
+}
+
+impl LineEnding {
+    pub fn bytes(&self) -> &'static [u8] {
+        match self {
+            LineEnding::CrLf => b"\r\n",
+            LineEnding::Lf => b"\n",
+        }
+    }
+}
+
+/// Format d'un document texte structuré
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum StructuredFormat {
+    Json,
+    Xml,
+}
+
+/// Encodage textuel d'une charge utile binaire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum PayloadEncoding {
+    /// Base64 standard (`+/`, padding `=`)
+    Base64,
+    /// Base64 URL-safe (`-_`, padding optionnel)
+    Base64Url,
+    /// Hexadécimal (deux chiffres par octet)
+    Hex,
+}
+
+/// Obfuscation simple d'une charge utile
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Obfuscation {
+    /// XOR avec une clé répétée depuis le début de la PDU (un octet : XOR simple)
+    Xor { key: Vec<u8> },
+    /// Addition d'un octet constant modulo 256
+    Add { key: u8 },
+}
+
+/// Algorithmes de compression reconnus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum CompressionAlgorithm {
+    /// Flux gzip (RFC 1952)
+    Gzip,
+    /// Flux zlib (RFC 1950)
+    Zlib,
+    /// Deflate brut (RFC 1951), sans en-tête
+    Deflate,
+    /// Trame zstd (RFC 8878)
+    Zstd,
+}
+
+/// Algorithmes de checksum reconnus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ChecksumAlgorithm {
+    /// Somme des octets modulo 256
+    Sum8,
+    /// Somme des octets modulo 65536
+    Sum16,
+    /// XOR de tous les octets
+    Xor8,
+    /// Checksum Internet (RFC 1071) : complément à un de la somme des mots de 16 bits
+    Internet16,
+    /// CRC-8 (polynôme 0x07)
+    Crc8,
+    /// CRC-16/CCITT-FALSE (polynôme 0x1021, init 0xFFFF)
+    Crc16Ccitt,
+    /// CRC-16/MODBUS (polynôme 0x8005 réfléchi, init 0xFFFF)
+    Crc16Modbus,
+    /// CRC-16/X-25, FCS-16 de HDLC (polynôme 0x1021 réfléchi, init et XOR final 0xFFFF)
+    Crc16X25,
+    /// CRC-32 IEEE 802.3 (polynôme 0x04C11DB7 réfléchi)
+    Crc32,
+}
+
+/// Emplacement d'un champ de checksum dans la PDU
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ChecksumLocation {
+    /// Champ à `offset` couvrant tous les octets qui le suivent
+    Header { offset: usize },
+    /// Champ en fin de PDU couvrant les octets de `covered_start` jusqu'au champ
+    Trailer { covered_start: usize },
 }
 
 impl Hypothesis {
     pub fn name(&self) -> &'static str {
         match self {
             Hypothesis::LengthPrefixBundle { .. } => "LengthPrefixBundle",
+            Hypothesis::RemainingLength { .. } => "RemainingLength",
+            Hypothesis::SyncLengthBundle { .. } => "SyncLengthBundle",
+            Hypothesis::VarintLengthPrefix { .. } => "VarintLengthPrefix",
+            Hypothesis::LengthSuffixBundle { .. } => "LengthSuffixBundle",
+            Hypothesis::ChannelMux { .. } => "ChannelMux",
+            Hypothesis::Fragmented { .. } => "Fragmented",
             Hypothesis::DelimiterBundle { .. } => "DelimiterBundle",
+            Hypothesis::ByteStuffed { .. } => "ByteStuffed",
+            Hypothesis::Hdlc { .. } => "Hdlc",
+            Hypothesis::LabelSequence { .. } => "LabelSequence",
+            Hypothesis::RecordArray { .. } => "RecordArray",
             Hypothesis::FixedHeader { .. } => "FixedHeader",
+            Hypothesis::FixedTrailer { .. } => "FixedTrailer",
             Hypothesis::ExtensibleBitmap { .. } => "ExtensibleBitmap",
             Hypothesis::Tlv { .. } => "TLV",
             Hypothesis::VarintKeyWireType { .. } => "VarintKeyWireType",
+            Hypothesis::ConstantField { .. } => "ConstantField",
+            Hypothesis::TypeSwitch { .. } => "TypeSwitch",
+            Hypothesis::TextLines { .. } => "TextLines",
+            Hypothesis::KeyValueHeader { .. } => "KeyValueHeader",
+            Hypothesis::ContentLengthMessages { .. } => "ContentLengthMessages",
+            Hypothesis::StructuredText { .. } => "StructuredText",
+            Hypothesis::Encoded { .. } => "Encoded",
+            Hypothesis::Compressed { .. } => "Compressed",
+            Hypothesis::Obfuscated { .. } => "Obfuscated",
+            Hypothesis::Opaque => "Opaque",
+            Hypothesis::TrailingPadding { .. } => "TrailingPadding",
+            Hypothesis::BitFields { .. } => "BitFields",
+            Hypothesis::Checksum { .. } => "Checksum",
+            Hypothesis::Composite(_) => "Composite",
+        }
+    }
+
+    /// Forme canonique : les paramètres sans effet sur le parsing prennent une valeur fixe, de
+    /// sorte que deux hypothèses équivalentes aient la même forme.
+    ///
+    /// L'endianness d'un champ d'un seul octet est sans effet (ramenée à big-endian), de même
+    /// que celle d'un TLV dont les longueurs ne font pas 2 ou 4 octets.
+    pub fn canonical(self) -> Self {
+        let single = |width: LengthWidth, endian: Endianness| {
+            if width == LengthWidth::One {
+                Endianness::Big
+            } else {
+                endian
+            }
+        };
+        match self {
+            Hypothesis::LengthPrefixBundle { offset, width, endian, includes_header, unit, adjust } => {
+                Hypothesis::LengthPrefixBundle {
+                    offset,
+                    width,
+                    endian: single(width, endian),
+                    includes_header,
+                    unit,
+                    adjust,
+                }
+            }
+            Hypothesis::RemainingLength { offset, width, endian } => Hypothesis::RemainingLength {
+                offset,
+                width,
+                endian: single(width, endian),
+            },
+            Hypothesis::SyncLengthBundle { sync, width, endian, includes_header } => Hypothesis::SyncLengthBundle {
+                sync,
+                width,
+                endian: single(width, endian),
+                includes_header,
+            },
+            Hypothesis::LengthSuffixBundle { offset, width, endian } => Hypothesis::LengthSuffixBundle {
+                offset,
+                width,
+                endian: single(width, endian),
+            },
+            Hypothesis::ChannelMux { channel_width, len_width, endian } => Hypothesis::ChannelMux {
+                channel_width,
+                len_width,
+                endian: single(len_width, endian),
+            },
+            Hypothesis::RecordArray {
+                record_len,
+                count_field: Some(count),
+            } => Hypothesis::RecordArray {
+                record_len,
+                count_field: Some(CountField {
+                    endian: single(count.width, count.endian),
+                    ..count
+                }),
+            },
+            Hypothesis::Tlv {
+                tag_offset,
+                tag_bytes,
+                len_offset,
+                len_rule,
+                length_includes_header,
+                tag_rule,
+                endian,
+            } => Hypothesis::Tlv {
+                tag_offset,
+                tag_bytes,
+                len_offset,
+                len_rule,
+                length_includes_header,
+                tag_rule,
+                endian: match len_rule {
+                    TlvLenRule::DefiniteMedium | TlvLenRule::DefiniteLong => endian,
+                    _ => Endianness::Big,
+                },
+            },
+            Hypothesis::Checksum { algorithm, endian, location } => Hypothesis::Checksum {
+                algorithm,
+                endian: if algorithm.width() == 1 { Endianness::Big } else { endian },
+                location,
+            },
+            // Une composition d'une seule hypothèse est cette hypothèse
+            Hypothesis::Composite(mut parts) if parts.len() == 1 => parts.remove(0).canonical(),
+            Hypothesis::Composite(parts) => {
+                Hypothesis::Composite(parts.into_iter().map(Hypothesis::canonical).collect())
+            }
+            other => other,
         }
     }
-}
 
+    /// Hypothèses voisines pour l'affinage local : offsets et tailles à ±1, autres largeurs de
+    /// champ, endianness et inclusion de l'en-tête inversées. Les hypothèses sans paramètre
+    /// positionnel (texte, encodages, délimiteurs...) n'ont pas de voisines.
+    pub fn neighbors(&self) -> Vec<Hypothesis> {
+        let near = |v: usize| [v.checked_sub(1), v.checked_add(1)].into_iter().flatten();
+        let widths = |w: LengthWidth| {
+            [LengthWidth::One, LengthWidth::Two, LengthWidth::Four].into_iter().filter(move |&o| o != w)
+        };
+        let flip = |e: Endianness| match e {
+            Endianness::Big => Endianness::Little,
+            Endianness::Little => Endianness::Big,
+        };
+        let mut neighbors = Vec::new();
+        match self {
+            Hypothesis::LengthPrefixBundle { offset, width, endian, includes_header, unit, adjust } => {
+                let base = |offset, width, endian, includes_header| Hypothesis::LengthPrefixBundle {
+                    offset,
+                    width,
+                    endian,
+                    includes_header,
+                    unit: *unit,
+                    adjust: *adjust,
+                };
+                neighbors.extend(near(*offset).map(|o| base(o, *width, *endian, *includes_header)));
+                neighbors.extend(widths(*width).map(|w| base(*offset, w, *endian, *includes_header)));
+                neighbors.push(base(*offset, *width, flip(*endian), *includes_header));
+                neighbors.push(base(*offset, *width, *endian, !includes_header));
+            }
+            Hypothesis::RemainingLength { offset, width, endian } => {
+                let base = |offset, width, endian| Hypothesis::RemainingLength { offset, width, endian };
+                neighbors.extend(near(*offset).map(|o| base(o, *width, *endian)));
+                neighbors.extend(widths(*width).map(|w| base(*offset, w, *endian)));
+                neighbors.push(base(*offset, *width, flip(*endian)));
+            }
+            Hypothesis::LengthSuffixBundle { offset, width, endian } => {
+                let base = |offset, width, endian| Hypothesis::LengthSuffixBundle { offset, width, endian };
+                neighbors.extend(near(*offset).map(|o| base(o, *width, *endian)));
+                neighbors.extend(widths(*width).map(|w| base(*offset, w, *endian)));
+                neighbors.push(base(*offset, *width, flip(*endian)));
+            }
+            Hypothesis::VarintLengthPrefix { offset, encoding } => {
+                neighbors.extend(
+                    near(*offset).map(|offset| Hypothesis::VarintLengthPrefix { offset, encoding: *encoding }),
+                );
+            }
+            Hypothesis::LabelSequence { offset } => {
+                neighbors.extend(near(*offset).map(|offset| Hypothesis::LabelSequence { offset }));
+            }
+            Hypothesis::FixedHeader { len } => {
+                neighbors.extend(near(*len).filter(|&len| len > 0).map(|len| Hypothesis::FixedHeader { len }));
+            }
+            Hypothesis::FixedTrailer { len } => {
+                neighbors.extend(near(*len).filter(|&len| len > 0).map(|len| Hypothesis::FixedTrailer { len }));
+            }
+            Hypothesis::RecordArray { record_len, count_field } => {
+                neighbors.extend(
+                    near(*record_len)
+                        .filter(|&len| len > 0)
+                        .map(|record_len| Hypothesis::RecordArray { record_len, count_field: *count_field }),
+                );
+            }
+            Hypothesis::ExtensibleBitmap { start, cont_bit, stop_value, max_bytes } => {
+                neighbors.extend(near(*start).map(|start| Hypothesis::ExtensibleBitmap {
+                    start,
+                    cont_bit: *cont_bit,
+                    stop_value: *stop_value,
+                    max_bytes: *max_bytes,
+                }));
+            }
+            Hypothesis::TypeSwitch { type_offset, type_width } => {
+                neighbors.extend(
+                    near(*type_offset).map(|type_offset| Hypothesis::TypeSwitch { type_offset, type_width: *type_width }),
+                );
+            }
+            Hypothesis::Tlv {
+                tag_offset,
+                tag_bytes,
+                len_offset,
+                len_rule,
+                length_includes_header,
+                tag_rule,
+                endian,
+            } => {
+                let base = |tag_offset, len_offset, length_includes_header, endian| Hypothesis::Tlv {
+                    tag_offset,
+                    tag_bytes: *tag_bytes,
+                    len_offset,
+                    len_rule: *len_rule,
+                    length_includes_header,
+                    tag_rule: *tag_rule,
+                    endian,
+                };
+                neighbors.extend(near(*tag_offset).map(|o| base(o, *len_offset, *length_includes_header, *endian)));
+                neighbors.extend(near(*len_offset).map(|o| base(*tag_offset, o, *length_includes_header, *endian)));
+                neighbors.push(base(*tag_offset, *len_offset, !length_includes_header, *endian));
+                neighbors.push(base(*tag_offset, *len_offset, *length_includes_header, flip(*endian)));
+            }
+            // Chaque partie est affinée à son tour, les autres restant inchangées
+            Hypothesis::Composite(parts) => {
+                for (i, part) in parts.iter().enumerate() {
+                    neighbors.extend(part.neighbors().into_iter().map(|neighbor| {
+                        let mut parts = parts.clone();
+                        parts[i] = neighbor;
+                        Hypothesis::Composite(parts)
+                    }));
+                }
+            }
+            _ => {}
+        }
+        neighbors
+    }
+}
@@ -1,13 +1,44 @@
-use crate::corpus::{Corpus, PduRef};
+use crate::checkpoint::{corpus_digest, Checkpoint, ExploredKey, ExploredScores};
+use crate::clustering::{cluster_pdus, ClusterConfig};
+use crate::compression::detect_compression;
+use crate::corpus::{Corpus, Direction, PduRef};
+use crate::fields::{analyze_fields, FieldAnalysis};
 use crate::hypothesis::Hypothesis;
-use crate::parser::ParsedCorpus;
+use crate::measures::{classify_opaque, CompressionBackend, OpaqueAnalysis};
+use crate::padding::{detect_padding, PaddingAnalysis};
+use crate::parser::{ParsedCorpus, Parser};
 use crate::plugin::PluginRegistry;
+use crate::plugins::parsers::{CompressedParser, ObfuscatedParser, TrailingPaddingParser};
+use crate::plugins::text::{
+    detect_encoding, detect_structured_text, EncodedParser, StructuredText, StructuredTextParser,
+};
+use crate::progress::{CancellationToken, Progress, ProgressSink};
 use crate::score::Score;
+use crate::state_machine::StateMachine;
 use rayon::prelude::*;
-use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Graine de la répartition des PDUs en plis de validation croisée
+const CROSS_VALIDATION_SEED: u64 = 0;
+/// Nombre minimal de PDUs d'un flow pour qu'il soit inféré seul et participe au consensus
+const CONSENSUS_MIN_FLOW_PDUS: usize = 4;
+/// Graine du tirage de l'échantillon des pré-filtres
+const PREFILTER_SEED: u64 = 0;
+/// Nombre de meilleures hypothèses dont les voisines sont évaluées au premier tour d'affinage
+const REFINED_HYPOTHESES: usize = 3;
+/// Graine de l'échantillon stratifié tiré quand le corpus dépasse le budget mémoire
+const MEMORY_SAMPLE_SEED: u64 = 0;
+/// Nombre minimal de PDUs gardées par le sous-échantillonnage, quel que soit le budget mémoire
+const MIN_SAMPLED_PDUS: usize = 32;
+/// Estimation (octets) du découpage d'une PDU : la PDU parsée et quatre segments
+const PARSED_PDU_ESTIMATE: usize =
+    std::mem::size_of::<crate::parser::ParsedPdu>() + 4 * std::mem::size_of::<crate::segment::Segment>();
 
 /// Résultat d'une hypothèse testée
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct HypothesisResult {
     pub hypothesis: Hypothesis,
     pub score: Score,
@@ -15,7 +46,7 @@ pub struct HypothesisResult {
 }
 
 /// Résultat d'une couche d'inférence
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Layer {
     pub hypothesis: Hypothesis,
     pub score: Score,
@@ -23,68 +54,418 @@ pub struct Layer {
     pub sdu_corpus: Option<Corpus>,
     /// Toutes les hypothèses testées pour cette couche (top-K)
     pub all_hypotheses: Vec<HypothesisResult>,
+    /// Analyse des champs d'en-tête de la couche
+    pub fields: FieldAnalysis,
+    /// Sous-structures par valeur de discriminant (hypothèse TypeSwitch)
+    pub branches: Vec<TypeBranch>,
+    /// Schéma de la couche terminale de texte structuré (JSON/XML)
+    pub structured: Option<StructuredText>,
+    /// Indices de la couche terminale opaque (chiffrée ou compressée)
+    pub opaque: Option<OpaqueAnalysis>,
+    /// Octet et distribution des longueurs du bourrage final retiré par la couche
+    pub padding: Option<PaddingAnalysis>,
+    /// Confiance dans l'hypothèse retenue face aux autres hypothèses évaluées
+    pub confidence: Option<Confidence>,
+    /// Rang de l'hypothèse retenue dans `all_hypotheses` (non nul après un retour arrière)
+    pub rank: usize,
 }
 
-// Implémentation manuelle de Serialize pour HypothesisResult
-impl serde::Serialize for HypothesisResult {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("HypothesisResult", 3)?;
-        state.serialize_field("hypothesis", &self.hypothesis)?;
-        state.serialize_field("score", &self.score)?;
-        state.serialize_field("parsed_pdu_count", &self.parsed.parsed_pdus.len())?;
-        state.end()
-    }
+/// Confiance dans l'hypothèse retenue pour une couche, d'après l'écart de score avec les
+/// hypothèses suivantes.
+///
+/// Un score MDL est une longueur de description : une hypothèse plus courte de `m` bits est
+/// `2^m` fois plus probable, ce qui tient lieu de facteur de Bayes.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Confidence {
+    /// Écart de score en bits avec la deuxième hypothèse (`None` sans rivale de score fini),
+    /// négatif quand un retour arrière a écarté une hypothèse mieux classée
+    pub margin_bits: Option<f64>,
+    /// log10 du facteur de Bayes entre l'hypothèse retenue et la deuxième
+    pub log10_bayes_factor: Option<f64>,
+    /// Probabilité de l'hypothèse retenue parmi les hypothèses évaluées (poids `2^-score`)
+    pub probability: f64,
+    /// Hypothèses d'une autre nature que la retenue à moins de `epsilon` bits
+    pub rivals: Vec<Hypothesis>,
+    /// Vrai si au moins une rivale de nature différente est à moins de `epsilon` bits
+    pub ambiguous: bool,
 }
 
-// Implémentation manuelle de Serialize pour Layer
-impl serde::Serialize for Layer {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("Layer", 5)?;
-        state.serialize_field("hypothesis", &self.hypothesis)?;
-        state.serialize_field("score", &self.score)?;
-        state.serialize_field("parsed_pdu_count", &self.parsed.parsed_pdus.len())?;
-        state.serialize_field("has_sdu_corpus", &self.sdu_corpus.is_some())?;
-        state.serialize_field("all_hypotheses_count", &self.all_hypotheses.len())?;
-        state.end()
+impl Confidence {
+    /// Confiance dans la première de `ranked` (triées par score croissant) ; `None` si elle
+    /// n'a pas de score fini
+    pub fn compute(ranked: &[HypothesisResult], epsilon: f64) -> Option<Self> {
+        let (best, rest) = ranked.split_first()?;
+        let best_bits = best.score.total_bits;
+        if !best_bits.is_finite() {
+            return None;
+        }
+        let finite = || rest.iter().filter(|r| r.score.total_bits.is_finite());
+
+        let margin_bits = finite().map(|r| r.score.total_bits - best_bits).reduce(f64::min);
+        // Probabilités normalisées depuis la meilleure pour éviter le dépassement de 2^-score
+        let weight: f64 = finite().map(|r| (-(r.score.total_bits - best_bits)).exp2()).sum();
+        let rivals: Vec<Hypothesis> = finite()
+            .filter(|r| r.score.total_bits - best_bits <= epsilon && r.hypothesis.name() != best.hypothesis.name())
+            .map(|r| r.hypothesis.clone())
+            .collect();
+
+        Some(Self {
+            margin_bits,
+            log10_bayes_factor: margin_bits.map(|m| m * std::f64::consts::LOG10_2),
+            probability: 1.0 / (1.0 + weight),
+            ambiguous: !rivals.is_empty(),
+            rivals,
+        })
     }
 }
 
-/// Résultat complet de l'inférence
-#[derive(Debug, Clone)]
+/// Sous-structure inférée pour une valeur de discriminant : type de message (TypeSwitch)
+/// ou canal (ChannelMux)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TypeBranch {
+    pub type_value: u64,
+    /// Nombre de PDUs (TypeSwitch) ou de trames (ChannelMux) de la branche
+    pub pdu_count: usize,
+    pub layers: Vec<Layer>,
+}
+
+/// Résultat complet de l'inférence.
+///
+/// La sérialisation est complète (découpage des PDUs, corpus de SDUs, hypothèses évaluées) :
+/// un résultat enregistré se relit par [`InferenceResult::from_json`] sans relancer l'inférence.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct InferenceResult {
     pub layers: Vec<Layer>,
     pub corpus: Corpus,
+    /// Succession des types de messages par flow, si la première couche distingue des types
+    pub state_machine: Option<StateMachine>,
+    /// Vrai si le budget de temps ou d'hypothèses a interrompu l'inférence : les couches sont
+    /// celles établies avant l'interruption
+    pub budget_exhausted: bool,
+    /// Nombre de PDUs du corpus d'origine quand le budget mémoire a imposé de l'échantillonner
+    /// (`corpus` est alors l'échantillon)
+    pub sampled_from: Option<usize>,
 }
 
-// Implémentation manuelle de Serialize pour InferenceResult
-impl serde::Serialize for InferenceResult {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("InferenceResult", 2)?;
-        state.serialize_field("layers", &self.layers)?;
-        state.serialize_field("corpus_pdu_count", &self.corpus.len())?;
-        state.serialize_field("corpus_total_bytes", &self.corpus.total_bytes())?;
-        state.end()
+impl InferenceResult {
+    /// Résultat de l'inférence des couches `layers` sur `corpus`, avec l'automate des types de messages
+    pub fn new(layers: Vec<Layer>, corpus: Corpus) -> Self {
+        let state_machine = StateMachine::from_layers(&corpus, &layers);
+        Self {
+            layers,
+            corpus,
+            state_machine,
+            budget_exhausted: false,
+            sampled_from: None,
+        }
+    }
+
+    pub fn from_json(text: &str) -> crate::Result<Self> {
+        serde_json::from_str(text).map_err(|e| crate::Error::ParseError(format!("inference result: {}", e)))
+    }
+
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> crate::Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
     }
+
+    /// Corpus reçu par la couche `depth` : le corpus analysé pour la première, le corpus de
+    /// SDUs de la précédente pour les suivantes
+    pub fn layer_corpus(&self, depth: usize) -> Option<&Corpus> {
+        match depth {
+            0 => Some(&self.corpus),
+            _ => self.layers.get(depth - 1)?.sdu_corpus.as_ref(),
+        }
+    }
+
+    /// PDU du corpus analysé (`corpus`) d'où viennent les octets `range` de la PDU `index`
+    /// reçue par la couche `depth`, et leur position dans cette PDU ; None si les octets ont
+    /// été décodés, décompressés ou réassemblés en chemin
+    pub fn resolve_to_origin(
+        &self,
+        depth: usize,
+        index: usize,
+        range: std::ops::Range<usize>,
+    ) -> Option<(usize, std::ops::Range<usize>)> {
+        let corpus = self.layer_corpus(depth)?;
+        let pdu = corpus.items.get(index)?;
+        match depth {
+            0 => Some((index, range)),
+            _ => self.corpus.locate(pdu, range),
+        }
+    }
+
+    /// Fichier `.proto` esquissé d'après la première couche parsée en clés varint (voir
+    /// [`crate::protobuf::ProtoMessage::infer`]) ; None sans couche protobuf
+    pub fn proto_file(&self) -> Option<String> {
+        self.layers.iter().enumerate().find_map(|(depth, layer)| {
+            let Hypothesis::VarintKeyWireType { key_max_bytes, .. } = layer.hypothesis else {
+                return None;
+            };
+            let corpus = self.layer_corpus(depth)?;
+            crate::protobuf::ProtoMessage::infer(corpus, &layer.parsed, "Message", key_max_bytes).map(|m| m.to_proto())
+        })
+    }
+
+    /// Recherche dans chaque couche les adresses IP et MAC vues dans la capture.
+    ///
+    /// Chaque couche est examinée sur les PDUs qu'elle a reçues ; les branches, dont le
+    /// corpus n'est pas conservé, ne le sont pas.
+    pub fn annotate_addresses(&mut self, addresses: &crate::addresses::CaptureAddresses) {
+        let mut input = Some(self.corpus.clone());
+        for layer in &mut self.layers {
+            let Some(corpus) = input.take() else {
+                break;
+            };
+            layer.fields.addresses = crate::addresses::address_fields(&corpus, addresses);
+            input = layer.sdu_corpus.clone();
+        }
+    }
+}
+
+/// Pile de couches inférée pour une direction d'une conversation
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DirectionStack {
+    pub direction: Direction,
+    pub result: InferenceResult,
+}
+
+/// Synthèse des piles des deux directions
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct DirectionSummary {
+    /// Nombre de PDUs par direction, dans l'ordre des piles
+    pub pdu_counts: Vec<usize>,
+    /// Nombre de premières couches de même hypothèse dans toutes les directions
+    pub shared_layers: usize,
+    /// Vrai si toutes les directions ont exactement la même pile
+    pub symmetric: bool,
+    /// PDUs sans direction connue, écartées de l'inférence
+    pub undirected_pdus: usize,
+}
+
+/// Résultat de l'inférence menée séparément pour chaque direction
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DirectionalResult {
+    pub stacks: Vec<DirectionStack>,
+    pub summary: DirectionSummary,
+}
+
+/// Groupe de PDUs de même type de message, inféré séparément
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MessageCluster {
+    /// Indices des PDUs du groupe dans le corpus d'origine
+    pub indices: Vec<usize>,
+    pub result: InferenceResult,
+}
+
+/// Résultat de l'inférence menée séparément pour chaque groupe de messages
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ClusteredResult {
+    pub clusters: Vec<MessageCluster>,
+}
+
+/// Pile inférée pour un flow, et nombre de couches du consensus qu'elle partage
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FlowStack {
+    pub flow_id: usize,
+    pub result: InferenceResult,
+    /// Nombre de premières couches du consensus retenues aussi par ce flow
+    pub agreeing_layers: usize,
+}
+
+/// Hypothèse retenue à une profondeur par la majorité des flows
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConsensusLayer {
+    pub hypothesis: Hypothesis,
+    /// Flows qui retiennent cette hypothèse à cette profondeur
+    pub agreeing_flows: usize,
+    /// Flows encore en accord aux profondeurs précédentes et qui ont une couche à celle-ci
+    pub candidate_flows: usize,
+    /// Proportion des flows candidats qui retiennent l'hypothèse
+    pub agreement: f64,
+    /// Autres hypothèses retenues par les flows candidats, et leur nombre de flows
+    pub dissent: Vec<(Hypothesis, usize)>,
+}
+
+/// Résultat de l'inférence menée séparément pour chaque flow, avec le modèle de consensus
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConsensusResult {
+    pub flows: Vec<FlowStack>,
+    /// Couches successives sur lesquelles les flows s'accordent, depuis la première
+    pub consensus: Vec<ConsensusLayer>,
+    /// Flows trop courts pour être inférés seuls
+    pub skipped_flows: usize,
+    /// PDUs sans flow d'origine, écartées
+    pub unassigned_pdus: usize,
 }
 
 /// Moteur d'inférence récursive
+#[derive(Clone)]
 pub struct InferenceEngine {
     pub max_depth: usize,
     pub top_k: usize,
     pub min_gain_epsilon: f64,
     pub min_sdu_size: usize,
+    /// Infère séparément chaque version quand un champ de version est détecté
+    pub split_by_version: bool,
+    /// Nombre de plis de la validation croisée (0 : hypothèses évaluées sur tout le corpus)
+    pub cross_validation_folds: usize,
+    /// Écart de score (bits) en deçà duquel une hypothèse d'une autre nature rend la couche ambiguë
+    pub ambiguity_epsilon: f64,
+    /// Modèle de compression du score « raw », à accorder avec celui du scoreur
+    pub compression: CompressionBackend,
+    /// Nombre maximal de nouvelles tentatives avec une hypothèse moins bien classée à une
+    /// couche déjà choisie, quand les couches suivantes n'expliquent plus les données
+    pub backtrack_budget: usize,
+    /// Destinataire des rapports d'avancement, à chaque hypothèse évaluée
+    pub progress: Option<Arc<dyn ProgressSink>>,
+    /// Jeton consulté avant chaque hypothèse : une fois annulé, l'inférence rend les couches établies
+    pub cancellation: Option<CancellationToken>,
+    /// Durée maximale d'un appel à `infer`, branches et retours arrière compris
+    pub time_budget: Option<Duration>,
+    /// Nombre maximal d'hypothèses évaluées par un appel à `infer`
+    pub max_hypotheses: Option<usize>,
+    /// Taille minimale des segments `Field` (valeurs TLV, chaînes de longueur...) dans lesquels
+    /// la récursion descend aussi, comme dans les SDUs (None : SDUs seules)
+    pub field_recursion: Option<usize>,
+    /// Nombre de PDUs de l'échantillon des pré-filtres (`Parser::plausible`), tiré à chaque
+    /// couche (0 : pas de pré-filtre)
+    pub prefilter_sample: usize,
+    /// Nombre de tours d'affinage local autour des meilleures hypothèses (0 : grille des
+    /// générateurs seule)
+    pub refinement_rounds: usize,
+    /// Mémoire (octets) que l'inférence devrait occuper : au-delà, le corpus est échantillonné
+    /// et seul le découpage de l'hypothèse retenue est gardé à chaque couche (None : sans limite)
+    pub memory_budget: Option<usize>,
+    /// Marge (bits par octet de l'échantillon des pré-filtres) du tri préalable : les hypothèses
+    /// sont d'abord scorées sur l'échantillon, et seules les `top_k` meilleures et celles à moins
+    /// de cette marge de la meilleure sont parsées et scorées sur tout le corpus (None : pas de tri)
+    pub screening_margin: Option<f64>,
+    /// Hypothèses imposées aux premières couches, dans l'ordre (modèle connu, voir
+    /// [`crate::spec::ModelSpec`]) : elles sont parsées et retenues sans mise en concurrence ni
+    /// seuil de gain, et l'inférence ne reprend qu'au-delà
+    pub pinned_layers: Vec<Hypothesis>,
+    /// Scores déjà calculés (voir [`InferenceEngine::checkpoint`]), partagés par les branches
+    /// et les retours arrière (None : pas de point de reprise)
+    pub(crate) explored: Option<Arc<Mutex<ExploredScores>>>,
+    /// Budget en cours, partagé par les branches et les retours arrière de l'appel en cours
+    pub(crate) budget: Option<Arc<Budget>>,
+}
+
+/// Budget d'un appel à `infer` : échéance et nombre d'hypothèses évaluées
+pub(crate) struct Budget {
+    deadline: Option<Instant>,
+    max_hypotheses: Option<usize>,
+    evaluated: AtomicUsize,
+    exhausted: AtomicBool,
+}
+
+impl Budget {
+    fn start(time: Option<Duration>, max_hypotheses: Option<usize>) -> Self {
+        Self {
+            deadline: time.map(|t| Instant::now() + t),
+            max_hypotheses,
+            evaluated: AtomicUsize::new(0),
+            exhausted: AtomicBool::new(false),
+        }
+    }
+
+    /// Vrai si l'échéance est passée ou si une évaluation a déjà été refusée
+    fn is_exhausted(&self) -> bool {
+        if self.exhausted.load(AtomicOrdering::Relaxed) {
+            return true;
+        }
+        let expired = self.deadline.is_some_and(|d| Instant::now() >= d);
+        if expired {
+            self.exhausted.store(true, AtomicOrdering::Relaxed);
+        }
+        expired
+    }
+
+    /// Réserve l'évaluation d'une hypothèse ; au-delà de la limite, le budget est épuisé
+    fn acquire(&self) -> bool {
+        if self.is_exhausted() {
+            return false;
+        }
+        let over = self
+            .max_hypotheses
+            .is_some_and(|max| self.evaluated.fetch_add(1, AtomicOrdering::Relaxed) >= max);
+        if over {
+            self.exhausted.store(true, AtomicOrdering::Relaxed);
+        }
+        !over
+    }
+}
+
+/// Suivi de l'évaluation des hypothèses d'une couche, partagé entre les threads rayon
+struct DepthProgress<'a> {
+    sink: Option<&'a dyn ProgressSink>,
+    depth: usize,
+    total: usize,
+    evaluated: AtomicUsize,
+    best: Mutex<Option<(Hypothesis, f64)>>,
+}
+
+impl DepthProgress<'_> {
+    /// Compte une hypothèse évaluée (`score` absent si elle n'a pu être parsée ou scorée)
+    fn record(&self, hypothesis: &Hypothesis, score: Option<&Score>) {
+        let Some(sink) = self.sink else {
+            return;
+        };
+        let evaluated = self.evaluated.fetch_add(1, AtomicOrdering::Relaxed) + 1;
+        let mut best = self.best.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(bits) = score.map(|s| s.total_bits).filter(|b| b.is_finite()) {
+            if best.as_ref().is_none_or(|(_, best_bits)| bits < *best_bits) {
+                *best = Some((hypothesis.clone(), bits));
+            }
+        }
+        sink.report(&Progress {
+            depth: self.depth,
+            evaluated,
+            total: self.total,
+            best: best.clone(),
+        });
+    }
+}
+
+/// Analyse des champs d'une couche, avec le schéma protobuf ou le dictionnaire TLV selon
+/// l'hypothèse retenue
+fn layer_fields(corpus: &Corpus, hypothesis: &Hypothesis, parsed: &ParsedCorpus) -> FieldAnalysis {
+    let mut fields = analyze_fields(corpus, parsed);
+    if matches!(hypothesis, Hypothesis::VarintKeyWireType { .. }) {
+        fields.proto = crate::protobuf::proto_schema(corpus, parsed);
+    }
+    if matches!(hypothesis, Hypothesis::Tlv { .. }) {
+        fields.tlv_tags = crate::tlv::tag_dictionary(corpus, parsed);
+    }
+    fields
+}
+
+/// Ramène les hypothèses à leur forme canonique et retire les doublons, dans l'ordre de
+/// première apparition : deux hypothèses équivalentes ne sont parsées et scorées qu'une fois
+fn dedup_hypotheses(hypotheses: impl IntoIterator<Item = Hypothesis>) -> Vec<Hypothesis> {
+    let mut seen = HashSet::new();
+    hypotheses
+        .into_iter()
+        .map(Hypothesis::canonical)
+        .filter(|h| seen.insert(h.clone()))
+        .collect()
+}
+
+/// Pré-filtre d'une hypothèse sur l'échantillon (sans échantillon, elle est gardée) ; une
+/// hypothèse écartée n'est ni parsée, ni scorée, ni décomptée du budget
+fn plausible(parser: &dyn Parser, sample: Option<&Corpus>, h: &Hypothesis) -> bool {
+    sample.is_none_or(|sample| parser.plausible(sample, h))
+}
+
+/// Classement des hypothèses scorées : score (voir `Score::ranking_cmp`), puis ordre des
+/// hypothèses pour départager les ex aequo indépendamment de l'ordre de génération
+fn rank_order(a: (&Hypothesis, &Score), b: (&Hypothesis, &Score)) -> std::cmp::Ordering {
+    a.1.ranking_cmp(b.1).then_with(|| a.0.cmp(b.0))
+}
+
+/// Nombre de couches qui structurent les données (la couche opaque finale ne compte pas)
+fn explained_depth(result: &InferenceResult) -> usize {
+    result.layers.iter().filter(|l| !matches!(l.hypothesis, Hypothesis::Opaque)).count()
 }
 
 impl InferenceEngine {
@@ -94,6 +475,23 @@ impl InferenceEngine {
             top_k: 10,
             min_gain_epsilon: 100.0, // bits
             min_sdu_size: 4,
+            split_by_version: false,
+            cross_validation_folds: 0,
+            ambiguity_epsilon: 8.0, // bits
+            compression: CompressionBackend::Deflate,
+            backtrack_budget: 0,
+            progress: None,
+            cancellation: None,
+            time_budget: None,
+            max_hypotheses: None,
+            refinement_rounds: 0,
+            prefilter_sample: 50,
+            field_recursion: None,
+            memory_budget: None,
+            screening_margin: None,
+            pinned_layers: Vec::new(),
+            explored: None,
+            budget: None,
         }
     }
 
@@ -107,17 +505,682 @@ impl InferenceEngine {
         self
     }
 
-    /// Infère la structure du protocole de manière récursive
+    pub fn with_version_split(mut self, enabled: bool) -> Self {
+        self.split_by_version = enabled;
+        self
+    }
+
+    /// Évalue les hypothèses par validation croisée à `k` plis (désactivée si `k < 2`)
+    pub fn with_cross_validation(mut self, k: usize) -> Self {
+        self.cross_validation_folds = k;
+        self
+    }
+
+    /// Modèle de compression du score des données non structurées
+    pub fn with_compression(mut self, compression: CompressionBackend) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Autorise `budget` retours arrière (0 : l'hypothèse la mieux classée est toujours retenue)
+    pub fn with_backtracking(mut self, budget: usize) -> Self {
+        self.backtrack_budget = budget;
+        self
+    }
+
+    /// Rapporte l'avancement de l'évaluation des hypothèses à `sink`
+    pub fn with_progress(mut self, sink: Arc<dyn ProgressSink>) -> Self {
+        self.progress = Some(sink);
+        self
+    }
+
+    /// Rend l'inférence annulable par `token`
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Vrai si le jeton d'annulation a été déclenché
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.as_ref().is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Limite la durée de chaque appel à `infer` ; à l'échéance, les couches établies sont rendues
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// Limite le nombre d'hypothèses parsées et scorées par chaque appel à `infer`
+    pub fn with_max_hypotheses(mut self, max: usize) -> Self {
+        self.max_hypotheses = Some(max);
+        self
+    }
+
+    /// Nombre de tours d'affinage local des meilleures hypothèses de chaque couche
+    pub fn with_refinement(mut self, rounds: usize) -> Self {
+        self.refinement_rounds = rounds;
+        self
+    }
+
+    /// Taille de l'échantillon sur lequel les hypothèses sont pré-filtrées (0 : désactivé)
+    pub fn with_prefilter(mut self, sample: usize) -> Self {
+        self.prefilter_sample = sample;
+        self
+    }
+
+    /// Fait aussi descendre la récursion dans les champs d'au moins `min_size` octets
+    pub fn with_field_recursion(mut self, min_size: usize) -> Self {
+        self.field_recursion = Some(min_size);
+        self
+    }
+
+    /// Abandonne, après un score sur l'échantillon des pré-filtres, les hypothèses hors des
+    /// `top_k` meilleures et à plus de `margin` bits par octet de la meilleure
+    pub fn with_screening(mut self, margin: f64) -> Self {
+        self.screening_margin = Some(margin);
+        self
+    }
+
+    /// Impose les hypothèses des premières couches (voir [`InferenceEngine::pinned_layers`])
+    pub fn with_pinned_layers(mut self, hypotheses: Vec<Hypothesis>) -> Self {
+        self.pinned_layers = hypotheses;
+        self
+    }
+
+    /// Hypothèses imposées à partir de la couche `depth`, pour un moteur qui reprend à cette profondeur
+    fn pinned_from(&self, depth: usize) -> Vec<Hypothesis> {
+        self.pinned_layers.iter().skip(depth).cloned().collect()
+    }
+
+    /// Conserve les scores calculés pour un point de reprise (voir [`InferenceEngine::checkpoint`])
+    pub fn with_checkpoints(self) -> Self {
+        self.resume(Checkpoint::default())
+    }
+
+    /// Reprend une inférence interrompue : les hypothèses déjà scorées dans `checkpoint` ne
+    /// sont que parsées, sans consommer le budget, et les nouveaux scores s'y ajoutent
+    pub fn resume(mut self, checkpoint: Checkpoint) -> Self {
+        self.explored = Some(Arc::new(Mutex::new(ExploredScores::from_checkpoint(checkpoint))));
+        self
+    }
+
+    /// Point de reprise : scores de toutes les hypothèses évaluées jusqu'ici par ce moteur et
+    /// ses copies, profondeur par profondeur (vide sans `with_checkpoints` ni `resume`)
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.explored
+            .as_ref()
+            .map(|explored| explored.lock().unwrap_or_else(|e| e.into_inner()).to_checkpoint())
+            .unwrap_or_default()
+    }
+
+    /// Clé des scores de `corpus` à la profondeur `depth`, si le moteur garde un point de reprise
+    fn explored_key(&self, corpus: &Corpus, depth: usize, folds: usize) -> Option<ExploredKey> {
+        self.explored.as_ref().map(|_| (depth, corpus_digest(corpus), folds))
+    }
+
+    fn explored_score(&self, key: Option<ExploredKey>, h: &Hypothesis) -> Option<Score> {
+        let explored = self.explored.as_ref()?.lock().unwrap_or_else(|e| e.into_inner());
+        explored.get(key?, h).cloned()
+    }
+
+    fn record_explored(&self, key: Option<ExploredKey>, h: &Hypothesis, score: &Score) {
+        if let (Some(explored), Some(key)) = (&self.explored, key) {
+            explored.lock().unwrap_or_else(|e| e.into_inner()).insert(key, h.clone(), score.clone());
+        }
+    }
+
+    /// Limite la mémoire de l'inférence à `bytes` octets (estimation)
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = Some(bytes);
+        self
+    }
+
+    /// Vrai si le corpus et les découpages `parsed` dépassent le budget mémoire
+    fn over_memory_budget<'a>(&self, corpus: &Corpus, parsed: impl IntoIterator<Item = &'a ParsedCorpus>) -> bool {
+        self.memory_budget.is_some_and(|budget| {
+            corpus.memory_footprint() + parsed.into_iter().map(ParsedCorpus::memory_footprint).sum::<usize>() > budget
+        })
+    }
+
+    /// Échantillon stratifié par longueur de `corpus`, si le corpus et le découpage de ses
+    /// `top_k` meilleures hypothèses ne tiennent pas dans le budget mémoire
+    fn memory_sample(&self, corpus: &Corpus) -> Option<Corpus> {
+        let budget = self.memory_budget?;
+        let per_pdu = corpus.memory_footprint() / corpus.len().max(1) + PARSED_PDU_ESTIMATE * (self.top_k + 1);
+        let n = (budget / per_pdu.max(1)).max(MIN_SAMPLED_PDUS);
+        (n < corpus.len()).then(|| corpus.stratified_sample(n, MEMORY_SAMPLE_SEED))
+    }
+
+    fn prefilter_sample(&self, corpus: &Corpus) -> Option<Corpus> {
+        (self.prefilter_sample > 0).then(|| corpus.sample(self.prefilter_sample, PREFILTER_SEED))
+    }
+
+    /// Vrai si l'inférence doit s'arrêter : annulée, ou budget épuisé
+    fn should_stop(&self) -> bool {
+        self.is_cancelled() || self.budget.as_ref().is_some_and(|b| b.is_exhausted())
+    }
+
+    /// Réserve l'évaluation d'une hypothèse : faux si l'inférence est annulée ou son budget épuisé
+    fn acquire_evaluation(&self) -> bool {
+        !self.is_cancelled() && self.budget.as_ref().is_none_or(|b| b.acquire())
+    }
+
+    fn depth_progress(&self, depth: usize, total: usize) -> DepthProgress<'_> {
+        DepthProgress {
+            sink: self.progress.as_deref(),
+            depth,
+            total,
+            evaluated: AtomicUsize::new(0),
+            best: Mutex::new(None),
+        }
+    }
+
+    /// Écart de score (bits) en deçà duquel deux hypothèses de nature différente sont ambiguës
+    pub fn with_ambiguity_epsilon(mut self, epsilon: f64) -> Self {
+        self.ambiguity_epsilon = epsilon;
+        self
+    }
+
+    /// Infère la structure du protocole de manière récursive.
+    ///
+    /// Quand la récursion cale (aucune hypothèse, ou un gain négligeable) et qu'il reste du
+    /// budget de retour arrière, la dernière couche choisie parmi plusieurs hypothèses est
+    /// refaite avec la suivante du classement, puis les précédentes une fois ses hypothèses
+    /// épuisées. Une tentative n'est retenue que si elle ne cale plus ou cale plus profond.
     pub fn infer(
         &self,
         corpus: Corpus,
         registry: &PluginRegistry,
     ) -> InferenceResult {
+        // Le budget court dès l'appel de plus haut niveau ; branches et retours arrière le partagent
+        if self.budget.is_none() && (self.time_budget.is_some() || self.max_hypotheses.is_some()) {
+            let engine = InferenceEngine {
+                budget: Some(Arc::new(Budget::start(self.time_budget, self.max_hypotheses))),
+                ..self.clone()
+            };
+            return engine.infer(corpus, registry);
+        }
+        // Le corpus échantillonné remplace l'original pour toute l'inférence
+        if let Some(sample) = self.memory_sample(&corpus) {
+            tracing::info!("Budget mémoire: {} PDUs échantillonnées sur {}", sample.len(), corpus.len());
+            let mut result = self.infer_with_backtracking(sample, registry);
+            result.sampled_from = Some(corpus.len());
+            return result;
+        }
+        self.infer_with_backtracking(corpus, registry)
+    }
+
+    /// Inférence de `infer`, budget installé et corpus échantillonné
+    fn infer_with_backtracking(&self, corpus: Corpus, registry: &PluginRegistry) -> InferenceResult {
+        let mut choices: Vec<usize> = Vec::new();
+        let (mut best, mut stalled) = self.infer_with_choices(corpus.clone(), registry, &choices);
+        let mut attempt_layers = best.layers.clone();
+
+        for _ in 0..self.backtrack_budget {
+            if !stalled || self.should_stop() {
+                break;
+            }
+            // Couche la plus profonde de la dernière tentative ayant encore une hypothèse à essayer
+            let Some(depth) = (0..attempt_layers.len()).rev().find(|&d| {
+                let finite = attempt_layers[d].all_hypotheses.iter().filter(|r| r.score.total_bits.is_finite()).count();
+                choices.get(d).copied().unwrap_or(0) + 1 < finite
+            }) else {
+                break;
+            };
+            choices.resize(depth + 1, 0);
+            choices[depth] += 1;
+            tracing::info!("Retour arrière: couche {} avec l'hypothèse de rang {}", depth, choices[depth]);
+
+            let (attempt, attempt_stalled) = self.infer_with_choices(corpus.clone(), registry, &choices);
+            // Une tentative interrompue n'est pas comparable aux précédentes
+            if self.should_stop() {
+                break;
+            }
+            if !attempt_stalled || explained_depth(&attempt) > explained_depth(&best) {
+                best = attempt.clone();
+                stalled = attempt_stalled;
+            }
+            attempt_layers = attempt.layers;
+        }
+        best.budget_exhausted = self.budget.as_ref().is_some_and(|b| b.is_exhausted());
+        best
+    }
+
+    /// Infère séparément la pile de couches de chaque direction (requêtes et réponses ont
+    /// souvent des formats différents), plutôt que de mêler les deux directions dans un corpus.
+    ///
+    /// Les directions sans PDU n'ont pas de pile ; les PDUs sans direction sont écartées.
+    pub fn infer_by_direction(&self, corpus: &Corpus, registry: &PluginRegistry) -> DirectionalResult {
+        let stacks: Vec<DirectionStack> = [Direction::ClientToServer, Direction::ServerToClient]
+            .into_iter()
+            .filter_map(|direction| {
+                let indices: Vec<usize> = (0..corpus.len())
+                    .filter(|&i| corpus.items[i].direction == Some(direction))
+                    .collect();
+                if indices.is_empty() {
+                    return None;
+                }
+                let selected = corpus.select(&indices, format!("{}_{:?}", corpus.meta.source, direction));
+                Some(DirectionStack {
+                    direction,
+                    result: self.infer(selected, registry),
+                })
+            })
+            .collect();
+
+        let hypotheses: Vec<Vec<&Hypothesis>> = stacks
+            .iter()
+            .map(|s| s.result.layers.iter().map(|l| &l.hypothesis).collect())
+            .collect();
+        let depth = hypotheses.iter().map(Vec::len).min().unwrap_or(0);
+        let shared_layers = (0..depth)
+            .take_while(|&d| hypotheses.iter().all(|h| h[d] == hypotheses[0][d]))
+            .count();
+        let summary = DirectionSummary {
+            pdu_counts: stacks.iter().map(|s| s.result.corpus.len()).collect(),
+            shared_layers,
+            symmetric: hypotheses.iter().all(|h| h.len() == shared_layers),
+            undirected_pdus: corpus.items.iter().filter(|p| p.direction.is_none()).count(),
+        };
+        DirectionalResult { stacks, summary }
+    }
+
+    /// Regroupe les PDUs par type de message (voir `cluster_pdus`) puis infère une pile par groupe.
+    ///
+    /// Un corpus mêlant des messages de formats différents impose sinon une hypothèse de
+    /// compromis qui ne convient bien à aucun d'eux.
+    pub fn infer_by_cluster(&self, corpus: &Corpus, registry: &PluginRegistry, config: &ClusterConfig) -> ClusteredResult {
+        let clusters = cluster_pdus(corpus, config)
+            .into_iter()
+            .enumerate()
+            .map(|(n, indices)| {
+                let selected = corpus.select(&indices, format!("{}_cluster{}", corpus.meta.source, n));
+                MessageCluster {
+                    result: self.infer(selected, registry),
+                    indices,
+                }
+            })
+            .collect();
+        ClusteredResult { clusters }
+    }
+
+    /// Infère une pile par flow puis dégage le modèle de consensus : à chaque profondeur,
+    /// l'hypothèse retenue par le plus de flows parmi ceux qui s'accordaient jusque-là, tant
+    /// qu'elle réunit au moins `min_agreement` (0 à 1) d'entre eux. Les couches opaques ne
+    /// font pas partie du consensus.
+    ///
+    /// Sur une capture bruitée, un modèle retrouvé indépendamment par la plupart des flows est
+    /// bien plus sûr que la pile d'un flow isolé.
+    pub fn infer_consensus(&self, corpus: &Corpus, registry: &PluginRegistry, min_agreement: f64) -> ConsensusResult {
+        let mut groups: std::collections::BTreeMap<usize, Vec<usize>> = std::collections::BTreeMap::new();
+        for (index, pdu) in corpus.items.iter().enumerate() {
+            if let Some(flow_id) = pdu.flow_id {
+                groups.entry(flow_id).or_default().push(index);
+            }
+        }
+        let skipped_flows = groups.values().filter(|indices| indices.len() < CONSENSUS_MIN_FLOW_PDUS).count();
+        let mut flows: Vec<FlowStack> = groups
+            .into_iter()
+            .filter(|(_, indices)| indices.len() >= CONSENSUS_MIN_FLOW_PDUS)
+            .map(|(flow_id, indices)| {
+                let selected = corpus.select(&indices, format!("{}_flow{}", corpus.meta.source, flow_id));
+                FlowStack {
+                    flow_id,
+                    result: self.infer(selected, registry),
+                    agreeing_layers: 0,
+                }
+            })
+            .collect();
+
+        let mut consensus = Vec::new();
+        let mut agreeing: Vec<usize> = (0..flows.len()).collect();
+        for depth in 0.. {
+            let mut votes: std::collections::BTreeMap<&Hypothesis, usize> = std::collections::BTreeMap::new();
+            for &f in &agreeing {
+                match flows[f].result.layers.get(depth).map(|l| &l.hypothesis) {
+                    Some(Hypothesis::Opaque) | None => {}
+                    Some(hypothesis) => *votes.entry(hypothesis).or_default() += 1,
+                }
+            }
+            let candidate_flows: usize = votes.values().sum();
+            // Le plus de voix, puis l'ordre des hypothèses pour départager
+            let Some((&winner, &agreeing_flows)) = votes.iter().max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0))) else {
+                break;
+            };
+            let agreement = agreeing_flows as f64 / candidate_flows as f64;
+            if agreement < min_agreement {
+                break;
+            }
+            let mut dissent: Vec<(Hypothesis, usize)> =
+                votes.iter().filter(|(h, _)| **h != winner).map(|(h, &n)| ((*h).clone(), n)).collect();
+            dissent.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            let winner = winner.clone();
+            agreeing.retain(|&f| flows[f].result.layers.get(depth).is_some_and(|l| l.hypothesis == winner));
+            consensus.push(ConsensusLayer {
+                hypothesis: winner,
+                agreeing_flows,
+                candidate_flows,
+                agreement,
+                dissent,
+            });
+        }
+        for flow in &mut flows {
+            flow.agreeing_layers = consensus
+                .iter()
+                .zip(&flow.result.layers)
+                .take_while(|(c, l)| c.hypothesis == l.hypothesis)
+                .count();
+        }
+
+        ConsensusResult {
+            flows,
+            consensus,
+            skipped_flows,
+            unassigned_pdus: corpus.items.iter().filter(|p| p.flow_id.is_none()).count(),
+        }
+    }
+
+    /// Met à jour `previous` avec les PDUs de `additional` sans tout recalculer.
+    ///
+    /// Couche par couche, les hypothèses déjà classées ne parsent que les nouvelles PDUs (le
+    /// parsing des anciennes est réutilisé) et sont rescorées sur le corpus enrichi. Tant que
+    /// l'hypothèse retenue reste en tête avec un gain suffisant, la couche est conservée et
+    /// seules les SDUs des nouvelles PDUs s'ajoutent à la couche suivante. À la première couche
+    /// qui change (ou qui ne se met pas à jour : branches, transformation, couche opaque),
+    /// l'inférence reprend normalement à partir de cette profondeur.
+    ///
+    /// Les parseurs qui comparent les PDUs entre elles (wire types protobuf dominants) ne
+    /// confrontent les nouvelles PDUs qu'entre elles.
+    pub fn infer_incremental(
+        &self,
+        previous: &InferenceResult,
+        additional: Corpus,
+        registry: &PluginRegistry,
+    ) -> InferenceResult {
+        let corpus = previous.corpus.concat(&additional);
         let mut layers = Vec::new();
         let mut current_corpus = corpus.clone();
+        let mut added = additional;
+
+        for (depth, layer) in previous.layers.iter().enumerate().take(self.max_depth) {
+            let Some((updated, next)) = self.update_layer(layer, &current_corpus, &added, registry) else {
+                let engine = InferenceEngine {
+                    max_depth: self.max_depth - depth,
+                    pinned_layers: self.pinned_from(depth),
+                    ..self.clone()
+                };
+                layers.extend(engine.infer(current_corpus, registry).layers);
+                return InferenceResult::new(layers, corpus);
+            };
+            layers.push(updated);
+            match next {
+                Some((next_corpus, next_added)) => {
+                    current_corpus = next_corpus;
+                    added = next_added;
+                }
+                None => return InferenceResult::new(layers, corpus),
+            }
+        }
+
+        // Toutes les couches tiennent : la récursion reprend là où elle s'était arrêtée
+        let depth = layers.len();
+        if depth < self.max_depth {
+            let engine = InferenceEngine {
+                max_depth: self.max_depth - depth,
+                pinned_layers: self.pinned_from(depth),
+                ..self.clone()
+            };
+            layers.extend(engine.infer(current_corpus, registry).layers);
+        }
+        InferenceResult::new(layers, corpus)
+    }
+
+    /// Couche `layer` mise à jour pour `corpus` (anciennes PDUs suivies des PDUs `added`), avec
+    /// les corpus de SDUs (complet, et des seules nouvelles PDUs) de la couche suivante ;
+    /// `None` si la couche ne se met pas à jour incrémentalement ou si son hypothèse est détrônée
+    fn update_layer(
+        &self,
+        layer: &Layer,
+        corpus: &Corpus,
+        added: &Corpus,
+        registry: &PluginRegistry,
+    ) -> Option<(Layer, Option<(Corpus, Corpus)>)> {
+        // Seules les couches choisies par classement, sans branches ni SDUs recomposées
+        if layer.confidence.is_none()
+            || layer.rank != 0
+            || !layer.branches.is_empty()
+            || matches!(
+                layer.hypothesis,
+                Hypothesis::Fragmented { .. } | Hypothesis::ByteStuffed { .. } | Hypothesis::Hdlc { .. }
+            )
+        {
+            return None;
+        }
+
+        let mut ranked: Vec<HypothesisResult> = layer
+            .all_hypotheses
+            .par_iter()
+            .filter_map(|result| {
+                if !self.acquire_evaluation() {
+                    return None;
+                }
+                let parser = registry.parsers().iter().find(|p| p.applicable(&result.hypothesis))?;
+                // Un découpage abandonné pour le budget mémoire est refait sur tout le corpus
+                let parsed = if result.parsed.parsed_pdus.len() + added.len() == corpus.len() {
+                    let mut parsed = result.parsed.clone();
+                    parsed
+                        .parsed_pdus
+                        .extend(parser.parse_corpus(added, &result.hypothesis).parsed_pdus);
+                    parsed
+                } else {
+                    parser.parse_corpus(corpus, &result.hypothesis)
+                };
+                let score = registry.score(corpus, &parsed, &result.hypothesis)?;
+                Some(HypothesisResult {
+                    hypothesis: result.hypothesis.clone(),
+                    score,
+                    parsed,
+                })
+            })
+            .collect();
+        ranked.sort_by(|a, b| rank_order((&a.hypothesis, &a.score), (&b.hypothesis, &b.score)));
+
+        let best = ranked.first()?;
+        if best.hypothesis != layer.hypothesis
+            || self.raw_score(corpus).total_bits - best.score.total_bits < self.min_gain_epsilon
+        {
+            return None;
+        }
+        let fields = layer_fields(corpus, &best.hypothesis, &best.parsed);
+        if self.split_by_version && fields.version.is_some() {
+            return None;
+        }
+
+        let mut parsed = best.parsed.clone();
+        let added_parsed = ParsedCorpus::new(parsed.parsed_pdus[corpus.len() - added.len()..].to_vec());
+        let sdu_corpus = self.extract_sdu_corpus(corpus, &parsed);
+        let next = sdu_corpus.clone().map(|next_corpus| {
+            let next_added = self
+                .extract_sdu_corpus(added, &added_parsed)
+                .unwrap_or_else(|| Corpus::from_messages(Vec::new(), &added.meta.source));
+            (next_corpus, next_added)
+        });
+        crate::strings::annotate_strings(corpus, &mut parsed, &fields.strings);
+
+        Some((
+            Layer {
+                hypothesis: best.hypothesis.clone(),
+                score: best.score.clone(),
+                parsed,
+                sdu_corpus,
+                confidence: Confidence::compute(&ranked, self.ambiguity_epsilon),
+                all_hypotheses: ranked,
+                fields,
+                branches: Vec::new(),
+                structured: None,
+                opaque: None,
+                padding: None,
+                rank: 0,
+            },
+            next,
+        ))
+    }
+
+    /// Parse et score les hypothèses en parallèle, avec le premier parseur applicable ; les
+    /// hypothèses sans parseur ou sans score sont écartées
+    fn evaluate(
+        &self,
+        corpus: &Corpus,
+        depth: usize,
+        registry: &PluginRegistry,
+        hypotheses: Vec<Hypothesis>,
+    ) -> Vec<(Hypothesis, Score, ParsedCorpus)> {
+        let progress = self.depth_progress(depth, hypotheses.len());
+        let sample = self.prefilter_sample(corpus);
+        let hypotheses = match (&sample, self.screening_margin) {
+            (Some(sample), Some(margin)) if sample.len() < corpus.len() => {
+                self.screen(sample, margin, registry, hypotheses, &progress)
+            }
+            _ => hypotheses,
+        };
+        let key = self.explored_key(corpus, depth, 0);
+        hypotheses
+            .into_par_iter()
+            .filter_map(|h| {
+                let parser = registry.parsers().iter().find(|p| p.applicable(&h));
+                if parser.is_some_and(|parser| !plausible(parser.as_ref(), sample.as_ref(), &h)) {
+                    progress.record(&h, None);
+                    return None;
+                }
+                // Une hypothèse du point de reprise n'est que parsée
+                if let (Some(parser), Some(score)) = (parser, self.explored_score(key, &h)) {
+                    progress.record(&h, Some(&score));
+                    let parsed = parser.parse_corpus(corpus, &h);
+                    return Some((h, score, parsed));
+                }
+                if !self.acquire_evaluation() {
+                    return None;
+                }
+                let scored = parser.and_then(|parser| {
+                    let parsed = parser.parse_corpus(corpus, &h);
+                    let score = registry.score(corpus, &parsed, &h)?;
+                    self.record_explored(key, &h, &score);
+                    Some((score, parsed))
+                });
+                progress.record(&h, scored.as_ref().map(|(score, _)| score));
+                scored.map(|(score, parsed)| (h, score, parsed))
+            })
+            .collect()
+    }
+
+    /// Tri préalable de `evaluate` : les hypothèses plausibles sont parsées et scorées sur
+    /// l'échantillon, et seules les `top_k` meilleures et celles à moins de `margin` bits par
+    /// octet de la meilleure sont rendues ; les autres sont abandonnées sans consommer le budget
+    fn screen(
+        &self,
+        sample: &Corpus,
+        margin: f64,
+        registry: &PluginRegistry,
+        hypotheses: Vec<Hypothesis>,
+        progress: &DepthProgress,
+    ) -> Vec<Hypothesis> {
+        let mut screened: Vec<(Hypothesis, Score)> = hypotheses
+            .into_par_iter()
+            .filter_map(|h| {
+                let score = registry
+                    .parsers()
+                    .iter()
+                    .find(|p| p.applicable(&h))
+                    .filter(|parser| parser.plausible(sample, &h))
+                    .and_then(|parser| registry.score(sample, &parser.parse_corpus(sample, &h), &h));
+                if score.is_none() {
+                    progress.record(&h, None);
+                }
+                Some((h, score?))
+            })
+            .collect();
+        screened.sort_by(|a, b| rank_order((&a.0, &a.1), (&b.0, &b.1)));
+
+        let Some(best) = screened.first().map(|(_, score)| score.total_bits) else {
+            return Vec::new();
+        };
+        let bound = best + margin * sample.total_bytes() as f64;
+        screened
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, (h, score))| {
+                if i < self.top_k || score.total_bits <= bound {
+                    return Some(h);
+                }
+                progress.record(&h, None);
+                None
+            })
+            .collect()
+    }
+
+    /// Affinage local après la grille des générateurs : les voisines (`Hypothesis::neighbors`)
+    /// des meilleures hypothèses sont évaluées, et celles qui font strictement mieux que
+    /// l'hypothèse dont elles dérivent sont gardées et affinées à leur tour, au plus
+    /// `refinement_rounds` fois
+    fn refine(
+        &self,
+        corpus: &Corpus,
+        depth: usize,
+        registry: &PluginRegistry,
+        scored: &mut Vec<(Hypothesis, Score, ParsedCorpus)>,
+    ) {
+        let mut seen: HashSet<Hypothesis> = scored.iter().map(|(h, _, _)| h.clone()).collect();
+        let mut ranked: Vec<&(Hypothesis, Score, ParsedCorpus)> = scored.iter().collect();
+        ranked.sort_by(|a, b| rank_order((&a.0, &a.1), (&b.0, &b.1)));
+        let mut frontier: Vec<(Hypothesis, f64)> = ranked
+            .into_iter()
+            .take(REFINED_HYPOTHESES)
+            .map(|(h, score, _)| (h.clone(), score.total_bits))
+            .collect();
+
+        for _ in 0..self.refinement_rounds {
+            if frontier.is_empty() || self.should_stop() {
+                break;
+            }
+            // Score à battre pour chaque voisine : le meilleur des hypothèses qui la proposent
+            let mut to_beat: HashMap<Hypothesis, f64> = HashMap::new();
+            let mut neighbors = Vec::new();
+            for (hypothesis, bits) in &frontier {
+                for neighbor in hypothesis.neighbors().into_iter().map(Hypothesis::canonical) {
+                    if seen.insert(neighbor.clone()) {
+                        neighbors.push(neighbor.clone());
+                        to_beat.insert(neighbor, *bits);
+                    } else if let Some(best) = to_beat.get_mut(&neighbor) {
+                        *best = best.min(*bits);
+                    }
+                }
+            }
+
+            let (improved, _): (Vec<_>, Vec<_>) = self
+                .evaluate(corpus, depth, registry, neighbors)
+                .into_iter()
+                .partition(|(h, score, _)| score.total_bits < to_beat[h]);
+            frontier = improved.iter().map(|(h, score, _)| (h.clone(), score.total_bits)).collect();
+            scored.extend(improved);
+        }
+    }
+
+    /// Inférence récursive imposant, à chaque profondeur, le rang de l'hypothèse retenue
+    /// (`choices`, 0 au-delà) ; indique aussi si la récursion a calé
+    fn infer_with_choices(
+        &self,
+        corpus: Corpus,
+        registry: &PluginRegistry,
+        choices: &[usize],
+    ) -> (InferenceResult, bool) {
+        let mut layers = Vec::new();
+        let mut current_corpus = corpus.clone();
+        // Vrai si la récursion s'arrête faute d'hypothèse satisfaisante
+        let mut stalled = false;
 
         for depth in 0..self.max_depth {
-            if current_corpus.is_empty() {
+            if current_corpus.is_empty() || self.should_stop() {
                 break;
             }
 
@@ -133,48 +1196,105 @@ impl InferenceEngine {
                 break;
             }
 
-            // Générer toutes les hypothèses
-            let mut hypotheses = Vec::new();
-            for generator in registry.generators() {
-                hypotheses.extend(generator.propose(&current_corpus));
+            // Une couche imposée n'est précédée d'aucune détection automatique
+            let pinned = self.pinned_layers.get(depth);
+
+            // Bourrage final : retiré avant d'évaluer les hypothèses, qu'il fausserait
+            if let Some(padding) = detect_padding(&current_corpus).filter(|_| pinned.is_none()) {
+                let layer = self.padding_layer(&current_corpus, padding, registry);
+                let next = layer.sdu_corpus.clone();
+                layers.push(layer);
+                match next {
+                    Some(stripped) => {
+                        current_corpus = stripped;
+                        continue;
+                    }
+                    None => break,
+                }
             }
 
-            if hypotheses.is_empty() {
+            // Documents JSON/XML : couche terminale, l'inférence binaire s'arrête
+            if let Some(structured) = detect_structured_text(&current_corpus).filter(|_| pinned.is_none()) {
+                layers.push(self.structured_layer(&current_corpus, structured, registry));
                 break;
             }
 
-            // Parser et scorer toutes les hypothèses (parallèle)
-            let scored: Vec<(Hypothesis, Score, ParsedCorpus)> = hypotheses
-                .into_par_iter()
-                .filter_map(|h| {
-                    // Trouver un parseur applicable
-                    let parser = registry.parsers().iter().find(|p| p.applicable(&h))?;
-
-                    // Parser
-                    let parsed = parser.parse_corpus(&current_corpus, &h);
+            // Charge utile encodée en texte ou compressée : la récursion continue sur les
+            // octets décodés (sans décodeur disponible, la couche est terminale)
+            let transform = if pinned.is_some() {
+                None
+            } else if let Some(encoding) = detect_encoding(&current_corpus) {
+                let hypothesis = Hypothesis::Encoded { encoding };
+                let parsed = EncodedParser.parse_corpus(&current_corpus, &hypothesis);
+                Some(self.decoded_layer(&current_corpus, hypothesis, parsed, registry, |data| {
+                    encoding.decode(data)
+                }))
+            } else if let Some(algorithm) = detect_compression(&current_corpus) {
+                let hypothesis = Hypothesis::Compressed { algorithm };
+                let parsed = CompressedParser.parse_corpus(&current_corpus, &hypothesis);
+                Some(self.decoded_layer(&current_corpus, hypothesis, parsed, registry, |data| {
+                    algorithm.decompress(data)
+                }))
+            } else if let Some(obfuscation) = crate::obfuscation::detect_obfuscation(&current_corpus) {
+                let hypothesis = Hypothesis::Obfuscated { obfuscation: obfuscation.clone() };
+                let parsed = ObfuscatedParser.parse_corpus(&current_corpus, &hypothesis);
+                Some(self.decoded_layer(&current_corpus, hypothesis, parsed, registry, |data| {
+                    Some(obfuscation.decode(data))
+                }))
+            } else {
+                None
+            };
+            if let Some(layer) = transform {
+                let next = layer.sdu_corpus.clone();
+                layers.push(layer);
+                match next {
+                    Some(decoded) => {
+                        current_corpus = decoded;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
 
-                    // Trouver un scoreur
-                    let scorer = registry.scorers().first()?;
+            let scored = if let Some(hypothesis) = pinned {
+                // Ni pré-filtre ni budget : le modèle imposé est appliqué tel quel
+                let parser = registry.parsers().iter().find(|p| p.applicable(hypothesis));
+                let scored = parser.and_then(|parser| {
+                    let parsed = parser.parse_corpus(&current_corpus, hypothesis);
+                    let score = registry.score(&current_corpus, &parsed, hypothesis)?;
+                    Some((hypothesis.clone(), score, parsed))
+                });
+                scored.into_iter().collect()
+            } else if self.cross_validation_folds >= 2 && current_corpus.len() >= self.cross_validation_folds {
+                self.cross_validated(&current_corpus, depth, registry)
+            } else {
+                // Générer toutes les hypothèses, sans doublons
+                let hypotheses =
+                    dedup_hypotheses(registry.generators().iter().flat_map(|g| g.propose(&current_corpus)));
 
-                    // Scorer
-                    let score = scorer.score(&current_corpus, &parsed, &h);
-
-                    Some((h, score, parsed))
-                })
-                .collect();
+                // Parser et scorer toutes les hypothèses, puis affiner les meilleures
+                let mut scored = self.evaluate(&current_corpus, depth, registry, hypotheses);
+                self.refine(&current_corpus, depth, registry, &mut scored);
+                scored
+            };
 
+            // Une évaluation interrompue ne départage pas les hypothèses
+            if self.should_stop() {
+                break;
+            }
             if scored.is_empty() {
+                stalled = true;
                 break;
             }
 
-            // Trier par score (min = meilleur)
+            // Trier par score (min = meilleur), ex aequo départagés de façon déterministe
             let mut sorted: Vec<_> = scored.into_iter().collect();
-            sorted.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+            sorted.sort_by(|a, b| rank_order((&a.0, &a.1), (&b.0, &b.1)));
 
             // Logging détaillé pour les hypothèses TLV Tag=1, Length=2
             use crate::hypothesis::{Hypothesis, TlvLenRule};
             for (h, score, parsed) in &sorted {
-                if let Hypothesis::Tlv { tag_bytes, len_rule, tag_offset, len_offset, length_includes_header } = h {
+                if let Hypothesis::Tlv { tag_bytes, len_rule, tag_offset, len_offset, length_includes_header, .. } = h {
                     if *tag_bytes == 1 && matches!(len_rule, TlvLenRule::DefiniteMedium) {
                         let exception_count: usize = parsed.parsed_pdus.iter()
                             .map(|p| p.exceptions.len())
@@ -186,7 +1306,7 @@ impl InferenceEngine {
                             .sum();
                         let total_sdu_bytes: usize = current_corpus.items.iter()
                             .zip(parsed.parsed_pdus.iter())
-                            .flat_map(|(pdu, parsed_pdu)| {
+                            .flat_map(|(_pdu, parsed_pdu)| {
                                 parsed_pdu.segments.iter()
                                     .filter_map(|s| {
                                         if matches!(s.kind, crate::segment::SegmentKind::Sdu) {
@@ -202,7 +1322,7 @@ impl InferenceEngine {
                         let mut exception_types = std::collections::HashMap::new();
                         for parsed_pdu in &parsed.parsed_pdus {
                             for exc in &parsed_pdu.exceptions {
-                                *exception_types.entry(exc.clone()).or_insert(0) += 1;
+                                *exception_types.entry(exc.message.clone()).or_insert(0) += 1;
                             }
                         }
                         
@@ -225,7 +1345,7 @@ impl InferenceEngine {
                         // Afficher les types d'exceptions les plus fréquents
                         if !exception_types.is_empty() && exception_count > 0 {
                             let mut exc_vec: Vec<_> = exception_types.into_iter().collect();
-                            exc_vec.sort_by(|a, b| b.1.cmp(&a.1));
+                            exc_vec.sort_by_key(|e| std::cmp::Reverse(e.1));
                             for (exc_type, count) in exc_vec.iter().take(10) {
                                 tracing::info!("  Exception: '{}' x{}", exc_type, count);
                             }
@@ -237,7 +1357,7 @@ impl InferenceEngine {
                                 for (pdu_idx, (pdu, parsed_pdu)) in current_corpus.items.iter().zip(parsed.parsed_pdus.iter()).enumerate() {
                                     if !parsed_pdu.exceptions.is_empty() {
                                         let pdu_data = pdu.as_slice();
-                                        let has_padding = parsed_pdu.exceptions.iter().any(|e| e.contains("padding") || e.contains("suspicious repetitive pattern"));
+                                        let has_padding = parsed_pdu.exceptions.iter().any(|e| e.message.contains("padding") || e.message.contains("suspicious repetitive pattern"));
                                         if has_padding {
                                             padding_pdu_indices.push(pdu_idx);
                                         }
@@ -281,11 +1401,16 @@ impl InferenceEngine {
                 .collect();
 
             if top_k_results.is_empty() {
+                stalled = true;
                 break;
             }
 
-            // Choisir le meilleur
-            let (best_hypothesis, best_score, best_parsed) = top_k_results[0].clone();
+            // Choisir le meilleur, ou celui du rang imposé par le retour arrière
+            let rank = choices.get(depth).copied().unwrap_or(0);
+            let Some((best_hypothesis, best_score, mut best_parsed)) = top_k_results.get(rank).cloned() else {
+                stalled = true;
+                break;
+            };
             
             // Logging du meilleur score
             tracing::info!(
@@ -313,23 +1438,88 @@ impl InferenceEngine {
             let raw_score = self.raw_score(&current_corpus);
             let gain = raw_score.total_bits - best_score.total_bits;
 
-            if gain < self.min_gain_epsilon {
+            if gain < self.min_gain_epsilon && pinned.is_none() {
                 // Pas assez de gain, arrêter
+                stalled = true;
                 break;
             }
 
-            // Extraire le corpus SDU pour la récursion
-            let sdu_corpus = self.extract_sdu_corpus(&current_corpus, &best_parsed);
+            // Extraire le corpus SDU pour la récursion (les fragments sont d'abord réassemblés)
+            let sdu_corpus = match &best_hypothesis {
+                Hypothesis::Fragmented { .. } => crate::fragmentation::reassemble(&current_corpus, &best_hypothesis),
+                Hypothesis::ByteStuffed { .. } | Hypothesis::Hdlc { .. } => {
+                    crate::stuffing::unstuffed_corpus(&current_corpus, &best_hypothesis)
+                }
+                _ => self.extract_sdu_corpus(&current_corpus, &best_parsed),
+            };
 
-            // Créer la liste de toutes les hypothèses testées
+            // Créer la liste de toutes les hypothèses testées ; au-delà du budget mémoire, seul
+            // le découpage de l'hypothèse retenue est gardé
+            let keep_parsed = !self.over_memory_budget(&current_corpus, top_k_results.iter().map(|(_, _, p)| p));
             let all_hypotheses: Vec<HypothesisResult> = top_k_results
                 .iter()
-                .map(|(h, s, p)| HypothesisResult {
+                .enumerate()
+                .map(|(i, (h, s, p))| HypothesisResult {
                     hypothesis: h.clone(),
                     score: s.clone(),
-                    parsed: p.clone(),
+                    parsed: if keep_parsed || i == rank { p.clone() } else { ParsedCorpus::new(Vec::new()) },
                 })
                 .collect();
+            // Confiance de l'hypothèse retenue : placée en tête, son écart est négatif après un retour arrière
+            let mut ranked = all_hypotheses.clone();
+            let chosen = ranked.remove(rank);
+            ranked.insert(0, chosen);
+            let confidence = Confidence::compute(&ranked, self.ambiguity_epsilon);
+            if confidence.as_ref().is_some_and(|c| c.ambiguous) {
+                tracing::info!("Couche ambiguë: {:?} proche de {:?}", best_hypothesis, confidence.as_ref().map(|c| &c.rivals));
+            }
+
+            let fields = layer_fields(&current_corpus, &best_hypothesis, &best_parsed);
+
+            // Un discriminant de type ou un multiplexage de canaux : chaque branche est
+            // inférée séparément, sur la profondeur restante, et la récursion principale s'arrête là
+            let branch_engine = InferenceEngine {
+                max_depth: self.max_depth - depth - 1,
+                pinned_layers: self.pinned_from(depth + 1),
+                ..self.clone()
+            };
+            let branches = match &best_hypothesis {
+                Hypothesis::TypeSwitch { type_offset, type_width } => Some(branch_engine.infer_branches(
+                    &current_corpus,
+                    &best_parsed,
+                    *type_offset,
+                    *type_width,
+                    registry,
+                )),
+                Hypothesis::ChannelMux { .. } => {
+                    Some(branch_engine.infer_channels(&current_corpus, &best_parsed, registry))
+                }
+                // Les SDUs réassemblées ou déséchappées ne se rattachent pas à une PDU
+                Hypothesis::Fragmented { .. } | Hypothesis::ByteStuffed { .. } | Hypothesis::Hdlc { .. } => None,
+                _ if self.split_by_version => fields.version.as_ref().map(|version| {
+                    branch_engine.infer_branches(&current_corpus, &best_parsed, version.offset, 1, registry)
+                }),
+                _ => None,
+            };
+            // Les chaînes ne sont découpées qu'une fois les SDUs et les branches extraites
+            crate::strings::annotate_strings(&current_corpus, &mut best_parsed, &fields.strings);
+            if let Some(branches) = branches {
+                layers.push(Layer {
+                    hypothesis: best_hypothesis,
+                    score: best_score,
+                    parsed: best_parsed,
+                    sdu_corpus,
+                    all_hypotheses,
+                    fields,
+                    branches,
+                    structured: None,
+                    opaque: None,
+                    padding: None,
+                    confidence,
+                    rank,
+                });
+                break;
+            }
 
             layers.push(Layer {
                 hypothesis: best_hypothesis,
@@ -337,6 +1527,13 @@ impl InferenceEngine {
                 parsed: best_parsed,
                 sdu_corpus: sdu_corpus.clone(),
                 all_hypotheses,
+                fields,
+                branches: Vec::new(),
+                structured: None,
+                opaque: None,
+                padding: None,
+                confidence,
+                rank,
             });
 
             // Continuer avec le SDU corpus
@@ -347,28 +1544,321 @@ impl InferenceEngine {
             }
         }
 
-        InferenceResult {
-            layers,
-            corpus,
+        // Aucune hypothèse ne structure le reste : le signaler s'il a l'air chiffré ou compressé
+        if stalled {
+            if let Some(analysis) = classify_opaque(&current_corpus) {
+                layers.push(self.opaque_layer(&current_corpus, analysis));
+            }
+        }
+
+        (InferenceResult::new(layers, corpus), stalled)
+    }
+
+    /// Validation croisée : les hypothèses sont proposées sur les PDUs d'apprentissage de chaque
+    /// pli et scorées sur ses PDUs de test. Seules les hypothèses proposées sur tous les plis
+    /// sont retenues ; leur score est la somme des scores de test, et leur parsing celui du
+    /// corpus complet (pour extraire les SDUs).
+    fn cross_validated(
+        &self,
+        corpus: &Corpus,
+        depth: usize,
+        registry: &PluginRegistry,
+    ) -> Vec<(Hypothesis, Score, ParsedCorpus)> {
+        let folds = corpus.folds(self.cross_validation_folds, CROSS_VALIDATION_SEED);
+        let proposals: Vec<Vec<Hypothesis>> = folds
+            .iter()
+            .map(|(train, _)| {
+                registry.generators().iter().flat_map(|g| g.propose(train)).map(Hypothesis::canonical).collect()
+            })
+            .collect();
+
+        let others: Vec<HashSet<&Hypothesis>> = proposals[1..].iter().map(|p| p.iter().collect()).collect();
+        let candidates = dedup_hypotheses(proposals[0].iter().filter(|h| others.iter().all(|p| p.contains(h))).cloned());
+
+        let progress = self.depth_progress(depth, candidates.len());
+        let sample = self.prefilter_sample(corpus);
+        let key = self.explored_key(corpus, depth, self.cross_validation_folds);
+        candidates
+            .into_par_iter()
+            .filter_map(|h| {
+                let parser = registry.parsers().iter().find(|p| p.applicable(&h));
+                if parser.is_some_and(|parser| !plausible(parser.as_ref(), sample.as_ref(), &h)) {
+                    progress.record(&h, None);
+                    return None;
+                }
+                if let (Some(parser), Some(score)) = (parser, self.explored_score(key, &h)) {
+                    progress.record(&h, Some(&score));
+                    let parsed = parser.parse_corpus(corpus, &h);
+                    return Some((h, score, parsed));
+                }
+                if !self.acquire_evaluation() {
+                    return None;
+                }
+                let scored = parser.and_then(|parser| {
+                    let held_out: Vec<(Score, usize)> = folds
+                        .iter()
+                        .map(|(_, test)| Some((registry.score(test, &parser.parse_corpus(test, &h), &h)?, test.len())))
+                        .collect::<Option<_>>()?;
+                    let score = Score::sum(&held_out);
+                    self.record_explored(key, &h, &score);
+                    Some((score, parser))
+                });
+                progress.record(&h, scored.as_ref().map(|(score, _)| score));
+                let (score, parser) = scored?;
+                let parsed = parser.parse_corpus(corpus, &h);
+                Some((h, score, parsed))
+            })
+            .collect()
+    }
+
+    /// Regroupe les PDUs par valeur du discriminant et infère la structure de chaque groupe
+    fn infer_branches(
+        &self,
+        corpus: &Corpus,
+        parsed: &ParsedCorpus,
+        type_offset: usize,
+        type_width: usize,
+        registry: &PluginRegistry,
+    ) -> Vec<TypeBranch> {
+        let mut groups: std::collections::BTreeMap<u64, Vec<usize>> = std::collections::BTreeMap::new();
+        for (index, pdu) in corpus.items.iter().enumerate() {
+            if let Some(value) = crate::fields::read_be(pdu.as_slice(), type_offset, type_width) {
+                groups.entry(value).or_default().push(index);
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(type_value, indices)| {
+                let group = corpus.select(&indices, format!("{}_type_{:x}", corpus.meta.source, type_value));
+                let group_parsed = ParsedCorpus::new(
+                    indices.iter().map(|&i| parsed.parsed_pdus[i].clone()).collect(),
+                );
+                let layers = match self.extract_sdu_corpus(&group, &group_parsed) {
+                    Some(sdu_corpus) if self.max_depth > 0 => self.infer(sdu_corpus, registry).layers,
+                    _ => Vec::new(),
+                };
+                TypeBranch {
+                    type_value,
+                    pdu_count: indices.len(),
+                    layers,
+                }
+            })
+            .collect()
+    }
+
+    /// Démultiplexe les trames par canal et infère la structure de chaque canal
+    fn infer_channels(&self, corpus: &Corpus, parsed: &ParsedCorpus, registry: &PluginRegistry) -> Vec<TypeBranch> {
+        use crate::segment::SegmentKind;
+
+        // Pour chaque canal, la même PDU ne garde que les SDUs des trames de ce canal
+        let mut channels: std::collections::BTreeMap<u64, Vec<crate::parser::ParsedPdu>> =
+            std::collections::BTreeMap::new();
+        let mut frame_counts: std::collections::BTreeMap<u64, usize> = std::collections::BTreeMap::new();
+        for (index, (pdu, parsed_pdu)) in corpus.items.iter().zip(&parsed.parsed_pdus).enumerate() {
+            let mut channel = None;
+            for segment in &parsed_pdu.segments {
+                match &segment.kind {
+                    SegmentKind::Field(name) if name == "channel" => {
+                        channel = crate::fields::read_be(pdu.as_slice(), segment.range.start, segment.len());
+                    }
+                    SegmentKind::Sdu => {
+                        let Some(value) = channel else { continue };
+                        *frame_counts.entry(value).or_default() += 1;
+                        let pdus = channels
+                            .entry(value)
+                            .or_insert_with(|| vec![crate::parser::ParsedPdu::new(Vec::new()); corpus.len()]);
+                        pdus[index].segments.push(segment.clone());
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        channels
+            .into_iter()
+            .map(|(channel, parsed_pdus)| {
+                let layers = match self.extract_sdu_corpus(corpus, &ParsedCorpus::new(parsed_pdus)) {
+                    Some(sdu_corpus) if self.max_depth > 0 => self.infer(sdu_corpus, registry).layers,
+                    _ => Vec::new(),
+                };
+                TypeBranch {
+                    type_value: channel,
+                    pdu_count: frame_counts[&channel],
+                    layers,
+                }
+            })
+            .collect()
+    }
+
+    /// Construit la couche terminale d'un corpus de texte structuré
+    fn structured_layer(
+        &self,
+        corpus: &Corpus,
+        structured: StructuredText,
+        registry: &PluginRegistry,
+    ) -> Layer {
+        let hypothesis = Hypothesis::StructuredText {
+            format: structured.format,
+        };
+        let parsed = StructuredTextParser.parse_corpus(corpus, &hypothesis);
+        let score = registry
+            .score(corpus, &parsed, &hypothesis)
+            .unwrap_or_else(|| self.raw_score(corpus));
+
+        Layer {
+            all_hypotheses: vec![HypothesisResult {
+                hypothesis: hypothesis.clone(),
+                score: score.clone(),
+                parsed: parsed.clone(),
+            }],
+            fields: FieldAnalysis::default(),
+            hypothesis,
+            score,
+            parsed,
+            sdu_corpus: None,
+            branches: Vec::new(),
+            structured: Some(structured),
+            opaque: None,
+            padding: None,
+            confidence: None,
+            rank: 0,
+        }
+    }
+
+    /// Construit la couche de bourrage : la SDU est le contenu de chaque PDU sans sa série finale
+    fn padding_layer(&self, corpus: &Corpus, padding: PaddingAnalysis, registry: &PluginRegistry) -> Layer {
+        let hypothesis = Hypothesis::TrailingPadding {
+            pad_byte: padding.pad_byte,
+        };
+        let parsed = TrailingPaddingParser.parse_corpus(corpus, &hypothesis);
+        let score = registry
+            .score(corpus, &parsed, &hypothesis)
+            .unwrap_or_else(|| self.raw_score(corpus));
+        let sdu_corpus = self.extract_sdu_corpus(corpus, &parsed);
+
+        Layer {
+            all_hypotheses: vec![HypothesisResult {
+                hypothesis: hypothesis.clone(),
+                score: score.clone(),
+                parsed: parsed.clone(),
+            }],
+            fields: FieldAnalysis::default(),
+            hypothesis,
+            score,
+            parsed,
+            sdu_corpus,
+            branches: Vec::new(),
+            structured: None,
+            opaque: None,
+            padding: Some(padding),
+            confidence: None,
+            rank: 0,
+        }
+    }
+
+    /// Construit la couche terminale d'un corpus opaque (chiffré ou compressé)
+    fn opaque_layer(&self, corpus: &Corpus, analysis: OpaqueAnalysis) -> Layer {
+        let parsed = ParsedCorpus::new(
+            corpus
+                .items
+                .iter()
+                .map(|pdu| {
+                    crate::parser::ParsedPdu::new(vec![crate::segment::Segment::new(
+                        crate::segment::SegmentKind::Field("opaque".to_string()),
+                        0..pdu.len(),
+                    )])
+                })
+                .collect(),
+        );
+
+        Layer {
+            hypothesis: Hypothesis::Opaque,
+            score: self.raw_score(corpus),
+            parsed,
+            sdu_corpus: None,
+            all_hypotheses: Vec::new(),
+            fields: FieldAnalysis::default(),
+            branches: Vec::new(),
+            structured: None,
+            opaque: Some(analysis),
+            padding: None,
+            confidence: None,
+            rank: 0,
+        }
+    }
+
+    /// Construit une couche de transformation (décodage) dont la SDU est le corpus décodé
+    fn decoded_layer(
+        &self,
+        corpus: &Corpus,
+        hypothesis: Hypothesis,
+        parsed: ParsedCorpus,
+        registry: &PluginRegistry,
+        decode: impl Fn(&[u8]) -> Option<Vec<u8>>,
+    ) -> Layer {
+        let score = registry
+            .score(corpus, &parsed, &hypothesis)
+            .unwrap_or_else(|| self.raw_score(corpus));
+
+        let mut items = Vec::new();
+        let mut multiplicities = Vec::new();
+        for (index, pdu) in corpus.items.iter().enumerate() {
+            if let Some(decoded) = decode(pdu.as_slice()) {
+                let len = decoded.len();
+                items.push(
+                    PduRef::new(decoded.into(), 0..len)
+                        .with_origin(pdu.timestamp, pdu.direction)
+                        .with_flow(pdu.flow_id)
+                        .derived_from(pdu, index, None),
+                );
+                multiplicities.push(corpus.multiplicity(index));
+            }
+        }
+        let sdu_corpus = (!items.is_empty()).then(|| {
+            let total_bytes = items.iter().map(|p| p.len()).sum();
+            let pdu_count = items.len();
+            Corpus::new(
+                items,
+                crate::corpus::CorpusMeta {
+                    source: format!("{}_decoded", corpus.meta.source),
+                    total_bytes,
+                    pdu_count,
+                    flow_id: corpus.meta.flow_id,
+                    multiplicities: corpus.meta.multiplicities.as_ref().map(|_| multiplicities),
+                },
+            )
+        });
+
+        Layer {
+            all_hypotheses: vec![HypothesisResult {
+                hypothesis: hypothesis.clone(),
+                score: score.clone(),
+                parsed: parsed.clone(),
+            }],
+            fields: FieldAnalysis::default(),
+            hypothesis,
+            score,
+            parsed,
+            sdu_corpus,
+            branches: Vec::new(),
+            structured: None,
+            opaque: None,
+            padding: None,
+            confidence: None,
+            rank: 0,
         }
     }
 
     /// Score pour un corpus "raw" (sans parsing)
     fn raw_score(&self, corpus: &Corpus) -> Score {
-        use crate::measures::compressed_size;
         use crate::score::ScoreBreakdown;
 
-        let total_bits = match compressed_size(
-            &corpus
-                .items
-                .iter()
-                .flat_map(|p| p.as_slice())
-                .copied()
-                .collect::<Vec<_>>(),
-        ) {
-            Ok(size) => size as f64 * 8.0,
-            Err(_) => corpus.total_bytes() as f64 * 8.0,
-        };
+        let total_bits = self
+            .compression
+            .model(corpus)
+            .compressed_bits(&corpus.items.iter().flat_map(|p| p.as_slice()).copied().collect::<Vec<_>>())
+            .unwrap_or(corpus.total_bytes() as f64 * 8.0);
 
         Score::new(ScoreBreakdown {
             mdl_model_bits: 0.0,
@@ -380,44 +1870,35 @@ impl InferenceEngine {
         })
     }
 
-    /// Extrait un nouveau corpus à partir des SDUs parsés
+    /// Extrait un nouveau corpus à partir des SDUs parsés, et des champs d'au moins
+    /// `field_recursion` octets si l'option est active
     fn extract_sdu_corpus(
         &self,
         corpus: &Corpus,
         parsed: &ParsedCorpus,
     ) -> Option<Corpus> {
-        let mut sdu_items = Vec::new();
-
-        for (pdu, parsed_pdu) in corpus.items.iter().zip(parsed.parsed_pdus.iter()) {
-            for segment in &parsed_pdu.segments {
-                if matches!(segment.kind, crate::segment::SegmentKind::Sdu) {
-                    let sdu_data = &pdu.as_slice()[segment.range.clone()];
-                    if sdu_data.len() >= self.min_sdu_size {
-                        sdu_items.push(PduRef::new(
-                            pdu.data.clone(),
-                            segment.range.clone(),
-                        ));
-                    }
-                }
+        let recursed = |segment: &crate::segment::Segment| match segment.kind {
+            crate::segment::SegmentKind::Sdu => true,
+            crate::segment::SegmentKind::Field(_) => {
+                self.field_recursion.is_some_and(|min| segment.range.len() >= min)
             }
-        }
+            _ => false,
+        };
+        let ranges: Vec<(usize, std::ops::Range<usize>)> = parsed
+            .parsed_pdus
+            .iter()
+            .enumerate()
+            .take(corpus.len())
+            .flat_map(|(index, parsed_pdu)| {
+                parsed_pdu.segments.iter().filter(|s| recursed(s)).map(move |s| (index, s.range.clone()))
+            })
+            .filter(|(_, range)| range.len() >= self.min_sdu_size)
+            .collect();
 
-        if sdu_items.is_empty() {
+        if ranges.is_empty() {
             return None;
         }
-
-        let pdu_count = sdu_items.len();
-        let total_bytes: usize = sdu_items.iter().map(|p| p.len()).sum();
-
-        Some(Corpus::new(
-            sdu_items,
-            crate::corpus::CorpusMeta {
-                source: format!("{}_sdu", corpus.meta.source),
-                total_bytes,
-                pdu_count,
-                flow_id: corpus.meta.flow_id,
-            },
-        ))
+        Some(corpus.sub_ranges(&ranges, format!("{}_sdu", corpus.meta.source)))
     }
 }
 
@@ -0,0 +1,114 @@
+use crate::corpus::Corpus;
+use crate::hypothesis::Hypothesis;
+use crate::measures::printable_ratio;
+use std::ops::Range;
+
+/// Nombre maximal de PDUs examinées pour la détection
+const DETECTION_SAMPLE: usize = 2048;
+/// Offsets explorés pour le début du nom
+const MAX_NAME_OFFSET: usize = 16;
+/// Taille maximale d'un label (RFC 1035 : les deux bits de poids fort sont réservés)
+const MAX_LABEL_LEN: usize = 63;
+/// Nombre maximal de pointeurs suivis pour résoudre un nom
+const MAX_POINTER_HOPS: usize = 16;
+/// Proportion minimale de PDUs portant un nom valide
+const LABEL_MIN_RATIO: f64 = 0.95;
+/// Proportion minimale d'octets imprimables dans les labels
+const LABEL_MIN_PRINTABLE: f64 = 0.9;
+
+/// Nom encodé en labels préfixés par leur longueur, lu à une position de la PDU
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelName {
+    /// Labels, octet de longueur compris
+    pub labels: Vec<Range<usize>>,
+    /// Pointeur de compression final (2 octets) et offset visé
+    pub pointer: Option<(Range<usize>, usize)>,
+    /// Fin du nom (après l'octet nul ou le pointeur)
+    pub end: usize,
+}
+
+/// Lit un nom à `start` : labels jusqu'à un octet nul ou un pointeur de compression
+/// (`0b11` + offset sur 14 bits), qui doit viser une position antérieure
+pub fn read_name(data: &[u8], start: usize) -> Option<LabelName> {
+    let mut labels = Vec::new();
+    let mut pos = start;
+    loop {
+        let len = *data.get(pos)? as usize;
+        match len {
+            0 => {
+                return Some(LabelName { labels, pointer: None, end: pos + 1 });
+            }
+            1..=MAX_LABEL_LEN => {
+                if pos + 1 + len > data.len() {
+                    return None;
+                }
+                labels.push(pos..pos + 1 + len);
+                pos += 1 + len;
+            }
+            _ if len & 0xc0 == 0xc0 => {
+                let target = (u16::from_be_bytes([data[pos], *data.get(pos + 1)?]) & 0x3fff) as usize;
+                if target >= pos {
+                    return None;
+                }
+                return Some(LabelName {
+                    labels,
+                    pointer: Some((pos..pos + 2, target)),
+                    end: pos + 2,
+                });
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Nom complet en notation pointée, en suivant les pointeurs de compression
+pub fn resolve_name(data: &[u8], start: usize) -> Option<String> {
+    let mut parts = Vec::new();
+    let mut pos = start;
+    for _ in 0..MAX_POINTER_HOPS {
+        let name = read_name(data, pos)?;
+        parts.extend(
+            name.labels
+                .iter()
+                .map(|label| String::from_utf8_lossy(&data[label.start + 1..label.end]).into_owned()),
+        );
+        match name.pointer {
+            Some((_, target)) => pos = target,
+            None => return Some(parts.join(".")),
+        }
+    }
+    None
+}
+
+/// Cherche l'offset d'un nom en labels (DNS et assimilés) présent dans presque toutes les PDUs
+pub fn detect_label_names(corpus: &Corpus) -> Vec<Hypothesis> {
+    let pdus: Vec<&[u8]> = corpus.items.iter().take(DETECTION_SAMPLE).map(|p| p.as_slice()).collect();
+    if pdus.is_empty() {
+        return Vec::new();
+    }
+
+    (0..MAX_NAME_OFFSET)
+        .filter(|&offset| {
+            let mut valid = 0;
+            let mut label_bytes = Vec::new();
+            let mut multi_label = false;
+            for data in &pdus {
+                let Some(name) = read_name(data, offset) else {
+                    continue;
+                };
+                if name.labels.is_empty() && name.pointer.is_none() {
+                    continue;
+                }
+                valid += 1;
+                multi_label |= name.labels.len() >= 2;
+                for label in &name.labels {
+                    label_bytes.extend_from_slice(&data[label.start + 1..label.end]);
+                }
+            }
+            multi_label
+                && valid as f64 >= pdus.len() as f64 * LABEL_MIN_RATIO
+                && printable_ratio(&label_bytes) >= LABEL_MIN_PRINTABLE
+        })
+        .map(|offset| Hypothesis::LabelSequence { offset })
+        .collect()
+}
@@ -1,24 +1,66 @@
+pub mod addresses;
+pub mod checkpoint;
+pub mod checksum;
+pub mod clustering;
+pub mod compression;
+pub mod config;
 pub mod corpus;
+pub mod description;
+pub mod diagram;
+pub mod diff;
+pub mod dissector;
 pub mod error;
+pub mod field_table;
+pub mod fields;
+pub mod fragmentation;
+pub mod hexdump;
 pub mod hypothesis;
 pub mod inference;
+pub mod labels;
 pub mod measures;
+pub mod obfuscation;
+pub mod padding;
 pub mod parser;
 pub mod pcap;
 pub mod plugin;
 pub mod plugins;
+pub mod progress;
+pub mod project;
+pub mod protobuf;
+pub mod report;
 pub mod score;
 pub mod segment;
+pub mod spec;
+pub mod state_machine;
+pub mod strings;
+pub mod stuffing;
+pub mod tlv;
 
 #[cfg(test)]
 mod tests;
 
-pub use corpus::{Corpus, Flow, PduRef, UdpDatagram};
+pub use checkpoint::{Checkpoint, DepthCheckpoint};
+pub use clustering::{cluster_pdus, ClusterConfig};
+pub use config::Config;
+pub use corpus::{Corpus, Flow, PduRef, Provenance, UdpDatagram};
+pub use description::FormatDescription;
+pub use diff::ResultDiff;
 pub use error::{Error, Result};
+pub use field_table::FieldTable;
+pub use fields::FieldAnalysis;
 pub use hypothesis::Hypothesis;
-pub use inference::{HypothesisResult, InferenceEngine, InferenceResult, Layer};
-pub use measures::{entropy, entropy_by_offset, AlignmentGain};
-pub use parser::{ParsedCorpus, ParsedPdu, Parser, SegmentKind};
+pub use inference::{
+    ClusteredResult, Confidence, ConsensusLayer, ConsensusResult, DirectionStack, DirectionSummary, DirectionalResult,
+    FlowStack, HypothesisResult, InferenceEngine, InferenceResult, Layer, MessageCluster, TypeBranch,
+};
+pub use measures::{
+    entropy, entropy_by_offset, needleman_wunsch, AlignmentGain, CompressionBackend, CompressionModel, MultipleAlignment,
+};
+pub use parser::{ExceptionSeverity, ParseException, ParsedCorpus, ParsedPdu, Parser, SegmentKind};
 pub use plugin::{HypothesisGenerator, PluginRegistry, Scorer};
+pub use progress::{CancellationToken, Progress, ProgressSink};
+pub use project::{EngineSettings, FieldAnnotation, Project};
 pub use score::{Score, ScoreBreakdown};
 pub use segment::Segment;
+pub use spec::{ModelSpec, ParseReport};
+pub use state_machine::{Exchange, MessageState, StateMachine, StateTransition};
@@ -1,6 +1,22 @@
 use crate::corpus::Corpus;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Nombre minimal d'octets pour qualifier un corpus d'opaque
+const OPAQUE_MIN_BYTES: usize = 64;
+/// Entropie normalisée minimale d'un corpus opaque
+const OPAQUE_MIN_ENTROPY: f64 = 0.9;
+/// Taux de compression minimal (taille compressée / brute) d'un corpus opaque
+const OPAQUE_MIN_COMPRESSION_RATIO: f64 = 0.95;
+/// Taille maximale du dictionnaire zstd entraîné sur les PDUs d'un corpus
+const ZSTD_DICTIONARY_SIZE: usize = 4096;
+/// Niveau de compression zstd
+const ZSTD_LEVEL: i32 = 19;
+/// Préréglage LZMA
+const LZMA_PRESET: u32 = 6;
+/// Ordre maximal du modèle PPM
+const PPM_ORDER: usize = 2;
+
 /// Calcule l'entropie de Shannon d'une séquence d'octets
 pub fn entropy(data: &[u8]) -> f64 {
     if data.is_empty() {
@@ -25,6 +41,19 @@ pub fn entropy(data: &[u8]) -> f64 {
         .sum()
 }
 
+/// Indique si un octet est du texte ASCII imprimable (tabulation et fins de ligne comprises)
+pub fn is_text_byte(byte: u8) -> bool {
+    matches!(byte, 0x20..=0x7e | b'\t' | b'\r' | b'\n')
+}
+
+/// Proportion d'octets texte dans une séquence
+pub fn printable_ratio(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    data.iter().filter(|&&b| is_text_byte(b)).count() as f64 / data.len() as f64
+}
+
 /// Calcule l'entropie par offset dans les PDUs
 pub fn entropy_by_offset(corpus: &Corpus, max_offset: usize) -> Vec<f64> {
     let mut samples: Vec<Vec<u8>> = vec![Vec::new(); max_offset];
@@ -41,47 +70,349 @@ pub fn entropy_by_offset(corpus: &Corpus, max_offset: usize) -> Vec<f64> {
     samples.iter().map(|s| entropy(s)).collect()
 }
 
+/// Entropie binaire de chaque bit des `header_len` premiers octets (bit de poids fort d'abord),
+/// sur les PDUs assez longues
+pub fn entropy_by_bit(corpus: &Corpus, header_len: usize) -> Vec<f64> {
+    let mut ones = vec![0usize; header_len * 8];
+    let mut total = 0;
+    for pdu in &corpus.items {
+        let Some(header) = pdu.as_slice().get(..header_len) else {
+            continue;
+        };
+        total += 1;
+        for (bit, count) in ones.iter_mut().enumerate() {
+            *count += ((header[bit / 8] >> (7 - bit % 8)) & 1) as usize;
+        }
+    }
+
+    ones.iter()
+        .map(|&count| {
+            if total == 0 || count == 0 || count == total {
+                return 0.0;
+            }
+            let p = count as f64 / total as f64;
+            -p * p.log2() - (1.0 - p) * (1.0 - p).log2()
+        })
+        .collect()
+}
+
 /// Gain d'alignement après réalignement
 #[derive(Debug, Clone)]
 pub struct AlignmentGain {
+    /// Coût en bits des octets rangés par offset absolu dans la PDU
     pub original_entropy: f64,
+    /// Coût en bits des octets rangés par offset relatif à l'ancre qui les précède
     pub aligned_entropy: f64,
     pub gain_bits: f64,
+    /// Offsets distincts des ancres, toutes PDUs confondues
     pub anchor_offsets: Vec<usize>,
 }
 
 impl AlignmentGain {
-    pub fn compute(
-        corpus: &Corpus,
-        anchor_offsets: &[usize],
-        max_offset: usize,
-    ) -> Self {
-        let original = entropy_by_offset(corpus, max_offset);
-        let original_entropy: f64 = original.iter().sum();
-
-        // Réaligner selon les ancres
-        let mut aligned_samples: Vec<Vec<u8>> = vec![Vec::new(); max_offset];
-        for pdu in &corpus.items {
-            let slice = pdu.as_slice();
-            for &anchor in anchor_offsets {
-                if anchor < slice.len() && anchor < max_offset {
-                    aligned_samples[anchor].push(slice[anchor]);
+    /// Compare le coût des octets rangés par offset absolu à leur coût une fois rangés par
+    /// offset relatif à la dernière ancre (`anchors[i]` : débuts de structure `(offset, classe)`
+    /// de la PDU `i`). Les ancres de classes différentes (début de message, de SDU...) ont
+    /// des colonnes distinctes.
+    ///
+    /// Le coût d'une colonne est son entropie multipliée par son nombre d'octets ; les offsets
+    /// à partir de `max_offset` partagent une dernière colonne, de sorte que tous les octets
+    /// sont comptés des deux côtés.
+    pub fn compute(corpus: &Corpus, anchors: &[Vec<(usize, usize)>], max_offset: usize) -> Self {
+        let classes = anchors.iter().flatten().map(|&(_, class)| class + 1).max().unwrap_or(1);
+        let mut original_samples: Vec<Vec<u8>> = vec![Vec::new(); max_offset + 1];
+        let mut aligned_samples: Vec<Vec<u8>> = vec![Vec::new(); (max_offset + 1) * classes];
+        let mut anchor_offsets = std::collections::BTreeSet::new();
+
+        for (pdu, pdu_anchors) in corpus.items.iter().zip(anchors) {
+            let mut sorted = pdu_anchors.clone();
+            sorted.sort_unstable();
+            anchor_offsets.extend(sorted.iter().map(|&(offset, _)| offset));
+            let mut next = 0;
+            let mut anchor = (0, 0);
+            for (i, &byte) in pdu.as_slice().iter().enumerate() {
+                while next < sorted.len() && sorted[next].0 <= i {
+                    anchor = sorted[next];
+                    next += 1;
                 }
+                original_samples[i.min(max_offset)].push(byte);
+                aligned_samples[anchor.1 * (max_offset + 1) + (i - anchor.0).min(max_offset)].push(byte);
             }
         }
 
-        let aligned: Vec<f64> = aligned_samples.iter().map(|s| entropy(s)).collect();
-        let aligned_entropy: f64 = aligned.iter().sum();
-
-        let gain_bits = (original_entropy - aligned_entropy) * corpus.total_bytes() as f64 / 8.0;
+        let cost = |samples: &[Vec<u8>]| samples.iter().map(|s| entropy(s) * s.len() as f64).sum::<f64>();
+        let original_entropy = cost(&original_samples);
+        let aligned_entropy = cost(&aligned_samples);
 
         Self {
             original_entropy,
             aligned_entropy,
-            gain_bits,
-            anchor_offsets: anchor_offsets.to_vec(),
+            gain_bits: original_entropy - aligned_entropy,
+            anchor_offsets: anchor_offsets.into_iter().collect(),
+        }
+    }
+}
+
+/// Proportion minimale de l'octet majoritaire pour qu'une colonne d'alignement soit conservée
+const CONSERVED_MIN_RATIO: f64 = 0.9;
+/// Pénalité d'un trou dans l'alignement (un octet apparié exactement vaut +1)
+const ALIGNMENT_GAP_SCORE: f64 = -1.0;
+
+/// Colonne d'un alignement multiple : octets apparentés d'une PDU à l'autre
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlignmentColumn {
+    /// Octet majoritaire de la colonne
+    pub consensus: u8,
+    /// Proportion des PDUs portant l'octet majoritaire (les trous comptent comme divergents)
+    pub conservation: f64,
+    /// Nombre de PDUs sans octet dans cette colonne
+    pub gaps: usize,
+}
+
+/// Nature d'une région d'alignement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegionKind {
+    /// Même octet dans (presque) toutes les PDUs
+    Conserved,
+    /// Octets présents dans toutes les PDUs mais qui varient
+    Variable,
+    /// Colonnes absentes de certaines PDUs : champ de longueur variable
+    Gapped,
+}
+
+/// Plage maximale de colonnes de même nature
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlignedRegion {
+    pub columns: std::ops::Range<usize>,
+    pub kind: RegionKind,
+    /// Offset du début de la région s'il est le même dans toutes les PDUs
+    pub offset: Option<usize>,
+}
+
+/// Alignement multiple progressif (à la Netzob) des PDUs d'un corpus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultipleAlignment {
+    /// Une ligne par PDU alignée, `None` pour un trou
+    pub rows: Vec<Vec<Option<u8>>>,
+    pub columns: Vec<AlignmentColumn>,
+}
+
+/// Comptes d'octets d'une colonne du profil en cours de construction
+#[derive(Clone)]
+struct ProfileColumn {
+    counts: [u32; 256],
+    present: u32,
+}
+
+impl ProfileColumn {
+    fn empty() -> Self {
+        Self {
+            counts: [0; 256],
+            present: 0,
+        }
+    }
+
+    /// Score d'appariement d'un octet : +1 s'il est seul présent dans la colonne, -1 s'il en est absent
+    fn score(&self, byte: u8) -> f64 {
+        if self.present == 0 {
+            return -1.0;
+        }
+        2.0 * self.counts[byte as usize] as f64 / self.present as f64 - 1.0
+    }
+}
+
+/// Aligne (Needleman–Wunsch) une séquence sur un profil ; renvoie le chemin
+/// `(colonne du profil, position dans la séquence)`, `None` marquant un trou.
+fn align_to_profile(profile: &[ProfileColumn], seq: &[u8]) -> Vec<(Option<usize>, Option<usize>)> {
+    let (n, m) = (profile.len(), seq.len());
+    let mut dp = vec![vec![0.0f64; m + 1]; n + 1];
+    for (i, row) in dp.iter_mut().enumerate() {
+        row[0] = i as f64 * ALIGNMENT_GAP_SCORE;
+    }
+    for (j, cell) in dp[0].iter_mut().enumerate() {
+        *cell = j as f64 * ALIGNMENT_GAP_SCORE;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let diagonal = dp[i - 1][j - 1] + profile[i - 1].score(seq[j - 1]);
+            let up = dp[i - 1][j] + ALIGNMENT_GAP_SCORE;
+            let left = dp[i][j - 1] + ALIGNMENT_GAP_SCORE;
+            dp[i][j] = diagonal.max(up).max(left);
+        }
+    }
+
+    // Retour arrière en privilégiant l'appariement pour un résultat déterministe
+    let mut path = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && dp[i][j] == dp[i - 1][j - 1] + profile[i - 1].score(seq[j - 1]) {
+            path.push((Some(i - 1), Some(j - 1)));
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && dp[i][j] == dp[i - 1][j] + ALIGNMENT_GAP_SCORE {
+            path.push((Some(i - 1), None));
+            i -= 1;
+        } else {
+            path.push((None, Some(j - 1)));
+            j -= 1;
         }
     }
+    path.reverse();
+    path
+}
+
+/// Alignement global de Needleman–Wunsch de deux séquences : paires `(position dans a,
+/// position dans b)`, `None` marquant un trou
+pub fn needleman_wunsch(a: &[u8], b: &[u8]) -> Vec<(Option<usize>, Option<usize>)> {
+    let profile: Vec<ProfileColumn> = a
+        .iter()
+        .map(|&byte| {
+            let mut column = ProfileColumn::empty();
+            column.counts[byte as usize] = 1;
+            column.present = 1;
+            column
+        })
+        .collect();
+    align_to_profile(&profile, b)
+}
+
+impl MultipleAlignment {
+    /// Aligne les `max_len` premiers octets d'au plus `max_pdus` PDUs du corpus.
+    ///
+    /// Alignement progressif : chaque PDU est alignée sur le profil (comptes d'octets par
+    /// colonne) des PDUs déjà alignées, qui s'enrichit au fur et à mesure. `None` sous deux PDUs.
+    pub fn compute(corpus: &Corpus, max_pdus: usize, max_len: usize) -> Option<Self> {
+        if corpus.len() < 2 || max_pdus < 2 {
+            return None;
+        }
+        let mut profile: Vec<ProfileColumn> = Vec::new();
+        let mut rows: Vec<Vec<Option<u8>>> = Vec::new();
+
+        for pdu in corpus.items.iter().take(max_pdus) {
+            let data = pdu.as_slice();
+            let seq = &data[..data.len().min(max_len)];
+            let path = align_to_profile(&profile, seq);
+
+            let mut new_profile = Vec::with_capacity(path.len());
+            let mut new_rows: Vec<Vec<Option<u8>>> = vec![Vec::with_capacity(path.len()); rows.len() + 1];
+            for (column, pos) in path {
+                let mut merged = column.map_or_else(ProfileColumn::empty, |c| profile[c].clone());
+                for (row, new_row) in rows.iter().zip(new_rows.iter_mut()) {
+                    new_row.push(column.and_then(|c| row[c]));
+                }
+                let byte = pos.map(|p| seq[p]);
+                if let Some(b) = byte {
+                    merged.counts[b as usize] += 1;
+                    merged.present += 1;
+                }
+                new_rows[rows.len()].push(byte);
+                new_profile.push(merged);
+            }
+            profile = new_profile;
+            rows = new_rows;
+        }
+
+        let columns = profile
+            .iter()
+            .map(|column| {
+                let consensus = (0..=255u8)
+                    .max_by_key(|&b| (column.counts[b as usize], std::cmp::Reverse(b)))
+                    .unwrap_or(0);
+                AlignmentColumn {
+                    consensus,
+                    conservation: column.counts[consensus as usize] as f64 / rows.len() as f64,
+                    gaps: rows.len() - column.present as usize,
+                }
+            })
+            .collect();
+        Some(Self { rows, columns })
+    }
+
+    fn column_kind(column: &AlignmentColumn) -> RegionKind {
+        if column.gaps > 0 {
+            RegionKind::Gapped
+        } else if column.conservation >= CONSERVED_MIN_RATIO {
+            RegionKind::Conserved
+        } else {
+            RegionKind::Variable
+        }
+    }
+
+    /// Offset dans chaque PDU du premier octet à partir de la colonne `column`, s'il est commun
+    fn common_offset(&self, column: usize) -> Option<usize> {
+        let mut offsets = self.rows.iter().map(|row| row[..column].iter().filter(|b| b.is_some()).count());
+        let first = offsets.next()?;
+        offsets.all(|o| o == first).then_some(first)
+    }
+
+    /// Découpe l'alignement en plages maximales de colonnes conservées, variables ou à trous
+    pub fn regions(&self) -> Vec<AlignedRegion> {
+        let mut regions: Vec<AlignedRegion> = Vec::new();
+        for (c, column) in self.columns.iter().enumerate() {
+            let kind = Self::column_kind(column);
+            match regions.last_mut() {
+                Some(region) if region.kind == kind => region.columns.end = c + 1,
+                _ => regions.push(AlignedRegion {
+                    columns: c..c + 1,
+                    kind,
+                    offset: self.common_offset(c),
+                }),
+            }
+        }
+        regions
+    }
+
+    /// Offsets de début des régions communs à toutes les PDUs : frontières de champs candidates
+    pub fn field_boundaries(&self) -> Vec<usize> {
+        let mut boundaries: Vec<usize> = self.regions().iter().filter_map(|r| r.offset).collect();
+        boundaries.dedup();
+        boundaries
+    }
+}
+
+/// Indices statistiques d'une charge utile opaque (chiffrée ou compressée)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpaqueAnalysis {
+    /// Entropie des octets du corpus, en bits par octet
+    pub entropy: f64,
+    /// Entropie rapportée au maximum atteignable pour la taille du corpus
+    pub normalized_entropy: f64,
+    /// Taille compressée rapportée à la taille brute
+    pub compression_ratio: f64,
+    /// χ² de la distribution des octets contre l'uniforme (absent si trop peu d'octets)
+    pub chi_square: Option<f64>,
+}
+
+/// Qualifie un corpus d'opaque : entropie élevée, distribution uniforme, incompressible
+pub fn classify_opaque(corpus: &Corpus) -> Option<OpaqueAnalysis> {
+    let data: Vec<u8> = corpus.items.iter().flat_map(|p| p.as_slice()).copied().collect();
+    if data.len() < OPAQUE_MIN_BYTES {
+        return None;
+    }
+
+    let entropy = entropy(&data);
+    let normalized_entropy = entropy / (data.len() as f64).log2().min(8.0);
+    let compression_ratio = compressed_size(&data).ok()? as f64 / data.len() as f64;
+
+    // Le test du χ² n'a de sens qu'avec au moins 5 occurrences attendues par valeur
+    let chi_square = (data.len() >= 256 * 5).then(|| {
+        let mut counts = [0usize; 256];
+        for &byte in &data {
+            counts[byte as usize] += 1;
+        }
+        let expected = data.len() as f64 / 256.0;
+        counts.iter().map(|&c| (c as f64 - expected).powi(2) / expected).sum::<f64>()
+    });
+    // 255 degrés de liberté : au-delà de moyenne + 4 écarts-types, la distribution n'est pas uniforme
+    let uniform = chi_square.is_none_or(|chi| chi < 255.0 + 4.0 * 510f64.sqrt());
+
+    (normalized_entropy >= OPAQUE_MIN_ENTROPY && compression_ratio >= OPAQUE_MIN_COMPRESSION_RATIO && uniform)
+        .then_some(OpaqueAnalysis {
+            entropy,
+            normalized_entropy,
+            compression_ratio,
+            chi_square,
+        })
 }
 
 /// Calcule la taille compressée (proxy pour MDL data)
@@ -96,3 +427,194 @@ pub fn compressed_size(data: &[u8]) -> crate::Result<usize> {
     Ok(compressed.len())
 }
 
+/// Modèle de compression servant à estimer une longueur de description (bits)
+pub trait CompressionModel: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Taille compressée de `data`, en bits
+    fn compressed_bits(&self, data: &[u8]) -> crate::Result<f64>;
+}
+
+/// DEFLATE (flate2), voir [`compressed_size`]
+pub struct DeflateModel;
+
+impl CompressionModel for DeflateModel {
+    fn name(&self) -> &'static str {
+        "deflate"
+    }
+
+    fn compressed_bits(&self, data: &[u8]) -> crate::Result<f64> {
+        Ok(compressed_size(data)? as f64 * 8.0)
+    }
+}
+
+/// zstd, avec un dictionnaire éventuellement entraîné sur les PDUs : sur de petits tampons, les
+/// motifs communs au corpus ne sont pas payés à chaque appel
+pub struct ZstdModel {
+    dictionary: Option<Vec<u8>>,
+}
+
+impl ZstdModel {
+    pub fn new() -> Self {
+        Self { dictionary: None }
+    }
+
+    /// Entraîne un dictionnaire sur `samples` ; sans dictionnaire si l'entraînement échoue
+    /// (trop peu d'échantillons ou d'octets)
+    pub fn trained(samples: &[&[u8]]) -> Self {
+        let dictionary = zstd::dict::from_samples(samples, ZSTD_DICTIONARY_SIZE).ok();
+        Self { dictionary }
+    }
+
+    pub fn has_dictionary(&self) -> bool {
+        self.dictionary.is_some()
+    }
+}
+
+impl Default for ZstdModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompressionModel for ZstdModel {
+    fn name(&self) -> &'static str {
+        "zstd"
+    }
+
+    fn compressed_bits(&self, data: &[u8]) -> crate::Result<f64> {
+        let compressed = match &self.dictionary {
+            Some(dictionary) => zstd::bulk::Compressor::with_dictionary(ZSTD_LEVEL, dictionary)?.compress(data)?,
+            None => zstd::bulk::compress(data, ZSTD_LEVEL)?,
+        };
+        Ok(compressed.len() as f64 * 8.0)
+    }
+}
+
+/// LZMA (liblzma, format `.lzma` sans somme de contrôle)
+pub struct LzmaModel;
+
+impl CompressionModel for LzmaModel {
+    fn name(&self) -> &'static str {
+        "lzma"
+    }
+
+    fn compressed_bits(&self, data: &[u8]) -> crate::Result<f64> {
+        use std::io::Write;
+
+        let options = xz2::stream::LzmaOptions::new_preset(LZMA_PRESET).map_err(std::io::Error::from)?;
+        let stream = xz2::stream::Stream::new_lzma_encoder(&options).map_err(std::io::Error::from)?;
+        let mut encoder = xz2::write::XzEncoder::new_stream(Vec::new(), stream);
+        encoder.write_all(data)?;
+        Ok(encoder.finish()?.len() as f64 * 8.0)
+    }
+}
+
+/// Estimation PPM d'ordre 2 (méthode C, avec exclusion) : somme des `-log2 p` d'un codeur
+/// arithmétique adaptatif, sans en-tête ni arrondi à l'octet
+pub struct PpmModel;
+
+impl CompressionModel for PpmModel {
+    fn name(&self) -> &'static str {
+        "ppm"
+    }
+
+    fn compressed_bits(&self, data: &[u8]) -> crate::Result<f64> {
+        // (ordre, contexte) -> occurrences par octet
+        let mut contexts: HashMap<(usize, u32), HashMap<u8, u32>> = HashMap::new();
+        let mut bits = 0.0;
+        for (pos, &byte) in data.iter().enumerate() {
+            let mut probability = 1.0;
+            let mut coded = false;
+            // Octets déjà prédits (et refusés) par un contexte d'ordre supérieur, comptés une fois
+            let mut excluded = [false; 256];
+            let mut excluded_count = 0;
+            for order in (0..=PPM_ORDER.min(pos)).rev() {
+                let context = data[pos - order..pos].iter().fold(0u32, |acc, &b| acc << 8 | b as u32);
+                let Some(counts) = contexts.get(&(order, context)) else {
+                    continue;
+                };
+                let candidates = counts.iter().filter(|(&b, _)| !excluded[b as usize]);
+                let (total, distinct) = candidates.fold((0u32, 0u32), |(t, d), (_, &c)| (t + c, d + 1));
+                if distinct == 0 {
+                    continue;
+                }
+                let denominator = (total + distinct) as f64;
+                match counts.get(&byte) {
+                    Some(&count) => {
+                        probability *= count as f64 / denominator;
+                        coded = true;
+                        break;
+                    }
+                    None => {
+                        probability *= distinct as f64 / denominator;
+                        for &b in counts.keys() {
+                            if !excluded[b as usize] {
+                                excluded[b as usize] = true;
+                                excluded_count += 1;
+                            }
+                        }
+                    }
+                }
+            }
+            if !coded {
+                probability /= (256 - excluded_count) as f64;
+            }
+            bits -= probability.log2();
+
+            for order in 0..=PPM_ORDER.min(pos) {
+                let context = data[pos - order..pos].iter().fold(0u32, |acc, &b| acc << 8 | b as u32);
+                *contexts.entry((order, context)).or_default().entry(byte).or_default() += 1;
+            }
+        }
+        Ok(bits)
+    }
+}
+
+/// Modèle de compression choisi pour le scoreur MDL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionBackend {
+    #[default]
+    Deflate,
+    /// zstd avec un dictionnaire entraîné sur les PDUs du corpus
+    Zstd,
+    Lzma,
+    Ppm,
+}
+
+impl CompressionBackend {
+    /// Vrai si le modèle dépend du corpus (dictionnaire entraîné sur ses PDUs) : il est alors
+    /// coûteux à construire et se garde pour toutes les hypothèses du même corpus
+    pub fn is_trained(self) -> bool {
+        matches!(self, CompressionBackend::Zstd)
+    }
+
+    /// Modèle de compression pour les données de `corpus`
+    pub fn model(self, corpus: &Corpus) -> Box<dyn CompressionModel> {
+        match self {
+            CompressionBackend::Deflate => Box::new(DeflateModel),
+            CompressionBackend::Zstd => {
+                let samples: Vec<&[u8]> = corpus.items.iter().map(|p| p.as_slice()).collect();
+                Box::new(ZstdModel::trained(&samples))
+            }
+            CompressionBackend::Lzma => Box::new(LzmaModel),
+            CompressionBackend::Ppm => Box::new(PpmModel),
+        }
+    }
+}
+
+impl std::str::FromStr for CompressionBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "deflate" => Ok(Self::Deflate),
+            "zstd" => Ok(Self::Zstd),
+            "lzma" => Ok(Self::Lzma),
+            "ppm" => Ok(Self::Ppm),
+            _ => Err(format!("modèle de compression inconnu: {}", s)),
+        }
+    }
+}
+
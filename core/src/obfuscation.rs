@@ -0,0 +1,183 @@
+use crate::corpus::Corpus;
+use crate::hypothesis::Obfuscation;
+use crate::measures::{entropy, is_text_byte, printable_ratio};
+use std::collections::HashMap;
+
+/// Nombre maximal de PDUs examinées pour la détection
+const DETECTION_SAMPLE: usize = 512;
+/// Longueur maximale d'une clé XOR répétée
+const MAX_KEY_LEN: usize = 8;
+/// Baisse d'entropie (bits/octet) exigée pour retenir une clé XOR répétée
+const MIN_ENTROPY_DROP: f64 = 1.0;
+/// Proportion d'octets imprimables d'un texte révélé
+const TEXT_MIN_PRINTABLE: f64 = 0.9;
+/// Proportion de lettres d'un texte révélé
+const TEXT_MIN_LETTERS: f64 = 0.4;
+/// Proportion d'espaces et de fins de ligne d'un texte révélé
+const TEXT_MIN_BLANKS: f64 = 0.05;
+/// Proportion d'octets imprimables au-delà de laquelle les données brutes sont déjà du texte
+const RAW_MAX_PRINTABLE: f64 = 0.5;
+/// Proportion minimale de PDUs commençant par une signature connue
+const MAGIC_MIN_RATIO: f64 = 0.95;
+/// Taille de la plus longue signature connue
+const MAX_MAGIC_LEN: usize = 5;
+/// Proportion maximale d'octets nuls de données obfusquées par une clé répétée
+const RAW_MAX_ZERO_RATIO: f64 = 0.02;
+/// Proportion minimale d'octets nuls après désobfuscation par une clé répétée
+const DECODED_MIN_ZERO_RATIO: f64 = 0.25;
+/// Signatures connues qu'une désobfuscation peut révéler en début de PDU
+const KNOWN_MAGICS: [&[u8]; 8] = [
+    b"HTTP/",
+    b"GET ",
+    b"POST ",
+    b"<?xml",
+    b"{\"",
+    b"SSH-",
+    &[0x1f, 0x8b],
+    &[0x28, 0xb5, 0x2f, 0xfd],
+];
+
+impl Obfuscation {
+    /// Retire l'obfuscation (la clé est appliquée depuis le début de chaque PDU)
+    pub fn decode(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Obfuscation::Xor { key } => data.iter().zip(key.iter().cycle()).map(|(b, k)| b ^ k).collect(),
+            Obfuscation::Add { key } => data.iter().map(|b| b.wrapping_sub(*key)).collect(),
+        }
+    }
+}
+
+/// Indique si les PDUs commencent presque toutes par une signature connue
+fn reveals_magic(pdus: &[Vec<u8>]) -> bool {
+    let matching = pdus
+        .iter()
+        .filter(|data| KNOWN_MAGICS.iter().any(|magic| data.starts_with(magic)))
+        .count();
+    matching as f64 >= pdus.len() as f64 * MAGIC_MIN_RATIO
+}
+
+/// Octet le plus fréquent de chaque position modulo `period`
+fn column_modes(pdus: &[&[u8]], period: usize) -> Vec<u8> {
+    let mut counts = vec![HashMap::<u8, usize>::new(); period];
+    for data in pdus {
+        for (i, &b) in data.iter().enumerate() {
+            *counts[i % period].entry(b).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .map(|column| {
+            column
+                .into_iter()
+                .max_by_key(|&(byte, count)| (count, std::cmp::Reverse(byte)))
+                .map_or(0, |(byte, _)| byte)
+        })
+        .collect()
+}
+
+/// Proportion d'octets nuls, abondants dans un protocole binaire en clair
+fn zero_ratio(data: &[u8]) -> f64 {
+    data.iter().filter(|&&b| b == 0).count() as f64 / data.len().max(1) as f64
+}
+
+/// Vrai si `data` a déjà les proportions de lettres, de blancs et d'imprimables d'un texte
+fn looks_like_text(data: &[u8]) -> bool {
+    let ratio = |class: fn(u8) -> bool| data.iter().filter(|&&b| class(b)).count() as f64 / data.len().max(1) as f64;
+    ratio(|b| b.is_ascii_alphabetic()) >= TEXT_MIN_LETTERS
+        && ratio(|b| b == b' ' || b == b'\n') >= TEXT_MIN_BLANKS
+        && ratio(is_text_byte) >= TEXT_MIN_PRINTABLE
+}
+
+/// Cherche une obfuscation simple (XOR ou addition d'un octet, XOR d'une clé répétée).
+///
+/// Les 256 clés d'un octet sont essayées : retenue si elle révèle une signature connue en
+/// tête des PDUs, sinon celle qui rend le plus de texte. Une clé répétée (octet le plus
+/// fréquent de chaque colonne, supposé nul en clair) doit faire réapparaître des octets nuls
+/// absents des données brutes et baisser nettement l'entropie. Sur un binaire sans signature,
+/// une clé d'un octet est l'octet le plus fréquent : il doit redevenir un zéro abondant.
+pub fn detect_obfuscation(corpus: &Corpus) -> Option<Obfuscation> {
+    let pdus: Vec<&[u8]> = corpus.items.iter().take(DETECTION_SAMPLE).map(|p| p.as_slice()).collect();
+    if pdus.is_empty() {
+        return None;
+    }
+    let raw: Vec<u8> = pdus.concat();
+    if raw.is_empty() {
+        return None;
+    }
+
+    let single_byte = (1..=255u8).flat_map(|key| [Obfuscation::Xor { key: vec![key] }, Obfuscation::Add { key }]);
+    let heads: Vec<Vec<u8>> = pdus.iter().map(|d| d[..d.len().min(MAX_MAGIC_LEN)].to_vec()).collect();
+    if !reveals_magic(&heads) {
+        let single: Vec<Obfuscation> = single_byte.collect();
+        if let Some(found) = single
+            .iter()
+            .find(|c| reveals_magic(&heads.iter().map(|h| c.decode(h)).collect::<Vec<_>>()))
+        {
+            return Some(found.clone());
+        }
+
+        // Texte : proportions calculées sur l'histogramme des octets. Des lettres et des
+        // blancs sont exigés, un binaire à peu de valeurs distinctes pouvant être décalé
+        // entièrement dans la plage imprimable.
+        let mut histogram = [0usize; 256];
+        for &b in &raw {
+            histogram[b as usize] += 1;
+        }
+        let ratio = |c: &Obfuscation, class: fn(u8) -> bool| {
+            let count: usize = (0..=255u8)
+                .filter(|&b| class(c.decode(&[b])[0]))
+                .map(|b| histogram[b as usize])
+                .sum();
+            count as f64 / raw.len() as f64
+        };
+        if printable_ratio(&raw) <= RAW_MAX_PRINTABLE {
+            let best = single
+                .iter()
+                .filter(|c| {
+                    ratio(c, |b| b.is_ascii_alphabetic()) >= TEXT_MIN_LETTERS
+                        && ratio(c, |b| b == b' ' || b == b'\n') >= TEXT_MIN_BLANKS
+                })
+                .map(|c| (c, ratio(c, is_text_byte)))
+                .fold(None::<(&Obfuscation, f64)>, |best, (c, printable)| match best {
+                    Some((_, p)) if p >= printable => best,
+                    _ => Some((c, printable)),
+                });
+            if let Some((c, printable)) = best {
+                if printable >= TEXT_MIN_PRINTABLE {
+                    return Some(c.clone());
+                }
+            }
+        }
+    }
+
+    if zero_ratio(&raw) > RAW_MAX_ZERO_RATIO {
+        return None;
+    }
+    let raw_entropy = entropy(&raw);
+    let repeating = (2..=MAX_KEY_LEN)
+        .map(|period| column_modes(&pdus, period))
+        // Une clé qui se répète sur une période plus courte est une clé d'un octet, vue plus bas
+        .filter(|key| (1..key.len()).all(|p| !key.len().is_multiple_of(p) || key[p..] != key[..key.len() - p]))
+        .map(|key| Obfuscation::Xor { key })
+        .find(|candidate| {
+            let decoded: Vec<u8> = pdus.iter().flat_map(|data| candidate.decode(data)).collect();
+            zero_ratio(&decoded) >= DECODED_MIN_ZERO_RATIO && entropy(&decoded) <= raw_entropy - MIN_ENTROPY_DROP
+        });
+    if repeating.is_some() || looks_like_text(&raw) {
+        return repeating;
+    }
+
+    // Une clé d'un octet ne change pas l'entropie : seuls les zéros réapparus la trahissent.
+    // XOR et addition ramènent tous deux l'octet le plus fréquent à zéro ; le clair retenu est
+    // celui aux plus petites valeurs, comme les champs d'un protocole binaire
+    let mode = column_modes(&pdus, 1)[0];
+    if mode == 0 {
+        return None;
+    }
+    [Obfuscation::Xor { key: vec![mode] }, Obfuscation::Add { key: mode }]
+        .into_iter()
+        .map(|candidate| (candidate.decode(&raw), candidate))
+        .filter(|(decoded, _)| zero_ratio(decoded) >= DECODED_MIN_ZERO_RATIO)
+        .min_by_key(|(decoded, _)| decoded.iter().map(|&b| b as u64).sum::<u64>())
+        .map(|(_, candidate)| candidate)
+}
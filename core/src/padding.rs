@@ -0,0 +1,83 @@
+use crate::corpus::Corpus;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Longueur minimale d'une série d'octets identiques pour être tenue pour du bourrage
+pub const MIN_PAD_RUN: usize = 2;
+/// Proportion minimale de PDUs bourrées pour retenir un octet de bourrage
+const PADDED_MIN_RATIO: f64 = 0.2;
+
+/// Nombre de PDUs pour une longueur de bourrage donnée
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PadLength {
+    pub length: usize,
+    pub count: usize,
+}
+
+/// Bourrage final détecté sur un corpus : octet, proportion de PDUs et distribution des longueurs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaddingAnalysis {
+    pub pad_byte: u8,
+    pub padded_pdus: usize,
+    pub total_pdus: usize,
+    pub lengths: Vec<PadLength>,
+}
+
+/// Longueur de la série finale de `pad_byte` (0 si elle est plus courte que `MIN_PAD_RUN`)
+pub fn trailing_pad_len(data: &[u8], pad_byte: u8) -> usize {
+    let run = data.iter().rev().take_while(|&&b| b == pad_byte).count();
+    if run >= MIN_PAD_RUN {
+        run
+    } else {
+        0
+    }
+}
+
+/// Détecte un bourrage final (série d'un même octet en fin de PDU) sur le corpus.
+///
+/// Une série de même longueur à la fin de toutes les PDUs est un trailer fixe, pas un
+/// bourrage : il faut des PDUs non bourrées ou des longueurs variables.
+pub fn detect_padding(corpus: &Corpus) -> Option<PaddingAnalysis> {
+    if corpus.is_empty() {
+        return None;
+    }
+
+    // Octet de bourrage candidat : l'octet final le plus souvent répété
+    let mut candidates: HashMap<u8, usize> = HashMap::new();
+    for pdu in &corpus.items {
+        let data = pdu.as_slice();
+        if let Some(&last) = data.last() {
+            if trailing_pad_len(data, last) > 0 {
+                *candidates.entry(last).or_default() += 1;
+            }
+        }
+    }
+    let (pad_byte, padded_pdus) = candidates
+        .into_iter()
+        .max_by_key(|&(byte, count)| (count, std::cmp::Reverse(byte)))?;
+
+    if (padded_pdus as f64) < corpus.len() as f64 * PADDED_MIN_RATIO {
+        return None;
+    }
+
+    let mut lengths: BTreeMap<usize, usize> = BTreeMap::new();
+    for pdu in &corpus.items {
+        let run = trailing_pad_len(pdu.as_slice(), pad_byte);
+        if run > 0 {
+            *lengths.entry(run).or_default() += 1;
+        }
+    }
+    if padded_pdus == corpus.len() && lengths.len() < 2 {
+        return None;
+    }
+
+    Some(PaddingAnalysis {
+        pad_byte,
+        padded_pdus,
+        total_pdus: corpus.len(),
+        lengths: lengths
+            .into_iter()
+            .map(|(length, count)| PadLength { length, count })
+            .collect(),
+    })
+}
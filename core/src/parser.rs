@@ -1,10 +1,83 @@
 use crate::{corpus::Corpus, hypothesis::Hypothesis, segment::Segment};
+use serde::{Deserialize, Serialize};
+
+/// Gravité d'une exception de parsing, de la plus bénigne à la plus grave
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExceptionSeverity {
+    /// Remarque sans incidence sur le découpage (octet non imprimable dans une ligne de texte)
+    Informational,
+    /// Écart local dont le parseur se remet (champ invalide, contrôle d'intégrité faux, resynchronisation)
+    Recoverable,
+    /// Structure annoncée au-delà de la fin de la PDU : l'hypothèse ne décrit pas les données
+    Fatal,
+}
+
+impl std::str::FromStr for ExceptionSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "informational" => Ok(Self::Informational),
+            "recoverable" => Ok(Self::Recoverable),
+            "fatal" => Ok(Self::Fatal),
+            _ => Err(format!("gravité d'exception inconnue: {}", s)),
+        }
+    }
+}
+
+/// Exception levée par un parseur
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseException {
+    pub severity: ExceptionSeverity,
+    pub message: String,
+}
+
+impl ParseException {
+    pub fn new(severity: ExceptionSeverity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+        }
+    }
+
+    pub fn fatal(message: impl Into<String>) -> Self {
+        Self::new(ExceptionSeverity::Fatal, message)
+    }
+
+    pub fn recoverable(message: impl Into<String>) -> Self {
+        Self::new(ExceptionSeverity::Recoverable, message)
+    }
+
+    pub fn informational(message: impl Into<String>) -> Self {
+        Self::new(ExceptionSeverity::Informational, message)
+    }
+}
+
+/// Un message seul est une exception récupérable
+impl From<String> for ParseException {
+    fn from(message: String) -> Self {
+        Self::recoverable(message)
+    }
+}
+
+impl From<&str> for ParseException {
+    fn from(message: &str) -> Self {
+        Self::recoverable(message)
+    }
+}
+
+impl std::fmt::Display for ParseException {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
 
 /// Résultat du parsing d'une PDU
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsedPdu {
     pub segments: Vec<Segment>,
-    pub exceptions: Vec<String>,
+    pub exceptions: Vec<ParseException>,
 }
 
 impl ParsedPdu {
@@ -15,11 +88,16 @@ impl ParsedPdu {
         }
     }
 
-    pub fn with_exception(mut self, msg: String) -> Self {
-        self.exceptions.push(msg);
+    pub fn with_exception(mut self, exception: impl Into<ParseException>) -> Self {
+        self.exceptions.push(exception.into());
         self
     }
 
+    /// Vérifie si une exception atteint la gravité `severity`
+    pub fn has_exception_at(&self, severity: ExceptionSeverity) -> bool {
+        self.exceptions.iter().any(|exc| exc.severity >= severity)
+    }
+
     /// Extrait les SDUs de cette PDU parsée
     pub fn sdus(&self) -> Vec<std::ops::Range<usize>> {
         self.segments
@@ -34,6 +112,11 @@ impl ParsedPdu {
             .collect()
     }
 
+    /// Vérifie si une structure annoncée dépasse la fin de la PDU (exception fatale)
+    pub fn has_overflow(&self) -> bool {
+        self.has_exception_at(ExceptionSeverity::Fatal)
+    }
+
     /// Vérifie si le parsing a réussi (pas d'erreurs)
     pub fn is_success(&self) -> bool {
         !self.segments.iter().any(|s| {
@@ -43,7 +126,7 @@ impl ParsedPdu {
 }
 
 /// Résultat du parsing d'un corpus
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsedCorpus {
     pub parsed_pdus: Vec<ParsedPdu>,
     pub diagnostics: Vec<String>,
@@ -57,6 +140,15 @@ impl ParsedCorpus {
         }
     }
 
+    /// Estimation de la mémoire occupée par le découpage (PDUs parsées et segments, hors
+    /// messages d'exception)
+    pub fn memory_footprint(&self) -> usize {
+        self.parsed_pdus
+            .iter()
+            .map(|p| std::mem::size_of::<ParsedPdu>() + p.segments.len() * std::mem::size_of::<Segment>())
+            .sum()
+    }
+
     pub fn parse_success_ratio(&self) -> f64 {
         if self.parsed_pdus.is_empty() {
             return 0.0;
@@ -68,6 +160,37 @@ impl ParsedCorpus {
             .count();
         success_count as f64 / self.parsed_pdus.len() as f64
     }
+
+    /// Débordements non explicables par une troncature de capture : une PDU tronquée
+    /// dont une structure dépasse la fin est neutre
+    pub fn has_overflow_in(&self, corpus: &Corpus) -> bool {
+        self.has_exception_at_in(corpus, ExceptionSeverity::Fatal)
+    }
+
+    /// Exceptions de gravité au moins `severity` hors PDUs tronquées ; sur une PDU tronquée,
+    /// seules les exceptions fatales (débordements) sont excusées
+    pub fn has_exception_at_in(&self, corpus: &Corpus, severity: ExceptionSeverity) -> bool {
+        self.parsed_pdus.iter().zip(corpus.items.iter()).any(|(p, pdu)| {
+            p.exceptions
+                .iter()
+                .any(|exc| exc.severity >= severity && !(pdu.truncated && exc.severity == ExceptionSeverity::Fatal))
+        })
+    }
+
+    /// Comme [`ParsedCorpus::parse_success_ratio`], en écartant les PDUs tronquées
+    /// dont l'échec vient d'un débordement au-delà des octets capturés
+    pub fn parse_success_ratio_in(&self, corpus: &Corpus) -> f64 {
+        let (success_count, counted) = self
+            .parsed_pdus
+            .iter()
+            .zip(corpus.items.iter())
+            .filter(|(p, pdu)| !(pdu.truncated && p.has_overflow()))
+            .fold((0usize, 0usize), |(ok, n), (p, _)| (ok + p.is_success() as usize, n + 1));
+        if counted == 0 {
+            return self.parse_success_ratio();
+        }
+        success_count as f64 / counted as f64
+    }
 }
 
 /// Trait pour les parseurs de protocole
@@ -77,6 +200,12 @@ pub trait Parser: Send + Sync {
     fn applicable(&self, h: &Hypothesis) -> bool;
 
     fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus;
+
+    /// Vérification rapide sur un échantillon du corpus, avant le parsing complet : `false`
+    /// écarte l'hypothèse sans la parser ni la scorer. Par défaut, toute hypothèse est gardée.
+    fn plausible(&self, _sample: &Corpus, _h: &Hypothesis) -> bool {
+        true
+    }
 }
 
 /// Type de segment (réexport pour compatibilité)
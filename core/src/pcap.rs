@@ -1,123 +1,67 @@
-use crate::corpus::{Direction, Flow, UdpDatagram};
+use crate::corpus::{Corpus, Direction, Flow, UdpDatagram};
 use crate::Error;
 use crate::Result;
-use std::collections::HashMap;
+use pcap_parser::Linktype;
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
 use std::sync::Arc;
 
-/// Parse un fichier PCAP et extrait tous les paquets UDP dans un seul flow
-pub fn parse_pcap(path: &str) -> Result<Flow> {
+/// Signature d'un flux gzip (RFC 1952)
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// Signature d'une trame zstd (RFC 8878)
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Ouvre une capture, décompressée à la volée si elle est archivée (`.pcap.gz`, `.pcap.zst`).
+///
+/// La compression est détectée par sa signature et non par l'extension du fichier.
+fn open_capture(path: &str) -> Result<Box<dyn std::io::Read + Send>> {
     use std::fs::File;
-    use std::io::BufReader;
+    use std::io::{BufRead, BufReader};
+
+    let mut reader = BufReader::with_capacity(1_048_576, File::open(path)?);
+    let magic = reader.fill_buf()?;
+
+    if magic.starts_with(&GZIP_MAGIC) {
+        // MultiGzDecoder : les archives concaténées (`cat a.gz b.gz`) sont lues en entier
+        return Ok(Box::new(flate2::read::MultiGzDecoder::new(reader)));
+    }
+    if magic.starts_with(&ZSTD_MAGIC) {
+        // Les trames concaténées (`cat a.zst b.zst`) sont lues à la suite, comme pour gzip
+        return Ok(Box::new(zstd::stream::read::Decoder::with_buffer(reader)?));
+    }
+    Ok(Box::new(reader))
+}
 
-    let file = File::open(path)?;
+/// Parcourt tous les paquets d'un fichier PCAP et appelle `f(timestamp, linktype, data, truncated)`
+/// pour chacun ; `truncated` indique que la trame a été coupée par le snaplen (caplen < longueur réelle)
+fn for_each_packet<F>(path: &str, mut f: F) -> Result<()>
+where
+    F: FnMut(f64, Linktype, &[u8], bool),
+{
     // Buffer généreux pour couvrir des paquets/snaplen très grands (snaplen vu à 262144)
     // On prend 1 MiB pour limiter les risques d'Incomplete répétés.
-    let reader = BufReader::with_capacity(1_048_576, file);
+    let reader = open_capture(path)?;
     let mut pcap_reader = pcap_parser::create_reader(1_048_576, reader)
         .map_err(|e| Error::PcapParse(format!("Failed to create reader: {:?}", e)))?;
 
-    let mut all_datagrams: Vec<UdpDatagram> = Vec::new();
+    // Le type de lien est annoncé dans l'en-tête global du fichier
+    let mut linktype = Linktype::ETHERNET;
 
     loop {
         match pcap_reader.next() {
             Ok((offset, pkt)) => {
-                let (ts, data) = match &pkt {
-                    pcap_parser::PcapBlockOwned::LegacyHeader(_) => {
-                        pcap_reader.consume(offset);
-                        continue;
+                match &pkt {
+                    pcap_parser::PcapBlockOwned::LegacyHeader(header) => {
+                        linktype = header.network;
                     }
                     pcap_parser::PcapBlockOwned::Legacy(block) => {
                         let ts = block.ts_sec as f64 + block.ts_usec as f64 / 1_000_000.0;
-                        (ts, &block.data)
+                        f(ts, linktype, block.data, block.caplen < block.origlen);
                     }
                     pcap_parser::PcapBlockOwned::NG(_block) => {
                         // Support pcapng : non géré pour l'instant
-                        pcap_reader.consume(offset);
-                        continue;
-                    }
-                };
-
-                // Parser le paquet Ethernet/IP/UDP
-                if let Ok(parsed) = etherparse::PacketHeaders::from_ethernet_slice(data) {
-                    if let Some(ip) = parsed.net {
-                        let (src_ip, dst_ip, ip_header_len) = match &ip {
-                            etherparse::NetHeaders::Ipv4(h, _) => {
-                                use std::net::Ipv4Addr;
-                                (
-                                    Ipv4Addr::from(h.source).to_string(),
-                                    Ipv4Addr::from(h.destination).to_string(),
-                                    h.header_len() as usize,
-                                )
-                            }
-                            etherparse::NetHeaders::Ipv6(_, _) => {
-                                ("::1".to_string(), "::1".to_string(), 40)
-                            }
-                            _ => continue,
-                        };
-
-                        if let Some(udp) = parsed.transport {
-                            if let etherparse::TransportHeader::Udp(udp_header) = udp {
-                                let src_port = udp_header.source_port;
-                                let dst_port = udp_header.destination_port;
-
-                                // Calculer l'offset du payload UDP
-                                let udp_header_len = 8;
-                                let eth_header_len = 14; // Ethernet header
-                                let payload_start = eth_header_len + ip_header_len + udp_header_len;
-
-                                // Utiliser la longueur UDP depuis l'en-tête UDP pour déterminer la taille réelle du payload
-                                // UDP length (dans l'en-tête UDP) = UDP header (8 bytes) + UDP payload
-                                // UDP payload length = UDP length - UDP header
-                                let udp_length = udp_header.length as usize;
-                                let udp_payload_length = if udp_length >= udp_header_len {
-                                    udp_length - udp_header_len
-                                } else {
-                                    // Si la longueur UDP est invalide, utiliser la taille disponible
-                                    if payload_start < data.len() {
-                                        data.len() - payload_start
-                                    } else {
-                                        pcap_reader.consume(offset);
-                                        continue;
-                                    }
-                                };
-
-                                // Extraire le payload UDP avec la taille correcte (sans padding Ethernet)
-                                let payload = if payload_start + udp_payload_length <= data.len() {
-                                    Arc::from(&data[payload_start..payload_start + udp_payload_length])
-                                } else if payload_start < data.len() {
-                                    // Si la taille calculée dépasse les données disponibles,
-                                    // utiliser ce qui est disponible (cas de capture tronquée)
-                                    Arc::from(&data[payload_start..])
-                                } else {
-                                    pcap_reader.consume(offset);
-                                    continue;
-                                };
-
-                                // Tous les paquets sont dans le même flow (flow_id = 0)
-                                let flow_id = 0;
-                                
-                                // Direction basée sur l'ordre des paquets (alternance simple)
-                                let direction = if all_datagrams.len() % 2 == 0 {
-                                    Direction::ClientToServer
-                                } else {
-                                    Direction::ServerToClient
-                                };
-
-                                let datagram = UdpDatagram {
-                                    timestamp: ts,
-                                    flow_id,
-                                    direction,
-                                    payload,
-                                };
-
-                                all_datagrams.push(datagram);
-                            }
-                        }
                     }
                 }
-
-                // Réinitialiser le compteur d'Incomplete après un succès
-                // (plus utilisé, laissé pour compat éventuelle)
                 pcap_reader.consume(offset);
             }
             Err(pcap_parser::PcapError::Eof) => break,
@@ -134,8 +78,623 @@ pub fn parse_pcap(path: &str) -> Result<Flow> {
         }
     }
 
+    Ok(())
+}
+
+/// Adresses source/destination et longueur totale de l'en-tête IP (extensions comprises)
+fn ip_endpoints(net: &etherparse::NetHeaders) -> (String, String, usize) {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    match net {
+        etherparse::NetHeaders::Ipv4(h, exts) => (
+            Ipv4Addr::from(h.source).to_string(),
+            Ipv4Addr::from(h.destination).to_string(),
+            h.header_len() + exts.header_len(),
+        ),
+        // Les en-têtes d'extension IPv6 (hop-by-hop, routing, fragment, ...) précèdent le header UDP
+        etherparse::NetHeaders::Ipv6(h, exts) => (
+            Ipv6Addr::from(h.source).to_string(),
+            Ipv6Addr::from(h.destination).to_string(),
+            h.header_len() + exts.header_len(),
+        ),
+    }
+}
+
+/// LINKTYPE_IEEE802_11 : trames 802.11 sans pseudo-en-tête (absent de pcap-parser)
+const LINKTYPE_IEEE802_11: Linktype = Linktype(105);
+/// LINKTYPE_IEEE802_11_RADIOTAP : trames 802.11 précédées d'un en-tête radiotap
+const LINKTYPE_IEEE802_11_RADIOTAP: Linktype = Linktype(127);
+
+/// Localise le payload d'une trame de données 802.11 encapsulé en LLC/SNAP.
+///
+/// Retourne l'offset du payload et son EtherType ; les trames de gestion/contrôle,
+/// les trames chiffrées (bit Protected) et les trames sans SNAP sont ignorées.
+fn ieee80211_payload(frame: &[u8]) -> Option<(usize, etherparse::EtherType)> {
+    let (fc0, fc1) = (*frame.first()?, *frame.get(1)?);
+    // Type (bits 2-3 du premier octet) : 2 = données
+    if (fc0 >> 2) & 0x03 != 2 || fc1 & 0x40 != 0 {
+        return None;
+    }
+    // Sous-types sans données (Null, CF-Ack, ...) : bit 2 du sous-type
+    if fc0 & 0x40 != 0 {
+        return None;
+    }
+
+    let mut header_len = 24;
+    // ToDS et FromDS : quatrième adresse (WDS)
+    if fc1 & 0x03 == 0x03 {
+        header_len += 6;
+    }
+    // Sous-type QoS : champ QoS Control, suivi d'un HT Control si le bit Order est positionné
+    if fc0 & 0x80 != 0 {
+        header_len += 2;
+        if fc1 & 0x80 != 0 {
+            header_len += 4;
+        }
+    }
+
+    // LLC/SNAP : DSAP=0xAA, SSAP=0xAA, contrôle 0x03, OUI 00:00:00 puis EtherType
+    let snap = frame.get(header_len..header_len + 8)?;
+    if snap[..6] != [0xaa, 0xaa, 0x03, 0x00, 0x00, 0x00] {
+        return None;
+    }
+    let ether_type = etherparse::EtherType(u16::from_be_bytes([snap[6], snap[7]]));
+    Some((header_len + 8, ether_type))
+}
+
+/// Longueur de l'en-tête radiotap (champ `it_len`, little-endian, octets 2-3)
+fn radiotap_len(data: &[u8]) -> Option<usize> {
+    let header = data.get(..4)?;
+    // Seule la version 0 de radiotap est définie
+    if header[0] != 0 {
+        return None;
+    }
+    Some(u16::from_le_bytes([header[2], header[3]]) as usize)
+}
+
+/// Offset et EtherType du payload d'une trame 802.11, précédée ou non d'un en-tête radiotap
+fn wireless_payload(linktype: Linktype, data: &[u8]) -> Option<(usize, etherparse::EtherType)> {
+    let radiotap = if linktype == LINKTYPE_IEEE802_11_RADIOTAP {
+        radiotap_len(data)?
+    } else {
+        0
+    };
+    let (offset, ether_type) = ieee80211_payload(data.get(radiotap..)?)?;
+    Some((radiotap + offset, ether_type))
+}
+
+/// Décode les en-têtes d'une trame selon le type de lien de la capture.
+///
+/// Retourne le nombre d'octets d'en-tête de lien consommés avant le décodage
+/// par etherparse (0 pour Ethernet, dont l'en-tête est compté dans `link`).
+fn decode_headers(linktype: Linktype, data: &[u8]) -> Option<(usize, etherparse::PacketHeaders<'_>)> {
+    use etherparse::{EtherType, PacketHeaders};
+
+    match linktype {
+        Linktype::ETHERNET => PacketHeaders::from_ethernet_slice(data).ok().map(|h| (0, h)),
+        // Linux cooked capture v1 : en-tête de 16 octets, protocole (EtherType) aux octets 14-15
+        Linktype::LINUX_SLL => {
+            let header = data.get(..16)?;
+            let ether_type = EtherType(u16::from_be_bytes([header[14], header[15]]));
+            PacketHeaders::from_ether_type(ether_type, &data[16..])
+                .ok()
+                .map(|h| (16, h))
+        }
+        // Linux cooked capture v2 : en-tête de 20 octets, protocole (EtherType) aux octets 0-1
+        Linktype::LINUX_SLL2 => {
+            let header = data.get(..20)?;
+            let ether_type = EtherType(u16::from_be_bytes([header[0], header[1]]));
+            PacketHeaders::from_ether_type(ether_type, &data[20..])
+                .ok()
+                .map(|h| (20, h))
+        }
+        // IP brut (interfaces tun, ...) : la trame commence directement par l'en-tête IP
+        Linktype::RAW | Linktype::IPV4 | Linktype::IPV6 => {
+            PacketHeaders::from_ip_slice(data).ok().map(|h| (0, h))
+        }
+        // Loopback BSD/macOS : famille d'adresse sur 4 octets (ordre hôte pour NULL,
+        // réseau pour LOOP) ; la version IP est relue dans l'en-tête IP lui-même
+        Linktype::NULL | Linktype::LOOP => {
+            let ip = data.get(4..)?;
+            PacketHeaders::from_ip_slice(ip).ok().map(|h| (4, h))
+        }
+        // Wi-Fi : en-tête radiotap éventuel, en-tête MAC 802.11 puis LLC/SNAP ;
+        // un éventuel FCS final est ignoré car les longueurs IP bornent le payload
+        LINKTYPE_IEEE802_11 | LINKTYPE_IEEE802_11_RADIOTAP => {
+            let (offset, ether_type) = wireless_payload(linktype, data)?;
+            PacketHeaders::from_ether_type(ether_type, &data[offset..])
+                .ok()
+                .map(|h| (offset, h))
+        }
+        _ => None,
+    }
+}
+
+/// Extrémités d'une direction de flow (src_ip, src_port, dst_ip, dst_port)
+type FlowKey = (String, u16, String, u16);
+
+/// Datagramme UDP décodé depuis une trame capturée
+struct UdpPacket<'a> {
+    src_ip: String,
+    dst_ip: String,
+    src_port: u16,
+    dst_port: u16,
+    payload: &'a [u8],
+    /// Le payload est incomplet (capture tronquée)
+    truncated: bool,
+}
+
+/// Décode une trame (lien/IP/UDP) et extrait le payload UDP
+fn decode_udp<'a>(linktype: Linktype, data: &'a [u8], options: &PcapOptions) -> Option<UdpPacket<'a>> {
+    let (link_prefix_len, parsed) = decode_headers(linktype, data)?;
+    udp_from_headers(link_prefix_len, &parsed, data, options.tunnel_depth())
+}
+
+/// Extrait le datagramme UDP d'en-têtes déjà décodés ; si `tunnel_depth > 0`,
+/// les encapsulations reconnues sont retirées et c'est le datagramme interne qui est retourné
+fn udp_from_headers<'a>(
+    link_prefix_len: usize,
+    parsed: &etherparse::PacketHeaders<'a>,
+    data: &'a [u8],
+    tunnel_depth: usize,
+) -> Option<UdpPacket<'a>> {
+    let (src_ip, dst_ip, ip_header_len) = ip_endpoints(parsed.net.as_ref()?);
+
+    // GRE est porté directement par IP (protocole 47)
+    if tunnel_depth > 0 {
+        if let etherparse::PayloadSlice::Ip(ip_payload) = &parsed.payload {
+            if ip_payload.ip_number == etherparse::IpNumber::GRE {
+                return decapsulate_gre(ip_payload.payload, tunnel_depth - 1);
+            }
+        }
+    }
+
+    let Some(etherparse::TransportHeader::Udp(udp_header)) = &parsed.transport else {
+        return None;
+    };
+
+    // Calculer l'offset du payload UDP à partir des en-têtes réellement décodés
+    // (Ethernet + éventuels tags VLAN 802.1Q / QinQ + IP + UDP)
+    let udp_header_len = 8;
+    let link_header_len = link_prefix_len
+        + parsed.link.as_ref().map_or(0, |l| l.header_len())
+        + parsed.vlan.as_ref().map_or(0, |v| v.header_len());
+    let payload_start = link_header_len + ip_header_len + udp_header_len;
+
+    // Utiliser la longueur UDP depuis l'en-tête UDP pour déterminer la taille réelle du payload
+    // UDP length (dans l'en-tête UDP) = UDP header (8 bytes) + UDP payload
+    // UDP payload length = UDP length - UDP header
+    let udp_length = udp_header.length as usize;
+    let udp_payload_length = if udp_length >= udp_header_len {
+        udp_length - udp_header_len
+    } else if payload_start < data.len() {
+        // Si la longueur UDP est invalide, utiliser la taille disponible
+        data.len() - payload_start
+    } else {
+        return None;
+    };
+
+    // Extraire le payload UDP avec la taille correcte (sans padding Ethernet)
+    let truncated = payload_start + udp_payload_length > data.len();
+    let payload = if payload_start + udp_payload_length <= data.len() {
+        &data[payload_start..payload_start + udp_payload_length]
+    } else if payload_start < data.len() {
+        // Si la taille calculée dépasse les données disponibles,
+        // utiliser ce qui est disponible (cas de capture tronquée)
+        &data[payload_start..]
+    } else {
+        return None;
+    };
+
+    if tunnel_depth > 0 {
+        let inner = match udp_header.destination_port {
+            VXLAN_PORT => decapsulate_vxlan(payload, tunnel_depth - 1),
+            GENEVE_PORT => decapsulate_geneve(payload, tunnel_depth - 1),
+            _ => None,
+        };
+        if inner.is_some() {
+            return inner;
+        }
+    }
+
+    Some(UdpPacket {
+        src_ip,
+        dst_ip,
+        src_port: udp_header.source_port,
+        dst_port: udp_header.destination_port,
+        payload,
+        truncated,
+    })
+}
+
+/// Décode une trame tronquée par le snaplen, que le décodage strict rejette
+/// (longueur IP supérieure aux octets capturés), et extrait le début du payload UDP.
+///
+/// Les tunnels et fragments ne sont pas traités : leurs en-têtes internes sont
+/// généralement perdus avec la fin de la trame.
+fn decode_truncated_udp(linktype: Linktype, data: &[u8]) -> Option<UdpPacket<'_>> {
+    use etherparse::{EtherType, LaxPacketHeaders, LaxPayloadSlice};
+
+    let parsed = match linktype {
+        Linktype::ETHERNET => LaxPacketHeaders::from_ethernet(data).ok()?,
+        Linktype::LINUX_SLL => {
+            let header = data.get(..16)?;
+            let ether_type = EtherType(u16::from_be_bytes([header[14], header[15]]));
+            LaxPacketHeaders::from_ether_type(ether_type, &data[16..])
+        }
+        Linktype::LINUX_SLL2 => {
+            let header = data.get(..20)?;
+            let ether_type = EtherType(u16::from_be_bytes([header[0], header[1]]));
+            LaxPacketHeaders::from_ether_type(ether_type, &data[20..])
+        }
+        Linktype::RAW | Linktype::IPV4 | Linktype::IPV6 => LaxPacketHeaders::from_ip(data).ok()?,
+        Linktype::NULL | Linktype::LOOP => LaxPacketHeaders::from_ip(data.get(4..)?).ok()?,
+        LINKTYPE_IEEE802_11 | LINKTYPE_IEEE802_11_RADIOTAP => {
+            let (offset, ether_type) = wireless_payload(linktype, data)?;
+            LaxPacketHeaders::from_ether_type(ether_type, &data[offset..])
+        }
+        _ => return None,
+    };
+
+    let (src_ip, dst_ip, _) = ip_endpoints(parsed.net.as_ref()?);
+    let Some(etherparse::TransportHeader::Udp(udp_header)) = &parsed.transport else {
+        return None;
+    };
+    let LaxPayloadSlice::Udp { payload, incomplete } = parsed.payload else {
+        return None;
+    };
+    if payload.is_empty() {
+        return None;
+    }
+
+    Some(UdpPacket {
+        src_ip,
+        dst_ip,
+        src_port: udp_header.source_port,
+        dst_port: udp_header.destination_port,
+        payload,
+        truncated: incomplete,
+    })
+}
+
+/// Profondeur maximale de tunnels imbriqués retirés
+const MAX_TUNNEL_DEPTH: usize = 4;
+/// Port UDP IANA de VXLAN
+const VXLAN_PORT: u16 = 4789;
+/// Port UDP IANA de GENEVE
+const GENEVE_PORT: u16 = 6081;
+/// EtherType "Transparent Ethernet Bridging" (trame Ethernet encapsulée)
+const ETHER_TYPE_TEB: u16 = 0x6558;
+
+/// Décode le paquet interne d'un tunnel à partir de son EtherType
+fn decode_inner(ether_type: u16, data: &[u8], tunnel_depth: usize) -> Option<UdpPacket<'_>> {
+    let parsed = if ether_type == ETHER_TYPE_TEB {
+        etherparse::PacketHeaders::from_ethernet_slice(data).ok()?
+    } else {
+        etherparse::PacketHeaders::from_ether_type(etherparse::EtherType(ether_type), data).ok()?
+    };
+    udp_from_headers(0, &parsed, data, tunnel_depth)
+}
+
+/// VXLAN (RFC 7348) : en-tête de 8 octets (flag I = 0x08) suivi d'une trame Ethernet
+fn decapsulate_vxlan(payload: &[u8], tunnel_depth: usize) -> Option<UdpPacket<'_>> {
+    let header = payload.get(..8)?;
+    if header[0] & 0x08 == 0 {
+        return None;
+    }
+    decode_inner(ETHER_TYPE_TEB, &payload[8..], tunnel_depth)
+}
+
+/// GENEVE (RFC 8926) : en-tête de 8 octets + options (longueur en mots de 4 octets)
+fn decapsulate_geneve(payload: &[u8], tunnel_depth: usize) -> Option<UdpPacket<'_>> {
+    let header = payload.get(..8)?;
+    if header[0] >> 6 != 0 {
+        return None;
+    }
+    let options_len = (header[0] & 0x3f) as usize * 4;
+    let protocol = u16::from_be_bytes([header[2], header[3]]);
+    decode_inner(protocol, payload.get(8 + options_len..)?, tunnel_depth)
+}
+
+/// GRE (RFC 2784/2890) : flags + protocole, puis checksum, clé et séquence optionnels
+fn decapsulate_gre(payload: &[u8], tunnel_depth: usize) -> Option<UdpPacket<'_>> {
+    let header = payload.get(..4)?;
+    let flags = u16::from_be_bytes([header[0], header[1]]);
+    // Seule la version 0 est gérée (la version 1 correspond à PPTP)
+    if flags & 0x0007 != 0 {
+        return None;
+    }
+    let protocol = u16::from_be_bytes([header[2], header[3]]);
+    let mut header_len = 4;
+    for bit in [0x8000, 0x2000, 0x1000] {
+        if flags & bit != 0 {
+            header_len += 4;
+        }
+    }
+    decode_inner(protocol, payload.get(header_len..)?, tunnel_depth)
+}
+
+/// Délai au-delà duquel un datagramme fragmenté incomplet est abandonné (secondes)
+const FRAGMENT_TIMEOUT_SECS: f64 = 30.0;
+
+/// Clé de réassemblage IPv4 : (source, destination, identification, protocole)
+type FragmentKey = ([u8; 4], [u8; 4], u16, u8);
+
+/// Fragments reçus pour un même datagramme IPv4
+#[derive(Debug)]
+struct FragmentBuffer {
+    /// En-tête du premier fragment (offset 0), ou à défaut du premier reçu
+    header: etherparse::Ipv4Header,
+    /// Données par offset (en octets)
+    fragments: BTreeMap<usize, Vec<u8>>,
+    /// Taille totale du payload, connue à la réception du dernier fragment
+    total_len: Option<usize>,
+    first_ts: f64,
+}
+
+impl FragmentBuffer {
+    /// Retourne le payload réassemblé si tous les fragments sont présents
+    fn assemble(&self) -> Option<Vec<u8>> {
+        let total_len = self.total_len?;
+        let mut payload = Vec::with_capacity(total_len);
+        for (&offset, data) in &self.fragments {
+            if offset > payload.len() {
+                return None;
+            }
+            // Fragments chevauchants : seules les données nouvelles sont ajoutées
+            let skip = payload.len() - offset;
+            if skip < data.len() {
+                payload.extend_from_slice(&data[skip..]);
+            }
+        }
+        (payload.len() >= total_len).then(|| {
+            payload.truncate(total_len);
+            payload
+        })
+    }
+}
+
+/// Réassemblage des datagrammes IPv4 fragmentés
+#[derive(Debug, Default)]
+struct Ipv4Defragmenter {
+    pending: HashMap<FragmentKey, FragmentBuffer>,
+}
+
+impl Ipv4Defragmenter {
+    /// Ajoute un fragment ; retourne le paquet IPv4 complet (en-tête + payload) une fois réassemblé
+    fn push(&mut self, ts: f64, header: &etherparse::Ipv4Header, payload: &[u8]) -> Option<Vec<u8>> {
+        self.pending.retain(|_, b| ts - b.first_ts <= FRAGMENT_TIMEOUT_SECS);
+
+        let key: FragmentKey = (header.source, header.destination, header.identification, header.protocol.0);
+        let offset = header.fragment_offset.value() as usize * 8;
+        let buffer = self.pending.entry(key).or_insert_with(|| FragmentBuffer {
+            header: header.clone(),
+            fragments: BTreeMap::new(),
+            total_len: None,
+            first_ts: ts,
+        });
+
+        if offset == 0 {
+            buffer.header = header.clone();
+        }
+        if !header.more_fragments {
+            buffer.total_len = Some(offset + payload.len());
+        }
+        match buffer.fragments.get(&offset) {
+            Some(existing) if existing.len() >= payload.len() => {}
+            _ => {
+                buffer.fragments.insert(offset, payload.to_vec());
+            }
+        }
+
+        let assembled = buffer.assemble()?;
+        let mut header = self.pending.remove(&key)?.header;
+        header.more_fragments = false;
+        header.fragment_offset = etherparse::IpFragOffset::ZERO;
+        header.set_payload_len(assembled.len()).ok()?;
+        header.header_checksum = header.calc_header_checksum();
+
+        let mut packet = header.to_bytes().to_vec();
+        packet.extend_from_slice(&assembled);
+        Some(packet)
+    }
+}
+
+/// Parcourt les datagrammes UDP d'une capture, après réassemblage des fragments IPv4
+fn for_each_udp<F>(path: &str, options: &PcapOptions, mut f: F) -> Result<()>
+where
+    F: FnMut(f64, UdpPacket<'_>),
+{
+    let mut defrag = Ipv4Defragmenter::default();
+
+    for_each_packet(path, |ts, linktype, data, truncated| {
+        let Some((linktype, data)) = options.link_view(linktype, data) else {
+            return;
+        };
+        let Some((link_prefix_len, parsed)) = decode_headers(linktype, data) else {
+            if truncated {
+                if let Some(udp) = decode_truncated_udp(linktype, data) {
+                    f(ts, udp);
+                }
+            }
+            return;
+        };
+
+        if let Some(etherparse::NetHeaders::Ipv4(header, _)) = &parsed.net {
+            if header.is_fragmenting_payload() {
+                let etherparse::PayloadSlice::Ip(fragment) = &parsed.payload else {
+                    return;
+                };
+                if let Some(packet) = defrag.push(ts, header, fragment.payload) {
+                    if let Some(udp) = decode_udp(Linktype::RAW, &packet, options) {
+                        f(ts, udp);
+                    }
+                }
+                return;
+            }
+        }
+
+        if let Some(udp) = udp_from_headers(link_prefix_len, &parsed, data, options.tunnel_depth()) {
+            f(ts, udp);
+        }
+    })
+}
+
+/// Options d'extraction des paquets d'une capture
+#[derive(Debug, Clone, Default)]
+pub struct PcapOptions {
+    /// Retirer les encapsulations VXLAN, GENEVE et GRE pour analyser le trafic interne
+    pub decapsulate_tunnels: bool,
+    /// Regrouper les deux directions d'une conversation dans un seul flow bidirectionnel
+    pub pair_directions: bool,
+    /// Nombre d'octets d'en-tête de lien à sauter avant l'en-tête IP, quel que soit
+    /// le type de lien annoncé (DLT_USER, formats propriétaires)
+    pub link_offset: Option<usize>,
+    /// Offsets IP par type de lien (numéro LINKTYPE_*), prioritaires sur `link_offset`
+    pub linktype_offsets: HashMap<u32, usize>,
+    /// Flows retenus (voir [`filter_flows`]), avant leur éventuelle fusion en un seul flow
+    pub flow_filter: FlowFilter,
+}
+
+/// Sélection des flows par 5-tuple et nombre de PDUs, stable d'un découpage de la capture à
+/// l'autre (contrairement à l'indice d'un flow) ; un critère absent retient tous les flows.
+///
+/// Un flow bidirectionnel est comparé avec son 5-tuple normalisé côté client.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FlowFilter {
+    pub src_ip: Option<IpAddr>,
+    pub dst_ip: Option<IpAddr>,
+    pub src_port: Option<u16>,
+    pub dst_port: Option<u16>,
+    /// Nombre minimal de PDUs (datagrammes, segments ou messages TCP) du flow
+    pub min_packets: usize,
+}
+
+impl FlowFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_src_ip(mut self, ip: IpAddr) -> Self {
+        self.src_ip = Some(ip);
+        self
+    }
+
+    pub fn with_dst_ip(mut self, ip: IpAddr) -> Self {
+        self.dst_ip = Some(ip);
+        self
+    }
+
+    pub fn with_src_port(mut self, port: u16) -> Self {
+        self.src_port = Some(port);
+        self
+    }
+
+    pub fn with_dst_port(mut self, port: u16) -> Self {
+        self.dst_port = Some(port);
+        self
+    }
+
+    pub fn with_min_packets(mut self, count: usize) -> Self {
+        self.min_packets = count;
+        self
+    }
+
+    pub fn matches(&self, flow: &Flow) -> bool {
+        let ip_matches = |expected: Option<IpAddr>, actual: &str| {
+            expected.is_none_or(|ip| actual.parse::<IpAddr>().is_ok_and(|actual| actual == ip))
+        };
+        ip_matches(self.src_ip, &flow.src_ip)
+            && ip_matches(self.dst_ip, &flow.dst_ip)
+            && self.src_port.is_none_or(|port| port == flow.src_port)
+            && self.dst_port.is_none_or(|port| port == flow.dst_port)
+            && flow.datagrams.len() >= self.min_packets
+    }
+}
+
+/// Flows retenus par `filter`, leurs `flow_id` renumérotés dans l'ordre
+pub fn filter_flows(flows: Vec<Flow>, filter: &FlowFilter) -> Vec<Flow> {
+    let mut kept: Vec<Flow> = flows.into_iter().filter(|flow| filter.matches(flow)).collect();
+    for (flow_id, flow) in kept.iter_mut().enumerate() {
+        for datagram in &mut flow.datagrams {
+            datagram.flow_id = flow_id;
+        }
+    }
+    kept
+}
+
+impl PcapOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tunnel_decapsulation(mut self, enabled: bool) -> Self {
+        self.decapsulate_tunnels = enabled;
+        self
+    }
+
+    pub fn with_direction_pairing(mut self, enabled: bool) -> Self {
+        self.pair_directions = enabled;
+        self
+    }
+
+    pub fn with_link_offset(mut self, offset: usize) -> Self {
+        self.link_offset = Some(offset);
+        self
+    }
+
+    pub fn with_linktype_offset(mut self, linktype: u32, offset: usize) -> Self {
+        self.linktype_offsets.insert(linktype, offset);
+        self
+    }
+
+    pub fn with_flow_filter(mut self, filter: FlowFilter) -> Self {
+        self.flow_filter = filter;
+        self
+    }
+
+    /// Trame à décoder : si un offset est configuré pour ce type de lien, la trame
+    /// est vue comme de l'IP brut commençant à cet offset
+    fn link_view<'a>(&self, linktype: Linktype, data: &'a [u8]) -> Option<(Linktype, &'a [u8])> {
+        match self.linktype_offsets.get(&(linktype.0 as u32)).copied().or(self.link_offset) {
+            Some(offset) => Some((Linktype::RAW, data.get(offset..)?)),
+            None => Some((linktype, data)),
+        }
+    }
+
+    fn tunnel_depth(&self) -> usize {
+        if self.decapsulate_tunnels {
+            MAX_TUNNEL_DEPTH
+        } else {
+            0
+        }
+    }
+}
+
+/// Parse un fichier PCAP et extrait tous les paquets UDP dans un seul flow
+pub fn parse_pcap(path: &str) -> Result<Flow> {
+    parse_pcap_with(path, &PcapOptions::default())
+}
+
+/// Comme [`parse_pcap`], avec des options d'extraction
+pub fn parse_pcap_with(path: &str, options: &PcapOptions) -> Result<Flow> {
+    parse_pcap_files(&[path], options)
+}
+
+/// Fusionne les paquets UDP de plusieurs fichiers PCAP dans un seul flow, par ordre chronologique
+pub fn parse_pcap_files<P: AsRef<str>>(paths: &[P], options: &PcapOptions) -> Result<Flow> {
+    // La direction de chaque datagramme est déterminée par son 5-tuple avant la fusion
+    let mut all_datagrams: Vec<UdpDatagram> = parse_pcap_files_flows(paths, options)?
+        .into_iter()
+        .flat_map(|f| f.datagrams)
+        .collect();
+
+    // Tous les paquets sont dans le même flow (flow_id = 0)
+    for datagram in &mut all_datagrams {
+        datagram.flow_id = 0;
+    }
+
     // Créer un seul flow avec tous les paquets
-    // Utiliser les valeurs du premier paquet pour les métadonnées du flow
     let flow = if all_datagrams.is_empty() {
         Flow {
             src_ip: "0.0.0.0".to_string(),
@@ -148,7 +707,7 @@ pub fn parse_pcap(path: &str) -> Result<Flow> {
     } else {
         // Trier par timestamp pour avoir un ordre chronologique
         all_datagrams.sort_by(|a, b| a.timestamp.partial_cmp(&b.timestamp).unwrap_or(std::cmp::Ordering::Equal));
-        
+
         Flow {
             src_ip: "All".to_string(),
             dst_ip: "All".to_string(),
@@ -162,3 +721,541 @@ pub fn parse_pcap(path: &str) -> Result<Flow> {
     Ok(flow)
 }
 
+/// Parse un fichier PCAP et extrait les paquets UDP en un flow par 5-tuple
+pub fn parse_pcap_flows(path: &str) -> Result<Vec<Flow>> {
+    parse_pcap_flows_with(path, &PcapOptions::default())
+}
+
+/// Comme [`parse_pcap_flows`], avec des options d'extraction
+pub fn parse_pcap_flows_with(path: &str, options: &PcapOptions) -> Result<Vec<Flow>> {
+    parse_pcap_files_flows(&[path], options)
+}
+
+/// Extrait les flows UDP de plusieurs fichiers PCAP ; les flows de même 5-tuple
+/// présents dans plusieurs fichiers sont fusionnés par ordre chronologique
+pub fn parse_pcap_files_flows<P: AsRef<str>>(paths: &[P], options: &PcapOptions) -> Result<Vec<Flow>> {
+    let mut flows: Vec<Flow> = Vec::new();
+    let mut index: HashMap<FlowKey, usize> = HashMap::new();
+
+    for path in paths {
+        for_each_udp(path.as_ref(), options, |ts, packet| {
+            let key: FlowKey = (
+                packet.src_ip.clone(),
+                packet.src_port,
+                packet.dst_ip.clone(),
+                packet.dst_port,
+            );
+            let flow_id = *index.entry(key).or_insert_with(|| {
+                flows.push(Flow {
+                    src_ip: packet.src_ip.clone(),
+                    dst_ip: packet.dst_ip.clone(),
+                    src_port: packet.src_port,
+                    dst_port: packet.dst_port,
+                    protocol: 17, // UDP
+                    datagrams: Vec::new(),
+                });
+                flows.len() - 1
+            });
+
+            flows[flow_id].datagrams.push(UdpDatagram {
+                timestamp: ts,
+                flow_id,
+                direction: Direction::ClientToServer,
+                payload: Arc::from(packet.payload),
+                truncated: packet.truncated,
+            });
+        })?;
+    }
+
+    for flow in &mut flows {
+        flow.datagrams.sort_by(|a, b| a.timestamp.partial_cmp(&b.timestamp).unwrap_or(std::cmp::Ordering::Equal));
+    }
+    infer_directions(&mut flows);
+
+    if options.pair_directions {
+        flows = pair_directions(flows);
+    }
+
+    Ok(filter_flows(flows, &options.flow_filter))
+}
+
+/// Adresses IP et MAC des en-têtes de toutes les trames des fichiers PCAP, UDP ou non
+pub fn capture_addresses<P: AsRef<str>>(
+    paths: &[P],
+    options: &PcapOptions,
+) -> Result<crate::addresses::CaptureAddresses> {
+    let mut addresses = crate::addresses::CaptureAddresses::default();
+    for path in paths {
+        for_each_packet(path.as_ref(), |_, linktype, data, _| {
+            let Some((linktype, data)) = options.link_view(linktype, data) else {
+                return;
+            };
+            let Some((_, parsed)) = decode_headers(linktype, data) else {
+                return;
+            };
+            if let Some(etherparse::LinkHeader::Ethernet2(ethernet)) = &parsed.link {
+                addresses.insert_mac(ethernet.source);
+                addresses.insert_mac(ethernet.destination);
+            }
+            match &parsed.net {
+                Some(etherparse::NetHeaders::Ipv4(h, _)) => {
+                    addresses.insert_ip(std::net::Ipv4Addr::from(h.source).into());
+                    addresses.insert_ip(std::net::Ipv4Addr::from(h.destination).into());
+                }
+                Some(etherparse::NetHeaders::Ipv6(h, _)) => {
+                    addresses.insert_ip(std::net::Ipv6Addr::from(h.source).into());
+                    addresses.insert_ip(std::net::Ipv6Addr::from(h.destination).into());
+                }
+                None => {}
+            }
+        })?;
+    }
+    Ok(addresses)
+}
+
+/// Premier port des plages éphémères (Linux : 32768, IANA : 49152)
+const EPHEMERAL_PORT_START: u16 = 32768;
+
+/// Détermine si l'extrémité `port` est le client d'une conversation avec `peer_port`.
+///
+/// Heuristiques, par ordre de priorité : un port bien connu (< 1024) est côté serveur,
+/// un port éphémère est côté client, et à défaut l'initiateur (premier paquet) est le client.
+fn is_client_port(port: u16, peer_port: u16, sent_first: bool) -> bool {
+    let (well_known, peer_well_known) = (port < 1024, peer_port < 1024);
+    if well_known != peer_well_known {
+        return peer_well_known;
+    }
+    let (ephemeral, peer_ephemeral) = (port >= EPHEMERAL_PORT_START, peer_port >= EPHEMERAL_PORT_START);
+    if ephemeral != peer_ephemeral {
+        return ephemeral;
+    }
+    sent_first
+}
+
+/// Affecte la direction des datagrammes de flows unidirectionnels en appariant
+/// les flows A→B et B→A d'une même conversation
+pub fn infer_directions(flows: &mut [Flow]) {
+    let first_ts: HashMap<FlowKey, f64> = flows
+        .iter()
+        .map(|f| {
+            let ts = f.datagrams.iter().map(|d| d.timestamp).fold(f64::INFINITY, f64::min);
+            ((f.src_ip.clone(), f.src_port, f.dst_ip.clone(), f.dst_port), ts)
+        })
+        .collect();
+
+    for flow in flows.iter_mut() {
+        let key = (flow.src_ip.clone(), flow.src_port, flow.dst_ip.clone(), flow.dst_port);
+        let reverse = (flow.dst_ip.clone(), flow.dst_port, flow.src_ip.clone(), flow.src_port);
+        let sent_first = match first_ts.get(&reverse) {
+            Some(&peer_ts) => first_ts[&key] <= peer_ts && key != reverse,
+            None => true,
+        };
+        let direction = if is_client_port(flow.src_port, flow.dst_port, sent_first) {
+            Direction::ClientToServer
+        } else {
+            Direction::ServerToClient
+        };
+        for datagram in &mut flow.datagrams {
+            datagram.direction = direction;
+        }
+    }
+}
+
+/// Regroupe les flows A→B et B→A d'une même conversation en un flow bidirectionnel.
+///
+/// Le 5-tuple est normalisé côté client (src = client, dst = serveur) et chaque
+/// datagramme conserve sa direction ; les `flow_id` sont renumérotés.
+pub fn pair_directions(flows: Vec<Flow>) -> Vec<Flow> {
+    let mut paired: Vec<Flow> = Vec::new();
+    let mut index: HashMap<FlowKey, usize> = HashMap::new();
+
+    for flow in flows {
+        let key = (flow.src_ip.clone(), flow.src_port, flow.dst_ip.clone(), flow.dst_port);
+        let reverse = (flow.dst_ip.clone(), flow.dst_port, flow.src_ip.clone(), flow.src_port);
+        let is_server_side = flow
+            .datagrams
+            .first()
+            .is_some_and(|d| d.direction == Direction::ServerToClient);
+
+        match index.get(&reverse).copied() {
+            // Le 5-tuple du flow apparié est déjà orienté côté client
+            Some(i) => paired[i].datagrams.extend(flow.datagrams),
+            None => {
+                let mut flow = flow;
+                if is_server_side {
+                    std::mem::swap(&mut flow.src_ip, &mut flow.dst_ip);
+                    std::mem::swap(&mut flow.src_port, &mut flow.dst_port);
+                }
+                index.insert(key, paired.len());
+                paired.push(flow);
+            }
+        }
+    }
+
+    for (flow_id, flow) in paired.iter_mut().enumerate() {
+        flow.datagrams.sort_by(|a, b| a.timestamp.partial_cmp(&b.timestamp).unwrap_or(std::cmp::Ordering::Equal));
+        for datagram in &mut flow.datagrams {
+            datagram.flow_id = flow_id;
+        }
+    }
+
+    paired
+}
+
+/// Découpage des PDUs pour les flows TCP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TcpPduMode {
+    /// Une PDU par segment TCP (après remise en ordre)
+    #[default]
+    Segments,
+    /// Une seule PDU par direction contenant tout le flux réassemblé
+    Stream,
+}
+
+/// État de réassemblage d'une direction TCP
+#[derive(Debug)]
+struct TcpHalfStream {
+    /// Numéro de séquence correspondant au premier octet de données
+    base_seq: u32,
+    /// Offset relatif (depuis base_seq) du prochain octet attendu
+    next_offset: u64,
+    /// Segments arrivés en avance, indexés par offset relatif
+    pending: BTreeMap<u64, (f64, Vec<u8>)>,
+    /// Segments remis dans l'ordre
+    delivered: Vec<(f64, Vec<u8>)>,
+    /// Trous constatés dans le flux (données jamais capturées)
+    gaps: usize,
+}
+
+impl TcpHalfStream {
+    fn new(base_seq: u32) -> Self {
+        Self {
+            base_seq,
+            next_offset: 0,
+            pending: BTreeMap::new(),
+            delivered: Vec::new(),
+            gaps: 0,
+        }
+    }
+
+    fn push(&mut self, ts: f64, seq: u32, payload: &[u8]) {
+        if payload.is_empty() {
+            return;
+        }
+
+        // Offset relatif modulo 2^32 ; un offset "négatif" (au-delà de la moitié de l'espace)
+        // correspond à une retransmission de données antérieures au début du flux
+        let rel = seq.wrapping_sub(self.base_seq);
+        if rel > u32::MAX / 2 {
+            return;
+        }
+        let rel = rel as u64;
+        let end = rel + payload.len() as u64;
+
+        // Retransmission de données déjà remises
+        if end <= self.next_offset {
+            return;
+        }
+
+        // Chevauchement partiel avec les données déjà remises : garder la partie nouvelle
+        let (rel, data) = if rel < self.next_offset {
+            let skip = (self.next_offset - rel) as usize;
+            (self.next_offset, payload[skip..].to_vec())
+        } else {
+            (rel, payload.to_vec())
+        };
+
+        // En cas de doublon hors-ordre, conserver le segment le plus long
+        match self.pending.get(&rel) {
+            Some((_, existing)) if existing.len() >= data.len() => {}
+            _ => {
+                self.pending.insert(rel, (ts, data));
+            }
+        }
+
+        self.drain(false);
+    }
+
+    /// Remet dans l'ordre les segments contigus ; si `force`, saute les trous
+    fn drain(&mut self, force: bool) {
+        while let Some((&rel, _)) = self.pending.iter().next() {
+            if rel > self.next_offset {
+                if !force {
+                    break;
+                }
+                self.gaps += 1;
+                self.next_offset = rel;
+            }
+
+            let (ts, data) = self.pending.remove(&rel).unwrap_or_default();
+            let end = rel + data.len() as u64;
+            if end <= self.next_offset {
+                continue;
+            }
+            let skip = (self.next_offset - rel) as usize;
+            self.delivered.push((ts, data[skip..].to_vec()));
+            self.next_offset = end;
+        }
+    }
+}
+
+/// Réassembleur TCP : suit les numéros de séquence par direction,
+/// remet les segments hors-ordre dans l'ordre et élimine les retransmissions
+#[derive(Debug, Default)]
+pub struct TcpReassembler {
+    streams: HashMap<FlowKey, TcpHalfStream>,
+    /// Ordre d'apparition des directions (pour des flow_id stables)
+    order: Vec<FlowKey>,
+    /// Directions ayant initié la connexion (SYN sans ACK)
+    initiators: Vec<FlowKey>,
+}
+
+impl TcpReassembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ajoute un segment TCP
+    #[allow(clippy::too_many_arguments)]
+    pub fn push(
+        &mut self,
+        ts: f64,
+        src_ip: &str,
+        src_port: u16,
+        dst_ip: &str,
+        dst_port: u16,
+        header: &etherparse::TcpHeader,
+        payload: &[u8],
+    ) {
+        let key: FlowKey = (src_ip.to_string(), src_port, dst_ip.to_string(), dst_port);
+
+        if header.syn && !header.ack && !self.initiators.contains(&key) {
+            self.initiators.push(key.clone());
+        }
+
+        if header.syn {
+            // Le SYN consomme un numéro de séquence : les données commencent à seq + 1.
+            // Un SYN retransmis ou tardif réinitialise la base tant qu'aucune donnée n'est remise.
+            let base = header.sequence_number.wrapping_add(1);
+            match self.streams.get_mut(&key) {
+                Some(stream) if stream.delivered.is_empty() && stream.pending.is_empty() => {
+                    stream.base_seq = base;
+                }
+                Some(_) => {}
+                None => {
+                    self.order.push(key.clone());
+                    self.streams.insert(key.clone(), TcpHalfStream::new(base));
+                }
+            }
+        }
+
+        if payload.is_empty() {
+            return;
+        }
+
+        let stream = self.streams.entry(key.clone()).or_insert_with(|| {
+            // Connexion déjà établie au début de la capture : le premier segment vu sert de base
+            self.order.push(key.clone());
+            TcpHalfStream::new(header.sequence_number)
+        });
+        stream.push(ts, header.sequence_number, payload);
+    }
+
+    /// Termine le réassemblage et produit un flow par direction
+    pub fn into_flows(mut self, mode: TcpPduMode) -> Vec<Flow> {
+        // Regrouper les deux directions d'une même connexion sous un même flow_id
+        let mut conn_ids: HashMap<FlowKey, usize> = HashMap::new();
+        let mut next_conn_id = 0;
+        for key in &self.order {
+            let reverse = (key.2.clone(), key.3, key.0.clone(), key.1);
+            let id = conn_ids.get(&reverse).copied().unwrap_or_else(|| {
+                next_conn_id += 1;
+                next_conn_id - 1
+            });
+            conn_ids.insert(key.clone(), id);
+        }
+
+        let mut flows = Vec::new();
+        for key in &self.order {
+            let Some(mut stream) = self.streams.remove(key) else {
+                continue;
+            };
+            stream.drain(true);
+            if stream.gaps > 0 {
+                tracing::warn!(
+                    "TCP {}:{} -> {}:{}: {} trou(s) dans le flux réassemblé",
+                    key.0,
+                    key.1,
+                    key.2,
+                    key.3,
+                    stream.gaps
+                );
+            }
+            if stream.delivered.is_empty() {
+                continue;
+            }
+
+            let flow_id = conn_ids[key];
+            let reverse = (key.2.clone(), key.3, key.0.clone(), key.1);
+            // Sans SYN observé, on se rabat sur les heuristiques de ports et l'ordre d'apparition
+            let is_client = if self.initiators.contains(key) {
+                true
+            } else if self.initiators.contains(&reverse) {
+                false
+            } else {
+                let first_seen = |k: &FlowKey| self.order.iter().position(|o| o == k);
+                let sent_first = match (first_seen(key), first_seen(&reverse)) {
+                    (Some(a), Some(b)) => a < b,
+                    _ => true,
+                };
+                is_client_port(key.1, key.3, sent_first)
+            };
+            let direction = if is_client {
+                Direction::ClientToServer
+            } else {
+                Direction::ServerToClient
+            };
+
+            let datagrams = match mode {
+                TcpPduMode::Segments => stream
+                    .delivered
+                    .into_iter()
+                    .map(|(ts, data)| UdpDatagram {
+                        timestamp: ts,
+                        flow_id,
+                        direction,
+                        payload: Arc::from(data),
+                        truncated: false,
+                    })
+                    .collect(),
+                TcpPduMode::Stream => {
+                    let timestamp = stream.delivered[0].0;
+                    let data: Vec<u8> = stream.delivered.into_iter().flat_map(|(_, d)| d).collect();
+                    vec![UdpDatagram {
+                        timestamp,
+                        flow_id,
+                        direction,
+                        payload: Arc::from(data),
+                        truncated: false,
+                    }]
+                }
+            };
+
+            flows.push(Flow {
+                src_ip: key.0.clone(),
+                dst_ip: key.2.clone(),
+                src_port: key.1,
+                dst_port: key.3,
+                protocol: 6, // TCP
+                datagrams,
+            });
+        }
+
+        flows
+    }
+}
+
+/// Parse un fichier PCAP et réassemble les flux TCP (un flow par direction)
+pub fn parse_pcap_tcp(path: &str, mode: TcpPduMode) -> Result<Vec<Flow>> {
+    parse_pcap_tcp_files(&[path], mode, &PcapOptions::default())
+}
+
+/// Réassemble les flux TCP de plusieurs fichiers PCAP (un flow par direction, ou par
+/// conversation avec `pair_directions`)
+pub fn parse_pcap_tcp_files<P: AsRef<str>>(
+    paths: &[P],
+    mode: TcpPduMode,
+    options: &PcapOptions,
+) -> Result<Vec<Flow>> {
+    let mut reassembler = TcpReassembler::new();
+
+    for path in paths {
+        for_each_packet(path.as_ref(), |ts, linktype, data, _truncated| {
+            let Some((linktype, data)) = options.link_view(linktype, data) else {
+                return;
+            };
+            let Some((_, parsed)) = decode_headers(linktype, data) else {
+                return;
+            };
+            let Some(net) = &parsed.net else {
+                return;
+            };
+            let (src_ip, dst_ip, _) = ip_endpoints(net);
+            let Some(etherparse::TransportHeader::Tcp(tcp)) = &parsed.transport else {
+                return;
+            };
+            // Le payload est borné par la longueur IP (sans padding Ethernet)
+            let etherparse::PayloadSlice::Tcp(payload) = parsed.payload else {
+                return;
+            };
+
+            reassembler.push(
+                ts,
+                &src_ip,
+                tcp.source_port,
+                &dst_ip,
+                tcp.destination_port,
+                tcp,
+                payload,
+            );
+        })?;
+    }
+
+    let mut flows = reassembler.into_flows(mode);
+    if options.pair_directions {
+        flows = pair_directions(flows);
+    }
+    Ok(filter_flows(flows, &options.flow_filter))
+}
+
+/// Adresses fictives du client et du serveur des captures synthétiques
+const SYNTHETIC_CLIENT: [u8; 4] = [10, 0, 0, 1];
+const SYNTHETIC_SERVER: [u8; 4] = [10, 0, 0, 2];
+/// Port du serveur des captures synthétiques ; le port client est tiré du flow d'origine
+const SYNTHETIC_SERVER_PORT: u16 = 9;
+const SYNTHETIC_CLIENT_PORT_BASE: u16 = 49152;
+/// Taille maximale du payload d'un datagramme UDP sur IPv4
+const UDP_MAX_PAYLOAD: usize = 65507;
+
+/// Capture PCAP synthétique (IP brut, LINKTYPE_RAW) : un datagramme UDP par PDU du corpus.
+///
+/// Les adresses et ports sont fictifs (10.0.0.1 client, 10.0.0.2 serveur) : la direction de
+/// chaque PDU fixe le sens du datagramme, son flow d'origine le port client, et son horodatage
+/// celui du paquet (à défaut, une microseconde par rang). Une PDU plus longue qu'un datagramme
+/// est tronquée, et sa longueur d'origine conservée dans l'en-tête du paquet.
+pub fn corpus_to_pcap(corpus: &Corpus) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&0xa1b2_c3d4u32.to_le_bytes());
+    out.extend_from_slice(&2u16.to_le_bytes());
+    out.extend_from_slice(&4u16.to_le_bytes());
+    out.extend_from_slice(&0i32.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&65535u32.to_le_bytes());
+    out.extend_from_slice(&(Linktype::RAW.0 as u32).to_le_bytes());
+
+    for (index, pdu) in corpus.items.iter().enumerate() {
+        let payload = pdu.as_slice();
+        let client_port = SYNTHETIC_CLIENT_PORT_BASE + (pdu.flow_id.unwrap_or(0) % 16384) as u16;
+        let builder = match pdu.direction {
+            Some(Direction::ServerToClient) => etherparse::PacketBuilder::ipv4(SYNTHETIC_SERVER, SYNTHETIC_CLIENT, 64)
+                .udp(SYNTHETIC_SERVER_PORT, client_port),
+            _ => etherparse::PacketBuilder::ipv4(SYNTHETIC_CLIENT, SYNTHETIC_SERVER, 64)
+                .udp(client_port, SYNTHETIC_SERVER_PORT),
+        };
+        let mut packet = Vec::new();
+        let sent = &payload[..payload.len().min(UDP_MAX_PAYLOAD)];
+        if builder.write(&mut packet, sent).is_err() {
+            continue;
+        }
+        let original_len = packet.len() + payload.len() - sent.len();
+
+        let timestamp = pdu.timestamp.unwrap_or(index as f64 * 1e-6).max(0.0);
+        let micros = (timestamp * 1e6).round() as u64;
+        out.extend_from_slice(&((micros / 1_000_000) as u32).to_le_bytes());
+        out.extend_from_slice(&((micros % 1_000_000) as u32).to_le_bytes());
+        out.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(original_len as u32).to_le_bytes());
+        out.extend_from_slice(&packet);
+    }
+    out
+}
@@ -45,6 +45,16 @@ impl PluginRegistry {
         self.scorers.push(scorer);
     }
 
+    /// Retire et rend tous les générateurs, par exemple pour en réenregistrer une partie
+    pub fn take_generators(&mut self) -> Vec<Box<dyn HypothesisGenerator>> {
+        std::mem::take(&mut self.generators)
+    }
+
+    /// Ne garde que les parseurs pour lesquels `keep` est vrai
+    pub fn retain_parsers(&mut self, mut keep: impl FnMut(&dyn crate::parser::Parser) -> bool) {
+        self.parsers.retain(|parser| keep(parser.as_ref()));
+    }
+
     pub fn generators(&self) -> &[Box<dyn HypothesisGenerator>] {
         &self.generators
     }
@@ -56,6 +66,13 @@ impl PluginRegistry {
     pub fn scorers(&self) -> &[Box<dyn Scorer>] {
         &self.scorers
     }
+
+    /// Score combiné de tous les scoreurs enregistrés (voir [`Score::combine`]), `None` sans
+    /// scoreur
+    pub fn score(&self, corpus: &Corpus, parsed: &ParsedCorpus, h: &Hypothesis) -> Option<Score> {
+        let scores: Vec<Score> = self.scorers.iter().map(|scorer| scorer.score(corpus, parsed, h)).collect();
+        Score::combine(&scores)
+    }
 }
 
 impl Default for PluginRegistry {
@@ -1,5 +1,10 @@
 use crate::corpus::Corpus;
-use crate::hypothesis::{Endianness, Hypothesis, LengthWidth, TlvLenRule};
+use crate::measures::{entropy_by_bit, entropy_by_offset, printable_ratio, MultipleAlignment};
+use crate::hypothesis::{
+    CountField, Endianness, EscapeScheme, Hypothesis, LengthWidth, LineEnding, TlvLenRule, TlvTagRule,
+    VarintEncoding,
+};
+use crate::parser::Parser;
 use crate::plugin::HypothesisGenerator;
 
 /// Générateur d'hypothèses pour length-prefix bundling
@@ -18,15 +23,254 @@ impl HypothesisGenerator for LengthPrefixGenerator {
         }
 
         // Générer des hypothèses pour différentes configurations
+        for offset in candidate_offsets(corpus) {
+            for width in [LengthWidth::One, LengthWidth::Two, LengthWidth::Four] {
+                for endian in [Endianness::Little, Endianness::Big] {
+                    for unit in LENGTH_UNITS {
+                        for includes_header in [false, true] {
+                            hypotheses.push(Hypothesis::LengthPrefixBundle {
+                                offset,
+                                width,
+                                endian,
+                                includes_header,
+                                unit,
+                                adjust: 0,
+                            });
+                        }
+                        if let Some(adjust) = length_adjust(corpus, offset, width, endian, unit) {
+                            hypotheses.push(Hypothesis::LengthPrefixBundle {
+                                offset,
+                                width,
+                                endian,
+                                includes_header: false,
+                                unit,
+                                adjust,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        hypotheses
+    }
+}
+
+/// Unités de longueur candidates : octets, mots de 16 et de 32 bits
+const LENGTH_UNITS: [usize; 3] = [1, 2, 4];
+/// Écart maximal entre la longueur annoncée et la longueur réelle pour proposer un ajustement
+const MAX_LENGTH_ADJUST: i64 = 16;
+/// Premiers offsets toujours examinés pour un champ en tête de PDU
+const MIN_FIELD_OFFSETS: usize = 4;
+/// Nombre de PDUs alignées pour repérer les frontières de champs
+const ALIGNMENT_SAMPLE_PDUS: usize = 32;
+/// Nombre d'octets de tête alignés
+const ALIGNMENT_PREFIX_LEN: usize = 64;
+/// Offset maximal d'une frontière de champ issue de l'alignement
+const MAX_ALIGNED_OFFSET: usize = 16;
+
+/// Offsets candidats pour un champ en tête de PDU : les premiers offsets, complétés des
+/// frontières entre régions conservées, variables et à trous de l'alignement multiple des PDUs
+fn candidate_offsets(corpus: &Corpus) -> Vec<usize> {
+    let mut offsets: Vec<usize> = (0..=MIN_FIELD_OFFSETS).collect();
+    if let Some(alignment) = MultipleAlignment::compute(corpus, ALIGNMENT_SAMPLE_PDUS, ALIGNMENT_PREFIX_LEN) {
+        offsets.extend(alignment.field_boundaries().into_iter().filter(|&o| o <= MAX_ALIGNED_OFFSET));
+    }
+    offsets.sort_unstable();
+    offsets.dedup();
+    offsets
+}
+
+/// Constante non nulle qui relie la longueur annoncée à la taille réelle de chaque PDU,
+/// si elle est la même pour tout le corpus (une PDU = un message)
+fn length_adjust(corpus: &Corpus, offset: usize, width: LengthWidth, endian: Endianness, unit: usize) -> Option<i32> {
+    let header_end = offset + width as usize;
+    let mut adjust = None;
+    for pdu in &corpus.items {
+        let data = pdu.as_slice();
+        let field = data.get(offset..header_end)?;
+        let len = match endian {
+            Endianness::Big => field.iter().fold(0i64, |acc, &b| (acc << 8) | b as i64),
+            Endianness::Little => field.iter().rev().fold(0i64, |acc, &b| (acc << 8) | b as i64),
+        };
+        let residual = (data.len() - header_end) as i64 - len * unit as i64;
+        match adjust {
+            None => adjust = Some(residual),
+            Some(a) if a != residual => return None,
+            _ => {}
+        }
+    }
+    adjust.filter(|&a| a != 0 && a.abs() <= MAX_LENGTH_ADJUST).map(|a| a as i32)
+}
+
+/// Offset maximal d'un champ de longueur « octets restants »
+const MAX_REMAINING_OFFSET: usize = 8;
+/// Proportion minimale de PDUs dont le champ vaut exactement le nombre d'octets restants
+const REMAINING_MIN_RATIO: f64 = 0.95;
+
+/// Générateur de champs de longueur égaux au nombre d'octets restants de la PDU.
+///
+/// Seuls les champs vérifiés sur le corpus sont proposés, et seulement si les PDUs n'ont pas
+/// toutes la même taille (une longueur constante ne se distingue pas d'un champ magic).
+pub struct RemainingLengthGenerator;
+
+impl HypothesisGenerator for RemainingLengthGenerator {
+    fn name(&self) -> &'static str {
+        "RemainingLengthGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        let mut hypotheses = Vec::new();
+
+        let lengths: std::collections::HashSet<usize> = corpus.items.iter().map(|p| p.len()).collect();
+        if lengths.len() < 2 {
+            return hypotheses;
+        }
+
+        for offset in 0..=MAX_REMAINING_OFFSET {
+            for width in [LengthWidth::One, LengthWidth::Two, LengthWidth::Four] {
+                for endian in [Endianness::Big, Endianness::Little] {
+                    if width == LengthWidth::One && endian == Endianness::Little {
+                        continue;
+                    }
+                    let header_end = offset + width as usize;
+                    let matching = corpus
+                        .items
+                        .iter()
+                        .filter(|pdu| {
+                            let data = pdu.as_slice();
+                            header_end <= data.len()
+                                && crate::plugins::parsers::read_length(data, offset, width, endian)
+                                    == data.len() - header_end
+                        })
+                        .count();
+                    if matching as f64 >= corpus.len() as f64 * REMAINING_MIN_RATIO {
+                        hypotheses.push(Hypothesis::RemainingLength { offset, width, endian });
+                    }
+                }
+            }
+        }
+
+        hypotheses
+    }
+}
+
+/// Taille maximale d'un mot de synchronisation
+const MAX_SYNC_LEN: usize = 4;
+
+/// Générateur de trames `[synchro][longueur][payload]`.
+///
+/// Le mot de synchronisation est pris parmi les préfixes constants des PDUs ; seules les
+/// combinaisons où chaque PDU se découpe exactement en trames commençant par ce mot sont
+/// proposées, ce qui valide à la fois la synchro et la longueur.
+pub struct SyncLengthGenerator;
+
+impl HypothesisGenerator for SyncLengthGenerator {
+    fn name(&self) -> &'static str {
+        "SyncLengthGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        let mut hypotheses = Vec::new();
+
+        let Some(first) = corpus.items.first().map(|p| p.as_slice()) else {
+            return hypotheses;
+        };
+        let prefix_len = corpus.items.iter().fold(first.len().min(MAX_SYNC_LEN), |len, pdu| {
+            first[..len].iter().zip(pdu.as_slice()).take_while(|(a, b)| a == b).count()
+        });
+
+        for sync_len in 1..=prefix_len {
+            let sync = &first[..sync_len];
+            for width in [LengthWidth::One, LengthWidth::Two, LengthWidth::Four] {
+                for endian in [Endianness::Big, Endianness::Little] {
+                    if width == LengthWidth::One && endian == Endianness::Little {
+                        continue;
+                    }
+                    for includes_header in [false, true] {
+                        let tiles = corpus
+                            .items
+                            .iter()
+                            .all(|pdu| sync_frames_tile(pdu.as_slice(), sync, width, endian, includes_header));
+                        if tiles {
+                            hypotheses.push(Hypothesis::SyncLengthBundle {
+                                sync: sync.to_vec(),
+                                width,
+                                endian,
+                                includes_header,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        hypotheses
+    }
+}
+
+/// Vrai si `data` se découpe exactement en trames `[sync][longueur][payload]`
+fn sync_frames_tile(data: &[u8], sync: &[u8], width: LengthWidth, endian: Endianness, includes_header: bool) -> bool {
+    let header_len = sync.len() + width as usize;
+    let mut pos = 0;
+    while pos < data.len() {
+        if !data[pos..].starts_with(sync) || pos + header_len > data.len() {
+            return false;
+        }
+        let len = crate::plugins::parsers::read_length(data, pos + sync.len(), width, endian);
+        let end = if includes_header { pos + len } else { pos + header_len + len };
+        if end < pos + header_len || end > data.len() {
+            return false;
+        }
+        pos = end;
+    }
+    true
+}
+
+/// Générateur d'hypothèses pour préfixe de longueur en varint
+pub struct VarintLengthPrefixGenerator;
+
+impl HypothesisGenerator for VarintLengthPrefixGenerator {
+    fn name(&self) -> &'static str {
+        "VarintLengthPrefixGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        let mut hypotheses = Vec::new();
+
+        if corpus.is_empty() {
+            return hypotheses;
+        }
+
+        for offset in candidate_offsets(corpus) {
+            for encoding in [VarintEncoding::Leb128, VarintEncoding::Quic] {
+                hypotheses.push(Hypothesis::VarintLengthPrefix { offset, encoding });
+            }
+        }
+
+        hypotheses
+    }
+}
+
+/// Générateur d'hypothèses pour longueur en fin de message
+pub struct LengthSuffixGenerator;
+
+impl HypothesisGenerator for LengthSuffixGenerator {
+    fn name(&self) -> &'static str {
+        "LengthSuffixGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        let mut hypotheses = Vec::new();
+
+        if corpus.is_empty() {
+            return hypotheses;
+        }
+
         for offset in 0..=4 {
             for width in [LengthWidth::One, LengthWidth::Two, LengthWidth::Four] {
                 for endian in [Endianness::Little, Endianness::Big] {
-                    hypotheses.push(Hypothesis::LengthPrefixBundle {
-                        offset,
-                        width,
-                        endian,
-                        includes_header: false,
-                    });
+                    hypotheses.push(Hypothesis::LengthSuffixBundle { offset, width, endian });
                 }
             }
         }
@@ -35,6 +279,53 @@ impl HypothesisGenerator for LengthPrefixGenerator {
     }
 }
 
+/// Générateur d'hypothèses pour multiplexage de canaux
+pub struct ChannelMuxGenerator;
+
+impl HypothesisGenerator for ChannelMuxGenerator {
+    fn name(&self) -> &'static str {
+        "ChannelMuxGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        let mut hypotheses = Vec::new();
+
+        if corpus.is_empty() {
+            return hypotheses;
+        }
+
+        for channel_width in [1, 2] {
+            for (len_width, endian) in [
+                (LengthWidth::One, Endianness::Big),
+                (LengthWidth::Two, Endianness::Big),
+                (LengthWidth::Two, Endianness::Little),
+            ] {
+                hypotheses.push(Hypothesis::ChannelMux { channel_width, len_width, endian });
+            }
+        }
+
+        hypotheses
+    }
+}
+
+/// Générateur d'en-têtes de fragmentation applicative
+pub struct FragmentationGenerator;
+
+impl HypothesisGenerator for FragmentationGenerator {
+    fn name(&self) -> &'static str {
+        "FragmentationGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        crate::fragmentation::detect_fragmentation(corpus)
+    }
+}
+
+/// Octets d'échappement essayés pour les délimiteurs de ligne
+const ESCAPE_CANDIDATES: [u8; 1] = [b'\\'];
+/// Caractères de citation essayés pour les délimiteurs de ligne
+const QUOTE_CANDIDATES: [u8; 2] = [b'"', b'\''];
+
 /// Générateur d'hypothèses pour delimiter bundling
 pub struct DelimiterGenerator;
 
@@ -58,14 +349,183 @@ impl HypothesisGenerator for DelimiterGenerator {
             vec![0xFF, 0xFF], // Double 0xFF
         ];
 
+        // Échappement et citation ne sont proposés que pour les délimiteurs de ligne, et
+        // seulement si les caractères correspondants apparaissent dans le corpus
+        let present = |b: u8| corpus.items.iter().any(|pdu| pdu.as_slice().contains(&b));
+        let escapes: Vec<Option<u8>> = std::iter::once(None)
+            .chain(ESCAPE_CANDIDATES.iter().copied().filter(|&b| present(b)).map(Some))
+            .collect();
+        let quotes: Vec<Option<u8>> = std::iter::once(None)
+            .chain(QUOTE_CANDIDATES.iter().copied().filter(|&b| present(b)).map(Some))
+            .collect();
+
         for pattern in patterns {
-            hypotheses.push(Hypothesis::DelimiterBundle { pattern });
+            let textual = pattern.last() == Some(&b'\n');
+            for &escape in &escapes {
+                for &quote in &quotes {
+                    if !textual && (escape.is_some() || quote.is_some()) {
+                        continue;
+                    }
+                    hypotheses.push(Hypothesis::DelimiterBundle { pattern: pattern.clone(), escape, quote });
+                }
+            }
         }
 
         hypotheses
     }
 }
 
+/// Générateur de tramages par byte-stuffing (SLIP par défaut)
+pub struct ByteStuffingGenerator {
+    schemes: Vec<EscapeScheme>,
+}
+
+impl ByteStuffingGenerator {
+    pub fn new() -> Self {
+        Self {
+            schemes: vec![EscapeScheme::slip()],
+        }
+    }
+
+    /// Ajoute un schéma d'échappement fourni par l'utilisateur
+    pub fn with_scheme(mut self, scheme: EscapeScheme) -> Self {
+        self.schemes.push(scheme);
+        self
+    }
+}
+
+impl Default for ByteStuffingGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HypothesisGenerator for ByteStuffingGenerator {
+    fn name(&self) -> &'static str {
+        "ByteStuffingGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        self.schemes
+            .iter()
+            .filter(|scheme| crate::stuffing::detect_stuffing(corpus, scheme))
+            .map(|scheme| Hypothesis::ByteStuffed { scheme: scheme.clone() })
+            .collect()
+    }
+}
+
+/// Générateur de tramage HDLC (fanions, échappement et FCS)
+pub struct HdlcGenerator;
+
+impl HypothesisGenerator for HdlcGenerator {
+    fn name(&self) -> &'static str {
+        "HdlcGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        crate::stuffing::detect_hdlc(corpus).into_iter().collect()
+    }
+}
+
+/// Générateur de noms en labels (DNS et assimilés)
+pub struct LabelSequenceGenerator;
+
+impl HypothesisGenerator for LabelSequenceGenerator {
+    fn name(&self) -> &'static str {
+        "LabelSequenceGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        crate::labels::detect_label_names(corpus)
+    }
+}
+
+/// Taille minimale d'un enregistrement
+const MIN_RECORD_LEN: usize = 2;
+/// Offsets explorés pour le champ compteur
+const MAX_COUNT_OFFSET: usize = 3;
+
+/// Générateur de tableaux d'enregistrements de taille fixe
+pub struct RecordArrayGenerator;
+
+impl RecordArrayGenerator {
+    /// Taille d'enregistrement commune quand le compteur `field` divise exactement le reste de
+    /// chaque PDU, avec au moins deux compteurs distincts
+    fn counted_record_len(corpus: &Corpus, field: CountField) -> Option<usize> {
+        let mut record_len = None;
+        let mut counts = std::collections::HashSet::new();
+        for pdu in &corpus.items {
+            let data = pdu.as_slice();
+            if data.len() < field.end() {
+                return None;
+            }
+            let count = crate::plugins::parsers::read_length(data, field.offset, field.width, field.endian);
+            let body = data.len() - field.end();
+            counts.insert(count);
+            if count == 0 {
+                if body != 0 {
+                    return None;
+                }
+                continue;
+            }
+            if !body.is_multiple_of(count) || *record_len.get_or_insert(body / count) != body / count {
+                return None;
+            }
+        }
+        record_len.filter(|&len| len >= MIN_RECORD_LEN && counts.len() >= 2)
+    }
+}
+
+impl HypothesisGenerator for RecordArrayGenerator {
+    fn name(&self) -> &'static str {
+        "RecordArrayGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        let mut hypotheses = Vec::new();
+
+        if corpus.is_empty() {
+            return hypotheses;
+        }
+
+        // Sans compteur : le PGCD des longueurs, si les PDUs portent des nombres
+        // d'enregistrements différents
+        let lengths: Vec<usize> = corpus.items.iter().map(|p| p.len()).collect();
+        let record_len = lengths.iter().fold(0, |a, &b| gcd(a, b));
+        let counts: std::collections::HashSet<usize> = lengths.iter().map(|l| l / record_len.max(1)).collect();
+        if record_len >= MIN_RECORD_LEN && counts.len() >= 2 {
+            hypotheses.push(Hypothesis::RecordArray { record_len, count_field: None });
+        }
+
+        for offset in 0..=MAX_COUNT_OFFSET {
+            for width in [LengthWidth::One, LengthWidth::Two] {
+                for endian in [Endianness::Big, Endianness::Little] {
+                    if width == LengthWidth::One && endian == Endianness::Little {
+                        continue;
+                    }
+                    let count_field = CountField { offset, width, endian };
+                    if let Some(record_len) = Self::counted_record_len(corpus, count_field) {
+                        hypotheses.push(Hypothesis::RecordArray {
+                            record_len,
+                            count_field: Some(count_field),
+                        });
+                    }
+                }
+            }
+        }
+
+        hypotheses
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
 /// Générateur d'hypothèses pour fixed header
 pub struct FixedHeaderGenerator;
 
@@ -90,6 +550,29 @@ impl HypothesisGenerator for FixedHeaderGenerator {
     }
 }
 
+/// Générateur d'hypothèses pour trailer fixe
+pub struct FixedTrailerGenerator;
+
+/// Taille maximale d'un trailer fixe (CRC-32, marqueurs de fin)
+const MAX_TRAILER_LEN: usize = 8;
+
+impl HypothesisGenerator for FixedTrailerGenerator {
+    fn name(&self) -> &'static str {
+        "FixedTrailerGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        let Some(min_len) = corpus.items.iter().map(|p| p.len()).min() else {
+            return Vec::new();
+        };
+
+        // Le trailer doit laisser au moins un octet de SDU dans chaque PDU
+        (1..=MAX_TRAILER_LEN.min(min_len.saturating_sub(1)))
+            .map(|len| Hypothesis::FixedTrailer { len })
+            .collect()
+    }
+}
+
 /// Générateur d'hypothèses pour bitmap extensible
 pub struct ExtensibleBitmapGenerator;
 
@@ -105,7 +588,7 @@ impl HypothesisGenerator for ExtensibleBitmapGenerator {
             return hypotheses;
         }
 
-        for start in 0..=4 {
+        for start in candidate_offsets(corpus) {
             for cont_bit in 0..8 {
                 for stop_value in [0u8, 1u8] {
                     hypotheses.push(Hypothesis::ExtensibleBitmap {
@@ -130,9 +613,13 @@ impl HypothesisGenerator for TlvGenerator {
         "TlvGenerator"
     }
 
-    fn propose(&self, _corpus: &Corpus) -> Vec<Hypothesis> {
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
         let mut hypotheses = Vec::new();
-        
+
+        // Endianness des longueurs multi-octets : celle des compteurs et longueurs repérés en
+        // tête des PDUs, big-endian (ordre réseau) à défaut
+        let inferred = crate::fields::infer_endianness(corpus).unwrap_or(Endianness::Big);
+
         // Générer toutes les combinaisons pertinentes
         // tag_offset: où commence le tag (0, 1, 2)
         // tag_bytes: taille du tag (1, 2, 3)
@@ -147,17 +634,40 @@ impl HypothesisGenerator for TlvGenerator {
                         TlvLenRule::DefiniteShort,   // 1 byte length
                         TlvLenRule::DefiniteMedium,  // 2 bytes length
                         TlvLenRule::DefiniteLong,    // 4 bytes length
+                        TlvLenRule::BerDefinite,     // 1 à 5 bytes (X.690)
                     ] {
+                        let endian = match len_rule {
+                            TlvLenRule::DefiniteMedium | TlvLenRule::DefiniteLong => inferred,
+                            _ => Endianness::Big,
+                        };
                         // Prioriser length_includes_header = true (plus commun dans les protocoles réseau)
                         // Tester avec et sans length incluant le header
                         for length_includes_header in [true, false] {
+                            // Une longueur BER ne compte jamais l'en-tête
+                            if length_includes_header && len_rule == TlvLenRule::BerDefinite {
+                                continue;
+                            }
                             hypotheses.push(Hypothesis::Tlv {
                                 tag_offset,
                                 tag_bytes,
                                 len_offset,
                                 len_rule,
                                 length_includes_header,
+                                tag_rule: TlvTagRule::Fixed,
+                                endian,
                             });
+                            // Tag BER multi-octets à partir d'un premier octet
+                            if tag_bytes == 1 && !length_includes_header {
+                                hypotheses.push(Hypothesis::Tlv {
+                                    tag_offset,
+                                    tag_bytes,
+                                    len_offset,
+                                    len_rule,
+                                    length_includes_header,
+                                    tag_rule: TlvTagRule::BerMultiByte,
+                                    endian,
+                                });
+                            }
                         }
                     }
                 }
@@ -194,3 +704,338 @@ impl HypothesisGenerator for VarintGenerator {
     }
 }
 
+
+/// Générateur de champs constants (magic numbers) à partir de l'entropie par offset
+pub struct ConstantFieldGenerator;
+
+/// Nombre d'offsets examinés en début de PDU
+const CONSTANT_SCAN_OFFSETS: usize = 64;
+
+impl HypothesisGenerator for ConstantFieldGenerator {
+    fn name(&self) -> &'static str {
+        "ConstantFieldGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        // Un octet identique dans deux PDUs seulement n'est pas significatif
+        if corpus.len() < 2 {
+            return Vec::new();
+        }
+
+        // Seuls les offsets présents dans toutes les PDUs sont candidats
+        let min_len = corpus.items.iter().map(|p| p.len()).min().unwrap_or(0);
+        let entropies = entropy_by_offset(corpus, CONSTANT_SCAN_OFFSETS.min(min_len));
+        let reference = corpus.items[0].as_slice();
+
+        // Une hypothèse par plage maximale d'offsets d'entropie nulle
+        let mut hypotheses = Vec::new();
+        let mut run_start = None;
+        for (offset, &h) in entropies.iter().chain(std::iter::once(&f64::INFINITY)).enumerate() {
+            match (run_start, h == 0.0) {
+                (None, true) => run_start = Some(offset),
+                (Some(start), false) => {
+                    hypotheses.push(Hypothesis::ConstantField {
+                        offset: start,
+                        value: reference[start..offset].to_vec(),
+                    });
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+
+        hypotheses
+    }
+}
+
+/// Générateur de discriminants de type de message (champs de faible cardinalité en début de PDU)
+pub struct TypeSwitchGenerator;
+
+/// Offsets examinés pour le discriminant
+const TYPE_SWITCH_MAX_OFFSET: usize = 8;
+
+impl HypothesisGenerator for TypeSwitchGenerator {
+    fn name(&self) -> &'static str {
+        "TypeSwitchGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        let mut hypotheses = Vec::new();
+        for type_offset in 0..=TYPE_SWITCH_MAX_OFFSET {
+            for type_width in [1, 2] {
+                if crate::fields::enum_field(corpus, type_offset, type_width).is_some() {
+                    hypotheses.push(Hypothesis::TypeSwitch { type_offset, type_width });
+                }
+            }
+        }
+        hypotheses
+    }
+}
+
+/// Générateur pour les protocoles texte ligne par ligne
+pub struct TextLinesGenerator;
+
+/// Proportion minimale d'octets imprimables sur le corpus
+const TEXT_MIN_PRINTABLE: f64 = 0.9;
+
+impl HypothesisGenerator for TextLinesGenerator {
+    fn name(&self) -> &'static str {
+        "TextLinesGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        if corpus.is_empty() {
+            return Vec::new();
+        }
+
+        let mut printable = 0.0;
+        let mut with_crlf = 0;
+        let mut with_lf = 0;
+        for pdu in &corpus.items {
+            let data = pdu.as_slice();
+            printable += printable_ratio(data) * data.len() as f64;
+            if data.windows(2).any(|w| w == b"\r\n") {
+                with_crlf += 1;
+            }
+            if data.contains(&b'\n') {
+                with_lf += 1;
+            }
+        }
+
+        let total = corpus.total_bytes().max(1) as f64;
+        if printable / total < TEXT_MIN_PRINTABLE {
+            return Vec::new();
+        }
+
+        // Au moins la moitié des PDUs doit contenir une fin de ligne
+        let half = corpus.len().div_ceil(2);
+        if with_crlf >= half {
+            vec![Hypothesis::TextLines { line_ending: LineEnding::CrLf }]
+        } else if with_lf >= half {
+            vec![Hypothesis::TextLines { line_ending: LineEnding::Lf }]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Générateur pour les blocs d'en-têtes `Nom: Valeur` (type HTTP)
+pub struct KeyValueHeaderGenerator;
+
+impl HypothesisGenerator for KeyValueHeaderGenerator {
+    fn name(&self) -> &'static str {
+        "KeyValueHeaderGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        if corpus.is_empty() {
+            return Vec::new();
+        }
+
+        let half = corpus.len().div_ceil(2);
+        [LineEnding::CrLf, LineEnding::Lf]
+            .into_iter()
+            .find(|ending| {
+                // Une majorité de PDUs doit porter un bloc d'en-têtes texte bien formé
+                corpus
+                    .items
+                    .iter()
+                    .filter(|pdu| {
+                        let data = pdu.as_slice();
+                        crate::plugins::parsers::header_block_end(data, *ending).is_some_and(|end| {
+                            printable_ratio(&data[..end]) >= TEXT_MIN_PRINTABLE
+                                && data[..end].contains(&b':')
+                        })
+                    })
+                    .count()
+                    >= half
+            })
+            .map(|line_ending| vec![Hypothesis::KeyValueHeader { line_ending }])
+            .unwrap_or_default()
+    }
+}
+
+/// En-têtes de longueur du corps reconnus (forme longue, forme compacte SIP)
+const CONTENT_LENGTH_HEADERS: [&str; 2] = ["Content-Length", "l"];
+
+/// Générateur de messages à en-têtes texte et corps binaire (SIP, RTSP).
+///
+/// Proposé quand une majorité de PDUs porte l'en-tête de longueur et que toutes se découpent
+/// exactement en messages `[en-têtes][corps]`.
+pub struct ContentLengthGenerator;
+
+impl HypothesisGenerator for ContentLengthGenerator {
+    fn name(&self) -> &'static str {
+        "ContentLengthGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        if corpus.is_empty() {
+            return Vec::new();
+        }
+
+        let half = corpus.len().div_ceil(2);
+        let mut hypotheses = Vec::new();
+        for line_ending in [LineEnding::CrLf, LineEnding::Lf] {
+            for name in CONTENT_LENGTH_HEADERS {
+                let mut with_length = 0;
+                let tiles = corpus.items.iter().all(|pdu| {
+                    let frames = content_length_frames(pdu.as_slice(), line_ending, name);
+                    with_length += frames.unwrap_or(0);
+                    frames.is_some()
+                });
+                if tiles && with_length >= half {
+                    hypotheses.push(Hypothesis::ContentLengthMessages { line_ending, length_header: name.to_string() });
+                }
+            }
+            if !hypotheses.is_empty() {
+                break;
+            }
+        }
+        hypotheses
+    }
+}
+
+/// Nombre de messages portant l'en-tête de longueur si `data` se découpe exactement en
+/// messages `[en-têtes texte][corps]`, `None` sinon
+fn content_length_frames(data: &[u8], line_ending: LineEnding, name: &str) -> Option<usize> {
+    let terminator = line_ending.bytes();
+    let mut with_length = 0;
+    let mut pos = 0;
+    while pos < data.len() {
+        let block_end = pos + crate::plugins::parsers::header_block_end(&data[pos..], line_ending)?;
+        if printable_ratio(&data[pos..block_end]) < TEXT_MIN_PRINTABLE {
+            return None;
+        }
+        let len = crate::plugins::parsers::content_length(data, pos..block_end, terminator, name).ok()?;
+        with_length += usize::from(len.is_some());
+        pos = block_end.checked_add(len.unwrap_or(0)).filter(|&end| end <= data.len())?;
+    }
+    (pos == data.len() && with_length > 0).then_some(with_length)
+}
+
+/// Générateur de champs de bits : frontières là où l'entropie par bit change de régime
+pub struct BitFieldGenerator;
+
+/// Nombre minimal de PDUs pour estimer l'entropie par bit
+const BIT_FIELD_MIN_PDUS: usize = 8;
+/// Nombre maximal de champs proposés pour un en-tête
+const BIT_FIELD_MAX_FIELDS: usize = 16;
+
+/// Régime d'entropie d'un bit : constant, peu variable, aléatoire
+fn bit_entropy_class(h: f64) -> u8 {
+    if h < 0.1 {
+        0
+    } else if h < 0.9 {
+        1
+    } else {
+        2
+    }
+}
+
+impl HypothesisGenerator for BitFieldGenerator {
+    fn name(&self) -> &'static str {
+        "BitFieldGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        let mut hypotheses = Vec::new();
+        if corpus.len() < BIT_FIELD_MIN_PDUS {
+            return hypotheses;
+        }
+
+        for header_len in [1, 2, 4] {
+            if corpus.items.iter().any(|pdu| pdu.len() <= header_len) {
+                break;
+            }
+            let classes: Vec<u8> = entropy_by_bit(corpus, header_len)
+                .into_iter()
+                .map(bit_entropy_class)
+                .collect();
+            let boundaries: Vec<usize> = (0..classes.len())
+                .filter(|&bit| bit == 0 || classes[bit] != classes[bit - 1])
+                .collect();
+
+            // Des frontières toutes alignées sur l'octet relèvent des hypothèses octet
+            if boundaries.iter().all(|b| b % 8 == 0) || boundaries.len() > BIT_FIELD_MAX_FIELDS {
+                continue;
+            }
+            hypotheses.push(Hypothesis::BitFields { header_len, boundaries });
+        }
+        hypotheses
+    }
+}
+
+/// Générateur d'hypothèses de checksum (seuls les checksums validés sur le corpus sont proposés)
+pub struct ChecksumGenerator;
+
+/// Proportion minimale de PDUs dont le checksum doit être valide
+const CHECKSUM_MIN_RATIO: f64 = 0.95;
+
+impl HypothesisGenerator for ChecksumGenerator {
+    fn name(&self) -> &'static str {
+        "ChecksumGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        crate::checksum::detect_checksums(corpus, CHECKSUM_MIN_RATIO)
+            .into_iter()
+            .map(|m| Hypothesis::Checksum {
+                algorithm: m.algorithm,
+                endian: m.endian,
+                location: m.location,
+            })
+            .collect()
+    }
+}
+
+/// Générateur d'hypothèses composites : en-tête fixe suivi, dans le reste de la PDU, de
+/// messages length-prefix regroupés
+pub struct CompositeGenerator;
+
+/// Taille maximale de l'en-tête fixe d'une hypothèse composite
+const MAX_COMPOSITE_HEADER: usize = 8;
+
+impl HypothesisGenerator for CompositeGenerator {
+    fn name(&self) -> &'static str {
+        "CompositeGenerator"
+    }
+
+    fn propose(&self, corpus: &Corpus) -> Vec<Hypothesis> {
+        let mut hypotheses = Vec::new();
+        if corpus.is_empty() {
+            return hypotheses;
+        }
+
+        for len in candidate_offsets(corpus).into_iter().filter(|l| (1..=MAX_COMPOSITE_HEADER).contains(l)) {
+            // L'en-tête doit laisser un reste dans chaque PDU
+            if corpus.items.iter().any(|p| p.len() <= len) {
+                continue;
+            }
+            let ranges: Vec<(usize, std::ops::Range<usize>)> =
+                corpus.items.iter().enumerate().map(|(i, p)| (i, len..p.len())).collect();
+            let remainder = corpus.sub_ranges(&ranges, format!("{}_remainder", corpus.meta.source));
+
+            let mut bundles: Vec<Hypothesis> = LengthPrefixGenerator
+                .propose(&remainder)
+                .into_iter()
+                .filter(|h| matches!(h, Hypothesis::LengthPrefixBundle { offset: 0, .. }))
+                .map(Hypothesis::canonical)
+                .collect();
+            bundles.sort();
+            bundles.dedup();
+
+            // Seuls les bundles qui découpent le reste sans erreur et regroupent effectivement
+            // plusieurs messages sont retenus ; les autres sont déjà couverts par FixedHeader seul
+            for bundle in bundles {
+                let parsed = crate::plugins::parsers::LengthPrefixParser.parse_corpus(&remainder, &bundle);
+                let clean = parsed.parsed_pdus.iter().all(|p| p.is_success() && !p.has_overflow());
+                if clean && parsed.parsed_pdus.iter().any(|p| p.sdus().len() >= 2) {
+                    hypotheses.push(Hypothesis::Composite(vec![Hypothesis::FixedHeader { len }, bundle]));
+                }
+            }
+        }
+
+        hypotheses
+    }
+}
@@ -1,35 +1,86 @@
 pub mod generators;
 pub mod parsers;
 pub mod scorers;
+pub mod text;
 
 pub use generators::*;
 pub use parsers::*;
 pub use scorers::*;
+pub use text::*;
 
 use crate::plugin::PluginRegistry;
 
 /// Crée un registre de plugins avec tous les plugins par défaut
 pub fn create_default_registry() -> PluginRegistry {
+    create_registry(MdlScorerConfig::default())
+}
+
+/// Crée un registre de plugins avec tous les plugins par défaut et un scoreur MDL configuré
+pub fn create_registry(scorer_config: MdlScorerConfig) -> PluginRegistry {
     let mut registry = PluginRegistry::new();
 
     // Enregistrer les générateurs
     registry.register_generator(Box::new(LengthPrefixGenerator));
+    registry.register_generator(Box::new(RemainingLengthGenerator));
+    registry.register_generator(Box::new(SyncLengthGenerator));
+    registry.register_generator(Box::new(VarintLengthPrefixGenerator));
+    registry.register_generator(Box::new(LengthSuffixGenerator));
+    registry.register_generator(Box::new(ChannelMuxGenerator));
+    registry.register_generator(Box::new(FragmentationGenerator));
     registry.register_generator(Box::new(DelimiterGenerator));
+    registry.register_generator(Box::new(ByteStuffingGenerator::new()));
+    registry.register_generator(Box::new(HdlcGenerator));
+    registry.register_generator(Box::new(LabelSequenceGenerator));
+    registry.register_generator(Box::new(RecordArrayGenerator));
     registry.register_generator(Box::new(FixedHeaderGenerator));
+    registry.register_generator(Box::new(FixedTrailerGenerator));
     registry.register_generator(Box::new(ExtensibleBitmapGenerator));
     registry.register_generator(Box::new(TlvGenerator));
     registry.register_generator(Box::new(VarintGenerator));
+    registry.register_generator(Box::new(ConstantFieldGenerator));
+    registry.register_generator(Box::new(TypeSwitchGenerator));
+    registry.register_generator(Box::new(TextLinesGenerator));
+    registry.register_generator(Box::new(KeyValueHeaderGenerator));
+    registry.register_generator(Box::new(ContentLengthGenerator));
+    registry.register_generator(Box::new(BitFieldGenerator));
+    registry.register_generator(Box::new(ChecksumGenerator));
+    registry.register_generator(Box::new(CompositeGenerator));
 
     // Enregistrer les parseurs
     registry.register_parser(Box::new(LengthPrefixParser));
+    registry.register_parser(Box::new(RemainingLengthParser));
+    registry.register_parser(Box::new(SyncLengthParser));
+    registry.register_parser(Box::new(VarintLengthPrefixParser));
+    registry.register_parser(Box::new(LengthSuffixParser));
+    registry.register_parser(Box::new(ChannelMuxParser));
+    registry.register_parser(Box::new(FragmentationParser));
     registry.register_parser(Box::new(DelimiterParser));
+    registry.register_parser(Box::new(ByteStuffingParser));
+    registry.register_parser(Box::new(HdlcParser));
+    registry.register_parser(Box::new(LabelSequenceParser));
+    registry.register_parser(Box::new(RecordArrayParser));
     registry.register_parser(Box::new(FixedHeaderParser));
+    registry.register_parser(Box::new(FixedTrailerParser));
     registry.register_parser(Box::new(ExtensibleBitmapParser));
     registry.register_parser(Box::new(TlvParser));
     registry.register_parser(Box::new(VarintParser));
+    registry.register_parser(Box::new(ConstantFieldParser));
+    registry.register_parser(Box::new(TypeSwitchParser));
+    registry.register_parser(Box::new(TextLinesParser));
+    registry.register_parser(Box::new(KeyValueHeaderParser));
+    registry.register_parser(Box::new(ContentLengthParser));
+    registry.register_parser(Box::new(StructuredTextParser));
+    registry.register_parser(Box::new(EncodedParser));
+    registry.register_parser(Box::new(CompressedParser));
+    registry.register_parser(Box::new(ObfuscatedParser));
+    registry.register_parser(Box::new(TrailingPaddingParser));
+    registry.register_parser(Box::new(BitFieldParser));
+    registry.register_parser(Box::new(ChecksumParser));
+    registry.register_parser(Box::new(CompositeParser::new()));
 
     // Enregistrer les scoreurs
-    registry.register_scorer(Box::new(MdlScorer::new()));
+    registry.register_scorer(Box::new(MdlScorer::with_config(scorer_config)));
+    registry.register_scorer(Box::new(FieldDistributionScorer));
 
     registry
 }
@@ -1,8 +1,9 @@
 use crate::corpus::Corpus;
 use crate::hypothesis::{
-    Endianness, Hypothesis, LengthWidth, TlvLenRule,
+    ChecksumLocation, Endianness, EscapeScheme, Hypothesis, LengthWidth, LineEnding, Obfuscation,
+    TlvLenRule, TlvTagRule,
 };
-use crate::parser::{ParsedCorpus, ParsedPdu, Parser};
+use crate::parser::{ParseException, ParsedCorpus, ParsedPdu, Parser};
 use crate::segment::{Segment, SegmentKind};
 
 /// Parseur pour length-prefix bundling
@@ -17,12 +18,31 @@ impl Parser for LengthPrefixParser {
         matches!(h, Hypothesis::LengthPrefixBundle { .. })
     }
 
+    fn plausible(&self, sample: &Corpus, h: &Hypothesis) -> bool {
+        let Hypothesis::LengthPrefixBundle { offset, width, endian, includes_header, unit, adjust } = h else {
+            return false;
+        };
+        // Le premier message doit tenir dans la PDU
+        mostly_fits(sample, |data| {
+            let header_end = offset + *width as usize;
+            if header_end > data.len() {
+                return false;
+            }
+            let len = read_length(data, *offset, *width, *endian);
+            let start = if *includes_header { 0 } else { header_end };
+            let message_end = start as i64 + len as i64 * *unit as i64 + *adjust as i64;
+            message_end >= header_end as i64 && message_end <= data.len() as i64
+        })
+    }
+
     fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
         let Hypothesis::LengthPrefixBundle {
             offset,
             width,
             endian,
-            includes_header: _,
+            includes_header,
+            unit,
+            adjust,
         } = h
         else {
             return ParsedCorpus::new(vec![]);
@@ -46,37 +66,24 @@ impl Parser for LengthPrefixParser {
                     break;
                 }
 
-                let len = match (width, endian) {
-                    (LengthWidth::One, _) => data[len_pos] as usize,
-                    (LengthWidth::Two, Endianness::Little) => {
-                        u16::from_le_bytes([data[len_pos], data[len_pos + 1]]) as usize
-                    }
-                    (LengthWidth::Two, Endianness::Big) => {
-                        u16::from_be_bytes([data[len_pos], data[len_pos + 1]]) as usize
-                    }
-                    (LengthWidth::Four, Endianness::Little) => {
-                        u32::from_le_bytes([
-                            data[len_pos],
-                            data[len_pos + 1],
-                            data[len_pos + 2],
-                            data[len_pos + 3],
-                        ]) as usize
-                    }
-                    (LengthWidth::Four, Endianness::Big) => {
-                        u32::from_be_bytes([
-                            data[len_pos],
-                            data[len_pos + 1],
-                            data[len_pos + 2],
-                            data[len_pos + 3],
-                        ]) as usize
-                    }
-                };
+                let len = read_length(data, len_pos, *width, *endian);
 
                 let header_end = len_pos + (*width as usize);
-                let message_end = header_end + len;
+                let start = if *includes_header { pos } else { header_end };
+                let message_end = start as i64 + len as i64 * *unit as i64 + *adjust as i64;
+
+                if message_end < header_end as i64 {
+                    exceptions.push(format!("Length smaller than header at pos {}", pos).into());
+                    segments.push(Segment::new(
+                        SegmentKind::Error("Length smaller than header".to_string()),
+                        pos..data.len(),
+                    ));
+                    break;
+                }
+                let message_end = message_end as usize;
 
                 if message_end > data.len() {
-                    exceptions.push(format!("Message extends beyond PDU at pos {}", pos));
+                    exceptions.push(ParseException::fatal(format!("Message extends beyond PDU at pos {}", pos)));
                     segments.push(Segment::new(
                         SegmentKind::Error("Message overflow".to_string()),
                         pos..data.len(),
@@ -112,98 +119,142 @@ impl Parser for LengthPrefixParser {
     }
 }
 
-/// Parseur pour delimiter bundling
-pub struct DelimiterParser;
+/// Parseur pour longueur égale au nombre d'octets restants : `[en-tête][longueur][SDU]`
+pub struct RemainingLengthParser;
 
-impl Parser for DelimiterParser {
+impl Parser for RemainingLengthParser {
     fn name(&self) -> &'static str {
-        "DelimiterParser"
+        "RemainingLengthParser"
     }
 
     fn applicable(&self, h: &Hypothesis) -> bool {
-        matches!(h, Hypothesis::DelimiterBundle { .. })
+        matches!(h, Hypothesis::RemainingLength { .. })
     }
 
     fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
-        let Hypothesis::DelimiterBundle { pattern } = h else {
+        let Hypothesis::RemainingLength { offset, width, endian } = h else {
             return ParsedCorpus::new(vec![]);
         };
-
-        let mut parsed_pdus = Vec::new();
-
-        for pdu in &corpus.items {
-            let data = pdu.as_slice();
-            let mut segments = Vec::new();
-            let mut pos = 0;
-            let mut exceptions = Vec::new();
-
-            while pos < data.len() {
-                // Chercher le pattern
-                let mut found = None;
-                for i in pos..data.len().saturating_sub(pattern.len() - 1) {
-                    if data[i..].starts_with(pattern) {
-                        found = Some(i);
-                        break;
-                    }
+        let header_end = offset + *width as usize;
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                if header_end > data.len() {
+                    return ParsedPdu::new(vec![Segment::new(
+                        SegmentKind::Error("Incomplete length field".to_string()),
+                        0..data.len(),
+                    )])
+                    .with_exception("Incomplete length field".to_string());
                 }
 
-                let next_boundary = found.unwrap_or(data.len());
-                if pos < next_boundary {
-                    segments.push(Segment::new(SegmentKind::Sdu, pos..next_boundary));
+                let mut segments = Vec::new();
+                if *offset > 0 {
+                    segments.push(Segment::new(SegmentKind::Pci, 0..*offset));
+                }
+                segments.push(Segment::new(SegmentKind::Field("length".to_string()), *offset..header_end));
+                if header_end < data.len() {
+                    segments.push(Segment::new(SegmentKind::Sdu, header_end..data.len()));
                 }
 
-                if found.is_some() {
-                    segments.push(Segment::new(
-                        SegmentKind::MessageBoundary,
-                        next_boundary..next_boundary + pattern.len(),
-                    ));
-                    pos = next_boundary + pattern.len();
+                let len = read_length(data, *offset, *width, *endian);
+                let remaining = data.len() - header_end;
+                let parsed = ParsedPdu::new(segments);
+                if len == remaining {
+                    parsed
                 } else {
-                    pos = data.len();
+                    parsed.with_exception(format!("Length {} differs from remaining bytes {}", len, remaining))
                 }
-            }
-
-            parsed_pdus.push(ParsedPdu { segments, exceptions });
-        }
+            })
+            .collect();
 
         ParsedCorpus::new(parsed_pdus)
     }
 }
 
-/// Parseur pour fixed header
-pub struct FixedHeaderParser;
+/// Parseur pour trames `[synchro][longueur][payload]`.
+///
+/// Quand le mot de synchronisation manque, les octets sont ignorés jusqu'à sa prochaine
+/// occurrence, comme le ferait un récepteur embarqué.
+pub struct SyncLengthParser;
 
-impl Parser for FixedHeaderParser {
+impl Parser for SyncLengthParser {
     fn name(&self) -> &'static str {
-        "FixedHeaderParser"
+        "SyncLengthParser"
     }
 
     fn applicable(&self, h: &Hypothesis) -> bool {
-        matches!(h, Hypothesis::FixedHeader { .. })
+        matches!(h, Hypothesis::SyncLengthBundle { .. })
     }
 
     fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
-        let Hypothesis::FixedHeader { len } = h else {
+        let Hypothesis::SyncLengthBundle { sync, width, endian, includes_header } = h else {
             return ParsedCorpus::new(vec![]);
         };
+        let header_len = sync.len() + *width as usize;
 
         let mut parsed_pdus = Vec::new();
 
         for pdu in &corpus.items {
             let data = pdu.as_slice();
             let mut segments = Vec::new();
+            let mut pos = 0;
             let mut exceptions = Vec::new();
 
-            if data.len() < *len {
+            while pos < data.len() {
+                if !data[pos..].starts_with(sync) {
+                    // Resynchronisation sur la prochaine occurrence du mot de synchro
+                    let next = (pos + 1..data.len())
+                        .find(|&i| data[i..].starts_with(sync))
+                        .unwrap_or(data.len());
+                    exceptions.push(format!("Sync word not found at pos {}", pos).into());
+                    segments.push(Segment::new(SegmentKind::Error("Lost sync".to_string()), pos..next));
+                    pos = next;
+                    continue;
+                }
+                if pos + header_len > data.len() {
+                    exceptions.push(format!("Incomplete header at pos {}", pos).into());
+                    segments.push(Segment::new(
+                        SegmentKind::Error("Incomplete length field".to_string()),
+                        pos..data.len(),
+                    ));
+                    break;
+                }
+
+                let len = read_length(data, pos + sync.len(), *width, *endian);
+                let message_end = if *includes_header { pos + len } else { pos + header_len + len };
+                if message_end < pos + header_len {
+                    exceptions.push(format!("Length smaller than header at pos {}", pos).into());
+                    segments.push(Segment::new(
+                        SegmentKind::Error("Length smaller than header".to_string()),
+                        pos..data.len(),
+                    ));
+                    break;
+                }
+                if message_end > data.len() {
+                    exceptions.push(ParseException::fatal(format!("Message extends beyond PDU at pos {}", pos)));
+                    segments.push(Segment::new(
+                        SegmentKind::Error("Message overflow".to_string()),
+                        pos..data.len(),
+                    ));
+                    break;
+                }
+
+                segments.push(Segment::new(SegmentKind::Field("sync".to_string()), pos..pos + sync.len()));
                 segments.push(Segment::new(
-                    SegmentKind::Error("PDU too short".to_string()),
-                    0..data.len(),
+                    SegmentKind::Field("length".to_string()),
+                    pos + sync.len()..pos + header_len,
                 ));
-            } else {
-                segments.push(Segment::new(SegmentKind::Pci, 0..*len));
-                if *len < data.len() {
-                    segments.push(Segment::new(SegmentKind::Sdu, *len..data.len()));
+                if pos + header_len < message_end {
+                    segments.push(Segment::new(SegmentKind::Sdu, pos + header_len..message_end));
+                }
+                if message_end < data.len() {
+                    segments.push(Segment::new(SegmentKind::MessageBoundary, message_end..message_end));
                 }
+
+                pos = message_end;
             }
 
             parsed_pdus.push(ParsedPdu { segments, exceptions });
@@ -213,26 +264,31 @@ impl Parser for FixedHeaderParser {
     }
 }
 
-/// Parseur pour bitmap extensible
-pub struct ExtensibleBitmapParser;
+/// Parseur pour préfixe de longueur en varint
+pub struct VarintLengthPrefixParser;
 
-impl Parser for ExtensibleBitmapParser {
+impl Parser for VarintLengthPrefixParser {
     fn name(&self) -> &'static str {
-        "ExtensibleBitmapParser"
+        "VarintLengthPrefixParser"
     }
 
     fn applicable(&self, h: &Hypothesis) -> bool {
-        matches!(h, Hypothesis::ExtensibleBitmap { .. })
+        matches!(h, Hypothesis::VarintLengthPrefix { .. })
+    }
+
+    fn plausible(&self, sample: &Corpus, h: &Hypothesis) -> bool {
+        let Hypothesis::VarintLengthPrefix { offset, encoding } = h else {
+            return false;
+        };
+        mostly_fits(sample, |data| {
+            data.get(*offset..)
+                .and_then(|rest| encoding.read(rest))
+                .is_some_and(|(len, len_bytes)| (offset + len_bytes).saturating_add(len as usize) <= data.len())
+        })
     }
 
     fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
-        let Hypothesis::ExtensibleBitmap {
-            start,
-            cont_bit,
-            stop_value,
-            max_bytes,
-        } = h
-        else {
+        let Hypothesis::VarintLengthPrefix { offset, encoding } = h else {
             return ParsedCorpus::new(vec![]);
         };
 
@@ -241,50 +297,46 @@ impl Parser for ExtensibleBitmapParser {
         for pdu in &corpus.items {
             let data = pdu.as_slice();
             let mut segments = Vec::new();
+            let mut pos = 0;
             let mut exceptions = Vec::new();
 
-            if data.len() < *start {
-                segments.push(Segment::new(
-                    SegmentKind::Error("PDU too short for bitmap start".to_string()),
-                    0..data.len(),
-                ));
-                parsed_pdus.push(ParsedPdu { segments, exceptions });
-                continue;
-            }
-
-            // Lire la bitmap jusqu'à ce que le bit de continuation soit à stop_value
-            let mut bitmap_pos = *start;
-            let mut bitmap_len = 0;
+            while pos < data.len() {
+                let len_pos = pos + *offset;
+                let Some((len, len_bytes)) = data.get(len_pos..).and_then(|rest| encoding.read(rest)) else {
+                    segments.push(Segment::new(
+                        SegmentKind::Error("Incomplete length field".to_string()),
+                        pos..data.len(),
+                    ));
+                    break;
+                };
 
-            while bitmap_pos < data.len() && bitmap_len < *max_bytes {
-                let byte = data[bitmap_pos];
-                let cont_bit_value = (byte >> *cont_bit) & 1;
-                bitmap_len += 1;
+                let header_end = len_pos + len_bytes;
+                let message_end = header_end.saturating_add(len as usize);
 
-                if cont_bit_value == *stop_value {
+                if message_end > data.len() {
+                    exceptions.push(ParseException::fatal(format!("Message extends beyond PDU at pos {}", pos)));
+                    segments.push(Segment::new(
+                        SegmentKind::Error("Message overflow".to_string()),
+                        pos..data.len(),
+                    ));
                     break;
                 }
-                bitmap_pos += 1;
-            }
 
-            let bitmap_end = *start + bitmap_len;
-            if bitmap_end > data.len() {
-                exceptions.push("Bitmap extends beyond PDU".to_string());
                 segments.push(Segment::new(
-                    SegmentKind::Error("Bitmap overflow".to_string()),
-                    0..data.len(),
+                    SegmentKind::Field("length".to_string()),
+                    pos..header_end,
                 ));
-            } else {
-                if *start > 0 {
-                    segments.push(Segment::new(SegmentKind::Pci, 0..*start));
+                if header_end < message_end {
+                    segments.push(Segment::new(SegmentKind::Sdu, header_end..message_end));
                 }
-                segments.push(Segment::new(
-                    SegmentKind::Field("bitmap".to_string()),
-                    *start..bitmap_end,
-                ));
-                if bitmap_end < data.len() {
-                    segments.push(Segment::new(SegmentKind::Sdu, bitmap_end..data.len()));
+                if message_end < data.len() {
+                    segments.push(Segment::new(
+                        SegmentKind::MessageBoundary,
+                        message_end..message_end,
+                    ));
                 }
+
+                pos = message_end;
             }
 
             parsed_pdus.push(ParsedPdu { segments, exceptions });
@@ -294,208 +346,98 @@ impl Parser for ExtensibleBitmapParser {
     }
 }
 
-/// Parseur pour TLV
-pub struct TlvParser;
+/// Part minimale des PDUs de l'échantillon où le découpage commence bien pour que
+/// l'hypothèse soit parsée sur tout le corpus (les PDUs tronquées peuvent déborder)
+const PLAUSIBLE_MIN_RATIO: f64 = 0.5;
 
-impl Parser for TlvParser {
+/// Vrai si `fits` est vérifié par au moins `PLAUSIBLE_MIN_RATIO` des PDUs de l'échantillon
+fn mostly_fits(sample: &Corpus, fits: impl Fn(&[u8]) -> bool) -> bool {
+    let count = sample.items.iter().filter(|pdu| fits(pdu.as_slice())).count();
+    count as f64 >= PLAUSIBLE_MIN_RATIO * sample.len() as f64
+}
+
+/// Lit un champ de longueur de `width` octets à `pos` (le champ doit tenir dans `data`)
+pub(crate) fn read_length(data: &[u8], pos: usize, width: LengthWidth, endian: Endianness) -> usize {
+    let bytes = &data[pos..pos + width as usize];
+    match endian {
+        Endianness::Big => bytes.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize),
+        Endianness::Little => bytes.iter().rev().fold(0usize, |acc, &b| (acc << 8) | b as usize),
+    }
+}
+
+/// Parseur pour longueur en fin de message : les messages sont découpés depuis la fin de la PDU
+pub struct LengthSuffixParser;
+
+impl Parser for LengthSuffixParser {
     fn name(&self) -> &'static str {
-        "TlvParser"
+        "LengthSuffixParser"
     }
 
     fn applicable(&self, h: &Hypothesis) -> bool {
-        matches!(h, Hypothesis::Tlv { .. })
+        matches!(h, Hypothesis::LengthSuffixBundle { .. })
+    }
+
+    fn plausible(&self, sample: &Corpus, h: &Hypothesis) -> bool {
+        let Hypothesis::LengthSuffixBundle { offset, width, endian } = h else {
+            return false;
+        };
+        // Le dernier message doit tenir dans la PDU
+        let tail_len = offset + *width as usize;
+        mostly_fits(sample, |data| {
+            data.len() >= tail_len && read_length(data, data.len() - tail_len, *width, *endian) <= data.len() - tail_len
+        })
     }
 
     fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
-        let Hypothesis::Tlv { tag_offset, tag_bytes, len_offset, len_rule, length_includes_header } = h else {
+        let Hypothesis::LengthSuffixBundle { offset, width, endian } = h else {
             return ParsedCorpus::new(vec![]);
         };
+        let tail_len = offset + *width as usize;
 
         let mut parsed_pdus = Vec::new();
 
         for pdu in &corpus.items {
             let data = pdu.as_slice();
+            // Segments collectés de la fin vers le début, remis dans l'ordre à la fin
             let mut segments = Vec::new();
             let mut exceptions = Vec::new();
-            let mut pos = 0;
+            let mut end = data.len();
 
-            while pos < data.len() {
-                // Vérifier qu'on a assez de place pour le tag à l'offset spécifié
-                let tag_start = pos + *tag_offset;
-                if tag_start + *tag_bytes > data.len() {
-                    exceptions.push("Incomplete tag".to_string());
+            while end > 0 {
+                if end < tail_len {
                     segments.push(Segment::new(
-                        SegmentKind::Error("Incomplete tag".to_string()),
-                        pos..data.len(),
+                        SegmentKind::Error("Incomplete length field".to_string()),
+                        0..end,
                     ));
                     break;
                 }
 
-                // Ajouter un préfixe PCI si tag_offset > 0
-                if *tag_offset > 0 && pos < tag_start {
-                    segments.push(Segment::new(
-                        SegmentKind::Pci,
-                        pos..tag_start,
-                    ));
-                }
-
-                // Tag
-                segments.push(Segment::new(
-                    SegmentKind::Field("tag".to_string()),
-                    tag_start..tag_start + *tag_bytes,
-                ));
-                
-                // Calculer où commence le length
-                let length_start = pos + *len_offset;
-
-                // Lire le length à l'offset spécifié
-                let len = match len_rule {
-                    TlvLenRule::DefiniteShort => {
-                        if length_start >= data.len() {
-                            exceptions.push("Incomplete length".to_string());
-                            break;
-                        }
-                        let l = data[length_start] as usize;
-                        l
-                    }
-                    TlvLenRule::DefiniteMedium => {
-                        if length_start + 2 > data.len() {
-                            exceptions.push("Incomplete length".to_string());
-                            break;
-                        }
-                        // Network-friendly = Big Endian (standard pour les protocoles réseau)
-                        // Always use big endian for network protocols
-                        let l = u16::from_be_bytes([data[length_start], data[length_start + 1]]) as usize;
-                        l
-                    }
-                    TlvLenRule::DefiniteLong => {
-                        if length_start + 4 > data.len() {
-                            exceptions.push("Incomplete length".to_string());
-                            break;
-                        }
-                        let l = u32::from_be_bytes([
-                            data[length_start],
-                            data[length_start + 1],
-                            data[length_start + 2],
-                            data[length_start + 3],
-                        ]) as usize;
-                        l
-                    }
-                    TlvLenRule::IndefiniteWithEoc => {
-                        // Chercher 0x00 0x00 à partir de length_start
-                        let mut found = false;
-                        let mut search_pos = length_start;
-                        while search_pos + 1 < data.len() {
-                            if data[search_pos] == 0x00 && data[search_pos + 1] == 0x00 {
-                                found = true;
-                                break;
-                            }
-                            search_pos += 1;
-                        }
-                        if !found {
-                            exceptions.push("EOC not found".to_string());
-                            break;
-                        }
-                        search_pos - length_start // Longueur jusqu'à EOC
-                    }
-                };
-                
-                let length_field_size = match len_rule {
-                    TlvLenRule::DefiniteShort => 1,
-                    TlvLenRule::DefiniteMedium => 2,
-                    TlvLenRule::DefiniteLong => 4,
-                    TlvLenRule::IndefiniteWithEoc => 0,
-                };
-                
-                // Calculer où se termine le length field
-                let length_end = length_start + length_field_size;
-                
-                // Ajouter un segment pour l'espace entre tag et length si nécessaire
-                if tag_start + *tag_bytes < length_start {
-                    segments.push(Segment::new(
-                        SegmentKind::Pci,
-                        tag_start + *tag_bytes..length_start,
-                    ));
-                }
-                
-                // Length field
-                if length_field_size > 0 {
+                let len_pos = end - tail_len;
+                let len = read_length(data, len_pos, *width, *endian);
+                let Some(message_start) = len_pos.checked_sub(len) else {
+                    exceptions.push(ParseException::fatal(format!("Message extends beyond PDU start at pos {}", end)));
                     segments.push(Segment::new(
-                        SegmentKind::Field("length".to_string()),
-                        length_start..length_end,
+                        SegmentKind::Error("Message overflow".to_string()),
+                        0..end,
                     ));
-                }
-                
-                // Calculer où commence la valeur
-                let value_start = length_end;
-                
-                // Détecter les length fields invalides (données corrompues, etc.)
-                // Note: Le padding Ethernet est maintenant pré-filtré lors du chargement PCAP
-                // 1. Length trop grand par rapport à ce qui reste dans le PDU
-                let remaining_bytes = data.len().saturating_sub(value_start);
-                if len > remaining_bytes + 1000 {
-                    // Length absurde (plus de 1000 bytes au-delà de ce qui reste)
-                    // Probablement des données corrompues ou un mauvais parsing
-                    exceptions.push(format!("Length field appears invalid: len={}, remaining={}, stopping TLV parsing", len, remaining_bytes));
                     break;
-                }
-                
-                // Utiliser length_includes_header comme spécifié dans l'hypothèse
-                // Dans notre cas, length_includes_header = true (le length inclut le header)
-                let header_size = length_end - tag_start;
-                let actual_len = if *length_includes_header {
-                    if len >= header_size {
-                        len - header_size
-                    } else {
-                        // Length trop petit pour inclure le header
-                        exceptions.push(format!("Length too small to include header: len={}, header_size={}", len, header_size));
-                        break;
-                    }
-                } else {
-                    len
                 };
 
-                // Vérifier que la valeur ne dépasse pas (déjà fait ci-dessus, mais double vérification)
-                if value_start + actual_len > data.len() {
-                    exceptions.push(format!("Value extends beyond PDU: value_start={}, actual_len={}, data_len={}, remaining={}", value_start, actual_len, data.len(), data.len() - value_start));
-                    break;
+                segments.push(Segment::new(SegmentKind::Field("length".to_string()), len_pos..end));
+                if message_start < len_pos {
+                    segments.push(Segment::new(SegmentKind::Sdu, message_start..len_pos));
                 }
-                
-                // Vérifier aussi qu'on a assez de données restantes
-                let remaining = data.len() - value_start;
-                if actual_len > remaining {
-                    exceptions.push(format!("Length too large for remaining data: actual_len={}, remaining={}", actual_len, remaining));
-                    break;
+                if message_start > 0 {
+                    segments.push(Segment::new(
+                        SegmentKind::MessageBoundary,
+                        message_start..message_start,
+                    ));
                 }
 
-                // Ne pas créer de segment SDU si la longueur est 0
-                if actual_len > 0 {
-                    segments.push(Segment::new(SegmentKind::Sdu, value_start..value_start + actual_len));
-                }
-                
-                // Avancer la position pour le prochain TLV
-                if matches!(len_rule, TlvLenRule::IndefiniteWithEoc) {
-                    // Pour IndefiniteWithEoc, chercher où se trouve EOC
-                    let mut eoc_pos = length_start;
-                    while eoc_pos + 1 < data.len() {
-                        if data[eoc_pos] == 0x00 && data[eoc_pos + 1] == 0x00 {
-                            pos = eoc_pos + 2; // Après EOC
-                            break;
-                        }
-                        eoc_pos += 1;
-                    }
-                } else {
-                    // Avancer la position pour le prochain TLV
-                    if *length_includes_header {
-                        // Si length inclut le header, avancer de 'len' depuis le début du tag
-                        pos = tag_start + len;
-                    } else {
-                        // Sinon, avancer normalement
-                        pos = value_start + actual_len;
-                    }
-                }
+                end = message_start;
             }
 
+            segments.reverse();
             parsed_pdus.push(ParsedPdu { segments, exceptions });
         }
 
@@ -503,26 +445,23 @@ impl Parser for TlvParser {
     }
 }
 
-/// Parseur pour varint (protobuf-like)
-pub struct VarintParser;
+/// Parseur pour multiplexage de canaux : une suite de trames `[canal][longueur][payload]`
+pub struct ChannelMuxParser;
 
-impl Parser for VarintParser {
+impl Parser for ChannelMuxParser {
     fn name(&self) -> &'static str {
-        "VarintParser"
+        "ChannelMuxParser"
     }
 
     fn applicable(&self, h: &Hypothesis) -> bool {
-        matches!(h, Hypothesis::VarintKeyWireType { .. })
+        matches!(h, Hypothesis::ChannelMux { .. })
     }
 
     fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
-        let Hypothesis::VarintKeyWireType {
-            key_max_bytes,
-            allow_embedded: _,
-        } = h
-        else {
+        let Hypothesis::ChannelMux { channel_width, len_width, endian } = h else {
             return ParsedCorpus::new(vec![]);
         };
+        let header_len = channel_width + *len_width as usize;
 
         let mut parsed_pdus = Vec::new();
 
@@ -533,109 +472,1793 @@ impl Parser for VarintParser {
             let mut pos = 0;
 
             while pos < data.len() {
-                // Lire la clé varint
-                let mut key_bytes = 0;
-                let mut key_value = 0u64;
-                let mut key_start = pos;
-
-                while key_bytes < *key_max_bytes && pos < data.len() {
-                    let byte = data[pos];
-                    key_value |= ((byte & 0x7F) as u64) << (key_bytes * 7);
-                    key_bytes += 1;
-                    pos += 1;
-
-                    if (byte & 0x80) == 0 {
-                        break;
-                    }
+                if pos + header_len > data.len() {
+                    segments.push(Segment::new(
+                        SegmentKind::Error("Incomplete mux header".to_string()),
+                        pos..data.len(),
+                    ));
+                    break;
                 }
 
-                if key_bytes >= *key_max_bytes && pos < data.len() && (data[pos - 1] & 0x80) != 0 {
-                    exceptions.push("Varint key too long".to_string());
+                let len_pos = pos + channel_width;
+                let payload_start = pos + header_len;
+                let payload_end = payload_start + read_length(data, len_pos, *len_width, *endian);
+                if payload_end > data.len() {
+                    exceptions.push(ParseException::fatal(format!("Message extends beyond PDU at pos {}", pos)));
+                    segments.push(Segment::new(
+                        SegmentKind::Error("Message overflow".to_string()),
+                        pos..data.len(),
+                    ));
                     break;
                 }
 
-                segments.push(Segment::new(
-                    SegmentKind::Field("key".to_string()),
-                    key_start..pos,
-                ));
+                let channel = crate::fields::read_be(data, pos, *channel_width).unwrap_or_default();
+                segments.push(
+                    Segment::new(SegmentKind::Field("channel".to_string()), pos..len_pos)
+                        .with_note(format!("0x{:x}", channel)),
+                );
+                segments.push(Segment::new(SegmentKind::Field("length".to_string()), len_pos..payload_start));
+                if payload_start < payload_end {
+                    segments.push(Segment::new(SegmentKind::Sdu, payload_start..payload_end));
+                }
+                if payload_end < data.len() {
+                    segments.push(Segment::new(SegmentKind::MessageBoundary, payload_end..payload_end));
+                }
 
-                // Dériver le wire type (3 bits de poids faible)
-                let wire_type = (key_value & 0x7) as u8;
-                let field_number = (key_value >> 3) as u32;
+                pos = payload_end;
+            }
 
-                // Lire la valeur selon le wire type
-                match wire_type {
-                    0 => {
-                        // Varint
-                        let mut val_bytes = 0;
-                        let val_start = pos;
-                        while val_bytes < 10 && pos < data.len() {
-                            let byte = data[pos];
-                            val_bytes += 1;
-                            pos += 1;
-                            if (byte & 0x80) == 0 {
-                                break;
-                            }
-                        }
-                        segments.push(Segment::new(
-                            SegmentKind::Field("value_varint".to_string()),
-                            val_start..pos,
-                        ));
+            parsed_pdus.push(ParsedPdu { segments, exceptions });
+        }
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur pour fragmentation applicative : en-tête de fragment, puis fragment de SDU.
+///
+/// Le réassemblage des fragments en SDU complètes est fait par le moteur d'inférence.
+pub struct FragmentationParser;
+
+impl Parser for FragmentationParser {
+    fn name(&self) -> &'static str {
+        "FragmentationParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::Fragmented { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::Fragmented { index_offset, index_width, flag_offset, .. } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let header_len = crate::fragmentation::fragment_header_len(h);
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                let Some((index, more)) = crate::fragmentation::fragment_fields(data, h) else {
+                    return ParsedPdu::new(vec![Segment::new(
+                        SegmentKind::Error("PDU too short for fragment header".to_string()),
+                        0..data.len(),
+                    )]);
+                };
+
+                let index_range = *index_offset..index_offset + index_width;
+                let mut segments = vec![
+                    Segment::new(SegmentKind::Field("fragment_index".to_string()), index_range.clone())
+                        .with_note(index.to_string()),
+                    Segment::new(SegmentKind::Field("fragment_flag".to_string()), *flag_offset..flag_offset + 1)
+                        .with_note(if more { "more" } else { "last" }.to_string()),
+                ];
+                // Octets d'en-tête qui ne sont ni l'index ni le drapeau
+                for offset in 0..header_len {
+                    if !index_range.contains(&offset) && offset != *flag_offset {
+                        segments.push(Segment::new(SegmentKind::Pci, offset..offset + 1));
                     }
-                    1 => {
-                        // Fixed64
-                        if pos + 8 > data.len() {
-                            exceptions.push("Incomplete fixed64".to_string());
-                            break;
-                        }
-                        segments.push(Segment::new(
-                            SegmentKind::Field("value_fixed64".to_string()),
-                            pos..pos + 8,
-                        ));
-                        pos += 8;
+                }
+                segments.sort_by_key(|s| s.range.start);
+                if header_len < data.len() {
+                    segments.push(Segment::new(SegmentKind::Sdu, header_len..data.len()));
+                }
+                ParsedPdu::new(segments)
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur pour delimiter bundling
+pub struct DelimiterParser;
+
+impl Parser for DelimiterParser {
+    fn name(&self) -> &'static str {
+        "DelimiterParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::DelimiterBundle { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::DelimiterBundle { pattern, escape, quote } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+
+        let mut parsed_pdus = Vec::new();
+
+        for pdu in &corpus.items {
+            let data = pdu.as_slice();
+            let mut segments = Vec::new();
+            let mut pos = 0;
+            let mut exceptions = Vec::new();
+
+            while pos < data.len() {
+                // Chercher le pattern hors des séquences échappées et des zones citées
+                let mut found = None;
+                let mut quoted = false;
+                let mut i = pos;
+                while i < data.len() {
+                    if Some(data[i]) == *escape {
+                        i += 2;
+                        continue;
                     }
-                    2 => {
-                        // Length-delimited
-                        if pos >= data.len() {
-                            exceptions.push("Incomplete length".to_string());
-                            break;
-                        }
-                        let len = data[pos] as usize;
-                        pos += 1;
-                        if pos + len > data.len() {
-                            exceptions.push("Length-delimited value extends beyond PDU".to_string());
-                            break;
+                    if Some(data[i]) == *quote {
+                        quoted = !quoted;
+                    } else if !quoted && data[i..].starts_with(pattern) {
+                        found = Some(i);
+                        break;
+                    }
+                    i += 1;
+                }
+                if quoted {
+                    exceptions.push(format!("Unterminated quote at offset {}", pos).into());
+                }
+
+                let next_boundary = found.unwrap_or(data.len());
+                if pos < next_boundary {
+                    segments.push(Segment::new(SegmentKind::Sdu, pos..next_boundary));
+                }
+
+                if found.is_some() {
+                    segments.push(Segment::new(
+                        SegmentKind::MessageBoundary,
+                        next_boundary..next_boundary + pattern.len(),
+                    ));
+                    pos = next_boundary + pattern.len();
+                } else {
+                    pos = data.len();
+                }
+            }
+
+            parsed_pdus.push(ParsedPdu { segments, exceptions });
+        }
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur pour tramage par byte-stuffing : délimiteurs et trames encore échappées.
+///
+/// Les SDUs transmises à la couche suivante sont déséchappées par le moteur d'inférence.
+pub struct ByteStuffingParser;
+
+impl Parser for ByteStuffingParser {
+    fn name(&self) -> &'static str {
+        "ByteStuffingParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::ByteStuffed { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::ByteStuffed { scheme } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                let mut segments: Vec<Segment> = data
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &b)| b == scheme.delimiter)
+                    .map(|(i, _)| Segment::new(SegmentKind::MessageBoundary, i..i + 1))
+                    .collect();
+                for frame in crate::stuffing::frames(data, scheme.delimiter) {
+                    let segment = match scheme.unescape(&data[frame.clone()]) {
+                        Some(unescaped) if unescaped.len() < frame.len() => {
+                            Segment::new(SegmentKind::Sdu, frame.clone())
+                                .with_note(format!("{} bytes unescaped", unescaped.len()))
                         }
+                        Some(_) => Segment::new(SegmentKind::Sdu, frame.clone()),
+                        None => Segment::new(
+                            SegmentKind::Error("Invalid escape sequence".to_string()),
+                            frame.clone(),
+                        ),
+                    };
+                    segments.push(segment);
+                }
+                segments.sort_by_key(|s| s.range.start);
+                ParsedPdu::new(segments)
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur pour trames HDLC : fanions, trames encore échappées et FCS final
+pub struct HdlcParser;
+
+impl Parser for HdlcParser {
+    fn name(&self) -> &'static str {
+        "HdlcParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::Hdlc { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::Hdlc { fcs } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let scheme = EscapeScheme::hdlc();
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                let mut segments: Vec<Segment> = data
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &b)| b == scheme.delimiter)
+                    .map(|(i, _)| Segment::new(SegmentKind::MessageBoundary, i..i + 1))
+                    .collect();
+                let mut mismatches = 0;
+                for frame in crate::stuffing::frames(data, scheme.delimiter) {
+                    let escaped = &data[frame.clone()];
+                    let Some(unescaped) = scheme.unescape(escaped) else {
                         segments.push(Segment::new(
-                            SegmentKind::Field("value_length".to_string()),
-                            pos - 1..pos,
+                            SegmentKind::Error("Invalid escape sequence".to_string()),
+                            frame,
                         ));
-                        segments.push(Segment::new(SegmentKind::Sdu, pos..pos + len));
-                        pos += len;
-                    }
-                    5 => {
-                        // Fixed32
-                        if pos + 4 > data.len() {
-                            exceptions.push("Incomplete fixed32".to_string());
-                            break;
-                        }
+                        continue;
+                    };
+                    let Some(algorithm) = fcs else {
+                        segments.push(Segment::new(SegmentKind::Sdu, frame));
+                        continue;
+                    };
+                    if unescaped.len() <= algorithm.width() {
                         segments.push(Segment::new(
-                            SegmentKind::Field("value_fixed32".to_string()),
-                            pos..pos + 4,
+                            SegmentKind::Error("Frame too short for FCS".to_string()),
+                            frame,
                         ));
-                        pos += 4;
+                        continue;
                     }
-                    _ => {
-                        exceptions.push(format!("Unknown wire type: {}", wire_type));
-                        break;
+                    // Le FCS peut lui-même contenir des octets échappés
+                    let sdu_end = frame.start + scheme.escaped_len(escaped, unescaped.len() - algorithm.width());
+                    segments.push(Segment::new(SegmentKind::Sdu, frame.start..sdu_end));
+                    segments.push(Segment::new(SegmentKind::Field("fcs".to_string()), sdu_end..frame.end));
+                    if !crate::stuffing::fcs_valid(&unescaped, *algorithm) {
+                        mismatches += 1;
                     }
                 }
-            }
+                segments.sort_by_key(|s| s.range.start);
+                let parsed = ParsedPdu::new(segments);
+                if mismatches > 0 {
+                    parsed.with_exception("FCS mismatch".to_string())
+                } else {
+                    parsed
+                }
+            })
+            .collect();
 
-            parsed_pdus.push(ParsedPdu { segments, exceptions });
-        }
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur pour noms en labels : en-tête, labels, terminateur ou pointeur, puis SDU
+pub struct LabelSequenceParser;
+
+impl Parser for LabelSequenceParser {
+    fn name(&self) -> &'static str {
+        "LabelSequenceParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::LabelSequence { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::LabelSequence { offset } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                let Some(name) = crate::labels::read_name(data, *offset) else {
+                    return ParsedPdu::new(vec![Segment::new(
+                        SegmentKind::Error("Invalid label sequence".to_string()),
+                        0..data.len(),
+                    )]);
+                };
+
+                let mut segments = Vec::new();
+                if *offset > 0 {
+                    segments.push(Segment::new(SegmentKind::Pci, 0..*offset));
+                }
+                for label in &name.labels {
+                    segments.push(
+                        Segment::new(SegmentKind::Field("label".to_string()), label.clone())
+                            .with_note(String::from_utf8_lossy(&data[label.start + 1..label.end]).into_owned()),
+                    );
+                }
+                match &name.pointer {
+                    Some((range, target)) => {
+                        let note = crate::labels::resolve_name(data, *target)
+                            .map_or_else(|| format!("-> {}", target), |resolved| format!("-> {} ({})", target, resolved));
+                        segments.push(Segment::new(SegmentKind::Field("pointer".to_string()), range.clone()).with_note(note));
+                    }
+                    None => segments.push(Segment::new(
+                        SegmentKind::Field("terminator".to_string()),
+                        name.end - 1..name.end,
+                    )),
+                }
+                if name.end < data.len() {
+                    segments.push(Segment::new(SegmentKind::Sdu, name.end..data.len()));
+                }
+                ParsedPdu::new(segments)
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur pour tableau d'enregistrements : compteur éventuel, puis un enregistrement par SDU
+pub struct RecordArrayParser;
+
+impl Parser for RecordArrayParser {
+    fn name(&self) -> &'static str {
+        "RecordArrayParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::RecordArray { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::RecordArray { record_len, count_field } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let start = count_field.map_or(0, |c| c.end());
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                if data.len() < start || *record_len == 0 {
+                    return ParsedPdu::new(vec![Segment::new(
+                        SegmentKind::Error("PDU too short for record count".to_string()),
+                        0..data.len(),
+                    )]);
+                }
+
+                let mut segments = Vec::new();
+                let mut exceptions = Vec::new();
+                let available = (data.len() - start) / record_len;
+                let count = match count_field {
+                    Some(field) => {
+                        if field.offset > 0 {
+                            segments.push(Segment::new(SegmentKind::Pci, 0..field.offset));
+                        }
+                        let count = read_length(data, field.offset, field.width, field.endian);
+                        segments.push(
+                            Segment::new(SegmentKind::Field("count".to_string()), field.offset..start)
+                                .with_note(count.to_string()),
+                        );
+                        if count > available {
+                            exceptions.push(ParseException::fatal(format!(
+                                "Record array extends beyond PDU: count={}, available={}",
+                                count, available
+                            )));
+                        }
+                        count.min(available)
+                    }
+                    None => available,
+                };
+
+                for index in 0..count {
+                    let record_start = start + index * record_len;
+                    segments.push(
+                        Segment::new(SegmentKind::Sdu, record_start..record_start + record_len)
+                            .with_note(format!("record {}", index)),
+                    );
+                }
+                let end = start + count * record_len;
+                if end < data.len() {
+                    segments.push(Segment::new(
+                        SegmentKind::Error("Trailing bytes after records".to_string()),
+                        end..data.len(),
+                    ));
+                }
+                ParsedPdu { segments, exceptions }
+            })
+            .collect();
 
         ParsedCorpus::new(parsed_pdus)
     }
 }
 
+/// Parseur pour fixed header
+pub struct FixedHeaderParser;
+
+impl Parser for FixedHeaderParser {
+    fn name(&self) -> &'static str {
+        "FixedHeaderParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::FixedHeader { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::FixedHeader { len } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+
+        let mut parsed_pdus = Vec::new();
+
+        for pdu in &corpus.items {
+            let data = pdu.as_slice();
+            let mut segments = Vec::new();
+            let exceptions = Vec::new();
+
+            if data.len() < *len {
+                segments.push(Segment::new(
+                    SegmentKind::Error("PDU too short".to_string()),
+                    0..data.len(),
+                ));
+            } else {
+                segments.push(Segment::new(SegmentKind::Pci, 0..*len));
+                if *len < data.len() {
+                    segments.push(Segment::new(SegmentKind::Sdu, *len..data.len()));
+                }
+            }
+
+            parsed_pdus.push(ParsedPdu { segments, exceptions });
+        }
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur pour trailer fixe : SDU puis `Field("trailer")` en fin de PDU
+pub struct FixedTrailerParser;
+
+impl Parser for FixedTrailerParser {
+    fn name(&self) -> &'static str {
+        "FixedTrailerParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::FixedTrailer { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::FixedTrailer { len } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                if data.len() < *len {
+                    return ParsedPdu::new(vec![Segment::new(
+                        SegmentKind::Error("PDU too short for trailer".to_string()),
+                        0..data.len(),
+                    )]);
+                }
+
+                let trailer_start = data.len() - len;
+                let mut segments = Vec::new();
+                if trailer_start > 0 {
+                    segments.push(Segment::new(SegmentKind::Sdu, 0..trailer_start));
+                }
+                segments.push(Segment::new(
+                    SegmentKind::Field("trailer".to_string()),
+                    trailer_start..data.len(),
+                ));
+                ParsedPdu::new(segments)
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur pour bitmap extensible
+pub struct ExtensibleBitmapParser;
+
+impl Parser for ExtensibleBitmapParser {
+    fn name(&self) -> &'static str {
+        "ExtensibleBitmapParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::ExtensibleBitmap { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::ExtensibleBitmap {
+            start,
+            cont_bit,
+            stop_value,
+            max_bytes,
+        } = h
+        else {
+            return ParsedCorpus::new(vec![]);
+        };
+
+        let mut parsed_pdus = Vec::new();
+
+        for pdu in &corpus.items {
+            let data = pdu.as_slice();
+            let mut segments = Vec::new();
+            let mut exceptions = Vec::new();
+
+            if data.len() < *start {
+                segments.push(Segment::new(
+                    SegmentKind::Error("PDU too short for bitmap start".to_string()),
+                    0..data.len(),
+                ));
+                parsed_pdus.push(ParsedPdu { segments, exceptions });
+                continue;
+            }
+
+            // Lire la bitmap jusqu'à ce que le bit de continuation soit à stop_value
+            let mut bitmap_pos = *start;
+            let mut bitmap_len = 0;
+
+            while bitmap_pos < data.len() && bitmap_len < *max_bytes {
+                let byte = data[bitmap_pos];
+                let cont_bit_value = (byte >> *cont_bit) & 1;
+                bitmap_len += 1;
+
+                if cont_bit_value == *stop_value {
+                    break;
+                }
+                bitmap_pos += 1;
+            }
+
+            let bitmap_end = *start + bitmap_len;
+            if bitmap_end > data.len() {
+                exceptions.push(ParseException::fatal("Bitmap extends beyond PDU"));
+                segments.push(Segment::new(
+                    SegmentKind::Error("Bitmap overflow".to_string()),
+                    0..data.len(),
+                ));
+            } else {
+                if *start > 0 {
+                    segments.push(Segment::new(SegmentKind::Pci, 0..*start));
+                }
+                segments.push(Segment::new(
+                    SegmentKind::Field("bitmap".to_string()),
+                    *start..bitmap_end,
+                ));
+                if bitmap_end < data.len() {
+                    segments.push(Segment::new(SegmentKind::Sdu, bitmap_end..data.len()));
+                }
+            }
+
+            parsed_pdus.push(ParsedPdu { segments, exceptions });
+        }
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur pour TLV
+pub struct TlvParser;
+
+impl Parser for TlvParser {
+    fn name(&self) -> &'static str {
+        "TlvParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::Tlv { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let (segments, exceptions) = parse_tlv_sequence(h, pdu.as_slice(), 0, 0);
+                ParsedPdu { segments, exceptions }
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Profondeur maximale d'imbrication des TLVs conteneurs
+const MAX_TLV_DEPTH: usize = 8;
+
+/// Parse une suite de TLVs de `start` à la fin de `data`.
+///
+/// Une valeur qui se parse elle-même sans exception en TLVs de même règle est remplacée par
+/// ces TLVs, dont les tags sont annotés de leur niveau d'imbrication : chaque PDU donne un
+/// arbre de TLVs dont seules les feuilles sont des SDUs.
+fn parse_tlv_sequence(h: &Hypothesis, data: &[u8], start: usize, depth: usize) -> (Vec<Segment>, Vec<ParseException>) {
+    let Hypothesis::Tlv { tag_offset, tag_bytes, len_offset, len_rule, length_includes_header, tag_rule, endian } = h
+    else {
+        return (Vec::new(), Vec::new());
+    };
+
+    let mut segments = Vec::new();
+    let mut exceptions = Vec::new();
+    let mut pos = start;
+
+    while pos < data.len() {
+        // Vérifier qu'on a assez de place pour le tag à l'offset spécifié
+        let tag_start = pos + *tag_offset;
+        if tag_start + *tag_bytes > data.len() {
+            exceptions.push("Incomplete tag".into());
+            segments.push(Segment::new(
+                SegmentKind::Error("Incomplete tag".to_string()),
+                pos..data.len(),
+            ));
+            break;
+        }
+
+        // Ajouter un préfixe PCI si tag_offset > 0
+        if *tag_offset > 0 && pos < tag_start {
+            segments.push(Segment::new(
+                SegmentKind::Pci,
+                pos..tag_start,
+            ));
+        }
+
+        // Longueur effective du tag (variable pour un tag BER multi-octets)
+        let tag_len = match tag_rule {
+            TlvTagRule::Fixed => *tag_bytes,
+            TlvTagRule::BerMultiByte => match read_ber_tag_len(&data[tag_start..]) {
+                Some(len) => len,
+                None => {
+                    exceptions.push("Incomplete tag".into());
+                    segments.push(Segment::new(
+                        SegmentKind::Error("Incomplete tag".to_string()),
+                        pos..data.len(),
+                    ));
+                    break;
+                }
+            },
+        };
+
+        // Tag
+        let tag = Segment::new(SegmentKind::Field("tag".to_string()), tag_start..tag_start + tag_len);
+        segments.push(if depth > 0 { tag.with_note(format!("niveau {}", depth)) } else { tag });
+        
+        // Calculer où commence le length (décalé des octets de tag supplémentaires)
+        let length_start = pos + *len_offset + tag_len.saturating_sub(*tag_bytes);
+
+        // Lire le length à l'offset spécifié
+        let len = match len_rule {
+            TlvLenRule::DefiniteShort => {
+                if length_start >= data.len() {
+                    exceptions.push("Incomplete length".into());
+                    break;
+                }
+                data[length_start] as usize
+            }
+            TlvLenRule::DefiniteMedium => {
+                if length_start + 2 > data.len() {
+                    exceptions.push("Incomplete length".into());
+                    break;
+                }
+                read_length(data, length_start, LengthWidth::Two, *endian)
+            }
+            TlvLenRule::DefiniteLong => {
+                if length_start + 4 > data.len() {
+                    exceptions.push("Incomplete length".into());
+                    break;
+                }
+                read_length(data, length_start, LengthWidth::Four, *endian)
+            }
+            TlvLenRule::IndefiniteWithEoc => {
+                // Chercher 0x00 0x00 à partir de length_start
+                let mut found = false;
+                let mut search_pos = length_start;
+                while search_pos + 1 < data.len() {
+                    if data[search_pos] == 0x00 && data[search_pos + 1] == 0x00 {
+                        found = true;
+                        break;
+                    }
+                    search_pos += 1;
+                }
+                if !found {
+                    exceptions.push("EOC not found".into());
+                    break;
+                }
+                search_pos - length_start // Longueur jusqu'à EOC
+            }
+            TlvLenRule::BerDefinite => match data.get(length_start..).and_then(read_ber_length) {
+                Some((len, _)) => len,
+                None => {
+                    exceptions.push("Invalid BER length".into());
+                    break;
+                }
+            },
+        };
+        
+        let length_field_size = match len_rule {
+            TlvLenRule::DefiniteShort => 1,
+            TlvLenRule::DefiniteMedium => 2,
+            TlvLenRule::DefiniteLong => 4,
+            TlvLenRule::IndefiniteWithEoc => 0,
+            TlvLenRule::BerDefinite => read_ber_length(&data[length_start..]).map_or(0, |(_, size)| size),
+        };
+        
+        // Calculer où se termine le length field
+        let length_end = length_start + length_field_size;
+        
+        // Ajouter un segment pour l'espace entre tag et length si nécessaire
+        if tag_start + tag_len < length_start {
+            segments.push(Segment::new(
+                SegmentKind::Pci,
+                tag_start + tag_len..length_start,
+            ));
+        }
+        
+        // Length field
+        if length_field_size > 0 {
+            segments.push(Segment::new(
+                SegmentKind::Field("length".to_string()),
+                length_start..length_end,
+            ));
+        }
+        
+        // Calculer où commence la valeur
+        let value_start = length_end;
+        
+        // Détecter les length fields invalides (données corrompues, etc.)
+        // Note: Le padding Ethernet est maintenant pré-filtré lors du chargement PCAP
+        // 1. Length trop grand par rapport à ce qui reste dans le PDU
+        let remaining_bytes = data.len().saturating_sub(value_start);
+        if len > remaining_bytes + 1000 {
+            // Length absurde (plus de 1000 bytes au-delà de ce qui reste)
+            // Probablement des données corrompues ou un mauvais parsing
+            exceptions.push(format!("Length field appears invalid: len={}, remaining={}, stopping TLV parsing", len, remaining_bytes).into());
+            break;
+        }
+        
+        // Utiliser length_includes_header comme spécifié dans l'hypothèse
+        // Dans notre cas, length_includes_header = true (le length inclut le header)
+        let header_size = length_end - tag_start;
+        let actual_len = if *length_includes_header {
+            if len >= header_size {
+                len - header_size
+            } else {
+                // Length trop petit pour inclure le header
+                exceptions.push(format!("Length too small to include header: len={}, header_size={}", len, header_size).into());
+                break;
+            }
+        } else {
+            len
+        };
+
+        // Vérifier que la valeur ne dépasse pas (déjà fait ci-dessus, mais double vérification)
+        if value_start + actual_len > data.len() {
+            exceptions.push(ParseException::fatal(format!("Value extends beyond PDU: value_start={}, actual_len={}, data_len={}, remaining={}", value_start, actual_len, data.len(), data.len() - value_start)));
+            break;
+        }
+        
+        // Vérifier aussi qu'on a assez de données restantes
+        let remaining = data.len() - value_start;
+        if actual_len > remaining {
+            exceptions.push(ParseException::fatal(format!("Length too large for remaining data: actual_len={}, remaining={}", actual_len, remaining)));
+            break;
+        }
+
+        // Ne pas créer de segment SDU si la longueur est 0
+        if actual_len > 0 {
+            let value = value_start..value_start + actual_len;
+            // Conteneur : une valeur entièrement faite de TLVs de même règle, dont au moins une
+            // valeur non vide, est dépliée ; en BER, seul un tag construit (bit 0x20) peut en être un
+            let ber = *tag_rule == TlvTagRule::BerMultiByte || *len_rule == TlvLenRule::BerDefinite;
+            let container = depth < MAX_TLV_DEPTH
+                && *len_rule != TlvLenRule::IndefiniteWithEoc
+                && (!ber || data[tag_start] & 0x20 != 0);
+            let nested = container
+                .then(|| parse_tlv_sequence(h, &data[..value.end], value.start, depth + 1))
+                .filter(|(children, nested_exceptions)| {
+                    nested_exceptions.is_empty() && children.iter().any(|s| s.kind == SegmentKind::Sdu)
+                });
+            match nested {
+                Some((children, _)) => segments.extend(children),
+                None => segments.push(Segment::new(SegmentKind::Sdu, value)),
+            }
+        }
+        
+        // Avancer la position pour le prochain TLV
+        if matches!(len_rule, TlvLenRule::IndefiniteWithEoc) {
+            // Pour IndefiniteWithEoc, chercher où se trouve EOC
+            let mut eoc_pos = length_start;
+            while eoc_pos + 1 < data.len() {
+                if data[eoc_pos] == 0x00 && data[eoc_pos + 1] == 0x00 {
+                    pos = eoc_pos + 2; // Après EOC
+                    break;
+                }
+                eoc_pos += 1;
+            }
+        } else {
+            // Avancer la position pour le prochain TLV
+            if *length_includes_header {
+                // Si length inclut le header, avancer de 'len' depuis le début du tag
+                pos = tag_start + len;
+            } else {
+                // Sinon, avancer normalement
+                pos = value_start + actual_len;
+            }
+        }
+    }
+
+    (segments, exceptions)
+}
+
+/// Nombre d'octets d'un tag BER : 1, ou plus en forme « high-tag-number » (numéro 0x1F suivi
+/// d'octets dont le bit de poids fort annonce la suite, au plus 4)
+fn read_ber_tag_len(data: &[u8]) -> Option<usize> {
+    let first = *data.first()?;
+    if first & 0x1f != 0x1f {
+        return Some(1);
+    }
+    let continuation = data[1..].iter().take(4).position(|&b| b & 0x80 == 0)?;
+    Some(continuation + 2)
+}
+
+/// Décode une longueur définie BER (X.690 §8.1.3) : longueur et taille du champ.
+///
+/// La forme indéfinie (0x80), la valeur réservée 0xFF et les longueurs de plus de 4 octets
+/// sont refusées.
+fn read_ber_length(data: &[u8]) -> Option<(usize, usize)> {
+    let first = *data.first()?;
+    if first < 0x80 {
+        return Some((first as usize, 1));
+    }
+    let count = (first & 0x7f) as usize;
+    if count == 0 || count > 4 {
+        return None;
+    }
+    let bytes = data.get(1..1 + count)?;
+    Some((bytes.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize), 1 + count))
+}
+
+/// Parseur pour varint (protobuf-like)
+pub struct VarintParser;
+
+impl Parser for VarintParser {
+    fn name(&self) -> &'static str {
+        "VarintParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::VarintKeyWireType { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::VarintKeyWireType {
+            key_max_bytes,
+            allow_embedded: _,
+        } = h
+        else {
+            return ParsedCorpus::new(vec![]);
+        };
+
+        let mut parsed_pdus = Vec::new();
+        // Clés de chaque PDU : (index du segment, (numéro de champ, wire type))
+        let mut pdu_keys = Vec::new();
+
+        for pdu in &corpus.items {
+            let data = pdu.as_slice();
+            let mut segments = Vec::new();
+            let mut exceptions = Vec::new();
+            let mut keys = Vec::new();
+            let mut pos = 0;
+
+            while pos < data.len() {
+                // Lire la clé varint
+                let mut key_bytes = 0;
+                let mut key_value = 0u64;
+                let key_start = pos;
+
+                while key_bytes < *key_max_bytes && pos < data.len() {
+                    let byte = data[pos];
+                    key_value |= ((byte & 0x7F) as u64) << (key_bytes * 7);
+                    key_bytes += 1;
+                    pos += 1;
+
+                    if (byte & 0x80) == 0 {
+                        break;
+                    }
+                }
+
+                if key_bytes >= *key_max_bytes && pos < data.len() && (data[pos - 1] & 0x80) != 0 {
+                    exceptions.push("Varint key too long".into());
+                    break;
+                }
+
+                // Dériver le wire type (3 bits de poids faible)
+                let wire_type = (key_value & 0x7) as u8;
+                let field_number = key_value >> 3;
+
+                if field_number == 0 || field_number > crate::protobuf::MAX_FIELD_NUMBER {
+                    exceptions.push(format!("Invalid field number: {}", field_number).into());
+                    segments.push(Segment::new(
+                        SegmentKind::Error("Invalid field number".to_string()),
+                        key_start..data.len(),
+                    ));
+                    break;
+                }
+
+                keys.push((segments.len(), (field_number, wire_type)));
+                segments.push(Segment::new(
+                    SegmentKind::Field("key".to_string()),
+                    key_start..pos,
+                ));
+
+                // Lire la valeur selon le wire type
+                match wire_type {
+                    0 => {
+                        // Varint
+                        let mut val_bytes = 0;
+                        let val_start = pos;
+                        while val_bytes < 10 && pos < data.len() {
+                            let byte = data[pos];
+                            val_bytes += 1;
+                            pos += 1;
+                            if (byte & 0x80) == 0 {
+                                break;
+                            }
+                        }
+                        segments.push(Segment::new(
+                            SegmentKind::Field("value_varint".to_string()),
+                            val_start..pos,
+                        ));
+                    }
+                    1 => {
+                        // Fixed64
+                        if pos + 8 > data.len() {
+                            exceptions.push("Incomplete fixed64".into());
+                            break;
+                        }
+                        segments.push(Segment::new(
+                            SegmentKind::Field("value_fixed64".to_string()),
+                            pos..pos + 8,
+                        ));
+                        pos += 8;
+                    }
+                    2 => {
+                        // Length-delimited
+                        if pos >= data.len() {
+                            exceptions.push("Incomplete length".into());
+                            break;
+                        }
+                        let len = data[pos] as usize;
+                        pos += 1;
+                        if pos + len > data.len() {
+                            exceptions.push(ParseException::fatal("Length-delimited value extends beyond PDU"));
+                            break;
+                        }
+                        segments.push(Segment::new(
+                            SegmentKind::Field("value_length".to_string()),
+                            pos - 1..pos,
+                        ));
+                        segments.push(Segment::new(SegmentKind::Sdu, pos..pos + len));
+                        pos += len;
+                    }
+                    5 => {
+                        // Fixed32
+                        if pos + 4 > data.len() {
+                            exceptions.push("Incomplete fixed32".into());
+                            break;
+                        }
+                        segments.push(Segment::new(
+                            SegmentKind::Field("value_fixed32".to_string()),
+                            pos..pos + 4,
+                        ));
+                        pos += 4;
+                    }
+                    _ => {
+                        exceptions.push(format!("Unknown wire type: {}", wire_type).into());
+                        break;
+                    }
+                }
+            }
+
+            parsed_pdus.push(ParsedPdu { segments, exceptions });
+            pdu_keys.push(keys);
+        }
+
+        // Un même numéro de champ doit garder son wire type d'une PDU à l'autre : les clés
+        // minoritaires invalident leur PDU
+        let dominant = crate::protobuf::dominant_wire_types(pdu_keys.iter().flatten().map(|(_, key)| key));
+        for (parsed_pdu, keys) in parsed_pdus.iter_mut().zip(&pdu_keys) {
+            for &(index, (number, wire_type)) in keys {
+                if dominant.get(&number) != Some(&wire_type) {
+                    parsed_pdu.segments[index].kind = SegmentKind::Error(format!("Inconsistent wire type for field {}", number));
+                    parsed_pdu.exceptions.push(format!("Inconsistent wire type for field {}", number).into());
+                }
+            }
+        }
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+
+/// Parseur pour les champs constants (magic)
+pub struct ConstantFieldParser;
+
+impl Parser for ConstantFieldParser {
+    fn name(&self) -> &'static str {
+        "ConstantFieldParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::ConstantField { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::ConstantField { offset, value } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let end = offset + value.len();
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                if data.get(*offset..end) != Some(value.as_slice()) {
+                    return ParsedPdu::new(vec![Segment::new(
+                        SegmentKind::Error("Magic mismatch".to_string()),
+                        0..data.len(),
+                    )]);
+                }
+
+                let mut segments = Vec::new();
+                if *offset > 0 {
+                    segments.push(Segment::new(SegmentKind::Pci, 0..*offset));
+                }
+                let hex: String = value.iter().map(|b| format!("{:02x}", b)).collect();
+                segments.push(
+                    Segment::new(SegmentKind::Field("magic".to_string()), *offset..end)
+                        .with_note(format!("0x{}", hex)),
+                );
+                if end < data.len() {
+                    segments.push(Segment::new(SegmentKind::Sdu, end..data.len()));
+                }
+                ParsedPdu::new(segments)
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur pour les discriminants de type : le discriminant est un champ, la suite la SDU
+pub struct TypeSwitchParser;
+
+impl Parser for TypeSwitchParser {
+    fn name(&self) -> &'static str {
+        "TypeSwitchParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::TypeSwitch { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::TypeSwitch { type_offset, type_width } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let type_end = type_offset + type_width;
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                let Some(value) = crate::fields::read_be(data, *type_offset, *type_width) else {
+                    return ParsedPdu::new(vec![Segment::new(
+                        SegmentKind::Error("PDU too short for type field".to_string()),
+                        0..data.len(),
+                    )]);
+                };
+
+                let mut segments = Vec::new();
+                if *type_offset > 0 {
+                    segments.push(Segment::new(SegmentKind::Pci, 0..*type_offset));
+                }
+                segments.push(
+                    Segment::new(SegmentKind::Field("type".to_string()), *type_offset..type_end)
+                        .with_note(format!("0x{:0width$x}", value, width = type_width * 2)),
+                );
+                if type_end < data.len() {
+                    segments.push(Segment::new(SegmentKind::Sdu, type_end..data.len()));
+                }
+                ParsedPdu::new(segments)
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur pour les protocoles texte : une ligne = un verbe suivi d'arguments
+pub struct TextLinesParser;
+
+impl Parser for TextLinesParser {
+    fn name(&self) -> &'static str {
+        "TextLinesParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::TextLines { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::TextLines { line_ending } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let terminator = line_ending.bytes();
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                let mut segments = Vec::new();
+                let mut exceptions = Vec::new();
+                let mut pos = 0;
+
+                while pos < data.len() {
+                    let line_end = data[pos..]
+                        .windows(terminator.len())
+                        .position(|w| w == terminator)
+                        .map(|i| pos + i);
+                    let content_end = line_end.unwrap_or(data.len());
+
+                    if data[pos..content_end].iter().any(|&b| !crate::measures::is_text_byte(b)) {
+                        exceptions.push(ParseException::informational(format!("Non-printable byte in text line at offset {}", pos)));
+                    }
+                    tokenize_line(data, pos..content_end, &mut segments);
+
+                    match line_end {
+                        Some(end) => {
+                            segments.push(Segment::new(SegmentKind::Pci, end..end + terminator.len()));
+                            pos = end + terminator.len();
+                        }
+                        None => pos = data.len(),
+                    }
+                }
+
+                let mut parsed = ParsedPdu::new(segments);
+                for exception in exceptions {
+                    parsed = parsed.with_exception(exception);
+                }
+                parsed
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Découpe une ligne en jetons séparés par espaces ou ':' ; le premier jeton est le verbe
+fn tokenize_line(data: &[u8], line: std::ops::Range<usize>, segments: &mut Vec<Segment>) {
+    let is_separator = |b: u8| b == b' ' || b == b':' || b == b'\t';
+    let mut pos = line.start;
+    let mut first = true;
+
+    while pos < line.end {
+        let run_end = (pos..line.end)
+            .find(|&i| is_separator(data[i]) != is_separator(data[pos]))
+            .unwrap_or(line.end);
+        if is_separator(data[pos]) {
+            segments.push(Segment::new(SegmentKind::Pci, pos..run_end));
+        } else {
+            let name = if first { "verb" } else { "arg" };
+            segments.push(
+                Segment::new(SegmentKind::Field(name.to_string()), pos..run_end)
+                    .with_note(String::from_utf8_lossy(&data[pos..run_end]).into_owned()),
+            );
+            first = false;
+        }
+        pos = run_end;
+    }
+}
+
+/// Fin du bloc d'en-têtes (ligne vide comprise), si la PDU en contient une
+pub(crate) fn header_block_end(data: &[u8], line_ending: LineEnding) -> Option<usize> {
+    let terminator = line_ending.bytes();
+    let blank = [terminator, terminator].concat();
+    // Bloc vide : la PDU commence directement par une ligne vide
+    if data.starts_with(terminator) {
+        return Some(terminator.len());
+    }
+    data.windows(blank.len())
+        .position(|w| w == blank.as_slice())
+        .map(|i| i + blank.len())
+}
+
+/// Parseur pour les en-têtes `Nom: Valeur` : ligne de départ optionnelle, en-têtes, corps en SDU
+pub struct KeyValueHeaderParser;
+
+impl Parser for KeyValueHeaderParser {
+    fn name(&self) -> &'static str {
+        "KeyValueHeaderParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::KeyValueHeader { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::KeyValueHeader { line_ending } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let terminator = line_ending.bytes();
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                let Some(block_end) = header_block_end(data, *line_ending) else {
+                    return ParsedPdu::new(vec![Segment::new(SegmentKind::Pci, 0..data.len())])
+                        .with_exception("Header block not terminated by an empty line".to_string());
+                };
+
+                let (mut segments, exceptions) = header_block_segments(data, 0..block_end, terminator);
+
+                if block_end < data.len() {
+                    segments.push(Segment::new(SegmentKind::Sdu, block_end..data.len()));
+                }
+
+                let mut parsed = ParsedPdu::new(segments);
+                for exception in exceptions {
+                    parsed = parsed.with_exception(exception);
+                }
+                parsed
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Segments d'un bloc d'en-têtes (ligne vide comprise) et lignes mal formées
+fn header_block_segments(
+    data: &[u8],
+    block: std::ops::Range<usize>,
+    terminator: &[u8],
+) -> (Vec<Segment>, Vec<ParseException>) {
+    let mut segments = Vec::new();
+    let mut exceptions = Vec::new();
+    let mut pos = block.start;
+    let mut first = true;
+
+    while pos + terminator.len() <= block.end {
+        let line_end = data[pos..block.end]
+            .windows(terminator.len())
+            .position(|w| w == terminator)
+            .map_or(block.end, |i| pos + i);
+
+        if line_end > pos {
+            match header_line(data, pos..line_end) {
+                Some(header) => segments.extend(header),
+                // Seule la première ligne peut être une ligne de départ (requête, statut)
+                None if first => tokenize_line(data, pos..line_end, &mut segments),
+                None => {
+                    exceptions.push(format!("Malformed header line at offset {}", pos).into());
+                    segments.push(Segment::new(SegmentKind::Pci, pos..line_end));
+                }
+            }
+        }
+        segments.push(Segment::new(SegmentKind::Pci, line_end..line_end + terminator.len()));
+        pos = line_end + terminator.len();
+        first = false;
+    }
+
+    (segments, exceptions)
+}
+
+/// Valeur de l'en-tête `name` (insensible à la casse) d'un bloc d'en-têtes, si elle est entière.
+///
+/// `Ok(None)` si l'en-tête est absent, `Err` si sa valeur n'est pas un entier.
+pub(crate) fn content_length(
+    data: &[u8],
+    block: std::ops::Range<usize>,
+    terminator: &[u8],
+    name: &str,
+) -> Result<Option<usize>, ()> {
+    for line in data[block].split(|&b| b == terminator[terminator.len() - 1]) {
+        let line = line.strip_suffix(&terminator[..terminator.len() - 1]).unwrap_or(line);
+        let Some(colon) = line.iter().position(|&b| b == b':') else {
+            continue;
+        };
+        if line[..colon].eq_ignore_ascii_case(name.as_bytes()) {
+            return std::str::from_utf8(&line[colon + 1..])
+                .ok()
+                .and_then(|value| value.trim().parse().ok())
+                .map(Some)
+                .ok_or(());
+        }
+    }
+    Ok(None)
+}
+
+/// Parseur pour messages texte à corps binaire : bloc d'en-têtes, puis corps dont la taille est
+/// donnée par l'en-tête de longueur (corps vide s'il est absent) ; les messages s'enchaînent
+pub struct ContentLengthParser;
+
+impl Parser for ContentLengthParser {
+    fn name(&self) -> &'static str {
+        "ContentLengthParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::ContentLengthMessages { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::ContentLengthMessages { line_ending, length_header } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let terminator = line_ending.bytes();
+
+        let mut parsed_pdus = Vec::new();
+
+        for pdu in &corpus.items {
+            let data = pdu.as_slice();
+            let mut segments = Vec::new();
+            let mut exceptions = Vec::new();
+            let mut pos = 0;
+
+            while pos < data.len() {
+                let Some(block_end) = header_block_end(&data[pos..], *line_ending).map(|end| pos + end) else {
+                    exceptions.push(format!("Header block not terminated by an empty line at pos {}", pos).into());
+                    segments.push(Segment::new(SegmentKind::Pci, pos..data.len()));
+                    break;
+                };
+
+                let (headers, malformed) = header_block_segments(data, pos..block_end, terminator);
+                segments.extend(headers);
+                exceptions.extend(malformed);
+
+                let body_len = match content_length(data, pos..block_end, terminator, length_header) {
+                    Ok(len) => len.unwrap_or(0),
+                    Err(()) => {
+                        exceptions.push(format!("Invalid {} value at pos {}", length_header, pos).into());
+                        0
+                    }
+                };
+                // Une longueur démesurée (`Content-Length: 18446744073709551615`) déborde aussi
+                let Some(body_end) = block_end.checked_add(body_len).filter(|&end| end <= data.len()) else {
+                    exceptions.push(ParseException::fatal(format!("Body extends beyond PDU at pos {}", block_end)));
+                    segments.push(Segment::new(
+                        SegmentKind::Error("Body overflow".to_string()),
+                        block_end..data.len(),
+                    ));
+                    break;
+                };
+
+                if block_end < body_end {
+                    segments.push(Segment::new(SegmentKind::Sdu, block_end..body_end));
+                }
+                if body_end < data.len() {
+                    segments.push(Segment::new(SegmentKind::MessageBoundary, body_end..body_end));
+                }
+                pos = body_end;
+            }
+
+            parsed_pdus.push(ParsedPdu { segments, exceptions });
+        }
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Segments d'une ligne `Nom: Valeur`, ou `None` si la ligne n'a pas cette forme
+fn header_line(data: &[u8], line: std::ops::Range<usize>) -> Option<Vec<Segment>> {
+    let colon = line.start + data[line.clone()].iter().position(|&b| b == b':')?;
+    let name = &data[line.start..colon];
+    if name.is_empty() || !name.iter().all(|&b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_') {
+        return None;
+    }
+
+    let value_start = (colon + 1..line.end)
+        .find(|&i| data[i] != b' ' && data[i] != b'\t')
+        .unwrap_or(line.end);
+    let name_text = String::from_utf8_lossy(name).into_owned();
+    let mut segments = vec![
+        Segment::new(SegmentKind::Field("key".to_string()), line.start..colon).with_note(name_text.clone()),
+        Segment::new(SegmentKind::Pci, colon..value_start),
+    ];
+    if value_start < line.end {
+        segments.push(
+            Segment::new(SegmentKind::Field("value".to_string()), value_start..line.end)
+                .with_note(name_text),
+        );
+    }
+    Some(segments)
+}
+
+/// Parseur des charges utiles compressées : la PDU entière est la SDU (à décompresser)
+pub struct CompressedParser;
+
+impl Parser for CompressedParser {
+    fn name(&self) -> &'static str {
+        "CompressedParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::Compressed { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::Compressed { algorithm } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                match algorithm.decompress(data) {
+                    Some(decompressed) => ParsedPdu::new(vec![Segment::new(SegmentKind::Sdu, 0..data.len())
+                        .with_note(format!("{:?}, {} decompressed bytes", algorithm, decompressed.len()))]),
+                    None if algorithm.has_signature(data) => {
+                        ParsedPdu::new(vec![Segment::new(SegmentKind::Pci, 0..data.len())])
+                            .with_exception(format!("{:?} payload could not be decompressed", algorithm))
+                    }
+                    None => ParsedPdu::new(vec![Segment::new(SegmentKind::Pci, 0..data.len())])
+                        .with_exception(format!("Not a {:?} payload", algorithm)),
+                }
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur d'une charge utile obfusquée : toute la PDU est la SDU, désobfusquée par le moteur
+pub struct ObfuscatedParser;
+
+impl Parser for ObfuscatedParser {
+    fn name(&self) -> &'static str {
+        "ObfuscatedParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::Obfuscated { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::Obfuscated { obfuscation } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let note = match obfuscation {
+            Obfuscation::Xor { key } => format!(
+                "XOR key {}",
+                key.iter().map(|k| format!("{:02x}", k)).collect::<String>()
+            ),
+            Obfuscation::Add { key } => format!("ADD key {:02x}", key),
+        };
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| ParsedPdu::new(vec![Segment::new(SegmentKind::Sdu, 0..pdu.len()).with_note(note.clone())]))
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur du bourrage final : le contenu est la SDU, la série finale un segment de bourrage
+pub struct TrailingPaddingParser;
+
+impl Parser for TrailingPaddingParser {
+    fn name(&self) -> &'static str {
+        "TrailingPaddingParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::TrailingPadding { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::TrailingPadding { pad_byte } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                let content_end = data.len() - crate::padding::trailing_pad_len(data, *pad_byte);
+                let mut segments = Vec::new();
+                if content_end > 0 {
+                    segments.push(Segment::new(SegmentKind::Sdu, 0..content_end));
+                }
+                if content_end < data.len() {
+                    segments.push(
+                        Segment::new(SegmentKind::Padding, content_end..data.len())
+                            .with_note(format!("0x{:02x} x{}", pad_byte, data.len() - content_end)),
+                    );
+                }
+                ParsedPdu::new(segments)
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur des champs de bits : l'en-tête est une PCI découpée en segments de bits, le reste la SDU
+pub struct BitFieldParser;
+
+/// Valeur des bits `bits` (bit 0 = bit de poids fort du premier octet)
+fn read_bits(data: &[u8], bits: std::ops::Range<usize>) -> u64 {
+    bits.fold(0u64, |acc, bit| (acc << 1) | ((data[bit / 8] >> (7 - bit % 8)) & 1) as u64)
+}
+
+impl Parser for BitFieldParser {
+    fn name(&self) -> &'static str {
+        "BitFieldParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::BitFields { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::BitFields { header_len, boundaries } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let header_bits = header_len * 8;
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                if data.len() < *header_len {
+                    return ParsedPdu::new(vec![Segment::new(
+                        SegmentKind::Error("PDU too short for bit fields".to_string()),
+                        0..data.len(),
+                    )]);
+                }
+
+                let mut segments = vec![Segment::new(SegmentKind::Pci, 0..*header_len)];
+                for (index, &start) in boundaries.iter().enumerate() {
+                    let end = boundaries.get(index + 1).copied().unwrap_or(header_bits);
+                    segments.push(
+                        Segment::from_bits(SegmentKind::Field(format!("bits[{}..{}]", start, end)), start..end)
+                            .with_note(format!("0x{:x}", read_bits(data, start..end))),
+                    );
+                }
+                if *header_len < data.len() {
+                    segments.push(Segment::new(SegmentKind::Sdu, *header_len..data.len()));
+                }
+                ParsedPdu::new(segments)
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur pour les champs de checksum
+pub struct ChecksumParser;
+
+impl Parser for ChecksumParser {
+    fn name(&self) -> &'static str {
+        "ChecksumParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::Checksum { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::Checksum { algorithm, endian, location } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let field_name = format!("checksum:{:?}", algorithm);
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                let Some((field, covered)) = crate::checksum::checksum_ranges(data.len(), algorithm.width(), *location)
+                else {
+                    return ParsedPdu::new(vec![Segment::new(
+                        SegmentKind::Error("PDU too short for checksum".to_string()),
+                        0..data.len(),
+                    )]);
+                };
+
+                // Les octets précédant le champ (en-tête) ou la zone couverte (fin) sont du PCI,
+                // la zone couverte est la SDU
+                let pci_end = field.start.min(covered.start);
+                let mut segments = Vec::new();
+                if pci_end > 0 {
+                    segments.push(Segment::new(SegmentKind::Pci, 0..pci_end));
+                }
+                let checksum = Segment::new(SegmentKind::Field(field_name.clone()), field);
+                match location {
+                    ChecksumLocation::Header { .. } => {
+                        segments.push(checksum);
+                        segments.push(Segment::new(SegmentKind::Sdu, covered));
+                    }
+                    ChecksumLocation::Trailer { .. } => {
+                        segments.push(Segment::new(SegmentKind::Sdu, covered));
+                        segments.push(checksum);
+                    }
+                }
+
+                let parsed = ParsedPdu::new(segments);
+                if algorithm.verify(data, *endian, *location) == Some(true) {
+                    parsed
+                } else {
+                    parsed.with_exception("Checksum mismatch".to_string())
+                }
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+/// Parseur combinateur pour hypothèse composite : la première partie découpe la PDU, chaque
+/// partie suivante découpe les SDUs laissées par la précédente
+pub struct CompositeParser {
+    parsers: Vec<Box<dyn Parser>>,
+}
+
+impl CompositeParser {
+    /// Combinateur des parseurs positionnels (en-têtes, cadrage, champs, TLV)
+    pub fn new() -> Self {
+        Self {
+            parsers: vec![
+                Box::new(FixedHeaderParser),
+                Box::new(FixedTrailerParser),
+                Box::new(LengthPrefixParser),
+                Box::new(RemainingLengthParser),
+                Box::new(VarintLengthPrefixParser),
+                Box::new(LengthSuffixParser),
+                Box::new(ConstantFieldParser),
+                Box::new(DelimiterParser),
+                Box::new(TlvParser),
+                Box::new(RecordArrayParser),
+            ],
+        }
+    }
+
+    /// Ajoute un parseur utilisable pour une partie
+    pub fn with_parser(mut self, parser: Box<dyn Parser>) -> Self {
+        self.parsers.push(parser);
+        self
+    }
+
+    fn parser_for(&self, part: &Hypothesis) -> Option<&dyn Parser> {
+        self.parsers.iter().find(|p| p.applicable(part)).map(|p| p.as_ref())
+    }
+}
+
+impl Default for CompositeParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser for CompositeParser {
+    fn name(&self) -> &'static str {
+        "CompositeParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        match h {
+            Hypothesis::Composite(parts) => {
+                !parts.is_empty() && parts.iter().all(|part| self.parser_for(part).is_some())
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::Composite(parts) = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let Some((first, rest)) = parts.split_first() else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let Some(parser) = self.parser_for(first) else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let mut parsed = parser.parse_corpus(corpus, first);
+
+        for part in rest {
+            let Some(parser) = self.parser_for(part) else {
+                return ParsedCorpus::new(vec![]);
+            };
+            let ranges: Vec<(usize, std::ops::Range<usize>)> = parsed
+                .parsed_pdus
+                .iter()
+                .enumerate()
+                .flat_map(|(index, parsed_pdu)| parsed_pdu.sdus().into_iter().map(move |range| (index, range)))
+                .collect();
+            if ranges.is_empty() {
+                break;
+            }
+            let sdus = corpus.sub_ranges(&ranges, format!("{}_composite", corpus.meta.source));
+            let inner = parser.parse_corpus(&sdus, part);
+            if inner.parsed_pdus.len() != ranges.len() {
+                return ParsedCorpus::new(vec![]);
+            }
+
+            // Chaque SDU est remplacée par les segments de la partie, ramenés à la position de la SDU
+            let mut inner_pdus = ranges.iter().zip(inner.parsed_pdus);
+            for parsed_pdu in &mut parsed.parsed_pdus {
+                let mut segments = Vec::with_capacity(parsed_pdu.segments.len());
+                for segment in std::mem::take(&mut parsed_pdu.segments) {
+                    if !matches!(segment.kind, SegmentKind::Sdu) {
+                        segments.push(segment);
+                        continue;
+                    }
+                    let Some(((_, range), inner_pdu)) = inner_pdus.next() else {
+                        segments.push(segment);
+                        continue;
+                    };
+                    let shift = range.start;
+                    segments.extend(inner_pdu.segments.into_iter().map(|mut s| {
+                        s.range = s.range.start + shift..s.range.end + shift;
+                        s.bits = s.bits.map(|bits| bits.start + shift * 8..bits.end + shift * 8);
+                        s
+                    }));
+                    parsed_pdu.exceptions.extend(inner_pdu.exceptions);
+                }
+                parsed_pdu.segments = segments;
+            }
+            parsed.diagnostics.extend(inner.diagnostics);
+        }
+
+        parsed
+    }
+}
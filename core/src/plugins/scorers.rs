@@ -1,21 +1,189 @@
 use crate::corpus::Corpus;
-use crate::hypothesis::Hypothesis;
-use crate::measures::{compressed_size, entropy};
-use crate::parser::ParsedCorpus;
+use crate::hypothesis::{Hypothesis, Obfuscation};
+use crate::measures::{entropy, AlignmentGain, CompressionBackend, CompressionModel};
+use crate::parser::{ExceptionSeverity, ParsedCorpus};
 use crate::plugin::Scorer;
 use crate::score::{Score, ScoreBreakdown};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Nombre de modèles de compression entraînés gardés par le scoreur (corpus des couches,
+/// branches et plis de validation croisée)
+const TRAINED_MODEL_CACHE: usize = 64;
+
+/// Traitement des exceptions de parsing selon leur gravité
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExceptionPolicy {
+    /// Gravité à partir de laquelle une exception (hors débordement d'une PDU tronquée)
+    /// rejette l'hypothèse ; `None` pour ne jamais rejeter
+    pub reject_at: Option<ExceptionSeverity>,
+    /// Bits de pénalité par exception fatale non rejetée
+    pub fatal_penalty_bits: f64,
+    /// Bits de pénalité par exception récupérable
+    pub recoverable_penalty_bits: f64,
+    /// Bits de pénalité par exception informative
+    pub informational_penalty_bits: f64,
+}
+
+impl Default for ExceptionPolicy {
+    fn default() -> Self {
+        Self {
+            reject_at: Some(ExceptionSeverity::Fatal),
+            fatal_penalty_bits: 64.0,
+            recoverable_penalty_bits: 16.0,
+            informational_penalty_bits: 4.0,
+        }
+    }
+}
+
+impl ExceptionPolicy {
+    /// Bits de pénalité d'une exception de gravité `severity`
+    pub fn penalty_bits(&self, severity: ExceptionSeverity) -> f64 {
+        match severity {
+            ExceptionSeverity::Fatal => self.fatal_penalty_bits,
+            ExceptionSeverity::Recoverable => self.recoverable_penalty_bits,
+            ExceptionSeverity::Informational => self.informational_penalty_bits,
+        }
+    }
+}
+
+/// Poids et coefficients du scoreur MDL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MdlScorerConfig {
+    /// Poids des bits du modèle
+    pub model_weight: f64,
+    /// Poids des bits des données (SDUs)
+    pub data_weight: f64,
+    /// Poids des pénalités
+    pub penalty_weight: f64,
+    /// Poids de la réduction d'entropie
+    pub entropy_drop_weight: f64,
+    /// Poids du gain d'alignement
+    pub alignment_weight: f64,
+    /// Ratio de succès du parsing en dessous duquel l'hypothèse est rejetée
+    pub min_parse_success_ratio: f64,
+    /// Nombre moyen de segments par PDU au-delà duquel le sur-découpage est pénalisé
+    pub max_avg_segments: f64,
+    /// Bits de pénalité par segment moyen au-delà de `max_avg_segments`
+    pub segment_penalty_bits: f64,
+    /// Bits de pénalité par segment de moins de deux octets
+    pub small_segment_penalty_bits: f64,
+    /// Rejet et pénalités des exceptions
+    pub exceptions: ExceptionPolicy,
+    /// Modèle de compression estimant les bits des PCI, champs et SDUs
+    pub compression: CompressionBackend,
+}
+
+impl Default for MdlScorerConfig {
+    fn default() -> Self {
+        Self {
+            model_weight: 1.0,
+            data_weight: 1.0,
+            penalty_weight: 1.0,
+            entropy_drop_weight: 1.0,
+            alignment_weight: 1.0,
+            min_parse_success_ratio: 0.95,
+            max_avg_segments: 10.0,
+            segment_penalty_bits: 8.0,
+            small_segment_penalty_bits: 4.0,
+            exceptions: ExceptionPolicy::default(),
+            compression: CompressionBackend::default(),
+        }
+    }
+}
+
+impl MdlScorerConfig {
+    /// Modifie un coefficient par son nom (celui du champ)
+    pub fn set(&mut self, key: &str, value: f64) -> Result<(), String> {
+        let field = match key {
+            "model_weight" => &mut self.model_weight,
+            "data_weight" => &mut self.data_weight,
+            "penalty_weight" => &mut self.penalty_weight,
+            "entropy_drop_weight" => &mut self.entropy_drop_weight,
+            "alignment_weight" => &mut self.alignment_weight,
+            "min_parse_success_ratio" => &mut self.min_parse_success_ratio,
+            "max_avg_segments" => &mut self.max_avg_segments,
+            "segment_penalty_bits" => &mut self.segment_penalty_bits,
+            "small_segment_penalty_bits" => &mut self.small_segment_penalty_bits,
+            "fatal_penalty_bits" => &mut self.exceptions.fatal_penalty_bits,
+            "recoverable_penalty_bits" => &mut self.exceptions.recoverable_penalty_bits,
+            "informational_penalty_bits" => &mut self.exceptions.informational_penalty_bits,
+            _ => return Err(format!("coefficient de scoreur inconnu: {}", key)),
+        };
+        *field = value;
+        Ok(())
+    }
+
+    /// Comme [`MdlScorerConfig::set`], la valeur étant donnée en texte ; `reject_at` prend
+    /// une gravité (`fatal`, `recoverable`, `informational`) ou `none`, `compression` un
+    /// modèle (`deflate`, `zstd`, `lzma`, `ppm`)
+    pub fn set_str(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let value = value.trim();
+        if key == "compression" {
+            self.compression = value.parse()?;
+            return Ok(());
+        }
+        if key == "reject_at" {
+            self.exceptions.reject_at = match value {
+                "none" => None,
+                severity => Some(severity.parse()?),
+            };
+            return Ok(());
+        }
+        let number = value.parse().map_err(|e| format!("valeur invalide pour {}: {}", key, e))?;
+        self.set(key, number)
+    }
+}
 
 /// Scoreur MDL standard
 pub struct MdlScorer {
-    pub min_parse_success_ratio: f64,
+    pub config: MdlScorerConfig,
+    /// Modèles entraînés par empreinte du corpus : un dictionnaire zstd n'est entraîné qu'une
+    /// fois par couche, et non pour chaque hypothèse
+    trained_models: Mutex<HashMap<u64, Arc<dyn CompressionModel>>>,
 }
 
 impl MdlScorer {
     pub fn new() -> Self {
+        Self::with_config(MdlScorerConfig::default())
+    }
+
+    pub fn with_config(config: MdlScorerConfig) -> Self {
         Self {
-            min_parse_success_ratio: 0.95,
+            config,
+            trained_models: Mutex::new(HashMap::new()),
         }
     }
+
+    /// Modèle de compression des données de `corpus`, construit une seule fois par corpus
+    /// s'il doit être entraîné
+    fn compression_model(&self, corpus: &Corpus) -> Arc<dyn CompressionModel> {
+        let backend = self.config.compression;
+        if !backend.is_trained() {
+            return Arc::from(backend.model(corpus));
+        }
+        let digest = crate::checkpoint::corpus_digest(corpus);
+        if let Some(model) = self.trained_models.lock().ok().and_then(|models| models.get(&digest).cloned()) {
+            return model;
+        }
+        // Entraîné hors du verrou : les hypothèses évaluées en parallèle ne s'attendent pas
+        let model: Arc<dyn CompressionModel> = Arc::from(backend.model(corpus));
+        if let Ok(mut models) = self.trained_models.lock() {
+            if models.len() >= TRAINED_MODEL_CACHE {
+                models.clear();
+            }
+            models.entry(digest).or_insert_with(|| model.clone());
+        }
+        model
+    }
+
+    /// Nombre de modèles entraînés gardés
+    pub fn trained_model_count(&self) -> usize {
+        self.trained_models.lock().map_or(0, |models| models.len())
+    }
 }
 
 impl Default for MdlScorer {
@@ -35,20 +203,16 @@ impl Scorer for MdlScorer {
         parsed: &ParsedCorpus,
         h: &Hypothesis,
     ) -> Score {
-        // Vérifier les exceptions "extends beyond PDU" - éliminateurs
-        // Ces exceptions indiquent que l'hypothèse ne peut pas parser correctement les données
-        let has_pdu_overflow_exceptions = parsed.parsed_pdus.iter().any(|p| {
-            p.exceptions.iter().any(|exc| {
-                exc.contains("extends beyond PDU") || 
-                exc.contains("Length too large for remaining data") ||
-                exc.contains("Message extends beyond PDU") ||
-                exc.contains("Bitmap extends beyond PDU") ||
-                exc.contains("Length-delimited value extends beyond PDU")
-            })
-        });
-
-        // Si des exceptions "extends beyond PDU" existent, rejeter l'hypothèse
-        if has_pdu_overflow_exceptions {
+        // Exceptions éliminatoires selon la politique (par défaut les débordements au-delà de la
+        // PDU) : l'hypothèse ne peut pas parser correctement les données, sauf sur une PDU
+        // tronquée à la capture où le débordement est attendu
+        let config = &self.config;
+        let rejected = config
+            .exceptions
+            .reject_at
+            .is_some_and(|severity| parsed.has_exception_at_in(corpus, severity));
+
+        if rejected {
             // Log pour debug
             if let Hypothesis::Tlv { tag_bytes, len_rule, len_offset, length_includes_header, .. } = h {
                 if *tag_bytes == 1 && matches!(len_rule, crate::hypothesis::TlvLenRule::DefiniteMedium) {
@@ -56,7 +220,7 @@ impl Scorer for MdlScorer {
                         .map(|p| p.exceptions.len())
                         .sum();
                     tracing::info!(
-                        "REJET: TLV Tag={} Len=2 (offset: tag={}, len={}, includes_header={}) a des exceptions 'extends beyond PDU' ({} exceptions totales)",
+                        "REJET: TLV Tag={} Len=2 (offset: tag={}, len={}, includes_header={}) a des exceptions éliminatoires ({} exceptions totales)",
                         tag_bytes, 0, len_offset, length_includes_header, exception_count
                     );
                 }
@@ -71,10 +235,10 @@ impl Scorer for MdlScorer {
             });
         }
 
-        let parse_success_ratio = parsed.parse_success_ratio();
+        let parse_success_ratio = parsed.parse_success_ratio_in(corpus);
 
         // Contrainte dure : PSR doit être >= seuil
-        if parse_success_ratio < self.min_parse_success_ratio {
+        if parse_success_ratio < config.min_parse_success_ratio {
             return Score::new(ScoreBreakdown {
                 mdl_model_bits: f64::INFINITY,
                 mdl_data_bits: f64::INFINITY,
@@ -89,21 +253,18 @@ impl Scorer for MdlScorer {
         let mut pci_data = Vec::new();
         let mut sdu_data = Vec::new();
         let mut field_data = Vec::new();
-        let mut total_pci_bytes = 0;
-        let mut total_sdu_bytes = 0;
         let mut _total_field_bytes = 0;
 
         for (pdu, parsed_pdu) in corpus.items.iter().zip(parsed.parsed_pdus.iter()) {
-            for segment in &parsed_pdu.segments {
+            // Les champs de bits sont déjà couverts par le segment d'octets qui les contient
+            for segment in parsed_pdu.segments.iter().filter(|s| s.bits.is_none()) {
                 let slice = &pdu.as_slice()[segment.range.clone()];
                 match segment.kind {
                     crate::segment::SegmentKind::Pci => {
                         pci_data.extend_from_slice(slice);
-                        total_pci_bytes += slice.len();
                     }
                     crate::segment::SegmentKind::Sdu => {
                         sdu_data.extend_from_slice(slice);
-                        total_sdu_bytes += slice.len();
                     }
                     crate::segment::SegmentKind::Field(_) => {
                         field_data.extend_from_slice(slice);
@@ -116,6 +277,8 @@ impl Scorer for MdlScorer {
             }
         }
 
+        let compressor = self.compression_model(corpus);
+
         // MDL Model : complexité de l'hypothèse
         // Inclut aussi les bits pour encoder les PCI et Fields (métadonnées du modèle)
         let mdl_model_bits = {
@@ -124,7 +287,7 @@ impl Scorer for MdlScorer {
             // Ajouter les bits pour encoder les PCI et Fields (métadonnées)
             let pci_bits = if !pci_data.is_empty() {
                 let pci_entropy = entropy(&pci_data);
-                let pci_compressed = compressed_size(&pci_data).map(|s| s as f64 * 8.0).unwrap_or(pci_entropy * pci_data.len() as f64);
+                let pci_compressed = compressor.compressed_bits(&pci_data).unwrap_or(pci_entropy * pci_data.len() as f64);
                 (pci_entropy * pci_data.len() as f64).min(pci_compressed)
             } else {
                 0.0
@@ -132,7 +295,7 @@ impl Scorer for MdlScorer {
             
             let field_bits = if !field_data.is_empty() {
                 let field_entropy = entropy(&field_data);
-                let field_compressed = compressed_size(&field_data).map(|s| s as f64 * 8.0).unwrap_or(field_entropy * field_data.len() as f64);
+                let field_compressed = compressor.compressed_bits(&field_data).unwrap_or(field_entropy * field_data.len() as f64);
                 (field_entropy * field_data.len() as f64).min(field_compressed)
             } else {
                 0.0
@@ -158,7 +321,7 @@ impl Scorer for MdlScorer {
                 // Les SDUs sont les données réellement "expliquées" par le modèle
                 // Ils devraient être bien compressibles si le modèle est bon
                 let sdu_entropy = entropy(&sdu_data);
-                let sdu_compressed = compressed_size(&sdu_data).map(|s| s as f64 * 8.0).unwrap_or(sdu_entropy * sdu_data.len() as f64);
+                let sdu_compressed = compressor.compressed_bits(&sdu_data).unwrap_or(sdu_entropy * sdu_data.len() as f64);
                 // Prendre le minimum entre entropie et compression
                 (sdu_entropy * sdu_data.len() as f64).min(sdu_compressed)
             } else {
@@ -178,28 +341,28 @@ impl Scorer for MdlScorer {
             .sum::<usize>() as f64
             / parsed.parsed_pdus.len().max(1) as f64;
 
-        if avg_segments > 10.0 {
-            penalties_bits += (avg_segments - 10.0) * 8.0;
+        if avg_segments > config.max_avg_segments {
+            penalties_bits += (avg_segments - config.max_avg_segments) * config.segment_penalty_bits;
         }
 
-        // Pénalité pour exceptions
+        // Pénalité pour exceptions, selon leur gravité
         // Note: Le padding Ethernet est maintenant pré-filtré lors du chargement PCAP
         // Donc on pénalise toutes les exceptions restantes
-        let exception_count: usize = parsed
+        penalties_bits += parsed
             .parsed_pdus
             .iter()
-            .map(|p| p.exceptions.len())
-            .sum();
-        penalties_bits += exception_count as f64 * 16.0;
+            .flat_map(|p| &p.exceptions)
+            .map(|exc| config.exceptions.penalty_bits(exc.severity))
+            .sum::<f64>();
 
         // Pénalité pour segments trop petits
         let small_segments = parsed
             .parsed_pdus
             .iter()
             .flat_map(|p| &p.segments)
-            .filter(|s| s.len() < 2)
+            .filter(|s| s.bits.is_none() && s.len() < 2)
             .count();
-        penalties_bits += small_segments as f64 * 4.0;
+        penalties_bits += small_segments as f64 * config.small_segment_penalty_bits;
 
         // Pénalité pour utilisation de little endian (non network-friendly)
         // Les protocoles réseau utilisent généralement big endian
@@ -222,7 +385,7 @@ impl Scorer for MdlScorer {
             } else {
                 // Calculer la taille compressée de chaque composant du modèle
                 let pci_compressed = if !pci_data.is_empty() {
-                    compressed_size(&pci_data).map(|s| s as f64 * 8.0).unwrap_or_else(|_| {
+                    compressor.compressed_bits(&pci_data).unwrap_or_else(|_| {
                         let pci_entropy = entropy(&pci_data);
                         pci_entropy * pci_data.len() as f64
                     })
@@ -231,7 +394,7 @@ impl Scorer for MdlScorer {
                 };
                 
                 let field_compressed = if !field_data.is_empty() {
-                    compressed_size(&field_data).map(|s| s as f64 * 8.0).unwrap_or_else(|_| {
+                    compressor.compressed_bits(&field_data).unwrap_or_else(|_| {
                         let field_entropy = entropy(&field_data);
                         field_entropy * field_data.len() as f64
                     })
@@ -240,7 +403,7 @@ impl Scorer for MdlScorer {
                 };
                 
                 let sdu_compressed = if !sdu_data.is_empty() {
-                    compressed_size(&sdu_data).map(|s| s as f64 * 8.0).unwrap_or_else(|_| {
+                    compressor.compressed_bits(&sdu_data).unwrap_or_else(|_| {
                         let sdu_entropy = entropy(&sdu_data);
                         sdu_entropy * sdu_data.len() as f64
                     })
@@ -252,7 +415,7 @@ impl Scorer for MdlScorer {
                 let model_compressed = pci_compressed + field_compressed + sdu_compressed;
                 
                 // Taille compressée des données brutes
-                let raw_compressed = compressed_size(&raw_data).map(|s| s as f64 * 8.0).unwrap_or_else(|_| {
+                let raw_compressed = compressor.compressed_bits(&raw_data).unwrap_or_else(|_| {
                     let raw_entropy = entropy(&raw_data);
                     raw_entropy * raw_data.len() as f64
                 });
@@ -272,31 +435,31 @@ impl Scorer for MdlScorer {
         if let Hypothesis::Tlv { tag_bytes, len_rule, len_offset, length_includes_header, .. } = h {
             if *tag_bytes == 1 && matches!(len_rule, crate::hypothesis::TlvLenRule::DefiniteMedium) && *len_offset == 1 && *length_includes_header {
                 let raw_data: Vec<u8> = corpus.items.iter().flat_map(|p| p.as_slice()).copied().collect();
-                let raw_compressed = compressed_size(&raw_data).map(|s| s as f64 * 8.0).unwrap_or(0.0);
+                let raw_compressed = compressor.compressed_bits(&raw_data).unwrap_or(0.0);
                 
                 // Calculer les tailles compressées séparément (comme dans entropy_drop)
                 let pci_compressed = if !pci_data.is_empty() {
-                    compressed_size(&pci_data).map(|s| s as f64 * 8.0).unwrap_or(0.0)
+                    compressor.compressed_bits(&pci_data).unwrap_or(0.0)
                 } else {
                     0.0
                 };
                 
                 let field_compressed = if !field_data.is_empty() {
-                    compressed_size(&field_data).map(|s| s as f64 * 8.0).unwrap_or(0.0)
+                    compressor.compressed_bits(&field_data).unwrap_or(0.0)
                 } else {
                     0.0
                 };
                 
                 let sdu_compressed = if !sdu_data.is_empty() {
-                    compressed_size(&sdu_data).map(|s| s as f64 * 8.0).unwrap_or(0.0)
+                    compressor.compressed_bits(&sdu_data).unwrap_or(0.0)
                 } else {
                     0.0
                 };
                 
                 let model_compressed = pci_compressed + field_compressed + sdu_compressed;
                 
-                let raw_ratio = if raw_data.len() > 0 { raw_compressed / raw_data.len() as f64 } else { 0.0 };
-                let sdu_ratio = if sdu_data.len() > 0 { sdu_compressed / sdu_data.len() as f64 } else { 0.0 };
+                let raw_ratio = if !raw_data.is_empty() { raw_compressed / raw_data.len() as f64 } else { 0.0 };
+                let sdu_ratio = if !sdu_data.is_empty() { sdu_compressed / sdu_data.len() as f64 } else { 0.0 };
                 let model_ratio = if (pci_data.len() + field_data.len() + sdu_data.len()) > 0 {
                     model_compressed / (pci_data.len() + field_data.len() + sdu_data.len()) as f64
                 } else {
@@ -323,26 +486,163 @@ impl Scorer for MdlScorer {
             }
         }
 
+        // Gain d'alignement : les octets rangés par offset depuis le début de leur structure
+        // (message, TLV, SDU) sont-ils plus prévisibles que rangés par offset absolu ?
+        let alignment_gain_bits =
+            AlignmentGain::compute(corpus, &structure_anchors(parsed), MAX_ALIGNMENT_OFFSET).gain_bits.max(0.0);
+
+        // Les composantes sont pondérées avant d'entrer dans le total
         Score::new(ScoreBreakdown {
-            mdl_model_bits,
-            mdl_data_bits,
+            mdl_model_bits: mdl_model_bits * config.model_weight,
+            mdl_data_bits: mdl_data_bits * config.data_weight,
             parse_success_ratio,
-            alignment_gain_bits: 0.0, // TODO: calculer si nécessaire (pour ExtensibleBitmap)
-            entropy_drop_bits,
+            alignment_gain_bits: alignment_gain_bits * config.alignment_weight,
+            entropy_drop_bits: entropy_drop_bits * config.entropy_drop_weight,
+            penalties_bits: penalties_bits * config.penalty_weight,
+        })
+    }
+}
+
+/// Octets examinés au début de chaque segment PCI ou champ
+const MAX_FIELD_COLUMN_BYTES: usize = 8;
+/// Nombre minimal de valeurs pour juger de la distribution d'une colonne
+const MIN_FIELD_SAMPLES: usize = 8;
+/// Entropie normalisée maximale d'une colonne de faible cardinalité
+const LOW_CARDINALITY_ENTROPY: f64 = 0.5;
+/// Entropie normalisée minimale d'une colonne d'allure aléatoire
+const RANDOM_FIELD_ENTROPY: f64 = 0.9;
+/// Écart maximal entre valeurs successives d'une colonne régulière
+const SMOOTH_MAX_STEP: i16 = 8;
+/// Proportion minimale de pas réguliers d'une colonne régulière
+const SMOOTH_MIN_RATIO: f64 = 0.8;
+/// Bits gagnés par valeur d'une colonne structurée
+const STRUCTURED_FIELD_BITS: f64 = 0.5;
+/// Bits de pénalité par valeur d'une colonne d'allure aléatoire
+const RANDOM_FIELD_BITS: f64 = 2.0;
+
+/// Scoreur complémentaire de la distribution des valeurs de PCI et de champs.
+///
+/// Les octets d'en-tête réels prennent peu de valeurs (type, version, flags) ou évoluent
+/// régulièrement (compteurs) ; des « champs » d'allure aléatoire signalent un découpage
+/// arbitraire. Le score ne comporte que des pénalités (négatives pour une récompense), à
+/// combiner avec celui du [`MdlScorer`].
+pub struct FieldDistributionScorer;
+
+impl Scorer for FieldDistributionScorer {
+    fn name(&self) -> &'static str {
+        "FieldDistributionScorer"
+    }
+
+    fn score(&self, corpus: &Corpus, parsed: &ParsedCorpus, _h: &Hypothesis) -> Score {
+        // Colonne (rang du segment PCI/champ dans la PDU, octet dans le segment) -> valeurs
+        let mut columns: std::collections::BTreeMap<(usize, usize), Vec<u8>> = std::collections::BTreeMap::new();
+        for (pdu, parsed_pdu) in corpus.items.iter().zip(parsed.parsed_pdus.iter()) {
+            let data = pdu.as_slice();
+            let headers = parsed_pdu.segments.iter().filter(|s| {
+                s.bits.is_none()
+                    && matches!(s.kind, crate::segment::SegmentKind::Pci | crate::segment::SegmentKind::Field(_))
+            });
+            for (rank, segment) in headers.enumerate() {
+                let Some(bytes) = data.get(segment.range.clone()) else {
+                    continue;
+                };
+                for (offset, &byte) in bytes.iter().take(MAX_FIELD_COLUMN_BYTES).enumerate() {
+                    columns.entry((rank, offset)).or_default().push(byte);
+                }
+            }
+        }
+
+        let mut penalties_bits = 0.0;
+        for values in columns.values().filter(|v| v.len() >= MIN_FIELD_SAMPLES) {
+            let normalized = entropy(values) / (values.len().min(256) as f64).log2();
+            let smooth_steps = values
+                .windows(2)
+                .filter(|w| (w[1] as i16 - w[0] as i16).abs() <= SMOOTH_MAX_STEP)
+                .count();
+            let smooth = smooth_steps as f64 >= (values.len() - 1) as f64 * SMOOTH_MIN_RATIO;
+            if normalized <= LOW_CARDINALITY_ENTROPY || smooth {
+                penalties_bits -= values.len() as f64 * STRUCTURED_FIELD_BITS;
+            } else if normalized >= RANDOM_FIELD_ENTROPY {
+                penalties_bits += values.len() as f64 * RANDOM_FIELD_BITS;
+            }
+        }
+
+        Score::new(ScoreBreakdown {
+            mdl_model_bits: 0.0,
+            mdl_data_bits: 0.0,
+            parse_success_ratio: 1.0,
+            alignment_gain_bits: 0.0,
+            entropy_drop_bits: 0.0,
             penalties_bits,
         })
     }
 }
 
+/// Offset relatif au-delà duquel les octets partagent une même colonne d'alignement
+const MAX_ALIGNMENT_OFFSET: usize = 64;
+
+/// Débuts de structure de chaque PDU parsée, avec leur classe : début de PDU ou de message
+/// (après une frontière), de TLV (tag), de SDU
+fn structure_anchors(parsed: &ParsedCorpus) -> Vec<Vec<(usize, usize)>> {
+    parsed
+        .parsed_pdus
+        .iter()
+        .map(|parsed_pdu| {
+            let mut anchors = vec![(0, 0)];
+            for segment in &parsed_pdu.segments {
+                match &segment.kind {
+                    crate::segment::SegmentKind::MessageBoundary => anchors.push((segment.range.end, 0)),
+                    crate::segment::SegmentKind::Field(name) if name == "tag" => anchors.push((segment.range.start, 1)),
+                    crate::segment::SegmentKind::Sdu => anchors.push((segment.range.start, 2)),
+                    _ => {}
+                }
+            }
+            anchors
+        })
+        .collect()
+}
+
 /// Estime les bits nécessaires pour encoder le modèle
 fn estimate_model_bits(h: &Hypothesis) -> f64 {
     match h {
         Hypothesis::LengthPrefixBundle { .. } => 32.0,
-        Hypothesis::DelimiterBundle { pattern } => 16.0 + pattern.len() as f64 * 8.0,
+        Hypothesis::RemainingLength { .. } => 24.0,
+        Hypothesis::SyncLengthBundle { sync, .. } => 24.0 + sync.len() as f64 * 8.0,
+        Hypothesis::VarintLengthPrefix { .. } => 24.0,
+        Hypothesis::LengthSuffixBundle { .. } => 32.0,
+        Hypothesis::ChannelMux { .. } => 40.0,
+        Hypothesis::Fragmented { .. } => 40.0,
+        Hypothesis::ByteStuffed { scheme } => 16.0 + scheme.table.len() as f64 * 16.0,
+        Hypothesis::LabelSequence { .. } => 16.0,
+        Hypothesis::RecordArray { count_field, .. } => 16.0 + if count_field.is_some() { 16.0 } else { 0.0 },
+        Hypothesis::Hdlc { fcs } => 8.0 + if fcs.is_some() { 8.0 } else { 0.0 },
+        Hypothesis::DelimiterBundle { pattern, escape, quote } => {
+            16.0 + pattern.len() as f64 * 8.0 + escape.map_or(0.0, |_| 8.0) + quote.map_or(0.0, |_| 8.0)
+        }
         Hypothesis::FixedHeader { len } => 16.0 + (*len as f64).log2() * 2.0,
+        Hypothesis::FixedTrailer { len } => 16.0 + (*len as f64).log2() * 2.0,
         Hypothesis::ExtensibleBitmap { .. } => 40.0,
         Hypothesis::Tlv { .. } => 24.0,
         Hypothesis::VarintKeyWireType { .. } => 24.0,
+        // La valeur constante fait partie de la description du modèle
+        Hypothesis::ConstantField { value, .. } => 16.0 + value.len() as f64 * 8.0,
+        Hypothesis::TypeSwitch { .. } => 24.0,
+        Hypothesis::TextLines { .. } => 16.0,
+        Hypothesis::KeyValueHeader { .. } => 24.0,
+        Hypothesis::ContentLengthMessages { length_header, .. } => 24.0 + length_header.len() as f64 * 8.0,
+        Hypothesis::StructuredText { .. } => 16.0,
+        Hypothesis::Encoded { .. } => 8.0,
+        Hypothesis::Compressed { .. } => 8.0,
+        Hypothesis::Obfuscated { obfuscation } => match obfuscation {
+            Obfuscation::Xor { key } => 8.0 + key.len() as f64 * 8.0,
+            Obfuscation::Add { .. } => 16.0,
+        },
+        Hypothesis::Opaque => 0.0,
+        Hypothesis::TrailingPadding { .. } => 16.0,
+        Hypothesis::BitFields { boundaries, .. } => 16.0 + boundaries.len() as f64 * 4.0,
+        Hypothesis::Checksum { .. } => 24.0,
+        // Les parties, et leur enchaînement
+        Hypothesis::Composite(parts) => 8.0 + parts.iter().map(estimate_model_bits).sum::<f64>(),
     }
 }
 
@@ -0,0 +1,395 @@
+use crate::corpus::Corpus;
+use crate::hypothesis::{Hypothesis, PayloadEncoding, StructuredFormat};
+use crate::parser::{ParsedCorpus, ParsedPdu, Parser};
+use crate::segment::{Segment, SegmentKind};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Proportion minimale de PDUs valides pour considérer le corpus comme du texte structuré
+const STRUCTURED_MIN_RATIO: f64 = 0.95;
+
+/// Taille moyenne minimale (en caractères) d'une charge utile encodée
+const ENCODED_MIN_AVG_LEN: usize = 8;
+
+/// Clé du schéma inféré : chemin, nombre d'occurrences et types de valeurs observés
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaKey {
+    /// Chemin de la clé (`a.b`, `items[].id` en JSON ; `root/child`, `root/child@attr` en XML)
+    pub path: String,
+    pub count: usize,
+    pub types: Vec<String>,
+}
+
+/// Couche terminale de texte structuré et son schéma de clés
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructuredText {
+    pub format: StructuredFormat,
+    pub documents: usize,
+    pub keys: Vec<SchemaKey>,
+}
+
+#[derive(Default)]
+struct SchemaBuilder {
+    keys: BTreeMap<String, (usize, BTreeSet<&'static str>)>,
+}
+
+impl SchemaBuilder {
+    fn record(&mut self, path: String, kind: &'static str, weight: usize) {
+        let entry = self.keys.entry(path).or_default();
+        entry.0 += weight;
+        entry.1.insert(kind);
+    }
+
+    fn build(self) -> Vec<SchemaKey> {
+        self.keys
+            .into_iter()
+            .map(|(path, (count, types))| SchemaKey {
+                path,
+                count,
+                types: types.into_iter().map(str::to_string).collect(),
+            })
+            .collect()
+    }
+}
+
+/// Détecte un corpus de documents JSON ou XML et résume son schéma de clés
+pub fn detect_structured_text(corpus: &Corpus) -> Option<StructuredText> {
+    if corpus.is_empty() {
+        return None;
+    }
+
+    [StructuredFormat::Json, StructuredFormat::Xml]
+        .into_iter()
+        .find_map(|format| summarize(corpus, format))
+}
+
+fn summarize(corpus: &Corpus, format: StructuredFormat) -> Option<StructuredText> {
+    let mut schema = SchemaBuilder::default();
+    let mut valid = 0;
+    let mut documents = 0;
+
+    for (index, pdu) in corpus.items.iter().enumerate() {
+        let weight = corpus.multiplicity(index);
+        let ok = match format {
+            StructuredFormat::Json => json_schema(pdu.as_slice(), &mut schema, weight),
+            StructuredFormat::Xml => xml_schema(pdu.as_slice(), &mut schema, weight),
+        };
+        if ok {
+            valid += 1;
+            documents += weight;
+        }
+    }
+
+    if (valid as f64) < corpus.len() as f64 * STRUCTURED_MIN_RATIO {
+        return None;
+    }
+
+    Some(StructuredText {
+        format,
+        documents,
+        keys: schema.build(),
+    })
+}
+
+/// Valide un document JSON (objet ou tableau à la racine) et enregistre ses clés
+fn json_schema(data: &[u8], schema: &mut SchemaBuilder, weight: usize) -> bool {
+    let Ok(value) = serde_json::from_slice::<serde_json::Value>(data) else {
+        return false;
+    };
+    // Un scalaire isolé (`12`, `"a"`) n'est pas un indice suffisant
+    if !value.is_object() && !value.is_array() {
+        return false;
+    }
+    walk_json(&value, "", schema, weight);
+    true
+}
+
+fn walk_json(value: &serde_json::Value, path: &str, schema: &mut SchemaBuilder, weight: usize) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                schema.record(child_path.clone(), json_type(child), weight);
+                walk_json(child, &child_path, schema, weight);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            let item_path = format!("{}[]", path);
+            for item in items {
+                walk_json(item, &item_path, schema, weight);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn json_type(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "bool",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+/// Valide un document XML (balises équilibrées, une seule racine) et enregistre éléments et attributs
+fn xml_schema(data: &[u8], schema: &mut SchemaBuilder, weight: usize) -> bool {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return false;
+    };
+    let text = text.trim();
+    if !text.starts_with('<') || !text.ends_with('>') {
+        return false;
+    }
+
+    let mut local = SchemaBuilder::default();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut roots = 0;
+    let mut rest = text;
+
+    while let Some(open) = rest.find('<') {
+        // Texte hors de la racine : seuls les blancs sont admis
+        if stack.is_empty() && !rest[..open].trim().is_empty() {
+            return false;
+        }
+        rest = &rest[open..];
+
+        let skip = if rest.starts_with("<?") {
+            rest.find("?>").map(|i| i + 2)
+        } else if rest.starts_with("<!--") {
+            rest.find("-->").map(|i| i + 3)
+        } else if rest.starts_with("<![CDATA[") {
+            rest.find("]]>").map(|i| i + 3)
+        } else if rest.starts_with("<!") {
+            rest.find('>').map(|i| i + 1)
+        } else {
+            None
+        };
+        if let Some(skip) = skip {
+            rest = &rest[skip..];
+            continue;
+        }
+
+        let Some(close) = rest.find('>') else {
+            return false;
+        };
+        let tag = &rest[1..close];
+        rest = &rest[close + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            if stack.pop() != Some(name.trim()) {
+                return false;
+            }
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let mut parts = tag.split_whitespace();
+        let Some(name) = parts.next() else {
+            return false;
+        };
+        if stack.is_empty() {
+            roots += 1;
+        }
+
+        let path = stack.iter().chain(std::iter::once(&name)).copied().collect::<Vec<_>>().join("/");
+        local.record(path.clone(), "element", weight);
+        for attribute in parts.filter_map(|p| p.split_once('=')) {
+            local.record(format!("{}@{}", path, attribute.0), "attribute", weight);
+        }
+
+        if !self_closing {
+            stack.push(name);
+        }
+    }
+
+    if !stack.is_empty() || roots != 1 || !rest.trim().is_empty() {
+        return false;
+    }
+    for (path, (count, types)) in local.keys {
+        for kind in types {
+            schema.record(path.clone(), kind, count);
+        }
+    }
+    true
+}
+
+/// Parseur de la couche terminale : chaque PDU est un document, sans SDU
+pub struct StructuredTextParser;
+
+impl Parser for StructuredTextParser {
+    fn name(&self) -> &'static str {
+        "StructuredTextParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::StructuredText { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::StructuredText { format } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+        let name = match format {
+            StructuredFormat::Json => "json",
+            StructuredFormat::Xml => "xml",
+        };
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                let mut scratch = SchemaBuilder::default();
+                let valid = match format {
+                    StructuredFormat::Json => json_schema(data, &mut scratch, 1),
+                    StructuredFormat::Xml => xml_schema(data, &mut scratch, 1),
+                };
+                let parsed = ParsedPdu::new(vec![Segment::new(
+                    SegmentKind::Field(name.to_string()),
+                    0..data.len(),
+                )]);
+                if valid {
+                    parsed
+                } else {
+                    parsed.with_exception(format!("Invalid {} document", name))
+                }
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
+
+impl PayloadEncoding {
+    /// Décode une charge utile ; les fins de ligne (découpage MIME) sont ignorées
+    pub fn decode(&self, data: &[u8]) -> Option<Vec<u8>> {
+        let text: Vec<u8> = data.iter().copied().filter(|b| !matches!(b, b'\r' | b'\n')).collect();
+        if text.is_empty() {
+            return None;
+        }
+        match self {
+            PayloadEncoding::Hex => decode_hex(&text),
+            PayloadEncoding::Base64 => decode_base64(&text, b'+', b'/', true),
+            PayloadEncoding::Base64Url => decode_base64(&text, b'-', b'_', false),
+        }
+    }
+}
+
+fn decode_hex(text: &[u8]) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    text.chunks(2)
+        .map(|pair| {
+            let high = (pair[0] as char).to_digit(16)?;
+            let low = (pair[1] as char).to_digit(16)?;
+            Some((high * 16 + low) as u8)
+        })
+        .collect()
+}
+
+fn decode_base64(text: &[u8], c62: u8, c63: u8, padded: bool) -> Option<Vec<u8>> {
+    let body = match text.iter().position(|&b| b == b'=') {
+        Some(pad) => {
+            // Le padding ne peut figurer qu'en fin (deux caractères au plus)
+            if text.len() - pad > 2 || text[pad..].iter().any(|&b| b != b'=') || !text.len().is_multiple_of(4) {
+                return None;
+            }
+            &text[..pad]
+        }
+        None if padded && !text.len().is_multiple_of(4) => return None,
+        None => text,
+    };
+    if body.len() % 4 == 1 {
+        return None;
+    }
+
+    let sextet = |b: u8| -> Option<u32> {
+        match b {
+            b'A'..=b'Z' => Some((b - b'A') as u32),
+            b'a'..=b'z' => Some((b - b'a') as u32 + 26),
+            b'0'..=b'9' => Some((b - b'0') as u32 + 52),
+            _ if b == c62 => Some(62),
+            _ if b == c63 => Some(63),
+            _ => None,
+        }
+    };
+
+    let mut out = Vec::with_capacity(body.len() * 3 / 4);
+    for chunk in body.chunks(4) {
+        let mut acc = 0u32;
+        for (i, &b) in chunk.iter().enumerate() {
+            acc |= sextet(b)? << (18 - 6 * i);
+        }
+        let bytes = acc.to_be_bytes();
+        out.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Some(out)
+}
+
+/// Détecte un corpus de charges utiles encodées en hex ou en base64
+pub fn detect_encoding(corpus: &Corpus) -> Option<PayloadEncoding> {
+    if corpus.is_empty() || corpus.total_bytes() < corpus.len() * ENCODED_MIN_AVG_LEN {
+        return None;
+    }
+
+    // L'hexadécimal est testé d'abord : son alphabet est inclus dans celui du base64
+    [PayloadEncoding::Hex, PayloadEncoding::Base64, PayloadEncoding::Base64Url]
+        .into_iter()
+        .find(|encoding| {
+            let decoded = corpus
+                .items
+                .iter()
+                .filter(|pdu| encoding.decode(pdu.as_slice()).is_some())
+                .count();
+            if (decoded as f64) < corpus.len() as f64 * STRUCTURED_MIN_RATIO {
+                return false;
+            }
+            // Un mot alphabétique est du base64 valide : exiger des chiffres quelque part
+            *encoding == PayloadEncoding::Hex
+                || corpus.items.iter().any(|pdu| pdu.as_slice().iter().any(u8::is_ascii_digit))
+        })
+}
+
+/// Parseur des charges utiles encodées : la PDU entière est la SDU (à décoder)
+pub struct EncodedParser;
+
+impl Parser for EncodedParser {
+    fn name(&self) -> &'static str {
+        "EncodedParser"
+    }
+
+    fn applicable(&self, h: &Hypothesis) -> bool {
+        matches!(h, Hypothesis::Encoded { .. })
+    }
+
+    fn parse_corpus(&self, corpus: &Corpus, h: &Hypothesis) -> ParsedCorpus {
+        let Hypothesis::Encoded { encoding } = h else {
+            return ParsedCorpus::new(vec![]);
+        };
+
+        let parsed_pdus = corpus
+            .items
+            .iter()
+            .map(|pdu| {
+                let data = pdu.as_slice();
+                match encoding.decode(data) {
+                    Some(decoded) => ParsedPdu::new(vec![Segment::new(SegmentKind::Sdu, 0..data.len())
+                        .with_note(format!("{:?}, {} decoded bytes", encoding, decoded.len()))]),
+                    None => ParsedPdu::new(vec![Segment::new(SegmentKind::Pci, 0..data.len())])
+                        .with_exception(format!("Invalid {:?} payload", encoding)),
+                }
+            })
+            .collect();
+
+        ParsedCorpus::new(parsed_pdus)
+    }
+}
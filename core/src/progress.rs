@@ -0,0 +1,45 @@
+use crate::hypothesis::Hypothesis;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Avancement de l'évaluation des hypothèses d'une couche
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    /// Profondeur de la couche dans la pile en cours d'inférence (une branche repart de 0)
+    pub depth: usize,
+    /// Hypothèses déjà parsées et scorées à cette profondeur
+    pub evaluated: usize,
+    /// Hypothèses proposées à cette profondeur
+    pub total: usize,
+    /// Meilleure hypothèse évaluée jusqu'ici et son score en bits
+    pub best: Option<(Hypothesis, f64)>,
+}
+
+/// Destinataire des rapports d'avancement de l'inférence.
+///
+/// `report` est appelé depuis les threads rayon, après chaque hypothèse évaluée : il doit
+/// rester rapide (mémoriser le dernier rapport, envoyer sur un canal...).
+pub trait ProgressSink: Send + Sync {
+    fn report(&self, progress: &Progress);
+}
+
+/// Jeton d'annulation partagé entre l'appelant et le moteur d'inférence.
+///
+/// Une fois annulée, l'inférence cesse d'évaluer des hypothèses et rend les couches déjà
+/// établies.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,192 @@
+use crate::checkpoint::corpus_digest;
+use crate::corpus::{Corpus, CorpusMeta};
+use crate::hypothesis::Hypothesis;
+use crate::inference::{InferenceEngine, InferenceResult};
+use crate::measures::CompressionBackend;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Version du format des fichiers de projet ; un fichier d'une autre version est refusé
+pub const PROJECT_FORMAT_VERSION: u32 = 1;
+
+/// Extension conventionnelle des fichiers de projet
+pub const PROJECT_EXTENSION: &str = "aire";
+
+/// Réglages sérialisables du moteur : tout [`InferenceEngine`] hormis la progression,
+/// l'annulation et l'état d'un appel en cours
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineSettings {
+    pub max_depth: usize,
+    pub top_k: usize,
+    pub min_gain_epsilon: f64,
+    pub min_sdu_size: usize,
+    pub split_by_version: bool,
+    pub cross_validation_folds: usize,
+    pub ambiguity_epsilon: f64,
+    pub compression: CompressionBackend,
+    pub backtrack_budget: usize,
+    pub time_budget: Option<Duration>,
+    pub max_hypotheses: Option<usize>,
+    pub field_recursion: Option<usize>,
+    pub prefilter_sample: usize,
+    pub refinement_rounds: usize,
+    pub memory_budget: Option<usize>,
+    pub screening_margin: Option<f64>,
+    #[serde(default)]
+    pub pinned_layers: Vec<Hypothesis>,
+}
+
+impl From<&InferenceEngine> for EngineSettings {
+    fn from(engine: &InferenceEngine) -> Self {
+        Self {
+            max_depth: engine.max_depth,
+            top_k: engine.top_k,
+            min_gain_epsilon: engine.min_gain_epsilon,
+            min_sdu_size: engine.min_sdu_size,
+            split_by_version: engine.split_by_version,
+            cross_validation_folds: engine.cross_validation_folds,
+            ambiguity_epsilon: engine.ambiguity_epsilon,
+            compression: engine.compression,
+            backtrack_budget: engine.backtrack_budget,
+            time_budget: engine.time_budget,
+            max_hypotheses: engine.max_hypotheses,
+            field_recursion: engine.field_recursion,
+            prefilter_sample: engine.prefilter_sample,
+            refinement_rounds: engine.refinement_rounds,
+            memory_budget: engine.memory_budget,
+            screening_margin: engine.screening_margin,
+            pinned_layers: engine.pinned_layers.clone(),
+        }
+    }
+}
+
+impl EngineSettings {
+    /// Moteur configuré avec ces réglages, sans progression ni annulation
+    pub fn engine(&self) -> InferenceEngine {
+        InferenceEngine {
+            max_depth: self.max_depth,
+            top_k: self.top_k,
+            min_gain_epsilon: self.min_gain_epsilon,
+            min_sdu_size: self.min_sdu_size,
+            split_by_version: self.split_by_version,
+            cross_validation_folds: self.cross_validation_folds,
+            ambiguity_epsilon: self.ambiguity_epsilon,
+            compression: self.compression,
+            backtrack_budget: self.backtrack_budget,
+            time_budget: self.time_budget,
+            max_hypotheses: self.max_hypotheses,
+            field_recursion: self.field_recursion,
+            prefilter_sample: self.prefilter_sample,
+            refinement_rounds: self.refinement_rounds,
+            memory_budget: self.memory_budget,
+            screening_margin: self.screening_margin,
+            pinned_layers: self.pinned_layers.clone(),
+            ..InferenceEngine::new()
+        }
+    }
+}
+
+/// Nom et note donnés par l'utilisateur aux octets `offset..offset + width` des messages de
+/// la couche `depth`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldAnnotation {
+    pub depth: usize,
+    pub offset: usize,
+    pub width: usize,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+}
+
+/// Projet d'analyse (fichier `.aire`) : métadonnées du corpus, réglages du moteur, résultat de
+/// l'inférence (corpus compris) et annotations de l'utilisateur.
+///
+/// Un projet sans résultat ne contient que les métadonnées du corpus : la capture doit être
+/// relue, et [`Project::matches`] vérifie que c'est bien la même.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub version: u32,
+    pub corpus: CorpusMeta,
+    /// Empreinte du corpus analysé (voir [`corpus_digest`])
+    pub corpus_digest: u64,
+    pub settings: EngineSettings,
+    pub result: Option<InferenceResult>,
+    #[serde(default)]
+    pub annotations: Vec<FieldAnnotation>,
+    /// Notes libres sur l'analyse
+    #[serde(default)]
+    pub notes: String,
+}
+
+impl Project {
+    /// Projet du corpus `corpus`, analysé avec les réglages de `engine`, encore sans résultat
+    pub fn new(corpus: &Corpus, engine: &InferenceEngine) -> Self {
+        Self {
+            version: PROJECT_FORMAT_VERSION,
+            corpus: corpus.meta.clone(),
+            corpus_digest: corpus_digest(corpus),
+            settings: EngineSettings::from(engine),
+            result: None,
+            annotations: Vec::new(),
+            notes: String::new(),
+        }
+    }
+
+    pub fn with_result(mut self, result: InferenceResult) -> Self {
+        self.result = Some(result);
+        self
+    }
+
+    /// Vrai si `corpus` a le contenu du corpus analysé dans ce projet
+    pub fn matches(&self, corpus: &Corpus) -> bool {
+        corpus_digest(corpus) == self.corpus_digest
+    }
+
+    /// Nomme le champ `offset..offset + width` de la couche `depth`, en remplaçant une
+    /// annotation existante au même endroit
+    pub fn annotate(&mut self, depth: usize, offset: usize, width: usize, name: &str, note: &str) {
+        self.annotations.retain(|a| (a.depth, a.offset) != (depth, offset));
+        self.annotations.push(FieldAnnotation {
+            depth,
+            offset,
+            width,
+            name: name.to_string(),
+            note: note.to_string(),
+        });
+        self.annotations.sort_by_key(|a| (a.depth, a.offset));
+    }
+
+    /// Annotation du champ qui commence à `offset` dans la couche `depth`
+    pub fn annotation(&self, depth: usize, offset: usize) -> Option<&FieldAnnotation> {
+        self.annotations.iter().find(|a| (a.depth, a.offset) == (depth, offset))
+    }
+
+    pub fn to_json(&self) -> String {
+        // Les scores infinis s'écrivent null : la sérialisation ne peut pas échouer
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn from_json(text: &str) -> crate::Result<Self> {
+        let project: Self =
+            serde_json::from_str(text).map_err(|e| crate::Error::ParseError(format!("project: {}", e)))?;
+        if project.version != PROJECT_FORMAT_VERSION {
+            return Err(crate::Error::ParseError(format!(
+                "project: format version {} (expected {})",
+                project.version, PROJECT_FORMAT_VERSION
+            )));
+        }
+        Ok(project)
+    }
+
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> crate::Result<()> {
+        // Écrit à côté puis renomme : une interruption pendant l'écriture garde l'ancien projet
+        let tmp = path.as_ref().with_extension("tmp");
+        std::fs::write(&tmp, self.to_json())?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> crate::Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
@@ -0,0 +1,248 @@
+use crate::corpus::Corpus;
+use crate::hypothesis::VarintEncoding;
+use crate::parser::ParsedCorpus;
+use crate::segment::SegmentKind;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Plus grand numéro de champ protobuf autorisé (2^29 - 1)
+pub const MAX_FIELD_NUMBER: u64 = (1 << 29) - 1;
+
+/// Champ protobuf observé sur le corpus
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtoField {
+    pub number: u64,
+    pub wire_type: u8,
+    /// Nombre total d'occurrences
+    pub occurrences: usize,
+    /// Nombre de PDUs où le champ apparaît
+    pub pdus: usize,
+    /// Le champ apparaît plusieurs fois dans au moins une PDU
+    pub repeated: bool,
+}
+
+impl ProtoField {
+    /// Type `.proto` le plus simple compatible avec le wire type
+    pub fn proto_type(&self) -> &'static str {
+        match self.wire_type {
+            0 => "uint64",
+            1 => "fixed64",
+            2 => "bytes",
+            5 => "fixed32",
+            _ => "unknown",
+        }
+    }
+}
+
+/// Esquisse de schéma protobuf : champs par numéro
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtoSchema {
+    pub fields: Vec<ProtoField>,
+    /// Nombre de PDUs analysées
+    pub pdus: usize,
+}
+
+/// Wire type majoritaire de chaque numéro de champ
+pub fn dominant_wire_types<'a>(keys: impl IntoIterator<Item = &'a (u64, u8)>) -> HashMap<u64, u8> {
+    let mut counts: HashMap<u64, HashMap<u8, usize>> = HashMap::new();
+    for &(number, wire_type) in keys {
+        *counts.entry(number).or_default().entry(wire_type).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .filter_map(|(number, wires)| {
+            let (wire_type, _) = wires
+                .into_iter()
+                .max_by_key(|&(wire_type, count)| (count, std::cmp::Reverse(wire_type)))?;
+            Some((number, wire_type))
+        })
+        .collect()
+}
+
+/// Clés (numéro, wire type) lues dans les segments `key` d'une PDU parsée
+fn pdu_keys(data: &[u8], segments: &[crate::segment::Segment]) -> Vec<(u64, u8)> {
+    segments
+        .iter()
+        .filter(|s| s.kind == SegmentKind::Field("key".to_string()))
+        .filter_map(|s| VarintEncoding::Leb128.read(data.get(s.range.clone())?))
+        .map(|(key, _)| (key >> 3, (key & 0x7) as u8))
+        .collect()
+}
+
+/// Esquisse le schéma protobuf d'une couche parsée en clés varint
+pub fn proto_schema(corpus: &Corpus, parsed: &ParsedCorpus) -> Option<ProtoSchema> {
+    let mut fields: BTreeMap<(u64, u8), ProtoField> = BTreeMap::new();
+    for (pdu, parsed_pdu) in corpus.items.iter().zip(parsed.parsed_pdus.iter()) {
+        let mut in_pdu: HashMap<(u64, u8), usize> = HashMap::new();
+        for key in pdu_keys(pdu.as_slice(), &parsed_pdu.segments) {
+            *in_pdu.entry(key).or_default() += 1;
+        }
+        for ((number, wire_type), count) in in_pdu {
+            let field = fields.entry((number, wire_type)).or_insert(ProtoField {
+                number,
+                wire_type,
+                occurrences: 0,
+                pdus: 0,
+                repeated: false,
+            });
+            field.occurrences += count;
+            field.pdus += 1;
+            field.repeated |= count > 1;
+        }
+    }
+
+    if fields.is_empty() {
+        return None;
+    }
+    Some(ProtoSchema {
+        fields: fields.into_values().collect(),
+        pdus: corpus.len(),
+    })
+}
+
+/// Profondeur maximale des messages imbriqués recherchés dans les champs de longueur
+const MAX_NESTING: usize = 8;
+/// Proportion minimale d'octets texte des valeurs d'un champ `string`
+const STRING_MIN_PRINTABLE: f64 = 0.95;
+
+/// Champ d'un message `.proto` inféré
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtoMessageField {
+    pub field: ProtoField,
+    /// Type `.proto` : scalaire, `string`, `bytes` ou nom du message imbriqué
+    pub type_name: String,
+    pub nested: Option<ProtoMessage>,
+}
+
+/// Message `.proto` inféré, avec ses messages imbriqués
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtoMessage {
+    pub name: String,
+    /// Champs par numéro croissant, chacun avec son wire type majoritaire
+    pub fields: Vec<ProtoMessageField>,
+}
+
+impl ProtoMessage {
+    /// Message inféré d'une couche parsée en clés varint d'au plus `key_max_bytes` octets.
+    ///
+    /// Un champ de longueur dont toutes les valeurs sont du texte UTF-8 devient une `string` ;
+    /// si toutes ses valeurs se parsent elles-mêmes sans exception en clés varint, un message
+    /// imbriqué ; sinon des `bytes`. Un numéro vu sous plusieurs wire types garde le plus
+    /// fréquent.
+    pub fn infer(corpus: &Corpus, parsed: &ParsedCorpus, name: &str, key_max_bytes: usize) -> Option<Self> {
+        Self::infer_nested(corpus, parsed, name, key_max_bytes, 0)
+    }
+
+    fn infer_nested(
+        corpus: &Corpus,
+        parsed: &ParsedCorpus,
+        name: &str,
+        key_max_bytes: usize,
+        depth: usize,
+    ) -> Option<Self> {
+        let schema = proto_schema(corpus, parsed)?;
+        let mut values = length_delimited_values(corpus, parsed);
+
+        let mut dominant: BTreeMap<u64, ProtoField> = BTreeMap::new();
+        for field in schema.fields {
+            if dominant.get(&field.number).is_none_or(|d| field.occurrences > d.occurrences) {
+                dominant.insert(field.number, field);
+            }
+        }
+        let fields = dominant
+            .into_values()
+            .map(|field| {
+                let values = values.remove(&field.number).unwrap_or_default();
+                let text = field.wire_type == 2 && is_text(&values);
+                let nested = (field.wire_type == 2 && !text && depth < MAX_NESTING)
+                    .then(|| nested_message(values, &format!("Field{}", field.number), key_max_bytes, depth + 1))
+                    .flatten();
+                let type_name = match &nested {
+                    Some(nested) => nested.name.clone(),
+                    None if text => "string".to_string(),
+                    None => field.proto_type().to_string(),
+                };
+                ProtoMessageField { field, type_name, nested }
+            })
+            .collect();
+        Some(Self { name: name.to_string(), fields })
+    }
+
+    /// Fichier `.proto` (proto3) du message : champs `field_<numéro>`, messages imbriqués
+    /// déclarés dans leur parent
+    pub fn to_proto(&self) -> String {
+        let mut out = String::from("syntax = \"proto3\";\n\n");
+        self.write(&mut out, 0);
+        out
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        let pad = "  ".repeat(indent);
+        out.push_str(&format!("{}message {} {{\n", pad, self.name));
+        for nested in self.fields.iter().filter_map(|f| f.nested.as_ref()) {
+            nested.write(out, indent + 1);
+        }
+        for f in &self.fields {
+            let number = f.field.number;
+            if f.field.proto_type() == "unknown" {
+                // Groupes (wire types 3 et 4) : sans équivalent en proto3
+                out.push_str(&format!("{}  // field_{} = {}: wire type {}\n", pad, number, number, f.field.wire_type));
+                continue;
+            }
+            let label = if f.field.repeated { "repeated " } else { "" };
+            out.push_str(&format!("{}  {}{} field_{} = {};\n", pad, label, f.type_name, number, number));
+        }
+        out.push_str(&format!("{}}}\n", pad));
+    }
+}
+
+/// Valeurs des champs de longueur (wire type 2) d'une couche parsée, par numéro de champ
+fn length_delimited_values(corpus: &Corpus, parsed: &ParsedCorpus) -> HashMap<u64, Vec<Vec<u8>>> {
+    let mut values: HashMap<u64, Vec<Vec<u8>>> = HashMap::new();
+    for (pdu, parsed_pdu) in corpus.items.iter().zip(parsed.parsed_pdus.iter()) {
+        let data = pdu.as_slice();
+        let mut number = None;
+        for segment in &parsed_pdu.segments {
+            match &segment.kind {
+                SegmentKind::Field(name) if name == "key" => {
+                    number = data
+                        .get(segment.range.clone())
+                        .and_then(|key| VarintEncoding::Leb128.read(key))
+                        .filter(|(key, _)| key & 0x7 == 2)
+                        .map(|(key, _)| key >> 3);
+                }
+                SegmentKind::Sdu => {
+                    if let (Some(number), Some(value)) = (number.take(), data.get(segment.range.clone())) {
+                        values.entry(number).or_default().push(value.to_vec());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    values
+}
+
+/// Vrai si les valeurs (au moins un octet en tout) sont du texte UTF-8 imprimable
+fn is_text(values: &[Vec<u8>]) -> bool {
+    let bytes: Vec<u8> = values.concat();
+    values.iter().all(|v| std::str::from_utf8(v).is_ok())
+        && crate::measures::printable_ratio(&bytes) >= STRING_MIN_PRINTABLE
+}
+
+/// Message imbriqué `name` si toutes les valeurs (dont au moins une non vide) se parsent
+/// sans exception en clés varint
+fn nested_message(values: Vec<Vec<u8>>, name: &str, key_max_bytes: usize, depth: usize) -> Option<ProtoMessage> {
+    use crate::parser::Parser;
+
+    if values.iter().all(Vec::is_empty) {
+        return None;
+    }
+    let corpus = Corpus::from_messages(values, name);
+    let hypothesis = crate::hypothesis::Hypothesis::VarintKeyWireType { key_max_bytes, allow_embedded: false };
+    let parsed = crate::plugins::parsers::VarintParser.parse_corpus(&corpus, &hypothesis);
+    if !parsed.parsed_pdus.iter().all(|p| p.is_success() && p.exceptions.is_empty()) {
+        return None;
+    }
+    ProtoMessage::infer_nested(&corpus, &parsed, name, key_max_bytes, depth)
+}
@@ -0,0 +1,337 @@
+use crate::clustering::{cluster_pdus, ClusterConfig};
+use crate::corpus::Corpus;
+use crate::description::{framing_rule, layer_fields, snake_case};
+use crate::inference::{InferenceResult, Layer};
+use crate::measures::entropy_by_offset;
+use crate::parser::ParsedPdu;
+use crate::segment::SegmentKind;
+use std::fmt::Write;
+
+/// Nombre d'offsets au plus dans le graphique d'entropie d'une couche
+const REPORT_ENTROPY_OFFSETS: usize = 64;
+/// Nombre de messages représentatifs (un par groupe de PDUs similaires) affichés par couche
+const REPORT_SAMPLES: usize = 3;
+/// Nombre d'octets au plus dans le vidage hexadécimal d'un message
+const REPORT_HEXDUMP_BYTES: usize = 256;
+/// Nombre d'hypothèses concurrentes listées par couche dans le rapport Markdown
+const REPORT_ALTERNATIVES: usize = 5;
+/// Nombre de valeurs observées listées par champ dans le rapport Markdown
+const REPORT_FIELD_VALUES: usize = 8;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin:1em 0}th,td{border:1px solid #ccc;padding:2px 8px;text-align:right}\
+th{background:#eee}td.h{text-align:left;font-family:monospace}tr.chosen{background:#e6f4e6}\
+pre{line-height:1.5}.pci{background:#cfe2ff}.sdu{background:#e6f4e6}.field{background:#ffe8b3}\
+.error{background:#f8c8c8}.padding{background:#ddd}.legend span{padding:0 6px;margin-right:4px}";
+
+/// Rapport HTML autonome du résultat (styles en ligne, graphiques SVG, sans script ni
+/// ressource externe) : résumé des couches, puis pour chaque couche la table des scores des
+/// hypothèses évaluées, l'entropie par offset et le découpage annoté de messages représentatifs
+pub fn html(result: &InferenceResult) -> String {
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html lang=\"fr\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<title>Inférence de protocole</title>\n");
+    let _ = writeln!(out, "<style>{}</style>\n</head>\n<body>", STYLE);
+    out.push_str("<h1>Inférence de protocole</h1>\n");
+
+    let _ = write!(
+        out,
+        "<p>{} PDUs, {} octets",
+        result.corpus.len(),
+        result.corpus.total_bytes()
+    );
+    if let Some(total) = result.sampled_from {
+        let _ = write!(out, " (échantillon de {} PDUs)", total);
+    }
+    out.push_str(".</p>\n");
+    if result.budget_exhausted {
+        out.push_str("<p><strong>Budget épuisé :</strong> l'inférence a été interrompue.</p>\n");
+    }
+
+    out.push_str("<h2>Couches</h2>\n<table>\n<tr><th>Couche</th><th>Hypothèse</th><th>Score (bits)</th>");
+    out.push_str("<th>PSR</th><th>Confiance</th><th>PDUs</th></tr>\n");
+    for (depth, layer) in result.layers.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td class=\"h\">{}</td><td>{:.1}</td><td>{:.3}</td><td>{}</td><td>{}</td></tr>",
+            depth,
+            escape(&format!("{:?}", layer.hypothesis)),
+            layer.score.total_bits,
+            layer.score.breakdown.parse_success_ratio,
+            layer.confidence.as_ref().map(|c| format!("{:.3}", c.probability)).unwrap_or_default(),
+            layer.parsed.parsed_pdus.len()
+        );
+    }
+    out.push_str("</table>\n");
+
+    for (depth, layer) in result.layers.iter().enumerate() {
+        let _ = writeln!(out, "<h2>Couche {}</h2>", depth);
+        scores_table(&mut out, layer);
+        if let Some(corpus) = result.layer_corpus(depth) {
+            entropy_chart(&mut out, corpus);
+            samples(&mut out, corpus, layer);
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Table des hypothèses évaluées pour la couche, la retenue surlignée
+fn scores_table(out: &mut String, layer: &Layer) {
+    out.push_str("<h3>Hypothèses évaluées</h3>\n<table>\n<tr><th>Rang</th><th>Hypothèse</th><th>Total</th>");
+    out.push_str("<th>Modèle</th><th>Données</th><th>Alignement</th><th>Entropie</th><th>Pénalités</th>");
+    out.push_str("<th>PSR</th></tr>\n");
+    for (rank, candidate) in layer.all_hypotheses.iter().enumerate() {
+        let b = &candidate.score.breakdown;
+        let _ = writeln!(
+            out,
+            "<tr{}><td>{}</td><td class=\"h\">{}</td><td>{:.1}</td><td>{:.1}</td><td>{:.1}</td><td>{:.1}</td>\
+             <td>{:.1}</td><td>{:.1}</td><td>{:.3}</td></tr>",
+            if rank == layer.rank { " class=\"chosen\"" } else { "" },
+            rank,
+            escape(&format!("{:?}", candidate.hypothesis)),
+            candidate.score.total_bits,
+            b.mdl_model_bits,
+            b.mdl_data_bits,
+            b.alignment_gain_bits,
+            b.entropy_drop_bits,
+            b.penalties_bits,
+            b.parse_success_ratio
+        );
+    }
+    out.push_str("</table>\n");
+}
+
+/// Histogramme SVG de l'entropie (0 à 8 bits) des premiers offsets des PDUs de la couche
+fn entropy_chart(out: &mut String, corpus: &Corpus) {
+    let max_len = corpus.items.iter().map(|p| p.len()).max().unwrap_or(0);
+    let entropies = entropy_by_offset(corpus, max_len.min(REPORT_ENTROPY_OFFSETS));
+    if entropies.is_empty() {
+        return;
+    }
+    let (bar, height) = (10, 80.0);
+    out.push_str("<h3>Entropie par offset</h3>\n");
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+        entropies.len() * bar,
+        height + 14.0
+    );
+    for (offset, entropy) in entropies.iter().enumerate() {
+        // Un offset constant a une entropie de -0 : l'afficher 0
+        let entropy = entropy.abs();
+        let h = entropy / 8.0 * height;
+        let _ = writeln!(
+            out,
+            "<rect x=\"{}\" y=\"{:.1}\" width=\"{}\" height=\"{:.1}\" fill=\"#4a7fc1\">\
+             <title>offset {} : {:.2} bits</title></rect>",
+            offset * bar,
+            height - h,
+            bar - 1,
+            h,
+            offset,
+            entropy
+        );
+        if offset % 8 == 0 {
+            let _ = writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\" font-size=\"10\">{}</text>",
+                offset * bar,
+                height + 12.0,
+                offset
+            );
+        }
+    }
+    out.push_str("</svg>\n");
+}
+
+/// Vidage hexadécimal annoté du premier message de chaque groupe de PDUs similaires
+fn samples(out: &mut String, corpus: &Corpus, layer: &Layer) {
+    let clusters = cluster_pdus(corpus, &ClusterConfig::default());
+    let representatives: Vec<usize> = clusters.iter().take(REPORT_SAMPLES).map(|c| c[0]).collect();
+    if representatives.is_empty() {
+        return;
+    }
+    out.push_str("<h3>Messages représentatifs</h3>\n<p class=\"legend\"><span class=\"pci\">PCI</span>");
+    out.push_str("<span class=\"sdu\">SDU</span><span class=\"field\">champ</span>");
+    out.push_str("<span class=\"error\">erreur</span><span class=\"padding\">bourrage</span></p>\n");
+    for index in representatives {
+        let (Some(pdu), Some(parsed)) = (corpus.items.get(index), layer.parsed.parsed_pdus.get(index)) else {
+            continue;
+        };
+        let _ = writeln!(out, "<h4>PDU {} ({} octets)</h4>", index, pdu.len());
+        out.push_str("<pre>");
+        hexdump(out, pdu.as_slice(), parsed);
+        out.push_str("</pre>\n");
+    }
+}
+
+/// Vidage de 16 octets par ligne ; chaque octet prend la couleur du plus petit segment qui le
+/// couvre, et le nom du champ (ou le message d'erreur) en infobulle
+fn hexdump(out: &mut String, data: &[u8], parsed: &ParsedPdu) {
+    let shown = &data[..data.len().min(REPORT_HEXDUMP_BYTES)];
+    let annotation = |i: usize| -> Option<(&'static str, Option<&str>)> {
+        let segment = parsed
+            .segments
+            .iter()
+            .filter(|s| s.range.contains(&i) && s.kind != SegmentKind::MessageBoundary)
+            .min_by_key(|s| s.len())?;
+        Some(match &segment.kind {
+            SegmentKind::Pci => ("pci", None),
+            SegmentKind::Sdu => ("sdu", None),
+            SegmentKind::Field(name) => ("field", Some(name.as_str())),
+            SegmentKind::Error(message) => ("error", Some(message.as_str())),
+            SegmentKind::Padding | SegmentKind::MessageBoundary => ("padding", None),
+        })
+    };
+    for (line, chunk) in shown.chunks(16).enumerate() {
+        let _ = write!(out, "{:08x}  ", line * 16);
+        for (i, byte) in chunk.iter().enumerate() {
+            match annotation(line * 16 + i) {
+                Some((class, Some(title))) => {
+                    let _ = write!(out, "<span class=\"{}\" title=\"{}\">{:02x}</span> ", class, escape(title), byte);
+                }
+                Some((class, None)) => {
+                    let _ = write!(out, "<span class=\"{}\">{:02x}</span> ", class, byte);
+                }
+                None => {
+                    let _ = write!(out, "{:02x} ", byte);
+                }
+            }
+        }
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| if (0x20..0x7f).contains(&b) { b as char } else { '.' })
+            .collect();
+        let _ = writeln!(out, "{:width$} |{}|", "", escape(&ascii), width = (16 - chunk.len()) * 3);
+    }
+    if shown.len() < data.len() {
+        let _ = writeln!(out, "... {} octets de plus", data.len() - shown.len());
+    }
+}
+
+/// Synthèse Markdown du résultat : pour chaque couche l'hypothèse retenue et ses paramètres,
+/// ses mesures (score, PSR, confiance), les meilleures hypothèses concurrentes avec leur écart
+/// en bits, et les champs d'en-tête identifiés avec leurs valeurs les plus fréquentes
+pub fn markdown(result: &InferenceResult) -> String {
+    let mut out = String::from("# Inférence de protocole\n\n");
+    let _ = write!(out, "{} PDUs, {} octets", result.corpus.len(), result.corpus.total_bytes());
+    if let Some(total) = result.sampled_from {
+        let _ = write!(out, " (échantillon de {} PDUs)", total);
+    }
+    let _ = writeln!(out, ", {} couche(s).", result.layers.len());
+    if result.budget_exhausted {
+        out.push_str("\n**Budget épuisé** : l'inférence a été interrompue.\n");
+    }
+    if let Some(machine) = &result.state_machine {
+        let _ = writeln!(
+            out,
+            "\nAutomate : {} types de messages (champ à l'offset {}), {} transitions sur {} flow(s).",
+            machine.states.len(),
+            machine.type_offset,
+            machine.transitions.len(),
+            machine.flows
+        );
+    }
+    markdown_layers(&mut out, &result.layers, "");
+    out
+}
+
+/// Sections des couches `layers`, numérotées sous `prefix` (`1.`, `1.0x2.`...) pour les branches
+fn markdown_layers(out: &mut String, layers: &[Layer], prefix: &str) {
+    for (depth, layer) in layers.iter().enumerate() {
+        let rule = framing_rule(&layer.hypothesis);
+        let _ = writeln!(out, "\n## Couche {}{} : {}\n", prefix, depth, rule.rule);
+        let _ = writeln!(out, "- Hypothèse : `{:?}`", layer.hypothesis);
+        for (name, value) in &rule.parameters {
+            let _ = writeln!(out, "  - `{}` : `{}`", name, value);
+        }
+        let score = &layer.score;
+        let _ = writeln!(
+            out,
+            "- Score : {:.1} bits (modèle {:.1}, données {:.1}, pénalités {:.1}), PSR {:.3}",
+            score.total_bits,
+            score.breakdown.mdl_model_bits,
+            score.breakdown.mdl_data_bits,
+            score.breakdown.penalties_bits,
+            score.breakdown.parse_success_ratio
+        );
+        if let Some(confidence) = &layer.confidence {
+            let _ = write!(out, "- Confiance : {:.3}", confidence.probability);
+            if let Some(margin) = confidence.margin_bits {
+                let _ = write!(out, ", écart {:.1} bits avec la suivante", margin);
+            }
+            if confidence.ambiguous {
+                let rivals: Vec<String> = confidence.rivals.iter().map(|r| format!("`{:?}`", r)).collect();
+                let _ = write!(out, " ; **ambiguë** face à {}", rivals.join(", "));
+            }
+            out.push('\n');
+        }
+        if let Some(sdus) = &layer.sdu_corpus {
+            let _ = writeln!(out, "- SDUs : {} ({} octets)", sdus.len(), sdus.total_bytes());
+        }
+
+        let alternatives: Vec<_> = layer
+            .all_hypotheses
+            .iter()
+            .enumerate()
+            .filter(|(rank, _)| *rank != layer.rank)
+            .take(REPORT_ALTERNATIVES)
+            .collect();
+        if !alternatives.is_empty() {
+            out.push_str("\n| Rang | Hypothèse concurrente | Score (bits) | Écart (bits) |\n|---:|---|---:|---:|\n");
+            for (rank, candidate) in alternatives {
+                let _ = writeln!(
+                    out,
+                    "| {} | `{:?}` | {:.1} | {:+.1} |",
+                    rank,
+                    candidate.hypothesis,
+                    candidate.score.total_bits,
+                    candidate.score.total_bits - score.total_bits
+                );
+            }
+        }
+
+        let fields = layer_fields(layer);
+        if !fields.is_empty() {
+            out.push_str("\n| Offset | Taille | Rôle | Ordre des octets | Valeurs |\n|---:|---:|---|---|---|\n");
+            for field in fields {
+                let mut values: Vec<String> =
+                    field.values.iter().take(REPORT_FIELD_VALUES).map(|v| format!("{:#x}", v)).collect();
+                if field.values.len() > REPORT_FIELD_VALUES {
+                    values.push("…".to_string());
+                }
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {} | {} |",
+                    field.offset,
+                    field.width,
+                    snake_case(&field.role),
+                    field.byte_order.map(|b| snake_case(&b)).unwrap_or_default(),
+                    values.join(", ")
+                );
+            }
+        }
+
+        for branch in &layer.branches {
+            let branch_prefix = format!("{}{}.{:#x}.", prefix, depth, branch.type_value);
+            let _ = writeln!(
+                out,
+                "\n## Branche {:#x} de la couche {}{} ({} PDUs, {} couche(s))",
+                branch.type_value,
+                prefix,
+                depth,
+                branch.pdu_count,
+                branch.layers.len()
+            );
+            markdown_layers(out, &branch.layers, &branch_prefix);
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
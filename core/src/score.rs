@@ -1,11 +1,19 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Relit un nombre de bits : JSON n'ayant pas d'infini, celui d'une hypothèse rejetée est
+/// écrit `null`
+fn bits<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::INFINITY))
+}
 
 /// Breakdown détaillé du score MDL
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScoreBreakdown {
     /// Bits pour encoder le modèle
+    #[serde(deserialize_with = "bits")]
     pub mdl_model_bits: f64,
     /// Bits pour encoder les données selon le modèle
+    #[serde(deserialize_with = "bits")]
     pub mdl_data_bits: f64,
     /// Ratio de succès du parsing (0.0-1.0)
     pub parse_success_ratio: f64,
@@ -14,6 +22,7 @@ pub struct ScoreBreakdown {
     /// Réduction d'entropie (bits économisés)
     pub entropy_drop_bits: f64,
     /// Pénalités diverses
+    #[serde(deserialize_with = "bits")]
     pub penalties_bits: f64,
 }
 
@@ -31,6 +40,7 @@ impl ScoreBreakdown {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Score {
     pub breakdown: ScoreBreakdown,
+    #[serde(deserialize_with = "bits")]
     pub total_bits: f64,
 }
 
@@ -42,11 +52,55 @@ impl Score {
             total_bits,
         }
     }
+
+    /// Combine les scores de plusieurs scoreurs sur une même hypothèse : les composantes
+    /// s'additionnent (un scoreur complémentaire n'apporte que des pénalités ou des gains) et
+    /// le PSR retenu est le plus faible. `None` si aucun score n'est fourni.
+    pub fn combine(scores: &[Score]) -> Option<Self> {
+        let (first, rest) = scores.split_first()?;
+        let mut breakdown = first.breakdown.clone();
+        for score in rest {
+            breakdown.mdl_model_bits += score.breakdown.mdl_model_bits;
+            breakdown.mdl_data_bits += score.breakdown.mdl_data_bits;
+            breakdown.parse_success_ratio = breakdown.parse_success_ratio.min(score.breakdown.parse_success_ratio);
+            breakdown.alignment_gain_bits += score.breakdown.alignment_gain_bits;
+            breakdown.entropy_drop_bits += score.breakdown.entropy_drop_bits;
+            breakdown.penalties_bits += score.breakdown.penalties_bits;
+        }
+        Some(Score::new(breakdown))
+    }
+
+    /// Somme des scores obtenus sur des parties disjointes d'un corpus, avec le nombre de PDUs
+    /// de chaque partie (validation croisée) ; le PSR est pondéré par ce nombre
+    pub fn sum(parts: &[(Score, usize)]) -> Self {
+        let pdus: usize = parts.iter().map(|(_, n)| n).sum();
+        let total = |component: fn(&ScoreBreakdown) -> f64| parts.iter().map(|(s, _)| component(&s.breakdown)).sum();
+        Score::new(ScoreBreakdown {
+            mdl_model_bits: total(|b| b.mdl_model_bits),
+            mdl_data_bits: total(|b| b.mdl_data_bits),
+            parse_success_ratio: parts.iter().map(|(s, n)| s.breakdown.parse_success_ratio * *n as f64).sum::<f64>()
+                / pdus.max(1) as f64,
+            alignment_gain_bits: total(|b| b.alignment_gain_bits),
+            entropy_drop_bits: total(|b| b.entropy_drop_bits),
+            penalties_bits: total(|b| b.penalties_bits),
+        })
+    }
+
+    /// Ordre de classement total des scores (le meilleur en premier) : moins de bits, puis
+    /// meilleur taux de parsing, puis modèle plus court. Les scores infinis ou NaN passent en
+    /// dernier, de sorte que le classement ne dépende pas de l'ordre d'évaluation.
+    pub fn ranking_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // total_cmp place un NaN négatif avant -inf : les NaN sont classés à part, après +inf
+        let bits = |a: f64, b: f64| a.is_nan().cmp(&b.is_nan()).then_with(|| a.total_cmp(&b));
+        bits(self.total_bits, other.total_bits)
+            .then_with(|| bits(-self.breakdown.parse_success_ratio, -other.breakdown.parse_success_ratio))
+            .then_with(|| bits(self.breakdown.mdl_model_bits, other.breakdown.mdl_model_bits))
+    }
 }
 
 impl PartialOrd for Score {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.total_bits.partial_cmp(&other.total_bits)
+        Some(self.cmp(other))
     }
 }
 
@@ -65,4 +119,3 @@ impl Ord for Score {
 }
 
 impl Eq for Score {}
-
@@ -13,6 +13,8 @@ pub enum SegmentKind {
     Field(String),
     /// Erreur de parsing
     Error(String),
+    /// Bourrage final (série d'un même octet sans contenu)
+    Padding,
 }
 
 /// Segment d'une PDU parsée
@@ -21,6 +23,10 @@ pub struct Segment {
     pub kind: SegmentKind,
     pub range: std::ops::Range<usize>,
     pub note: Option<String>,
+    /// Position exacte en bits (depuis le bit de poids fort du premier octet) d'un champ
+    /// sub-octet ; `range` couvre alors les octets qui le contiennent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bits: Option<std::ops::Range<usize>>,
 }
 
 impl Segment {
@@ -29,6 +35,17 @@ impl Segment {
             kind,
             range,
             note: None,
+            bits: None,
+        }
+    }
+
+    /// Segment de bits `bits` ; la plage d'octets est celle qui le contient
+    pub fn from_bits(kind: SegmentKind, bits: std::ops::Range<usize>) -> Self {
+        Self {
+            kind,
+            range: bits.start / 8..bits.end.div_ceil(8),
+            note: None,
+            bits: Some(bits),
         }
     }
 
@@ -40,5 +57,9 @@ impl Segment {
     pub fn len(&self) -> usize {
         self.range.end - self.range.start
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
 }
 
@@ -0,0 +1,206 @@
+use crate::corpus::Corpus;
+use crate::field_table::FieldTable;
+use crate::hypothesis::Hypothesis;
+use crate::inference::{InferenceEngine, InferenceResult};
+use crate::plugin::PluginRegistry;
+use crate::{Error, Result};
+use serde::Serialize;
+use toml::{Table, Value};
+
+/// Modèle de protocole écrit à la main ou extrait d'une inférence : la pile des hypothèses,
+/// de la couche des PDUs à la plus profonde, au format TOML.
+///
+/// ```toml
+/// [[layers]]
+/// kind = "length_prefix_bundle"
+/// offset = 0
+/// width = "Two"
+/// endian = "Big"
+/// includes_header = false
+/// unit = 1
+/// adjust = 0
+///
+/// [[layers]]
+/// kind = "fixed_header"
+/// len = 4
+/// ```
+///
+/// Chaque couche est une hypothèse sous la forme de [`Hypothesis::to_spec`]. Imposé au moteur
+/// ([`crate::InferenceEngine::with_pinned_layers`]), le modèle fixe les premières couches ; les
+/// branches d'un discriminant de type ne s'y décrivent pas, les couches qui suivent s'appliquent
+/// à chaque branche.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelSpec {
+    pub layers: Vec<Hypothesis>,
+}
+
+impl ModelSpec {
+    /// Pile retenue par une inférence, sans la couche opaque finale
+    pub fn from_result(result: &InferenceResult) -> Self {
+        Self {
+            layers: result
+                .layers
+                .iter()
+                .map(|layer| layer.hypothesis.clone())
+                .filter(|h| !matches!(h, Hypothesis::Opaque))
+                .collect(),
+        }
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let table: Table = text.parse().map_err(|e| Error::ParseError(format!("model: {}", e)))?;
+        let layers = match table.get("layers") {
+            None => Vec::new(),
+            Some(Value::Array(layers)) => layers
+                .iter()
+                .enumerate()
+                .map(|(depth, layer)| match layer {
+                    Value::Table(spec) => Hypothesis::from_spec(spec),
+                    _ => Err(Error::InvalidHypothesis(format!("layer {}: expected a table", depth))),
+                })
+                .collect::<Result<_>>()?,
+            Some(_) => return Err(Error::ParseError("model: `layers` must be an array of tables".to_string())),
+        };
+        Ok(Self { layers })
+    }
+
+    pub fn to_toml(&self) -> String {
+        let layers = self.layers.iter().map(|h| Value::Table(h.to_spec())).collect();
+        let mut table = Table::new();
+        table.insert("layers".to_string(), Value::Array(layers));
+        toml::to_string(&table).unwrap_or_default()
+    }
+
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Décode `corpus` avec les couches du modèle, sans rien inférer : chaque hypothèse est
+    /// appliquée aux SDUs de la précédente, quelle que soit leur taille. La pile s'arrête plus
+    /// tôt si une couche n'a pas de parseur ou ne laisse pas de SDUs.
+    pub fn apply(&self, corpus: Corpus, registry: &PluginRegistry) -> InferenceResult {
+        let engine = InferenceEngine {
+            min_sdu_size: 0,
+            ..InferenceEngine::new()
+        };
+        engine
+            .with_max_depth(self.layers.len())
+            .with_pinned_layers(self.layers.clone())
+            .infer(corpus, registry)
+    }
+}
+
+/// Bilan du décodage d'une capture par un modèle ([`ModelSpec::apply`]), couche par couche
+#[derive(Debug, Clone, Serialize)]
+pub struct ParseReport {
+    pub layers: Vec<LayerParseReport>,
+    /// Couches du modèle qui n'ont pas pu être appliquées
+    pub missing_layers: usize,
+}
+
+/// Bilan d'une couche : taux de succès du parsing, PDUs en échec et champs décodés
+#[derive(Debug, Clone, Serialize)]
+pub struct LayerParseReport {
+    pub depth: usize,
+    pub hypothesis: Hypothesis,
+    pub pdu_count: usize,
+    pub parse_success_ratio: f64,
+    /// PDUs dont le parsing a échoué (segment d'erreur)
+    pub failed_pdus: Vec<usize>,
+    pub exception_count: usize,
+    pub fields: Option<FieldTable>,
+}
+
+impl ParseReport {
+    pub fn new(model: &ModelSpec, result: &InferenceResult) -> Self {
+        let layers: Vec<LayerParseReport> = result
+            .layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| !matches!(layer.hypothesis, Hypothesis::Opaque))
+            .map(|(depth, layer)| {
+                let parsed = &layer.parsed.parsed_pdus;
+                LayerParseReport {
+                    depth,
+                    hypothesis: layer.hypothesis.clone(),
+                    pdu_count: parsed.len(),
+                    parse_success_ratio: match result.layer_corpus(depth) {
+                        Some(corpus) => layer.parsed.parse_success_ratio_in(corpus),
+                        None => layer.parsed.parse_success_ratio(),
+                    },
+                    failed_pdus: (0..parsed.len()).filter(|&i| !parsed[i].is_success()).collect(),
+                    exception_count: parsed.iter().map(|p| p.exceptions.len()).sum(),
+                    fields: FieldTable::from_result(result, depth),
+                }
+            })
+            .collect();
+        Self {
+            missing_layers: model.layers.len().saturating_sub(layers.len()),
+            layers,
+        }
+    }
+}
+
+impl Hypothesis {
+    /// Hypothèse sous forme de table : `kind`, nom de la variante en snake_case, suivi de ses
+    /// paramètres (les énumérations imbriquées gardent leur nom de variante : `width = "Two"`,
+    /// `obfuscation = { Xor = { key = [...] } }`)
+    pub fn to_spec(&self) -> Table {
+        let (variant, fields) = match Value::try_from(self) {
+            Ok(Value::String(variant)) => (variant, Table::new()),
+            Ok(Value::Table(table)) => match table.into_iter().next() {
+                Some((variant, Value::Table(fields))) => (variant, fields),
+                Some((variant, _)) => (variant, Table::new()),
+                None => (String::new(), Table::new()),
+            },
+            _ => (String::new(), Table::new()),
+        };
+        let mut spec = Table::new();
+        spec.insert("kind".to_string(), Value::String(kind_name(&variant)));
+        spec.extend(fields);
+        spec
+    }
+
+    /// Hypothèse décrite par une table de [`Hypothesis::to_spec`]
+    pub fn from_spec(spec: &Table) -> Result<Self> {
+        let mut fields = spec.clone();
+        let kind = match fields.remove("kind") {
+            Some(Value::String(kind)) => kind,
+            _ => return Err(Error::InvalidHypothesis("missing `kind`".to_string())),
+        };
+        let variant = variant_name(&kind);
+        let value = if fields.is_empty() {
+            Value::String(variant)
+        } else {
+            Value::Table(Table::from_iter([(variant, Value::Table(fields))]))
+        };
+        value
+            .try_into()
+            .map_err(|e| Error::InvalidHypothesis(format!("{}: {}", kind, e)))
+    }
+}
+
+/// `LengthPrefixBundle` → `length_prefix_bundle`
+fn kind_name(variant: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in variant.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            out.push('_');
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+/// `length_prefix_bundle` → `LengthPrefixBundle`
+fn variant_name(kind: &str) -> String {
+    kind.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
@@ -0,0 +1,151 @@
+use crate::corpus::{Corpus, Direction};
+use crate::fields::read_be;
+use crate::hypothesis::Hypothesis;
+use crate::inference::Layer;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Type de message et nombre d'occurrences, en début et en fin de flow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageState {
+    pub type_value: u64,
+    pub count: usize,
+    /// Nombre de flows qui commencent par ce type
+    pub initial: usize,
+    /// Nombre de flows qui se terminent par ce type
+    pub terminal: usize,
+}
+
+/// Succession observée de deux types de messages dans un flow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateTransition {
+    pub from: u64,
+    pub to: u64,
+    pub count: usize,
+    /// Proportion des messages de type `from` suivis d'un message de type `to`
+    pub probability: f64,
+}
+
+/// Réponse (serveur vers client) qui suit directement une requête (client vers serveur)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Exchange {
+    pub request: u64,
+    pub response: u64,
+    pub count: usize,
+}
+
+/// Automate des types de messages : quel type suit quel autre, flow par flow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateMachine {
+    /// Champ discriminant (big-endian) dont la valeur donne le type de chaque PDU
+    pub type_offset: usize,
+    pub type_width: usize,
+    /// Nombre de flows (séquences de PDUs) observés
+    pub flows: usize,
+    /// Types de messages par valeur croissante
+    pub states: Vec<MessageState>,
+    /// Transitions par couple `(from, to)` croissant
+    pub transitions: Vec<StateTransition>,
+    /// Proportion des messages consécutifs de directions opposées, si les directions sont connues
+    pub alternation: Option<f64>,
+    /// Couples requête/réponse, du plus fréquent au moins fréquent
+    pub exchanges: Vec<Exchange>,
+}
+
+impl StateMachine {
+    /// Automate des types de messages d'après le discriminant de la première couche : celui
+    /// d'une hypothèse TypeSwitch, à défaut le champ énuméré le plus proche du début des PDUs.
+    ///
+    /// Les couches plus profondes travaillent sur des SDUs qui ne se rattachent pas toujours à
+    /// une PDU, et ne sont pas examinées.
+    pub fn from_layers(corpus: &Corpus, layers: &[Layer]) -> Option<Self> {
+        let layer = layers.first()?;
+        let (type_offset, type_width) = match layer.hypothesis {
+            Hypothesis::TypeSwitch { type_offset, type_width } => (type_offset, type_width),
+            _ => layer.fields.enums.iter().min_by_key(|e| e.offset).map(|e| (e.offset, e.width))?,
+        };
+        Self::from_types(corpus, type_offset, type_width)
+    }
+
+    /// Automate des types lus au champ big-endian (`type_offset`, `type_width`).
+    ///
+    /// Les PDUs sont regroupées par flow d'origine (toutes ensemble si le corpus n'en distingue
+    /// pas) et gardent l'ordre du corpus ; une PDU trop courte pour le champ est ignorée.
+    /// `None` s'il n'y a pas au moins deux messages typés.
+    pub fn from_types(corpus: &Corpus, type_offset: usize, type_width: usize) -> Option<Self> {
+        let mut flows: BTreeMap<Option<usize>, Vec<(u64, Option<Direction>)>> = BTreeMap::new();
+        for pdu in &corpus.items {
+            if let Some(value) = read_be(pdu.as_slice(), type_offset, type_width) {
+                flows.entry(pdu.flow_id).or_default().push((value, pdu.direction));
+            }
+        }
+        if flows.values().map(Vec::len).sum::<usize>() < 2 {
+            return None;
+        }
+
+        let mut states: BTreeMap<u64, MessageState> = BTreeMap::new();
+        let mut transitions: BTreeMap<(u64, u64), usize> = BTreeMap::new();
+        let mut exchanges: BTreeMap<(u64, u64), usize> = BTreeMap::new();
+        let (mut directed_pairs, mut alternating_pairs) = (0, 0);
+        for messages in flows.values() {
+            for &(value, _) in messages {
+                states
+                    .entry(value)
+                    .or_insert(MessageState {
+                        type_value: value,
+                        count: 0,
+                        initial: 0,
+                        terminal: 0,
+                    })
+                    .count += 1;
+            }
+            if let (Some(first), Some(last)) = (messages.first(), messages.last()) {
+                states.get_mut(&first.0).unwrap().initial += 1;
+                states.get_mut(&last.0).unwrap().terminal += 1;
+            }
+            for pair in messages.windows(2) {
+                let ((from, from_dir), (to, to_dir)) = (pair[0], pair[1]);
+                *transitions.entry((from, to)).or_default() += 1;
+                if let (Some(from_dir), Some(to_dir)) = (from_dir, to_dir) {
+                    directed_pairs += 1;
+                    if from_dir != to_dir {
+                        alternating_pairs += 1;
+                    }
+                    if from_dir == Direction::ClientToServer && to_dir == Direction::ServerToClient {
+                        *exchanges.entry((from, to)).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        // Un message sans successeur (fin de flow) ne compte pas dans les sorties de son type
+        let mut outgoing: BTreeMap<u64, usize> = BTreeMap::new();
+        for (&(from, _), &count) in &transitions {
+            *outgoing.entry(from).or_default() += count;
+        }
+        let transitions = transitions
+            .into_iter()
+            .map(|((from, to), count)| StateTransition {
+                from,
+                to,
+                count,
+                probability: count as f64 / outgoing[&from] as f64,
+            })
+            .collect();
+        let mut exchanges: Vec<Exchange> = exchanges
+            .into_iter()
+            .map(|((request, response), count)| Exchange { request, response, count })
+            .collect();
+        exchanges.sort_by(|a, b| b.count.cmp(&a.count).then((a.request, a.response).cmp(&(b.request, b.response))));
+
+        Some(Self {
+            type_offset,
+            type_width,
+            flows: flows.len(),
+            states: states.into_values().collect(),
+            transitions,
+            alternation: (directed_pairs > 0).then(|| alternating_pairs as f64 / directed_pairs as f64),
+            exchanges,
+        })
+    }
+}
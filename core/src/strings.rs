@@ -0,0 +1,175 @@
+use crate::corpus::Corpus;
+use crate::parser::ParsedCorpus;
+use crate::segment::{Segment, SegmentKind};
+use serde::{Deserialize, Serialize};
+
+/// Positions explorées au début de chaque segment PCI ou SDU
+const MAX_STRING_OFFSET: usize = 16;
+/// Nombre minimal de caractères d'une chaîne
+const MIN_STRING_CHARS: usize = 3;
+/// Proportion minimale des PDUs portant le segment où la chaîne doit se lire
+const MIN_STRING_RATIO: f64 = 0.9;
+/// Nombre de valeurs décodées conservées en exemple
+const STRING_SAMPLES: usize = 3;
+
+/// Encodage d'une chaîne de caractères
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StringEncoding {
+    /// ASCII imprimable
+    Ascii,
+    /// UTF-8 comportant des caractères non ASCII
+    Utf8,
+    /// UTF-16 little-endian (caractères ASCII suivis d'un octet nul)
+    Utf16Le,
+}
+
+/// Délimitation d'une chaîne
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StringFraming {
+    /// Terminée par un caractère nul (compris dans la chaîne)
+    NullTerminated,
+    /// Précédée de sa longueur en octets, sur un octet
+    LengthPrefixed,
+}
+
+/// Chaîne lue à la même position d'un segment PCI ou SDU dans presque toutes les PDUs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StringField {
+    /// Rang du segment porteur parmi les segments PCI et SDU de la PDU
+    pub segment: usize,
+    /// Position relative au début du segment
+    pub offset: usize,
+    pub encoding: StringEncoding,
+    pub framing: StringFraming,
+    /// Exemples de valeurs décodées
+    pub samples: Vec<String>,
+}
+
+/// Chaîne lue au début de `data` : encodage, délimitation, taille totale et valeur décodée
+fn read_string(data: &[u8]) -> Option<(StringEncoding, StringFraming, usize, String)> {
+    if let Some(&len) = data.first() {
+        let len = len as usize;
+        if let Some(content) = data.get(1..1 + len) {
+            if let Some((encoding, text)) = decode(content) {
+                return Some((encoding, StringFraming::LengthPrefixed, 1 + len, text));
+            }
+        }
+    }
+
+    // UTF-16LE : paires (caractère ASCII, 0x00) jusqu'à un 0x0000
+    let units = data.chunks_exact(2).take_while(|pair| pair[1] == 0 && pair[0] != 0).count();
+    if units >= MIN_STRING_CHARS && data.get(units * 2..units * 2 + 2) == Some(&[0, 0][..]) {
+        let text: String = data[..units * 2].chunks_exact(2).map(|pair| pair[0] as char).collect();
+        if text.bytes().all(is_printable) {
+            return Some((StringEncoding::Utf16Le, StringFraming::NullTerminated, units * 2 + 2, text));
+        }
+    }
+
+    let end = data.iter().position(|&b| b == 0)?;
+    let (encoding, text) = decode(&data[..end])?;
+    Some((encoding, StringFraming::NullTerminated, end + 1, text))
+}
+
+/// Décode un contenu ASCII imprimable ou UTF-8 sans caractère de contrôle
+fn decode(content: &[u8]) -> Option<(StringEncoding, String)> {
+    let text = std::str::from_utf8(content).ok()?;
+    if text.chars().count() < MIN_STRING_CHARS || text.chars().any(char::is_control) {
+        return None;
+    }
+    if content.iter().copied().all(is_printable) {
+        Some((StringEncoding::Ascii, text.to_string()))
+    } else {
+        Some((StringEncoding::Utf8, text.to_string()))
+    }
+}
+
+fn is_printable(b: u8) -> bool {
+    (0x20..=0x7e).contains(&b)
+}
+
+/// Segments PCI et SDU d'une PDU, dans l'ordre
+fn carriers(segments: &[Segment]) -> impl Iterator<Item = (usize, &Segment)> {
+    segments
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| matches!(s.kind, SegmentKind::Pci | SegmentKind::Sdu))
+}
+
+/// Chaînes lues à une même position relative d'un même segment PCI ou SDU, avec le même
+/// encodage et la même délimitation, dans presque toutes les PDUs qui portent ce segment.
+///
+/// Les chaînes d'un segment ne se chevauchent pas : l'exploration reprend après la plus
+/// courte des occurrences.
+pub fn string_fields(corpus: &Corpus, parsed: &ParsedCorpus) -> Vec<StringField> {
+    let pdus: Vec<(&[u8], Vec<&Segment>)> = corpus
+        .items
+        .iter()
+        .zip(parsed.parsed_pdus.iter())
+        .map(|(pdu, parsed_pdu)| (pdu.as_slice(), carriers(&parsed_pdu.segments).map(|(_, s)| s).collect()))
+        .collect();
+    let segment_count = pdus.iter().map(|(_, segments)| segments.len()).max().unwrap_or(0);
+
+    let mut fields = Vec::new();
+    for segment in 0..segment_count {
+        let bodies: Vec<&[u8]> = pdus
+            .iter()
+            .filter_map(|(data, segments)| segments.get(segment).and_then(|s| data.get(s.range.clone())))
+            .collect();
+        let mut offset = 0;
+        while offset < MAX_STRING_OFFSET {
+            let reads: Vec<_> = bodies.iter().filter_map(|body| read_string(body.get(offset..)?)).collect();
+            let Some(&(encoding, framing, _, _)) = reads.first() else {
+                offset += 1;
+                continue;
+            };
+            let consistent: Vec<_> = reads.iter().filter(|r| r.0 == encoding && r.1 == framing).collect();
+            if bodies.is_empty() || (consistent.len() as f64) < bodies.len() as f64 * MIN_STRING_RATIO {
+                offset += 1;
+                continue;
+            }
+            let mut samples: Vec<String> = Vec::new();
+            for (_, _, _, text) in &consistent {
+                if samples.len() < STRING_SAMPLES && !samples.contains(text) {
+                    samples.push(text.clone());
+                }
+            }
+            fields.push(StringField { segment, offset, encoding, framing, samples });
+            offset += consistent.iter().map(|r| r.2).min().unwrap_or(1);
+        }
+    }
+    fields
+}
+
+/// Découpe les segments PCI et SDU porteurs de `fields` pour faire apparaître chaque chaîne en
+/// segment `Field("string")`, annoté de sa valeur décodée
+pub fn annotate_strings(corpus: &Corpus, parsed: &mut ParsedCorpus, fields: &[StringField]) {
+    // Des derniers segments et positions vers les premiers : les rangs et débuts de segments
+    // restant à traiter ne bougent pas
+    let mut ordered: Vec<&StringField> = fields.iter().collect();
+    ordered.sort_by_key(|f| std::cmp::Reverse((f.segment, f.offset)));
+
+    for (pdu, parsed_pdu) in corpus.items.iter().zip(parsed.parsed_pdus.iter_mut()) {
+        let data = pdu.as_slice();
+        for field in &ordered {
+            let Some((index, segment)) = carriers(&parsed_pdu.segments).nth(field.segment) else {
+                continue;
+            };
+            let start = segment.range.start + field.offset;
+            let Some((encoding, framing, len, text)) = data.get(start..segment.range.end).and_then(read_string) else {
+                continue;
+            };
+            if encoding != field.encoding || framing != field.framing {
+                continue;
+            }
+            let (kind, range) = (segment.kind.clone(), segment.range.clone());
+            let pieces = [
+                Segment::new(kind.clone(), range.start..start),
+                Segment::new(SegmentKind::Field("string".to_string()), start..start + len).with_note(text),
+                Segment::new(kind, start + len..range.end),
+            ];
+            parsed_pdu
+                .segments
+                .splice(index..=index, pieces.into_iter().filter(|s| !s.is_empty()));
+        }
+    }
+}
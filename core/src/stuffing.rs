@@ -0,0 +1,200 @@
+use crate::corpus::{Corpus, CorpusMeta, PduRef};
+use crate::hypothesis::{ChecksumAlgorithm, EscapeScheme, Hypothesis};
+use std::ops::Range;
+
+/// Nombre maximal de PDUs examinées pour la détection
+const DETECTION_SAMPLE: usize = 2048;
+/// Proportion minimale de PDUs terminées par le délimiteur et correctement échappées
+const STUFFING_MIN_RATIO: f64 = 0.95;
+
+/// Délimiteur de trame SLIP (RFC 1055)
+const SLIP_END: u8 = 0xC0;
+/// Octet d'échappement SLIP
+const SLIP_ESC: u8 = 0xDB;
+/// Fanion de trame HDLC (RFC 1662)
+const HDLC_FLAG: u8 = 0x7E;
+/// Octet d'échappement HDLC ; l'octet suivant est l'original XOR 0x20
+const HDLC_ESC: u8 = 0x7D;
+/// FCS HDLC reconnus, transmis octet de poids faible en premier
+const HDLC_FCS: [ChecksumAlgorithm; 2] = [ChecksumAlgorithm::Crc16X25, ChecksumAlgorithm::Crc32];
+
+impl EscapeScheme {
+    /// Schéma SLIP : `0xDB 0xDC` pour `0xC0`, `0xDB 0xDD` pour `0xDB`
+    pub fn slip() -> Self {
+        Self {
+            delimiter: SLIP_END,
+            escape: SLIP_ESC,
+            table: vec![(0xDC, SLIP_END), (0xDD, SLIP_ESC)],
+        }
+    }
+
+    /// Schéma HDLC asynchrone : fanion, échappement et caractères de contrôle (ACCM complète)
+    /// transmis XOR 0x20 après `0x7D`
+    pub fn hdlc() -> Self {
+        Self {
+            delimiter: HDLC_FLAG,
+            escape: HDLC_ESC,
+            table: (0x00..0x20)
+                .chain([HDLC_ESC, HDLC_FLAG])
+                .map(|b| (b ^ 0x20, b))
+                .collect(),
+        }
+    }
+
+    /// Nombre d'octets échappés de `frame` qui portent ses `unescaped_len` premiers octets d'origine
+    pub fn escaped_len(&self, frame: &[u8], unescaped_len: usize) -> usize {
+        let mut pos = 0;
+        for _ in 0..unescaped_len {
+            pos += if frame.get(pos) == Some(&self.escape) { 2 } else { 1 };
+        }
+        pos.min(frame.len())
+    }
+
+    /// Retire l'échappement d'une trame ; `None` si une séquence d'échappement est invalide
+    pub fn unescape(&self, frame: &[u8]) -> Option<Vec<u8>> {
+        let mut out = Vec::with_capacity(frame.len());
+        let mut bytes = frame.iter();
+        while let Some(&b) = bytes.next() {
+            if b == self.escape {
+                let code = *bytes.next()?;
+                let (_, original) = self.table.iter().find(|(escaped, _)| *escaped == code)?;
+                out.push(*original);
+            } else {
+                out.push(b);
+            }
+        }
+        Some(out)
+    }
+}
+
+/// Trames non vides entre les délimiteurs d'une PDU
+pub fn frames(data: &[u8], delimiter: u8) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for (i, &b) in data.iter().enumerate() {
+        if b == delimiter {
+            if start < i {
+                ranges.push(start..i);
+            }
+            start = i + 1;
+        }
+    }
+    if start < data.len() {
+        ranges.push(start..data.len());
+    }
+    ranges
+}
+
+/// Indique si le corpus est tramé par byte-stuffing selon `scheme` : les PDUs se terminent
+/// par le délimiteur (sauf troncature) et toutes leurs séquences d'échappement sont valides
+pub fn detect_stuffing(corpus: &Corpus, scheme: &EscapeScheme) -> bool {
+    let sample: Vec<&PduRef> = corpus.items.iter().take(DETECTION_SAMPLE).collect();
+    if sample.is_empty() {
+        return false;
+    }
+
+    let framed = sample
+        .iter()
+        .filter(|pdu| {
+            let data = pdu.as_slice();
+            (pdu.truncated || data.last() == Some(&scheme.delimiter))
+                && frames(data, scheme.delimiter)
+                    .into_iter()
+                    .all(|frame| scheme.unescape(&data[frame]).is_some())
+        })
+        .count();
+    framed as f64 >= sample.len() as f64 * STUFFING_MIN_RATIO
+}
+
+/// Cherche un tramage HDLC et, le cas échéant, le FCS qui valide les trames déséchappées
+pub fn detect_hdlc(corpus: &Corpus) -> Option<Hypothesis> {
+    let scheme = EscapeScheme::hdlc();
+    if !detect_stuffing(corpus, &scheme) {
+        return None;
+    }
+
+    let frames: Vec<Vec<u8>> = corpus
+        .items
+        .iter()
+        .take(DETECTION_SAMPLE)
+        .flat_map(|pdu| {
+            let data = pdu.as_slice();
+            frames(data, scheme.delimiter)
+                .into_iter()
+                .filter_map(|frame| scheme.unescape(&data[frame]))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let fcs = HDLC_FCS.into_iter().find(|algorithm| {
+        let valid = frames.iter().filter(|frame| fcs_valid(frame, *algorithm)).count();
+        !frames.is_empty() && valid as f64 >= frames.len() as f64 * STUFFING_MIN_RATIO
+    });
+    Some(Hypothesis::Hdlc { fcs })
+}
+
+/// Vérifie le FCS en fin de trame déséchappée
+pub fn fcs_valid(frame: &[u8], algorithm: ChecksumAlgorithm) -> bool {
+    let width = algorithm.width();
+    frame.len() > width && {
+        let (covered, field) = frame.split_at(frame.len() - width);
+        algorithm.read(field, crate::hypothesis::Endianness::Little) == algorithm.compute(covered)
+    }
+}
+
+/// Schéma d'échappement et taille du FCS final décrits par une hypothèse de tramage
+pub fn framing(h: &Hypothesis) -> Option<(EscapeScheme, usize)> {
+    match h {
+        Hypothesis::ByteStuffed { scheme } => Some((scheme.clone(), 0)),
+        Hypothesis::Hdlc { fcs } => Some((EscapeScheme::hdlc(), fcs.map_or(0, |a| a.width()))),
+        _ => None,
+    }
+}
+
+/// Corpus des trames déséchappées et sans FCS, pour la couche suivante ; les trames
+/// invalides sont écartées
+pub fn unstuffed_corpus(corpus: &Corpus, h: &Hypothesis) -> Option<Corpus> {
+    let (scheme, fcs_len) = framing(h)?;
+
+    let mut items = Vec::new();
+    let mut multiplicities = Vec::new();
+    for (index, pdu) in corpus.items.iter().enumerate() {
+        let data = pdu.as_slice();
+        for frame in frames(data, scheme.delimiter) {
+            let end = frame.end;
+            let Some(mut unescaped) = scheme.unescape(&data[frame]) else {
+                continue;
+            };
+            if unescaped.len() <= fcs_len {
+                continue;
+            }
+            unescaped.truncate(unescaped.len() - fcs_len);
+            let len = unescaped.len();
+            items.push(
+                PduRef::new(unescaped.into(), 0..len)
+                    .with_origin(pdu.timestamp, pdu.direction)
+                    .with_flow(pdu.flow_id)
+                    // Seule la trame qui atteint la fin d'une PDU tronquée l'est aussi
+                    .with_truncated(pdu.truncated && end >= data.len())
+                    .derived_from(pdu, index, None),
+            );
+            multiplicities.push(corpus.multiplicity(index));
+        }
+    }
+
+    if items.is_empty() {
+        return None;
+    }
+
+    let total_bytes = items.iter().map(|p| p.len()).sum();
+    let pdu_count = items.len();
+    Some(Corpus::new(
+        items,
+        CorpusMeta {
+            source: format!("{}_unstuffed", corpus.meta.source),
+            total_bytes,
+            pdu_count,
+            flow_id: corpus.meta.flow_id,
+            multiplicities: corpus.meta.multiplicities.as_ref().map(|_| multiplicities),
+        },
+    ))
+}
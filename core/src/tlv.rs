@@ -0,0 +1,128 @@
+use crate::corpus::Corpus;
+use crate::parser::ParsedCorpus;
+use crate::segment::{Segment, SegmentKind};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Statistiques d'une valeur de tag à un niveau d'imbrication donné
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TlvTagStats {
+    /// Octets du tag tels qu'ils apparaissent dans les PDUs
+    pub tag: Vec<u8>,
+    /// Niveau d'imbrication (0 pour les TLVs de premier niveau)
+    pub depth: usize,
+    /// Nombre total d'occurrences
+    pub occurrences: usize,
+    /// Nombre de PDUs où le tag apparaît
+    pub pdus: usize,
+    pub min_len: usize,
+    pub max_len: usize,
+    pub mean_len: f64,
+}
+
+impl TlvTagStats {
+    /// Taille de la valeur si elle est la même pour toutes les occurrences
+    pub fn fixed_len(&self) -> Option<usize> {
+        (self.min_len == self.max_len).then_some(self.min_len)
+    }
+
+    /// Tag en hexadécimal (`0x10`, `0x9f02`)
+    pub fn tag_hex(&self) -> String {
+        format!("0x{}", self.tag.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+    }
+}
+
+/// Dictionnaire des tags observés dans une couche TLV
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TlvDictionary {
+    /// Tags par niveau puis par valeur
+    pub tags: Vec<TlvTagStats>,
+    /// Nombre de PDUs analysées
+    pub pdus: usize,
+}
+
+/// Niveau d'imbrication d'un segment `tag`, lu dans sa note (`niveau N`)
+fn tag_depth(segment: &Segment) -> Option<usize> {
+    if segment.kind != SegmentKind::Field("tag".to_string()) {
+        return None;
+    }
+    match &segment.note {
+        Some(note) => note.strip_prefix("niveau ")?.parse().ok(),
+        None => Some(0),
+    }
+}
+
+/// Taille de la valeur du tag `segments[index]` : des octets qui suivent le champ de longueur
+/// jusqu'au prochain tag de même niveau ou de niveau supérieur (valeurs imbriquées comprises)
+fn value_len(segments: &[Segment], index: usize, depth: usize) -> usize {
+    let mut next = index + 1;
+    while segments.get(next).is_some_and(|s| s.kind == SegmentKind::Pci) && tag_depth_at(segments, next + 1).is_none() {
+        next += 1;
+    }
+    if segments.get(next).is_some_and(|s| s.kind == SegmentKind::Field("length".to_string())) {
+        next += 1;
+    }
+    let value_start = segments[next - 1].range.end;
+
+    let mut value_end = value_start;
+    for (offset, segment) in segments[next..].iter().enumerate() {
+        let ends_value = segment.kind == SegmentKind::MessageBoundary
+            || tag_depth(segment).is_some_and(|d| d <= depth)
+            // Préfixe PCI du tag suivant (tag_offset > 0)
+            || (segment.kind == SegmentKind::Pci
+                && tag_depth_at(segments, next + offset + 1).is_some_and(|d| d <= depth));
+        if ends_value {
+            break;
+        }
+        value_end = segment.range.end;
+    }
+    value_end - value_start
+}
+
+fn tag_depth_at(segments: &[Segment], index: usize) -> Option<usize> {
+    segments.get(index).and_then(tag_depth)
+}
+
+/// (PDU, taille de la valeur) de chaque occurrence d'un tag
+type Occurrences = Vec<(usize, usize)>;
+
+/// Dictionnaire des tags d'une couche parsée en TLV : occurrences et tailles de valeur
+pub fn tag_dictionary(corpus: &Corpus, parsed: &ParsedCorpus) -> Option<TlvDictionary> {
+    let mut tags: BTreeMap<(usize, Vec<u8>), Occurrences> = BTreeMap::new();
+    for (pdu_index, (pdu, parsed_pdu)) in corpus.items.iter().zip(parsed.parsed_pdus.iter()).enumerate() {
+        let data = pdu.as_slice();
+        for (index, segment) in parsed_pdu.segments.iter().enumerate() {
+            let Some(depth) = tag_depth(segment) else {
+                continue;
+            };
+            let Some(tag) = data.get(segment.range.clone()) else {
+                continue;
+            };
+            tags.entry((depth, tag.to_vec()))
+                .or_default()
+                .push((pdu_index, value_len(&parsed_pdu.segments, index, depth)));
+        }
+    }
+
+    if tags.is_empty() {
+        return None;
+    }
+    Some(TlvDictionary {
+        tags: tags
+            .into_iter()
+            .map(|((depth, tag), occurrences)| {
+                let lens = || occurrences.iter().map(|&(_, len)| len);
+                TlvTagStats {
+                    tag,
+                    depth,
+                    occurrences: occurrences.len(),
+                    pdus: occurrences.iter().map(|&(pdu, _)| pdu).collect::<BTreeSet<_>>().len(),
+                    min_len: lens().min().unwrap_or(0),
+                    max_len: lens().max().unwrap_or(0),
+                    mean_len: lens().sum::<usize>() as f64 / occurrences.len() as f64,
+                }
+            })
+            .collect(),
+        pdus: corpus.len(),
+    })
+}
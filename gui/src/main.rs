@@ -1,6 +1,6 @@
 use eframe::egui;
 use protocol_infer_core::{
-    pcap, plugins, Corpus, Flow, InferenceEngine, InferenceResult,
+    pcap, plugins, CancellationToken, Corpus, Flow, InferenceEngine, InferenceResult, Progress, ProgressSink,
 };
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc;
@@ -19,11 +19,28 @@ fn main() -> Result<(), eframe::Error> {
     )
 }
 
+/// Mémorise le dernier avancement de l'inférence et redemande un affichage
+struct GuiProgress {
+    latest: Arc<Mutex<Option<Progress>>>,
+    ctx: egui::Context,
+}
+
+impl ProgressSink for GuiProgress {
+    fn report(&self, progress: &Progress) {
+        *self.latest.lock().unwrap() = Some(progress.clone());
+        self.ctx.request_repaint();
+    }
+}
+
 struct ProtocolInferApp {
     flow: Option<Flow>,
     inference_result: Option<InferenceResult>,
     inference_in_progress: Arc<Mutex<bool>>,
     inference_receiver: Option<mpsc::Receiver<InferenceResult>>,
+    /// Dernier avancement rapporté par l'inférence en cours
+    inference_progress: Arc<Mutex<Option<Progress>>>,
+    /// Jeton d'annulation de l'inférence en cours
+    cancellation: Option<CancellationToken>,
     selected_pdu: Option<(usize, usize)>, // (layer_idx, pdu_idx)
     hexdump_data: Vec<u8>,
     hexdump_offset: usize,
@@ -36,6 +53,8 @@ impl Default for ProtocolInferApp {
             inference_result: None,
             inference_in_progress: Arc::new(Mutex::new(false)),
             inference_receiver: None,
+            inference_progress: Arc::new(Mutex::new(None)),
+            cancellation: None,
             selected_pdu: None,
             hexdump_data: Vec::new(),
             hexdump_offset: 0,
@@ -50,6 +69,7 @@ impl eframe::App for ProtocolInferApp {
             if let Ok(result) = receiver.try_recv() {
                 self.inference_result = Some(result);
                 self.inference_receiver = None;
+                self.cancellation = None;
                 *self.inference_in_progress.lock().unwrap() = false;
             }
         }
@@ -68,7 +88,7 @@ impl eframe::App for ProtocolInferApp {
 
                 if self.flow.is_some() {
                     if ui.button("Lancer Inférence").clicked() {
-                        self.start_inference();
+                        self.start_inference(ctx);
                     }
                 }
 
@@ -231,7 +251,7 @@ impl eframe::App for ProtocolInferApp {
                                                     ui.separator();
                                                     ui.label("Exceptions:");
                                                     for exc in &parsed_pdu.exceptions {
-                                                        ui.label(format!("  ⚠ {}", exc));
+                                                        ui.label(format!("  ⚠ [{:?}] {}", exc.severity, exc));
                                                     }
                                                 }
                                             });
@@ -264,11 +284,39 @@ impl eframe::App for ProtocolInferApp {
                     if let Some((layer_idx, pdu_idx)) = selected_pdu_for_hexdump {
                         if let Some(layer) = result_for_hexdump.layers.get(layer_idx) {
                             if let Some(parsed_pdu) = layer.parsed.parsed_pdus.get(pdu_idx) {
-                                // Récupérer les données originales du corpus
-                                if let Some(original_pdu) = result_for_hexdump.corpus.items.get(pdu_idx) {
-                                    self.show_hexdump_with_segments(ui, original_pdu, parsed_pdu);
-                                } else {
-                                    ui.label(format!("PDU {} de la couche {} (données non disponibles)", pdu_idx, layer_idx));
+                                // Ramener la PDU de la couche à sa position dans le datagramme d'origine
+                                let layer_len = result_for_hexdump
+                                    .layer_corpus(layer_idx)
+                                    .and_then(|c| c.items.get(pdu_idx))
+                                    .map(|p| p.len())
+                                    .unwrap_or(0);
+                                let origin = result_for_hexdump.resolve_to_origin(layer_idx, pdu_idx, 0..layer_len);
+                                match origin.and_then(|(index, range)| {
+                                    result_for_hexdump.corpus.items.get(index).map(|pdu| (index, pdu, range))
+                                }) {
+                                    Some((index, original_pdu, range)) => {
+                                        if layer_idx > 0 {
+                                            ui.label(format!(
+                                                "Datagramme {}, octets {}..{}",
+                                                index, range.start, range.end
+                                            ));
+                                        }
+                                        // Segments décalés à la position de la PDU dans le datagramme
+                                        let mut shifted = parsed_pdu.clone();
+                                        for segment in &mut shifted.segments {
+                                            segment.range = segment.range.start + range.start..segment.range.end + range.start;
+                                        }
+                                        self.show_hexdump_with_segments(ui, original_pdu, &shifted);
+                                    }
+                                    None => {
+                                        // Octets décodés ou réassemblés : affichés tels que la couche les a reçus
+                                        match result_for_hexdump.layer_corpus(layer_idx).and_then(|c| c.items.get(pdu_idx)) {
+                                            Some(pdu) => self.show_hexdump_with_segments(ui, pdu, parsed_pdu),
+                                            None => {
+                                                ui.label(format!("PDU {} de la couche {} (données non disponibles)", pdu_idx, layer_idx));
+                                            }
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -282,8 +330,29 @@ impl eframe::App for ProtocolInferApp {
                 egui::CentralPanel::default().show(ctx, |ui| {
                     ui.centered_and_justified(|ui| {
                         if *self.inference_in_progress.lock().unwrap() {
-                            ui.spinner();
-                            ui.label("Inférence en cours...");
+                            ui.vertical_centered(|ui| {
+                                ui.spinner();
+                                ui.label("Inférence en cours...");
+                                if let Some(progress) = self.inference_progress.lock().unwrap().as_ref() {
+                                    ui.add(
+                                        egui::ProgressBar::new(progress.evaluated as f32 / progress.total.max(1) as f32)
+                                            .text(format!(
+                                                "Couche {} : {}/{} hypothèses",
+                                                progress.depth, progress.evaluated, progress.total
+                                            )),
+                                    );
+                                    if let Some((hypothesis, bits)) = &progress.best {
+                                        ui.label(format!("Meilleure: {} ({:.1} bits)", hypothesis.name(), bits));
+                                    }
+                                }
+                                if let Some(token) = &self.cancellation {
+                                    if token.is_cancelled() {
+                                        ui.label("Annulation en cours...");
+                                    } else if ui.button("Annuler").clicked() {
+                                        token.cancel();
+                                    }
+                                }
+                            });
                         } else {
                             ui.label("Lancez l'inférence pour voir les messages");
                         }
@@ -313,7 +382,7 @@ impl ProtocolInferApp {
         }
     }
 
-    fn start_inference(&mut self) {
+    fn start_inference(&mut self, ctx: &egui::Context) {
         if *self.inference_in_progress.lock().unwrap() {
             return;
         }
@@ -328,11 +397,18 @@ impl ProtocolInferApp {
 
         *in_progress.lock().unwrap() = true;
         self.inference_receiver = Some(receiver);
+        *self.inference_progress.lock().unwrap() = None;
+        let token = CancellationToken::new();
+        self.cancellation = Some(token.clone());
+        let sink = Arc::new(GuiProgress {
+            latest: Arc::clone(&self.inference_progress),
+            ctx: ctx.clone(),
+        });
 
         thread::spawn(move || {
             let corpus = Corpus::from_datagrams(&flow.datagrams, Some(0));
             let registry = plugins::create_default_registry();
-            let engine = InferenceEngine::new();
+            let engine = InferenceEngine::new().with_progress(sink).with_cancellation(token);
             let result = engine.infer(corpus, &registry);
             let _ = sender.send(result);
             *in_progress.lock().unwrap() = false;
@@ -351,10 +427,233 @@ impl ProtocolInferApp {
                     for (idx, layer) in result.layers.iter().enumerate() {
                         ui.collapsing(format!("Layer {} - {}", idx, layer.hypothesis.name()), |ui| {
                             ui.label(format!("Hypothèse sélectionnée: {}", layer.hypothesis.name()));
+                            if let Some(confidence) = &layer.confidence {
+                                let margin = confidence
+                                    .margin_bits
+                                    .map(|m| format!("{:.1} bits d'avance", m))
+                                    .unwrap_or_else(|| "sans rivale".to_string());
+                                ui.label(format!("Confiance: {:.0}% ({})", confidence.probability * 100.0, margin));
+                                if layer.rank > 0 {
+                                    ui.label(format!("Retenue par retour arrière (rang {})", layer.rank + 1));
+                                }
+                                if confidence.ambiguous {
+                                    let rivals = confidence.rivals.iter().map(|h| h.name()).collect::<Vec<_>>().join(", ");
+                                    ui.colored_label(egui::Color32::YELLOW, format!("⚠ Ambiguë avec: {}", rivals));
+                                }
+                            }
                             ui.separator();
                             
                             // Afficher les détails spécifiques selon le type d'hypothèse
                             self.show_hypothesis_details(ui, &layer.hypothesis);
+
+                            if !layer.fields.enums.is_empty() {
+                                ui.separator();
+                                ui.label("Champs énumérés:");
+                                for field in &layer.fields.enums {
+                                    let values = field
+                                        .values
+                                        .iter()
+                                        .map(|v| format!("0x{:02x} ({:.0}%)", v.value, v.frequency * 100.0))
+                                        .collect::<Vec<_>>()
+                                        .join(", ");
+                                    ui.label(format!("Offset {}: {}", field.offset, values));
+                                }
+                            }
+
+                            if let Some(version) = &layer.fields.version {
+                                ui.separator();
+                                let values = version
+                                    .values
+                                    .iter()
+                                    .map(|v| format!("{} ({:.0}%)", v.value, v.frequency * 100.0))
+                                    .collect::<Vec<_>>()
+                                    .join(", ");
+                                ui.label(format!("Champ de version, offset {}: {}", version.offset, values));
+                            }
+
+                            if !layer.fields.flags.is_empty() {
+                                ui.separator();
+                                ui.label("Champs de drapeaux:");
+                                for field in &layer.fields.flags {
+                                    let bits = field
+                                        .bits
+                                        .iter()
+                                        .map(|b| format!("0x{:x} ({:.0}%)", b.mask, b.frequency * 100.0))
+                                        .collect::<Vec<_>>()
+                                        .join(", ");
+                                    ui.label(format!("Offset {} ({} octets): {}", field.offset, field.width, bits));
+                                }
+                            }
+
+                            if !layer.fields.endianness.is_empty() {
+                                ui.separator();
+                                ui.label("Endianness des compteurs et longueurs:");
+                                for evidence in &layer.fields.endianness {
+                                    ui.label(format!(
+                                        "Offset {} ({} octets, {:?}): {:?} ({:.0}% contre {:.0}%)",
+                                        evidence.offset,
+                                        evidence.width,
+                                        evidence.role,
+                                        evidence.endian,
+                                        evidence.support * 100.0,
+                                        evidence.rival_support * 100.0
+                                    ));
+                                }
+                            }
+
+                            if !layer.fields.floats.is_empty() {
+                                ui.separator();
+                                ui.label("Champs flottants:");
+                                for field in &layer.fields.floats {
+                                    ui.label(format!(
+                                        "Offset {}: float{} {:?}, de {} à {}",
+                                        field.offset,
+                                        field.width * 8,
+                                        field.endian,
+                                        field.min,
+                                        field.max
+                                    ));
+                                }
+                            }
+
+                            if !layer.fields.strings.is_empty() {
+                                ui.separator();
+                                ui.label("Chaînes de caractères:");
+                                for field in &layer.fields.strings {
+                                    ui.label(format!(
+                                        "Segment {}, offset {} ({:?}, {:?}): {}",
+                                        field.segment,
+                                        field.offset,
+                                        field.encoding,
+                                        field.framing,
+                                        field.samples.join(", ")
+                                    ));
+                                }
+                            }
+
+                            if !layer.fields.addresses.is_empty() {
+                                ui.separator();
+                                ui.label("Adresses de la capture embarquées:");
+                                for field in &layer.fields.addresses {
+                                    ui.label(format!(
+                                        "Offset {} ({:?}, {} PDUs): {}",
+                                        field.offset,
+                                        field.kind,
+                                        field.matches,
+                                        field.samples.join(", ")
+                                    ));
+                                }
+                            }
+
+                            if !layer.fields.session_ids.is_empty() {
+                                ui.separator();
+                                ui.label("Identifiants de session:");
+                                for field in &layer.fields.session_ids {
+                                    ui.label(format!(
+                                        "Offset {} ({} octets): {} valeurs pour {} flows",
+                                        field.offset, field.width, field.distinct, field.flows
+                                    ));
+                                }
+                            }
+
+                            if !layer.fields.bcd.is_empty() {
+                                ui.separator();
+                                ui.label("Champs BCD:");
+                                for field in &layer.fields.bcd {
+                                    ui.label(format!(
+                                        "Offset {} ({} octets{}): {}",
+                                        field.offset,
+                                        field.len,
+                                        if field.swapped { ", TBCD" } else { "" },
+                                        field.samples.join(", ")
+                                    ));
+                                }
+                            }
+
+                            if let Some(proto) = &layer.fields.proto {
+                                ui.separator();
+                                ui.label(format!("Schéma protobuf ({} PDUs):", proto.pdus));
+                                for field in &proto.fields {
+                                    ui.label(format!(
+                                        "{} {} field_{} = {}; ({} PDUs)",
+                                        if field.repeated { "repeated" } else { "optional" },
+                                        field.proto_type(),
+                                        field.number,
+                                        field.number,
+                                        field.pdus
+                                    ));
+                                }
+                            }
+
+                            if let Some(dictionary) = &layer.fields.tlv_tags {
+                                ui.separator();
+                                ui.label(format!("Tags TLV ({} PDUs):", dictionary.pdus));
+                                for tag in &dictionary.tags {
+                                    let len = match tag.fixed_len() {
+                                        Some(len) => format!("toujours {} octets", len),
+                                        None => format!("{}..{} octets, moyenne {:.1}", tag.min_len, tag.max_len, tag.mean_len),
+                                    };
+                                    ui.label(format!(
+                                        "{}{} ×{} ({} PDUs): {}",
+                                        "  ".repeat(tag.depth),
+                                        tag.tag_hex(),
+                                        tag.occurrences,
+                                        tag.pdus,
+                                        len
+                                    ));
+                                }
+                            }
+
+                            if let Some(structured) = &layer.structured {
+                                ui.separator();
+                                ui.label(format!("Schéma ({} documents):", structured.documents));
+                                for key in &structured.keys {
+                                    ui.label(format!("{} ×{} [{}]", key.path, key.count, key.types.join(", ")));
+                                }
+                            }
+
+                            if let Some(opaque) = &layer.opaque {
+                                ui.separator();
+                                ui.label(format!(
+                                    "Entropie: {:.2} bits/octet ({:.0}% du maximum), compression: {:.2}",
+                                    opaque.entropy,
+                                    opaque.normalized_entropy * 100.0,
+                                    opaque.compression_ratio
+                                ));
+                            }
+
+                            if let Some(padding) = &layer.padding {
+                                ui.separator();
+                                let lengths = padding
+                                    .lengths
+                                    .iter()
+                                    .map(|l| format!("{}×{}", l.length, l.count))
+                                    .collect::<Vec<_>>()
+                                    .join(", ");
+                                ui.label(format!(
+                                    "Bourrage 0x{:02x} sur {}/{} PDUs, longueurs: {}",
+                                    padding.pad_byte, padding.padded_pdus, padding.total_pdus, lengths
+                                ));
+                            }
+
+                            if !layer.branches.is_empty() {
+                                ui.separator();
+                                ui.label("Structures par type ou par canal:");
+                                for branch in &layer.branches {
+                                    let stack = branch
+                                        .layers
+                                        .iter()
+                                        .map(|l| l.hypothesis.name())
+                                        .collect::<Vec<_>>()
+                                        .join(" → ");
+                                    ui.label(format!(
+                                        "Type 0x{:x} ({} PDUs): {}",
+                                        branch.type_value,
+                                        branch.pdu_count,
+                                        if stack.is_empty() { "brut" } else { stack.as_str() }
+                                    ));
+                                }
+                            }
                             
                             ui.separator();
                             ui.label("Métriques de l'hypothèse sélectionnée:");
@@ -497,6 +796,27 @@ impl ProtocolInferApp {
                             }
                         });
                     }
+
+                    if let Some(machine) = &result.state_machine {
+                        ui.collapsing(format!("Automate ({} types de messages)", machine.states.len()), |ui| {
+                            ui.label(format!(
+                                "Discriminant: offset {}, {} octet(s), {} flow(s)",
+                                machine.type_offset, machine.type_width, machine.flows
+                            ));
+                            if let Some(alternation) = machine.alternation {
+                                ui.label(format!("Alternance des directions: {:.0}%", alternation * 100.0));
+                            }
+                            for transition in &machine.transitions {
+                                ui.label(format!(
+                                    "0x{:x} → 0x{:x}: {} ({:.0}%)",
+                                    transition.from,
+                                    transition.to,
+                                    transition.count,
+                                    transition.probability * 100.0
+                                ));
+                            }
+                        });
+                    }
                 });
             });
     }
@@ -505,22 +825,26 @@ impl ProtocolInferApp {
         use protocol_infer_core::hypothesis::*;
         
         match hypothesis {
-            Hypothesis::Tlv { tag_offset, tag_bytes, len_offset, len_rule, length_includes_header } => {
+            Hypothesis::Tlv { tag_offset, tag_bytes, len_offset, len_rule, length_includes_header, tag_rule, endian } => {
                 ui.label("Détails TLV:");
                 ui.separator();
                 
                 let len_bytes = match len_rule {
-                    TlvLenRule::DefiniteShort => 1,
-                    TlvLenRule::DefiniteMedium => 2,
-                    TlvLenRule::DefiniteLong => 4,
-                    TlvLenRule::IndefiniteWithEoc => 0,
+                    TlvLenRule::DefiniteShort => "1",
+                    TlvLenRule::DefiniteMedium => "2",
+                    TlvLenRule::DefiniteLong => "4",
+                    TlvLenRule::IndefiniteWithEoc => "0",
+                    TlvLenRule::BerDefinite => "1 à 5",
                 };
                 
                 let endian_str = match len_rule {
                     TlvLenRule::DefiniteShort => "N/A (1 byte)",
-                    TlvLenRule::DefiniteMedium => "Big Endian",
-                    TlvLenRule::DefiniteLong => "Big Endian",
+                    TlvLenRule::DefiniteMedium | TlvLenRule::DefiniteLong => match endian {
+                        Endianness::Big => "Big Endian",
+                        Endianness::Little => "Little Endian",
+                    },
                     TlvLenRule::IndefiniteWithEoc => "N/A (indefinite)",
+                    TlvLenRule::BerDefinite => "Big Endian (forme longue)",
                 };
                 
                 ui.horizontal(|ui| {
@@ -529,7 +853,11 @@ impl ProtocolInferApp {
                 });
                 ui.horizontal(|ui| {
                     ui.label("Tag bytes:");
-                    ui.label(format!("{}", tag_bytes));
+                    if matches!(tag_rule, TlvTagRule::BerMultiByte) {
+                        ui.label(format!("{} (BER multi-octets)", tag_bytes));
+                    } else {
+                        ui.label(format!("{}", tag_bytes));
+                    }
                 });
                 ui.horizontal(|ui| {
                     ui.label("Length offset:");
@@ -537,7 +865,7 @@ impl ProtocolInferApp {
                 });
                 ui.horizontal(|ui| {
                     ui.label("Length bytes:");
-                    ui.label(format!("{}", len_bytes));
+                    ui.label(len_bytes);
                 });
                 ui.horizontal(|ui| {
                     ui.label("Endianness:");
@@ -558,9 +886,24 @@ impl ProtocolInferApp {
                 } else if matches!(len_rule, TlvLenRule::IndefiniteWithEoc) {
                     ui.separator();
                     ui.label("Note: Mode indéfini avec EOC (0x00 0x00)");
+                } else if matches!(len_rule, TlvLenRule::BerDefinite) {
+                    ui.separator();
+                    ui.label("Note: Longueur BER, forme courte ou longue (0x81..0x84)");
                 }
             }
-            Hypothesis::LengthPrefixBundle { offset, width, endian, includes_header } => {
+            Hypothesis::VarintLengthPrefix { offset, encoding } => {
+                ui.label("Détails Varint Length-Prefix:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Length offset:");
+                    ui.label(format!("{} octets", offset));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Encodage:");
+                    ui.label(format!("{:?}", encoding));
+                });
+            }
+            Hypothesis::LengthPrefixBundle { offset, width, endian, includes_header, unit, adjust } => {
                 ui.label("Détails Length-Prefix:");
                 ui.separator();
                 ui.horizontal(|ui| {
@@ -579,6 +922,14 @@ impl ProtocolInferApp {
                     ui.label("Includes header:");
                     ui.label(format!("{}", includes_header));
                 });
+                ui.horizontal(|ui| {
+                    ui.label("Length unit:");
+                    ui.label(format!("{} octets", unit));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Length adjust:");
+                    ui.label(format!("{:+}", adjust));
+                });
                 ui.horizontal(|ui| {
                     ui.label("Header length:");
                     ui.label(format!("{} octets", offset + *width as usize));
@@ -620,7 +971,7 @@ impl ProtocolInferApp {
                     ui.label(format!("{}", max_bytes));
                 });
             }
-            Hypothesis::DelimiterBundle { pattern } => {
+            Hypothesis::DelimiterBundle { pattern, escape, quote } => {
                 ui.label("Détails Delimiter:");
                 ui.separator();
                 ui.horizontal(|ui| {
@@ -631,6 +982,67 @@ impl ProtocolInferApp {
                     ui.label("Pattern length:");
                     ui.label(format!("{} octets", pattern.len()));
                 });
+                if let Some(escape) = escape {
+                    ui.horizontal(|ui| {
+                        ui.label("Échappement:");
+                        ui.label(format!("0x{:02x}", escape));
+                    });
+                }
+                if let Some(quote) = quote {
+                    ui.horizontal(|ui| {
+                        ui.label("Citation:");
+                        ui.label(format!("{:?}", *quote as char));
+                    });
+                }
+            }
+            Hypothesis::ByteStuffed { scheme } => {
+                ui.label("Détails Byte stuffing:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Délimiteur:");
+                    ui.label(format!("0x{:02x}", scheme.delimiter));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Échappement:");
+                    ui.label(format!("0x{:02x}", scheme.escape));
+                });
+                for (escaped, original) in &scheme.table {
+                    ui.label(format!("0x{:02x} 0x{:02x} → 0x{:02x}", scheme.escape, escaped, original));
+                }
+            }
+            Hypothesis::Hdlc { fcs } => {
+                ui.label("Détails HDLC:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("FCS:");
+                    ui.label(fcs.map_or("aucun".to_string(), |a| format!("{:?}", a)));
+                });
+            }
+            Hypothesis::LabelSequence { offset } => {
+                ui.label("Détails Label Sequence:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Offset du nom:");
+                    ui.label(format!("{} octets", offset));
+                });
+            }
+            Hypothesis::RecordArray { record_len, count_field } => {
+                ui.label("Détails Record Array:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Taille d'enregistrement:");
+                    ui.label(format!("{} octets", record_len));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Compteur:");
+                    ui.label(match count_field {
+                        Some(field) => format!(
+                            "offset {}, {} octet(s), {:?}",
+                            field.offset, field.width as usize, field.endian
+                        ),
+                        None => "aucun".to_string(),
+                    });
+                });
             }
             Hypothesis::VarintKeyWireType { key_max_bytes, allow_embedded } => {
                 ui.label("Détails Varint:");
@@ -644,6 +1056,233 @@ impl ProtocolInferApp {
                     ui.label(format!("{}", allow_embedded));
                 });
             }
+            Hypothesis::ConstantField { offset, value } => {
+                ui.label("Détails Champ constant (magic):");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Offset:");
+                    ui.label(format!("{} octets", offset));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Valeur:");
+                    ui.label(value.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" "));
+                });
+            }
+            Hypothesis::Checksum { algorithm, endian, location } => {
+                ui.label("Détails Checksum:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Algorithme:");
+                    ui.label(format!("{:?}", algorithm));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Endianness:");
+                    ui.label(format!("{:?}", endian));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Emplacement:");
+                    ui.label(match location {
+                        ChecksumLocation::Header { offset } => format!("en-tête, offset {}", offset),
+                        ChecksumLocation::Trailer { covered_start } => {
+                            format!("fin de PDU, couvre depuis l'offset {}", covered_start)
+                        }
+                    });
+                });
+            }
+            Hypothesis::TextLines { line_ending } => {
+                ui.label("Détails TextLines:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Fin de ligne:");
+                    ui.label(format!("{:?}", line_ending));
+                });
+            }
+            Hypothesis::KeyValueHeader { line_ending } => {
+                ui.label("Détails KeyValueHeader:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Fin de ligne:");
+                    ui.label(format!("{:?}", line_ending));
+                });
+            }
+            Hypothesis::ContentLengthMessages { line_ending, length_header } => {
+                ui.label("Détails ContentLengthMessages:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Fin de ligne:");
+                    ui.label(format!("{:?}", line_ending));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("En-tête de longueur:");
+                    ui.label(length_header);
+                });
+            }
+            Hypothesis::StructuredText { format } => {
+                ui.label("Détails StructuredText:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Format:");
+                    ui.label(format!("{:?}", format));
+                });
+            }
+            Hypothesis::Encoded { encoding } => {
+                ui.label("Détails Encoded:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Encodage:");
+                    ui.label(format!("{:?}", encoding));
+                });
+            }
+            Hypothesis::Compressed { algorithm } => {
+                ui.label("Détails Compressed:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Algorithme:");
+                    ui.label(format!("{:?}", algorithm));
+                });
+            }
+            Hypothesis::Obfuscated { obfuscation } => {
+                ui.label("Détails Obfuscated:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Transformation:");
+                    ui.label(match obfuscation {
+                        Obfuscation::Xor { key } => format!(
+                            "XOR, clé {}",
+                            key.iter().map(|k| format!("{:02x}", k)).collect::<String>()
+                        ),
+                        Obfuscation::Add { key } => format!("ADD, clé {:02x}", key),
+                    });
+                });
+            }
+            Hypothesis::Opaque => {
+                ui.label("Charge utile opaque (probablement chiffrée ou compressée)");
+            }
+            Hypothesis::TrailingPadding { pad_byte } => {
+                ui.label("Détails TrailingPadding:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Octet de bourrage:");
+                    ui.label(format!("0x{:02x}", pad_byte));
+                });
+            }
+            Hypothesis::BitFields { header_len, boundaries } => {
+                ui.label("Détails BitFields:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Taille de l'en-tête:");
+                    ui.label(format!("{} octet(s)", header_len));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Frontières (bits):");
+                    ui.label(format!("{:?}", boundaries));
+                });
+            }
+            Hypothesis::FixedTrailer { len } => {
+                ui.label("Détails FixedTrailer:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Longueur:");
+                    ui.label(format!("{} octets", len));
+                });
+            }
+            Hypothesis::RemainingLength { offset, width, endian } => {
+                ui.label("Détails RemainingLength:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Length offset:");
+                    ui.label(format!("{} octets", offset));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Length width:");
+                    ui.label(format!("{} octets", *width as usize));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Endianness:");
+                    ui.label(format!("{:?}", endian));
+                });
+            }
+            Hypothesis::SyncLengthBundle { sync, width, endian, includes_header } => {
+                ui.label("Détails SyncLengthBundle:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Sync:");
+                    ui.label(sync.iter().map(|b| format!("{:02x}", b)).collect::<String>());
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Length width:");
+                    ui.label(format!("{} octets", *width as usize));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Endianness:");
+                    ui.label(format!("{:?}", endian));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Includes header:");
+                    ui.label(format!("{}", includes_header));
+                });
+            }
+            Hypothesis::LengthSuffixBundle { offset, width, endian } => {
+                ui.label("Détails LengthSuffixBundle:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Octets après la longueur:");
+                    ui.label(format!("{}", offset));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Width:");
+                    ui.label(format!("{:?}", width));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Endianness:");
+                    ui.label(format!("{:?}", endian));
+                });
+            }
+            Hypothesis::ChannelMux { channel_width, len_width, endian } => {
+                ui.label("Détails ChannelMux:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Taille du canal:");
+                    ui.label(format!("{} octet(s)", channel_width));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Longueur:");
+                    ui.label(format!("{:?}, {:?}", len_width, endian));
+                });
+            }
+            Hypothesis::Fragmented { index_offset, index_width, flag_offset, flag_mask, last_when_set } => {
+                ui.label("Détails Fragmented:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Index:");
+                    ui.label(format!("offset {}, {} octet(s)", index_offset, index_width));
+                });
+                ui.horizontal(|ui| {
+                    ui.label(if *last_when_set { "Drapeau dernier fragment:" } else { "Drapeau autres fragments:" });
+                    ui.label(format!("offset {}, masque 0x{:02x}", flag_offset, flag_mask));
+                });
+            }
+            Hypothesis::TypeSwitch { type_offset, type_width } => {
+                ui.label("Détails TypeSwitch:");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Offset du type:");
+                    ui.label(format!("{}", type_offset));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Taille du type:");
+                    ui.label(format!("{} octet(s)", type_width));
+                });
+            }
+            Hypothesis::Composite(parts) => {
+                ui.label("Détails Composite:");
+                ui.separator();
+                for (idx, part) in parts.iter().enumerate() {
+                    ui.collapsing(format!("Partie {} - {}", idx, part.name()), |ui| {
+                        self.show_hypothesis_details(ui, part);
+                    });
+                }
+            }
         }
     }
 
@@ -686,6 +1325,7 @@ impl ProtocolInferApp {
                                     }
                                 },
                                 protocol_infer_core::SegmentKind::Error(_) => egui::Color32::from_rgb(255, 100, 100),
+                                protocol_infer_core::SegmentKind::Padding => egui::Color32::from_rgb(220, 220, 220),
                             }
                         } else {
                             egui::Color32::TRANSPARENT
@@ -733,6 +1373,7 @@ impl ProtocolInferApp {
                                     }
                                 },
                                 protocol_infer_core::SegmentKind::Error(_) => egui::Color32::from_rgb(255, 100, 100),
+                                protocol_infer_core::SegmentKind::Padding => egui::Color32::from_rgb(220, 220, 220),
                             }
                         } else {
                             egui::Color32::TRANSPARENT